@@ -14,18 +14,35 @@ pub enum FetcherConfigError {
 	#[error("Google OAuth2 token isn't set up")]
 	GoogleOAuth2TokenMissing,
 
+	#[error("Microsoft OAuth2 token isn't set up")]
+	MicrosoftOAuth2TokenMissing,
+
 	#[error("Email password isn't set up")]
 	EmailPasswordMissing,
 
 	#[error("Email imap field is missing and it's not clear what it should be")]
 	EmailImapFieldMissing,
 
+	#[error(
+		"Connecting to IMAP without TLS is insecure and has to be allowed with allow_insecure_connection: true"
+	)]
+	EmailInsecureConnectionNotAllowed,
+
 	#[error("Telegram bot token isn't set up")]
 	TelegramBotTokenMissing,
 
 	#[error("Discord bot token isn't set up")]
 	DiscordBotTokenMissing,
 
+	#[error("Slack webhook URL isn't set up")]
+	SlackWebhookUrlMissing,
+
+	#[error("Reddit app credentials aren't set up")]
+	RedditAuthMissing,
+
+	#[error("reddit - {0} can't be set for users, only for subreddits")]
+	RedditUserUnsupported(&'static str),
+
 	#[error("Importing is unavailable")]
 	ImportingUnavailable,
 
@@ -37,21 +54,92 @@ pub enum FetcherConfigError {
 	//BadDurationFormat(#[from] duration_str::DError),
 	BadDurationFormat(String),
 
+	#[error("read_list - max_age is not a valid duration format, e.g. 12h, 30d")]
+	BadReadListMaxAge(String),
+
+	#[error("backoff - base and max are not valid duration formats, e.g. 1m, 6h")]
+	BadBackoffDuration(String),
+
+	#[error("max_entry_age is not a valid duration format, e.g. 12h, 30d")]
+	BadMaxEntryAge(String),
+
+	#[error("timeout is not a valid duration format, e.g. 30s, 5m")]
+	BadTimeout(String),
+
+	#[error("sink - min_send_interval is not a valid duration format, e.g. 5s, 1m")]
+	BadMinSendInterval(String),
+
+	#[error("http - retry_delay is not a valid duration format, e.g. 5s, 1m")]
+	BadRetryDelay(String),
+
+	#[error("read_filter_type - not_older_than requires max_entry_age to be set")]
+	MaxEntryAgeMissing,
+
+	#[error(
+		"read_filter_type - newer_than_read and not_present_in_read_list can't be used together"
+	)]
+	MultipleStatefulReadFilters,
+
+	#[error(
+		"entry_to_msg_map_enabled - can't be disabled when {0} is enabled, it needs to know which message an entry was sent as"
+	)]
+	EntryToMsgMapDisabled(&'static str),
+
+	#[error("take - {0} is not a valid percentage, e.g. 50%")]
+	BadTakePercentage(String),
+
+	#[error("json - {1:?} is not a valid JSONPath expression")]
+	BadJsonPath(#[source] serde_json_path::ParseError, String),
+
+	#[error("json - an item filter can't check both equals and contains at the same time")]
+	JsonItemFilterConflict,
+
+	#[error("refresh - {1:?} is not a valid cron schedule, e.g. \"0 9-17 * * Mon-Fri\"")]
+	BadCronSchedule(#[source] cron::error::Error, String),
+
 	#[error("refresh - at is not a valid time format, e.g. 14:30")]
 	BadTimeFormat(#[from] chrono::ParseError),
 
+	#[error("http - post requires either body or form to be set")]
+	HttpPostBodyMissing,
+
+	#[error(
+		"http - post can't have both body and form set, and form can only be sent as form content_type"
+	)]
+	HttpPostBodyConflict,
+
 	#[error("Error setting up HTTP client")]
 	FetcherCoreHttp(#[from] fetcher_core::source::http::HttpError),
 
 	#[error("Error setting up HTML parser")]
 	FetcherCoreHtml(#[from] fetcher_core::action::transform::entry::html::HtmlError),
 
+	#[error("Invalid template")]
+	FetcherCoreTemplate(#[from] fetcher_core::action::transform::template::TemplateError),
+
+	#[error("Error setting up XML parser")]
+	FetcherCoreXml(#[from] fetcher_core::action::transform::entry::xml::XmlError),
+
 	#[error("Error setting up regex")]
 	FetcherCoreBadRegex(#[from] fetcher_core::error::BadRegexError),
 
 	#[error("Error setting up extract action")]
 	FetcherCoreExtract(#[from] fetcher_core::action::transform::field::extract::ExtractError),
 
+	#[error("Error setting up extract_fields action")]
+	FetcherCoreExtractFields(
+		#[from] fetcher_core::action::transform::entry::extract_fields::ExtractFieldsError,
+	),
+
+	#[error("when - sinks can't be used in then, put them after the when action instead")]
+	WhenWithSink,
+
+	#[error("websub - can only be used with an http source that gets a single feed")]
+	WebSubSourceNotHttpGet,
+
+	#[error("gemini - invalid certificate fingerprint")]
+	GeminiBadFingerprint(#[from] fetcher_core::source::gemini::known_hosts::BadFingerprintError),
+
 	#[error("Error setting up a source")]
 	FetcherCoreSource(#[source] Box<fetcher_core::source::error::SourceError>),
 }
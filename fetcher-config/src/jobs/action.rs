@@ -4,75 +4,154 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+#![expect(
+	clippy::same_name_method,
+	reason = "the derived (de)serialization of Action with remote = Self is wrapped by the manual impls"
+)]
+
+pub mod affix;
+pub mod auto;
+pub mod case;
 pub mod contains;
 pub mod decode_html;
+pub mod dedup;
 pub mod extract;
+pub mod extract_fields;
+pub mod feed;
+pub mod filter;
 pub mod html;
+pub mod http;
 pub mod import;
 pub mod json;
+pub mod parse_date;
 pub mod remove_html;
 pub mod replace;
 pub mod set;
 pub mod shorten;
+pub mod sort;
+pub mod split;
 pub mod take;
+pub mod translate;
 pub mod trim;
+pub mod truncate_after;
 pub mod use_as;
+pub mod when;
+pub mod xml;
 
 use self::{
-	contains::Contains, decode_html::DecodeHtml, extract::Extract, html::Html, import::Import,
-	json::Json, remove_html::RemoveHtml, replace::Replace, set::Set, shorten::Shorten, take::Take,
-	trim::Trim, use_as::Use,
+	affix::Affix, auto::Auto, case::Case, contains::Contains, decode_html::DecodeHtml,
+	dedup::Dedup, extract::Extract, extract_fields::ExtractFields, feed::Feed, filter::Filter,
+	html::Html, http::Http, import::Import, json::Json, parse_date::ParseDate,
+	remove_html::RemoveHtml, replace::Replace, set::Set, shorten::Shorten, sort::Sort,
+	split::Split, take::Take, translate::Translate, trim::Trim, truncate_after::TruncateAfter,
+	use_as::Use, when::When, xml::Xml,
 };
 use super::{external_data::ProvideExternalData, sink::Sink};
-use crate::FetcherConfigError;
+use crate::{
+	FetcherConfigError,
+	serde_extentions::shorthand::{self, Shorthands},
+};
 use fetcher_core::{
 	action::{
 		Action as CAction,
 		transform::{
-			Caps as CCaps, DebugPrint as CDebugPrint, Feed as CFeed, Http as CHttp,
+			Case as CCase, DebugPrint as CDebugPrint, Feed as CFeed, Http as CHttp,
+			entry::{
+				auto::Auto as CAuto, json_feed::JsonFeed as CJsonFeed,
+				open_graph::OpenGraph as COpenGraph,
+			},
 			field::{Field as CField, TransformFieldWrapper as CTransformFieldWrapper},
 		},
 	},
 	read_filter::ReadFilter as CReadFilter,
 };
 
-use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
-#[serde(rename_all = "snake_case", deny_unknown_fields)]
+#[allow(clippy::large_enum_variant)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(rename_all = "snake_case", deny_unknown_fields, remote = "Self")]
+#[schemars(transform = shorthand::transform_schema::<Action>)]
 pub enum Action {
 	// filters
 	ReadFilter,
 	Take(Take),
 	Contains(Contains),
+	Filter(Filter),
+	Dedup(Dedup),
+	Sort(Sort),
 
 	// entry transforms
 	DebugPrint,
 	Feed,
+	FormattedFeed(Feed),
 	Html(Html),
-	Http,
+	Http(Option<Http>),
 	Json(Json),
+	JsonFeed,
+	OpenGraph,
+	Auto(Option<Auto>),
+	Xml(Xml),
 	Use(Use),
+	Translate(Translate),
+	Split(Split),
 
 	// field transforms
 	Caps,
+	Case(Case),
 	Set(Set),
 	Shorten(Shorten),
+	Affix(Affix),
 	Trim(Trim),
+	TruncateAfter(TruncateAfter),
 	Replace(Replace),
 	Extract(Extract),
+	ExtractFields(ExtractFields),
+	ParseDate(ParseDate),
 	RemoveHtml(RemoveHtml),
 	DecodeHtml(DecodeHtml),
 
 	// other
 	Sink(Sink),
 	Import(Import),
+	When(When),
+}
+
+// auto and http can be written without a payload, the same as auto: ~
+impl Shorthands for Action {
+	const SHORTHANDS: &'static [&'static str] = &["http", "auto"];
+
+	fn deserialize_tagged<'de, D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		Self::deserialize(deserializer)
+	}
+}
+
+impl<'de> Deserialize<'de> for Action {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		shorthand::deserialize(deserializer)
+	}
+}
+
+impl Serialize for Action {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		Self::serialize(self, serializer)
+	}
 }
 
 // TODO: add media
-#[derive(Deserialize, Serialize, Clone, Hash, PartialEq, Eq, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Hash, PartialEq, Eq, Debug)]
 #[serde(rename_all = "snake_case", deny_unknown_fields)]
 pub enum Field {
 	Title,
@@ -84,9 +163,11 @@ pub enum Field {
 }
 
 impl Action {
+	/// `tag` is the tag of the task, to fill templates with
 	pub fn decode_from_conf<RF, D>(
 		self,
 		rf: Option<Arc<RwLock<RF>>>,
+		tag: Option<&str>,
 		external: &D,
 	) -> Result<Option<Vec<CAction>>, FetcherConfigError>
 	where
@@ -117,36 +198,54 @@ impl Action {
 					return Ok(None);
 				}
 			}
-			Action::Take(x) => filter!(x.decode_from_conf()),
+			Action::Take(x) => filter!(x.decode_from_conf()?),
 			Action::Contains(x) => x.decode_from_conf()?,
+			Action::Filter(x) => filter!(x.decode_from_conf()?),
+			Action::Dedup(x) => filter!(x.decode_from_conf()),
+			Action::Sort(x) => filter!(x.decode_from_conf()),
 
 			// entry transforms
-			Action::Feed => transform!(CFeed),
+			Action::Feed => transform!(CFeed::default()),
+			Action::FormattedFeed(x) => transform!(x.decode_from_conf(tag)?),
 			Action::Html(x) => transform!(x.decode_from_conf()?),
-			Action::Http => transform!(CHttp::new(CField::Link)?),
+			Action::Http(None) => transform!(CHttp::new(CField::Link)?),
+			Action::Http(Some(http)) => transform!(http.decode_from_conf()?),
 			Action::Json(x) => transform!(x.decode_from_conf()?),
+			Action::JsonFeed => transform!(CJsonFeed),
+			Action::OpenGraph => transform!(COpenGraph),
+			Action::Auto(None) => transform!(CAuto::default()),
+			Action::Auto(Some(auto)) => transform!(auto.decode_from_conf()?),
+			Action::Xml(x) => transform!(x.decode_from_conf()?),
 			Action::Use(x) => x.decode_from_conf(),
+			Action::Translate(x) => transform!(x.decode_from_conf()?),
+			Action::Split(x) => transform!(x.decode_from_conf()?),
 
 			// field transforms
 			Action::Caps => transform!(CTransformFieldWrapper {
 				field: CField::Title,
-				transformator: CCaps,
+				transformator: CCase::default(),
 			}),
+			Action::Case(x) => x.decode_from_conf(),
 			Action::DebugPrint => transform!(CDebugPrint),
-			Action::Set(s) => s.decode_from_conf(),
+			Action::Set(s) => vec![s.decode_from_conf(tag)?],
 			Action::Shorten(x) => x.decode_from_conf(),
+			Action::Affix(x) => x.decode_from_conf(),
 			Action::Trim(x) => transform!(x.decode_from_conf()),
+			Action::TruncateAfter(x) => x.decode_from_conf(),
 			Action::Replace(x) => transform!(x.decode_from_conf()?),
 			Action::Extract(x) => transform!(x.decode_from_conf()?),
+			Action::ExtractFields(x) => transform!(x.decode_from_conf()?),
+			Action::ParseDate(x) => transform!(x.decode_from_conf()),
 			Action::RemoveHtml(x) => x.decode_from_conf()?,
 			Action::DecodeHtml(x) => x.decode_from_conf(),
 
 			// other
 			Action::Sink(x) => vec![CAction::Sink(x.decode_from_conf(external)?)],
-			Action::Import(x) => match x.decode_from_conf(rf, external) {
+			Action::Import(x) => match x.decode_from_conf(rf, tag, external) {
 				Ok(Some(v)) => v,
 				not_ok => return not_ok,
 			},
+			Action::When(x) => vec![x.decode_from_conf(rf, tag, external)?],
 		};
 
 		Ok(Some(act))
@@ -0,0 +1,41 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::Field;
+use fetcher_core::action::{
+	Action as CAction,
+	transform::field::{TransformFieldWrapper as CTransformFieldWrapper, affix::Affix as CAffix},
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(transparent)]
+pub struct Affix(pub HashMap<Field, Inner>);
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Inner {
+	pub prepend: Option<String>,
+	pub append: Option<String>,
+}
+
+impl Affix {
+	#[must_use]
+	pub fn decode_from_conf(self) -> Vec<CAction> {
+		self.0
+			.into_iter()
+			.map(|(field, Inner { prepend, append })| {
+				CAction::Transform(Box::new(CTransformFieldWrapper {
+					field: field.decode_from_conf(),
+					transformator: CAffix { prepend, append },
+				}))
+			})
+			.collect()
+	}
+}
@@ -0,0 +1,29 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::{html::Html, json::Json};
+use crate::FetcherConfigError;
+use fetcher_core::action::transform::entry::auto::Auto as CAuto;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Auto {
+	pub json: Option<Json>,
+	pub html: Option<Html>,
+}
+
+impl Auto {
+	pub fn decode_from_conf(self) -> Result<CAuto, FetcherConfigError> {
+		Ok(CAuto {
+			json: self.json.map(Json::decode_from_conf).transpose()?,
+			html: self.html.map(Html::decode_from_conf).transpose()?,
+			..Default::default()
+		})
+	}
+}
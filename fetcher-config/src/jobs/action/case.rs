@@ -0,0 +1,60 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::Field;
+use fetcher_core::action::{
+	Action as CAction,
+	transform::field::{
+		TransformFieldWrapper as CTransformFieldWrapper,
+		case::{Case as CCase, CaseMode as CCaseMode},
+	},
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(transparent)]
+pub struct Case(pub HashMap<Field, CaseMode>);
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum CaseMode {
+	Upper,
+	Lower,
+	Title,
+	Sentence,
+}
+
+impl Case {
+	#[must_use]
+	pub fn decode_from_conf(self) -> Vec<CAction> {
+		self.0
+			.into_iter()
+			.map(|(field, mode)| {
+				CAction::Transform(Box::new(CTransformFieldWrapper {
+					field: field.decode_from_conf(),
+					transformator: CCase {
+						mode: mode.decode_from_conf(),
+					},
+				}))
+			})
+			.collect()
+	}
+}
+
+impl CaseMode {
+	#[must_use]
+	pub fn decode_from_conf(self) -> CCaseMode {
+		match self {
+			CaseMode::Upper => CCaseMode::Upper,
+			CaseMode::Lower => CCaseMode::Lower,
+			CaseMode::Title => CCaseMode::Title,
+			CaseMode::Sentence => CCaseMode::Sentence,
+		}
+	}
+}
@@ -4,6 +4,7 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -13,19 +14,34 @@ use fetcher_core::action::{Action as CAction, filter::Contains as CContains};
 
 type RegEx = String;
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
-#[serde(deny_unknown_fields)]
-pub struct Contains(pub HashMap<Field, RegEx>);
+// can't deny unknown fields with flatten but unknown keys are rejected by Field anyways
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+pub struct Contains {
+	#[serde(flatten)]
+	pub fields: HashMap<Field, RegEx>,
+	pub case_sensitive: Option<bool>,
+	pub regex: Option<bool>,
+}
 
 impl Contains {
 	pub fn decode_from_conf(self) -> Result<Vec<CAction>, ConfigError> {
-		self.0
+		Ok(self
+			.decode_conditions()?
+			.into_iter()
+			.map(|contains| CAction::Filter(Box::new(contains)))
+			.collect())
+	}
+
+	/// Decode into a condition for every field, without wrapping them into filters
+	pub fn decode_conditions(self) -> Result<Vec<CContains>, ConfigError> {
+		let case_sensitive = self.case_sensitive.unwrap_or(true);
+		let is_regex = self.regex.unwrap_or(true);
+
+		self.fields
 			.into_iter()
 			.map(|(field, re)| {
-				Ok(CAction::Filter(Box::new(CContains::new(
-					&re,
-					field.decode_from_conf(),
-				)?)))
+				CContains::with_options(&re, field.decode_from_conf(), is_regex, case_sensitive)
+					.map_err(Into::into)
 			})
 			.collect()
 	}
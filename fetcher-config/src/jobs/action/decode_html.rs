@@ -10,12 +10,13 @@ use fetcher_core::action::{
 	transform::field::decode_html::DecodeHtml as CDecodeHtml,
 };
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::{OneOrMany, serde_as};
 
 // Decode HTML escape codes
 #[serde_as]
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 pub struct DecodeHtml {
 	#[serde_as(deserialize_as = "OneOrMany<_>")]
 	pub r#in: Vec<Field>,
@@ -0,0 +1,26 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::Field;
+use fetcher_core::action::filter::Dedup as CDedup;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_with::{OneOrMany, serde_as};
+
+#[serde_as]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(transparent)]
+pub struct Dedup(#[serde_as(deserialize_as = "OneOrMany<_>")] pub Vec<Field>);
+
+impl Dedup {
+	#[must_use]
+	pub fn decode_from_conf(self) -> CDedup {
+		CDedup {
+			fields: self.0.into_iter().map(Field::decode_from_conf).collect(),
+		}
+	}
+}
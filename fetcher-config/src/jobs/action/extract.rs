@@ -10,11 +10,12 @@ use fetcher_core::action::transform::{
 	field::{Extract as CExtract, TransformFieldWrapper as CTransformFieldWrapper},
 };
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::Field;
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 pub struct Extract {
 	from_field: Field,
 	re: String,
@@ -0,0 +1,30 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::Field;
+use crate::error::FetcherConfigError;
+use fetcher_core::action::transform::entry::extract_fields::ExtractFields as CExtractFields;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ExtractFields {
+	pub from_field: Option<Field>,
+	pub re: String,
+	pub passthrough_if_not_found: Option<bool>,
+}
+
+impl ExtractFields {
+	pub fn decode_from_conf(self) -> Result<CExtractFields, FetcherConfigError> {
+		Ok(CExtractFields::new(
+			self.from_field.unwrap_or(Field::Body).decode_from_conf(),
+			&self.re,
+			self.passthrough_if_not_found.unwrap_or(false),
+		)?)
+	}
+}
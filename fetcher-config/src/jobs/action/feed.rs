@@ -0,0 +1,27 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use fetcher_core::action::transform::entry::feed::{Feed as CFeed, Template as CTemplate};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::FetcherConfigError;
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Feed {
+	pub template: String,
+}
+
+impl Feed {
+	/// `{tag}` in the template is replaced with the `tag` of the task
+	pub fn decode_from_conf(self, tag: Option<&str>) -> Result<CFeed, FetcherConfigError> {
+		Ok(CFeed {
+			template: Some(CTemplate::parse(&self.template)?.with_tag(tag)),
+		})
+	}
+}
@@ -0,0 +1,111 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::Field;
+use crate::error::FetcherConfigError as ConfigError;
+use fetcher_core::action::filter::{
+	Contains as CContains,
+	rules::{Rule as CRule, Rules as CRules, Verdict as CVerdict},
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+type RegEx = String;
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Filter {
+	pub rules: Vec<Rule>,
+	pub otherwise: Option<Verdict>,
+	pub case_sensitive: Option<bool>,
+	pub regex: Option<bool>,
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Rule {
+	KeepIf(Condition),
+	DropIf(Condition),
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(transparent)]
+pub struct Condition(
+	#[serde(with = "crate::serde_extentions::tuple")]
+	#[schemars(with = "std::collections::HashMap<Field, RegEx>")]
+	pub Inner,
+);
+
+#[derive(Clone, Debug)]
+pub struct Inner {
+	pub field: Field,
+	pub re: RegEx,
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Verdict {
+	Keep,
+	Drop,
+}
+
+impl Filter {
+	pub fn decode_from_conf(self) -> Result<CRules, ConfigError> {
+		let case_sensitive = self.case_sensitive.unwrap_or(true);
+		let is_regex = self.regex.unwrap_or(true);
+
+		let rules = self
+			.rules
+			.into_iter()
+			.map(|rule| {
+				let (Condition(Inner { field, re }), verdict) = match rule {
+					Rule::KeepIf(condition) => (condition, CVerdict::Keep),
+					Rule::DropIf(condition) => (condition, CVerdict::Drop),
+				};
+
+				Ok(CRule {
+					condition: CContains::with_options(
+						&re,
+						field.decode_from_conf(),
+						is_regex,
+						case_sensitive,
+					)?,
+					verdict,
+				})
+			})
+			.collect::<Result<_, ConfigError>>()?;
+
+		Ok(CRules {
+			rules,
+			otherwise: self
+				.otherwise
+				.map_or(CVerdict::Keep, Verdict::decode_from_conf),
+		})
+	}
+}
+
+impl Verdict {
+	#[must_use]
+	pub fn decode_from_conf(self) -> CVerdict {
+		match self {
+			Verdict::Keep => CVerdict::Keep,
+			Verdict::Drop => CVerdict::Drop,
+		}
+	}
+}
+
+impl<'a> From<&'a Inner> for (&'a Field, &'a RegEx) {
+	fn from(Inner { field, re }: &'a Inner) -> Self {
+		(field, re)
+	}
+}
+
+impl From<(Field, RegEx)> for Inner {
+	fn from((field, re): (Field, RegEx)) -> Self {
+		Self { field, re }
+	}
+}
@@ -10,14 +10,17 @@ use self::query::{ElementDataQuery, ElementQuery, ItemQuery};
 use crate::FetcherConfigError;
 use fetcher_core::{action::transform::entry::html::Html as CHtml, utils::OptionExt};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct Html {
+	pub container: Option<ItemQuery>,
 	pub item: Option<ItemQuery>,
 	pub title: Option<ElementDataQuery>,
 	pub text: Option<Vec<ElementDataQuery>>,
+	pub text_separator: Option<String>,
 	pub id: Option<ElementDataQuery>,
 	pub link: Option<ElementDataQuery>,
 	pub img: Option<ElementDataQuery>,
@@ -26,6 +29,12 @@ pub struct Html {
 impl Html {
 	pub fn decode_from_conf(self) -> Result<CHtml, FetcherConfigError> {
 		Ok(CHtml {
+			container: self.container.map(|x| {
+				x.query
+					.into_iter()
+					.map(ElementQuery::decode_from_conf)
+					.collect()
+			}),
 			item: self.item.map(|x| {
 				x.query
 					.into_iter()
@@ -38,6 +47,7 @@ impl Html {
 					.map(ElementDataQuery::decode_from_conf)
 					.collect::<Result<_, _>>()
 			})?,
+			text_separator: self.text_separator,
 			id: self.id.try_map(ElementDataQuery::decode_from_conf)?,
 			link: self.link.try_map(ElementDataQuery::decode_from_conf)?,
 			img: self.img.try_map(ElementDataQuery::decode_from_conf)?,
@@ -10,14 +10,16 @@ use fetcher_core::{
 	utils::OptionExt,
 };
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")] // deny_unknown_fields not allowed since it's flattened in [`Query`]
 pub enum ElementKind {
 	Tag(String),
 	Class(String),
 	#[serde(with = "crate::serde_extentions::tuple")]
+	#[schemars(with = "std::collections::HashMap<String, String>")]
 	Attr(ElementAttr),
 }
 
@@ -27,27 +29,29 @@ pub struct ElementAttr {
 	pub value: String,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(rename_all = "snake_case", deny_unknown_fields)]
 pub enum DataLocation {
 	Text,
 	Attr(String),
+	AttrAny(Vec<String>),
+	InnerHtml,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)] // deny_unknown_fields not allowed since it uses flatten
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)] // deny_unknown_fields not allowed since it uses flatten
 pub struct ElementQuery {
 	#[serde(flatten)]
 	pub kind: ElementKind,
 	pub ignore: Option<Vec<ElementKind>>,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct ItemQuery {
 	pub query: Vec<ElementQuery>,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)] // deny_unknown_fields not allowed since it's flattened in [`ElementQuery`]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)] // deny_unknown_fields not allowed since it's flattened in [`ElementQuery`]
 pub struct ElementDataQuery {
 	pub optional: Option<bool>,
 	pub query: Vec<ElementQuery>,
@@ -55,7 +59,7 @@ pub struct ElementDataQuery {
 	pub regex: Option<HtmlQueryRegex>,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct HtmlQueryRegex {
 	pub re: String,
@@ -78,11 +82,13 @@ impl ElementKind {
 impl DataLocation {
 	#[must_use]
 	pub fn decode_from_conf(self) -> c_query::DataLocation {
-		use DataLocation::{Attr, Text};
+		use DataLocation::{Attr, AttrAny, InnerHtml, Text};
 
 		match self {
 			Text => c_query::DataLocation::Text,
 			Attr(v) => c_query::DataLocation::Attr(v),
+			AttrAny(v) => c_query::DataLocation::AttrAny(v),
+			InnerHtml => c_query::DataLocation::InnerHtml,
 		}
 	}
 }
@@ -0,0 +1,35 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::Field;
+use crate::FetcherConfigError;
+use fetcher_core::action::transform::entry::http::{DEFAULT_RETRY_DELAY, Http as CHttp};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Http {
+	pub from: Option<Field>,
+	pub retries: Option<u32>,
+	pub retry_delay: Option<String>,
+}
+
+impl Http {
+	pub fn decode_from_conf(self) -> Result<CHttp, FetcherConfigError> {
+		let retry_delay = self
+			.retry_delay
+			.map(duration_str::parse_std)
+			.transpose()
+			.map_err(FetcherConfigError::BadRetryDelay)?
+			.unwrap_or(DEFAULT_RETRY_DELAY);
+
+		let from_field = self.from.unwrap_or(Field::Link).decode_from_conf();
+
+		Ok(CHttp::new(from_field)?.with_retries(self.retries.unwrap_or(0), retry_delay))
+	}
+}
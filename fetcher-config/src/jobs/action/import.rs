@@ -11,11 +11,12 @@ use crate::{
 use fetcher_core::{action::Action as CAction, read_filter::ReadFilter as CReadFilter};
 
 use itertools::process_results;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 pub struct Import(pub String);
 
 impl Import {
@@ -23,6 +24,7 @@ impl Import {
 	pub fn decode_from_conf<RF, D>(
 		self,
 		rf: Option<Arc<RwLock<RF>>>,
+		tag: Option<&str>,
 		external: &D,
 	) -> Result<Option<Vec<CAction>>>
 	where
@@ -33,7 +35,7 @@ impl Import {
 			ExternalDataResult::Ok(x) => {
 				let v = process_results(
 					x.into_iter()
-						.map(|x| x.decode_from_conf(rf.clone(), external)),
+						.map(|x| x.decode_from_conf(rf.clone(), tag, external)),
 					|i| {
 						i.flatten(/* option */).flatten(/* inner vec */).collect::<Vec<_>>()
 					},
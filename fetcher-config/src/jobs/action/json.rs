@@ -13,9 +13,11 @@ use fetcher_core::{
 	utils::OptionExt,
 };
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json_path::JsonPath;
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct Json {
 	pub item: Option<Query>,
@@ -24,9 +26,11 @@ pub struct Json {
 	pub id: Option<StringQuery>,
 	pub link: Option<StringQuery>,
 	pub img: Option<Vec<StringQuery>>,
+	pub ignore: Option<Vec<ItemFilter>>,
+	pub only: Option<Vec<ItemFilter>>,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(untagged)]
 pub enum Key {
 	String(String),
@@ -34,16 +38,23 @@ pub enum Key {
 }
 pub type Keys = Vec<Key>;
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum QueryKind {
+	Keys(Keys),
+	JsonPath(String),
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct Query {
 	#[serde(rename = "query")]
-	pub keys: Keys,
+	pub kind: QueryKind,
 	// TODO: should itemq really be allowed to be marked as optional?
 	pub optional: Option<bool>,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct StringQuery {
 	#[serde(flatten)]
@@ -51,7 +62,15 @@ pub struct StringQuery {
 	pub regex: Option<JsonQueryRegex>,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ItemFilter {
+	pub query: QueryKind,
+	pub equals: Option<String>,
+	pub contains: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct JsonQueryRegex {
 	re: String,
@@ -61,7 +80,7 @@ pub struct JsonQueryRegex {
 impl Json {
 	pub fn decode_from_conf(self) -> Result<CJson, FetcherConfigError> {
 		Ok(CJson {
-			item: self.item.map(Query::decode_from_conf),
+			item: self.item.try_map(Query::decode_from_conf)?,
 			title: self.title.try_map(StringQuery::decode_from_conf)?,
 
 			text: self.text.try_map(|v| {
@@ -78,6 +97,17 @@ impl Json {
 					.map(StringQuery::decode_from_conf)
 					.collect::<Result<_, _>>()
 			})?,
+
+			ignore: self.ignore.try_map(|v| {
+				v.into_iter()
+					.map(ItemFilter::decode_from_conf)
+					.collect::<Result<_, _>>()
+			})?,
+			only: self.only.try_map(|v| {
+				v.into_iter()
+					.map(ItemFilter::decode_from_conf)
+					.collect::<Result<_, _>>()
+			})?,
 		})
 	}
 }
@@ -92,24 +122,53 @@ impl Key {
 	}
 }
 
+impl QueryKind {
+	pub fn decode_from_conf(self) -> Result<c_json::QueryKind, FetcherConfigError> {
+		Ok(match self {
+			QueryKind::Keys(keys) => {
+				c_json::QueryKind::Keys(keys.into_iter().map(Key::decode_from_conf).collect())
+			}
+			QueryKind::JsonPath(path) => c_json::QueryKind::JsonPath(
+				JsonPath::parse(&path).map_err(|e| FetcherConfigError::BadJsonPath(e, path))?,
+			),
+		})
+	}
+}
+
 impl Query {
-	pub fn decode_from_conf(self) -> c_json::Query {
-		c_json::Query {
-			keys: self.keys.into_iter().map(Key::decode_from_conf).collect(),
+	pub fn decode_from_conf(self) -> Result<c_json::Query, FetcherConfigError> {
+		Ok(c_json::Query {
+			kind: self.kind.decode_from_conf()?,
 			optional: self.optional.unwrap_or(false),
-		}
+		})
 	}
 }
 
 impl StringQuery {
 	pub fn decode_from_conf(self) -> Result<c_json::StringQuery, FetcherConfigError> {
 		Ok(c_json::StringQuery {
-			query: self.query.decode_from_conf(),
+			query: self.query.decode_from_conf()?,
 			regex: self.regex.try_map(JsonQueryRegex::decode_from_conf)?,
 		})
 	}
 }
 
+impl ItemFilter {
+	pub fn decode_from_conf(self) -> Result<c_json::ItemFilter, FetcherConfigError> {
+		let condition = match (self.equals, self.contains) {
+			(None, None) => c_json::ItemCondition::Present,
+			(Some(s), None) => c_json::ItemCondition::Equals(s),
+			(None, Some(s)) => c_json::ItemCondition::Contains(s),
+			(Some(_), Some(_)) => return Err(FetcherConfigError::JsonItemFilterConflict),
+		};
+
+		Ok(c_json::ItemFilter {
+			query: self.query.decode_from_conf()?,
+			condition,
+		})
+	}
+}
+
 impl JsonQueryRegex {
 	pub fn decode_from_conf(self) -> Result<CReplace, FetcherConfigError> {
 		CReplace::new(&self.re, self.replace_with).map_err(Into::into)
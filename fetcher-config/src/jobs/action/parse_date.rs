@@ -0,0 +1,40 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use fetcher_core::action::transform::{
+	Transform as CTransform,
+	field::{ParseDate as CParseDate, TransformFieldWrapper as CTransformFieldWrapper},
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_with::{OneOrMany, serde_as};
+
+use super::Field;
+
+#[serde_as]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ParseDate {
+	pub in_field: Field,
+	#[serde_as(deserialize_as = "Option<OneOrMany<_>>")]
+	#[serde(default)]
+	pub formats: Option<Vec<String>>,
+	pub relative: Option<bool>,
+}
+
+impl ParseDate {
+	#[must_use]
+	pub fn decode_from_conf(self) -> impl CTransform {
+		CTransformFieldWrapper {
+			field: self.in_field.decode_from_conf(),
+			transformator: CParseDate {
+				formats: self.formats.unwrap_or_default(),
+				relative: self.relative.unwrap_or(true),
+			},
+		}
+	}
+}
@@ -15,12 +15,13 @@ use fetcher_core::action::{
 };
 
 use itertools::process_results;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::{OneOrMany, serde_as};
 
 // Remove HTML tags and trim any remaining whitespace
 #[serde_as]
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 pub struct RemoveHtml {
 	#[serde_as(deserialize_as = "OneOrMany<_>")]
 	pub r#in: Vec<Field>,
@@ -45,7 +46,7 @@ fn remove_html_action_for(field: Field) -> Result<[CAction; 2], ConfigError> {
 
 	let trim = CAction::Transform(Box::new(CTransformFieldWrapper {
 		field: field.decode_from_conf(),
-		transformator: CTrim,
+		transformator: CTrim::default(),
 	}));
 
 	Ok([remove_html, trim])
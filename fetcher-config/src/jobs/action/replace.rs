@@ -10,23 +10,31 @@ use fetcher_core::action::transform::{
 	field::{Replace as CReplace, TransformFieldWrapper as CTransformFieldWrapper},
 };
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::Field;
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct Replace {
 	pub re: String,
 	pub in_field: Field,
 	pub with: String,
+	pub regex: Option<bool>,
 }
 
 impl Replace {
 	pub fn decode_from_conf(self) -> Result<impl CTransform, ConfigError> {
+		let transformator = if self.regex.unwrap_or(true) {
+			CReplace::new(&self.re, self.with)?
+		} else {
+			CReplace::new_literal(&self.re, &self.with)
+		};
+
 		Ok(CTransformFieldWrapper {
 			field: self.in_field.decode_from_conf(),
-			transformator: CReplace::new(&self.re, self.with)?,
+			transformator,
 		})
 	}
 }
@@ -4,36 +4,111 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use std::collections::HashMap;
-
 use super::Field;
+use crate::FetcherConfigError;
 use fetcher_core::action::Action as CAction;
 use fetcher_core::action::transform::{
-	field::TransformFieldWrapper as CTransformFieldWrapper, field::set::Set as CSet,
+	field::set::{Set as CSet, SetFields as CSetFields},
+	template::Template as CTemplate,
 };
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::{OneOrMany, serde_as};
+use std::collections::HashMap;
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(transparent)]
 pub struct Set(pub HashMap<Field, Option<Values>>);
 
 impl Set {
-	#[must_use]
-	pub fn decode_from_conf(self) -> Vec<CAction> {
-		self.0
+	/// `{tag}` in the templates is replaced with the `tag` of the task
+	pub fn decode_from_conf(self, tag: Option<&str>) -> Result<CAction, FetcherConfigError> {
+		let fields = self
+			.0
 			.into_iter()
 			.map(|(field, values)| {
-				CAction::Transform(Box::new(CTransformFieldWrapper {
-					field: field.decode_from_conf(),
-					transformator: CSet(values.map(|x| x.0)),
-				}))
+				let templates = values
+					.map(|x| {
+						x.0.into_iter()
+							.map(|value| value.decode_from_conf(tag))
+							.collect::<Result<Vec<_>, _>>()
+					})
+					.transpose()?;
+
+				Ok((field.decode_from_conf(), CSet(templates)))
 			})
-			.collect()
+			.collect::<Result<_, FetcherConfigError>>()?;
+
+		Ok(CAction::Transform(Box::new(CSetFields(fields))))
 	}
 }
 
 #[serde_as]
-#[derive(Deserialize, Serialize, Clone, Debug)]
-pub struct Values(#[serde_as(deserialize_as = "OneOrMany<_>")] pub Vec<String>);
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+pub struct Values(#[serde_as(deserialize_as = "OneOrMany<_>")] pub Vec<Value>);
+
+/// A value is used as is, unless it's explicitly marked as a template
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum Value {
+	Literal(String),
+	Template { template: String },
+}
+
+impl Value {
+	pub fn decode_from_conf(self, tag: Option<&str>) -> Result<CTemplate, FetcherConfigError> {
+		Ok(match self {
+			Value::Literal(literal) => CTemplate::literal(literal),
+			Value::Template { template } => CTemplate::parse(&template)?.with_tag(tag),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+
+	use super::*;
+
+	use fetcher_core::{entry::Entry, sink::message::Message};
+
+	#[test]
+	fn literal_and_template_values() {
+		let values: Values = serde_json::from_value(serde_json::json!([
+			"{ not a template }",
+			{ "template": "[{tag}] {title}" },
+		]))
+		.unwrap();
+
+		let entry = Entry {
+			msg: Message {
+				title: Some("Hello".to_owned()),
+				..Default::default()
+			},
+			..Default::default()
+		};
+
+		let rendered = values
+			.0
+			.into_iter()
+			.map(|value| {
+				value
+					.decode_from_conf(Some("news"))
+					.unwrap()
+					.render_entry(&entry)
+			})
+			.collect::<Vec<_>>();
+
+		assert_eq!(rendered, ["{ not a template }", "[news] Hello"]);
+	}
+
+	#[test]
+	fn bad_template() {
+		let value = Value::Template {
+			template: "{author}".to_owned(),
+		};
+
+		assert!(value.decode_from_conf(None).is_err());
+	}
+}
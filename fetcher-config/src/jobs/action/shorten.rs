@@ -8,16 +8,25 @@ use super::Field;
 use fetcher_core::action::{
 	Action as CAction,
 	transform::field::{
-		TransformFieldWrapper as CTransformFieldWrapper, shorten::Shorten as CShorten,
+		TransformFieldWrapper as CTransformFieldWrapper,
+		shorten::{DEFAULT_SUFFIX, Shorten as CShorten},
 	},
 };
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(transparent)]
-pub struct Shorten(pub HashMap<Field, usize>);
+pub struct Shorten(pub HashMap<Field, Len>);
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum Len {
+	Len(usize),
+	WithSuffix { len: usize, suffix: Option<String> },
+}
 
 impl Shorten {
 	#[must_use]
@@ -25,9 +34,17 @@ impl Shorten {
 		self.0
 			.into_iter()
 			.map(|(field, len)| {
+				let (len, suffix) = match len {
+					Len::Len(len) => (len, None),
+					Len::WithSuffix { len, suffix } => (len, suffix),
+				};
+
 				CAction::Transform(Box::new(CTransformFieldWrapper {
 					field: field.decode_from_conf(),
-					transformator: CShorten { len },
+					transformator: CShorten {
+						len,
+						suffix: suffix.unwrap_or_else(|| DEFAULT_SUFFIX.to_owned()),
+					},
 				}))
 			})
 			.collect()
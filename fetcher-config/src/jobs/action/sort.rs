@@ -0,0 +1,72 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::Field;
+use fetcher_core::action::filter::sort::{
+	Sort as CSort, SortBy as CSortBy, SortOrder as CSortOrder,
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Sort {
+	pub field: Field,
+	pub by: Option<SortBy>,
+	pub order: Option<SortOrder>,
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum SortBy {
+	Value,
+	Length,
+	Date,
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum SortOrder {
+	Ascending,
+	Descending,
+}
+
+impl Sort {
+	#[must_use]
+	pub fn decode_from_conf(self) -> CSort {
+		CSort {
+			field: self.field.decode_from_conf(),
+			by: self
+				.by
+				.map_or_else(CSortBy::default, SortBy::decode_from_conf),
+			order: self
+				.order
+				.map_or_else(CSortOrder::default, SortOrder::decode_from_conf),
+		}
+	}
+}
+
+impl SortBy {
+	#[must_use]
+	pub fn decode_from_conf(self) -> CSortBy {
+		match self {
+			SortBy::Value => CSortBy::Value,
+			SortBy::Length => CSortBy::Length,
+			SortBy::Date => CSortBy::Date,
+		}
+	}
+}
+
+impl SortOrder {
+	#[must_use]
+	pub fn decode_from_conf(self) -> CSortOrder {
+		match self {
+			SortOrder::Ascending => CSortOrder::Ascending,
+			SortOrder::Descending => CSortOrder::Descending,
+		}
+	}
+}
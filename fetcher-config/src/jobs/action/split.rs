@@ -0,0 +1,32 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::Field;
+use crate::error::FetcherConfigError as ConfigError;
+use fetcher_core::action::transform::entry::split::Split as CSplit;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Split {
+	pub re: String,
+	pub in_field: Field,
+	pub regex: Option<bool>,
+}
+
+impl Split {
+	pub fn decode_from_conf(self) -> Result<CSplit, ConfigError> {
+		let field = self.in_field.decode_from_conf();
+
+		Ok(if self.regex.unwrap_or(true) {
+			CSplit::new(field, &self.re)?
+		} else {
+			CSplit::new_literal(field, &self.re)
+		})
+	}
+}
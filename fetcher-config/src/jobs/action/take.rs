@@ -4,34 +4,49 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use fetcher_core::action::filter::take::{Take as CTake, TakeFrom as CTakeFrom};
+use crate::FetcherConfigError as ConfigError;
+use fetcher_core::action::filter::take::{
+	Take as CTake, TakeAmount as CTakeAmount, TakeFrom as CTakeFrom,
+};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(transparent)]
-pub struct Take(#[serde(with = "crate::serde_extentions::tuple")] pub Inner);
+pub struct Take(
+	#[serde(with = "crate::serde_extentions::tuple")]
+	#[schemars(with = "std::collections::HashMap<TakeWhich, TakeNum>")]
+	pub Inner,
+);
 
 #[derive(Clone, Debug)]
 pub struct Inner {
 	pub which: TakeWhich,
-	pub num: usize,
+	pub num: TakeNum,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(rename_all = "snake_case", deny_unknown_fields)]
 pub enum TakeWhich {
 	FromNewest,
 	FromOldest,
 }
 
+/// Either an exact number of entries, e.g. `5`, or a percentage of them, e.g. `50%`
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum TakeNum {
+	Count(usize),
+	Percent(String),
+}
+
 impl Take {
-	#[must_use]
-	pub fn decode_from_conf(self) -> CTake {
-		CTake {
+	pub fn decode_from_conf(self) -> Result<CTake, ConfigError> {
+		Ok(CTake {
 			from: self.0.which.decode_from_conf(),
-			num: self.0.num,
-		}
+			amount: self.0.num.decode_from_conf()?,
+		})
 	}
 }
 
@@ -45,14 +60,28 @@ impl TakeWhich {
 	}
 }
 
-impl<'a> From<&'a Inner> for (&'a TakeWhich, &'a usize) {
+impl TakeNum {
+	pub fn decode_from_conf(self) -> Result<CTakeAmount, ConfigError> {
+		match self {
+			TakeNum::Count(num) => Ok(CTakeAmount::Count(num)),
+			TakeNum::Percent(s) => s
+				.strip_suffix('%')
+				.and_then(|percent| percent.trim().parse::<u8>().ok())
+				.filter(|&percent| percent <= 100)
+				.map(CTakeAmount::Percent)
+				.ok_or(ConfigError::BadTakePercentage(s)),
+		}
+	}
+}
+
+impl<'a> From<&'a Inner> for (&'a TakeWhich, &'a TakeNum) {
 	fn from(Inner { which, num }: &'a Inner) -> Self {
 		(which, num)
 	}
 }
 
-impl From<(TakeWhich, usize)> for Inner {
-	fn from((which, num): (TakeWhich, usize)) -> Self {
+impl From<(TakeWhich, TakeNum)> for Inner {
+	fn from((which, num): (TakeWhich, TakeNum)) -> Self {
 		Self { which, num }
 	}
 }
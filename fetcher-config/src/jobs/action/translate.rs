@@ -0,0 +1,48 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::Field;
+use crate::FetcherConfigError as ConfigError;
+use fetcher_core::action::transform::entry::translate::{
+	DeepL as CDeepL, LibreTranslate as CLibreTranslate, Translate as CTranslate,
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_with::{OneOrMany, serde_as};
+use url::Url;
+
+#[serde_as]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Translate {
+	pub to: String,
+	#[serde_as(deserialize_as = "Option<OneOrMany<_>>")]
+	#[serde(default)]
+	pub r#in: Option<Vec<Field>>,
+	pub with: Backend,
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Backend {
+	Deepl { api_key: String },
+	LibreTranslate { url: Url, api_key: Option<String> },
+}
+
+impl Translate {
+	pub fn decode_from_conf(self) -> Result<CTranslate, ConfigError> {
+		let fields = self.r#in.unwrap_or_else(|| vec![Field::Title, Field::Body]);
+		let fields = fields.into_iter().map(Field::decode_from_conf).collect();
+
+		Ok(match self.with {
+			Backend::Deepl { api_key } => CTranslate::new(CDeepL::new(api_key)?, self.to, fields),
+			Backend::LibreTranslate { url, api_key } => {
+				CTranslate::new(CLibreTranslate::new(url, api_key)?, self.to, fields)
+			}
+		})
+	}
+}
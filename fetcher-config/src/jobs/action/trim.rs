@@ -7,23 +7,64 @@
 use super::Field;
 use fetcher_core::action::transform::{
 	Transform as CTransform,
-	field::{TransformFieldWrapper as CTransformFieldWrapper, trim::Trim as CTrim},
+	field::{
+		TransformFieldWrapper as CTransformFieldWrapper,
+		trim::{Trim as CTrim, TrimMode as CTrimMode},
+	},
 };
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
-#[serde(transparent)]
-pub struct Trim {
+// treat trim: field the same as trim: {field: field, mode: lines}
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum Trim {
+	Untagged(Field),
+	Tagged(TaggedTrim),
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TaggedTrim {
 	pub field: Field,
+	pub mode: Option<TrimMode>,
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum TrimMode {
+	Lines,
+	CollapseWhitespace,
+	Chars(String),
+	ZeroWidth,
 }
 
 impl Trim {
 	#[must_use]
 	pub fn decode_from_conf(self) -> impl CTransform {
+		let (field, mode) = match self {
+			Self::Untagged(field) => (field, None),
+			Self::Tagged(TaggedTrim { field, mode }) => (field, mode),
+		};
+
 		CTransformFieldWrapper {
-			field: self.field.decode_from_conf(),
-			transformator: CTrim,
+			field: field.decode_from_conf(),
+			transformator: CTrim {
+				mode: mode.map(TrimMode::decode_from_conf).unwrap_or_default(),
+			},
+		}
+	}
+}
+
+impl TrimMode {
+	#[must_use]
+	pub fn decode_from_conf(self) -> CTrimMode {
+		match self {
+			TrimMode::Lines => CTrimMode::Lines,
+			TrimMode::CollapseWhitespace => CTrimMode::CollapseWhitespace,
+			TrimMode::Chars(chars) => CTrimMode::Chars(chars.chars().collect()),
+			TrimMode::ZeroWidth => CTrimMode::ZeroWidth,
 		}
 	}
 }
@@ -7,29 +7,58 @@
 use super::Field;
 use fetcher_core::action::{Action as CAction, transform::Use as CUse};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
-#[serde(transparent)]
-pub struct Use(pub HashMap<Field, As>);
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum Use {
+	FromTo(FromTo),
+	Map(HashMap<Field, As>),
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct FromTo {
+	pub from: Field,
+	pub to: Field,
+	pub clear: Option<bool>,
+	pub overwrite: Option<bool>,
+}
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 pub struct As {
 	pub r#as: Field,
+	pub clear: Option<bool>,
+	pub overwrite: Option<bool>,
 }
 
 impl Use {
 	#[must_use]
 	pub fn decode_from_conf(self) -> Vec<CAction> {
-		self.0
-			.into_iter()
-			.map(|(field, as_field)| {
-				CAction::Transform(Box::new(CUse {
-					field: field.decode_from_conf(),
-					as_field: as_field.r#as.decode_from_conf(),
-				}))
-			})
-			.collect()
+		match self {
+			Self::FromTo(FromTo {
+				from,
+				to,
+				clear,
+				overwrite,
+			}) => vec![decode(from, to, clear, overwrite)],
+			Self::Map(map) => map
+				.into_iter()
+				.map(|(field, as_field)| {
+					decode(field, as_field.r#as, as_field.clear, as_field.overwrite)
+				})
+				.collect(),
+		}
 	}
 }
+
+fn decode(from: Field, to: Field, clear: Option<bool>, overwrite: Option<bool>) -> CAction {
+	CAction::Transform(Box::new(CUse {
+		field: from.decode_from_conf(),
+		as_field: to.decode_from_conf(),
+		clear: clear.unwrap_or(false),
+		overwrite: overwrite.unwrap_or(true),
+	}))
+}
@@ -0,0 +1,55 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::{Action, contains::Contains};
+use crate::{FetcherConfigError, jobs::external_data::ProvideExternalData};
+use fetcher_core::{
+	action::{Action as CAction, when::When as CWhen},
+	read_filter::ReadFilter as CReadFilter,
+};
+
+use itertools::process_results;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct When {
+	pub condition: Contains,
+	pub then: Vec<Action>,
+}
+
+impl When {
+	#[allow(clippy::needless_pass_by_value)]
+	pub fn decode_from_conf<RF, D>(
+		self,
+		rf: Option<Arc<RwLock<RF>>>,
+		tag: Option<&str>,
+		external: &D,
+	) -> Result<CAction, FetcherConfigError>
+	where
+		RF: CReadFilter + 'static,
+		D: ProvideExternalData + ?Sized,
+	{
+		let then = process_results(
+			self.then
+				.into_iter()
+				.map(|act| act.decode_from_conf(rf.clone(), tag, external)),
+			|i| i.flatten(/* option */).flatten(/* inner vec */).collect::<Vec<_>>(),
+		)?;
+
+		if then.iter().any(|act| matches!(act, CAction::Sink(_))) {
+			return Err(FetcherConfigError::WhenWithSink);
+		}
+
+		Ok(CAction::Transform(Box::new(CWhen {
+			condition: self.condition.decode_conditions()?,
+			then,
+		})))
+	}
+}
@@ -0,0 +1,84 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::json::JsonQueryRegex;
+use crate::FetcherConfigError;
+use fetcher_core::{
+	action::transform::entry::xml::{self as c_xml, Xml as CXml},
+	utils::OptionExt,
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Xml {
+	pub item: Option<Query>,
+	pub title: Option<StringQuery>,
+	pub text: Option<Vec<StringQuery>>,
+	pub id: Option<StringQuery>,
+	pub link: Option<StringQuery>,
+	pub img: Option<Vec<StringQuery>>,
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Query {
+	#[serde(rename = "query")]
+	pub xpath: String,
+	pub optional: Option<bool>,
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct StringQuery {
+	#[serde(flatten)]
+	pub query: Query,
+	pub regex: Option<JsonQueryRegex>,
+}
+
+impl Xml {
+	pub fn decode_from_conf(self) -> Result<CXml, FetcherConfigError> {
+		Ok(CXml {
+			item: self.item.try_map(Query::decode_from_conf)?,
+			title: self.title.try_map(StringQuery::decode_from_conf)?,
+
+			text: self.text.try_map(|v| {
+				v.into_iter()
+					.map(StringQuery::decode_from_conf)
+					.collect::<Result<_, _>>()
+			})?,
+
+			id: self.id.try_map(StringQuery::decode_from_conf)?,
+			link: self.link.try_map(StringQuery::decode_from_conf)?,
+
+			img: self.img.try_map(|v| {
+				v.into_iter()
+					.map(StringQuery::decode_from_conf)
+					.collect::<Result<_, _>>()
+			})?,
+		})
+	}
+}
+
+impl Query {
+	pub fn decode_from_conf(self) -> Result<c_xml::Query, FetcherConfigError> {
+		Ok(c_xml::Query::new(
+			self.xpath,
+			self.optional.unwrap_or(false),
+		)?)
+	}
+}
+
+impl StringQuery {
+	pub fn decode_from_conf(self) -> Result<c_xml::StringQuery, FetcherConfigError> {
+		Ok(c_xml::StringQuery {
+			query: self.query.decode_from_conf()?,
+			regex: self.regex.try_map(JsonQueryRegex::decode_from_conf)?,
+		})
+	}
+}
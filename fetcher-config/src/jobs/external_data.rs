@@ -4,13 +4,20 @@
  * file, you can obtain one at https://mozilla.org/mpl/2.0/.
  */
 
+pub mod env;
+
+pub use self::env::EnvExternalData;
+
 use super::{
 	action::Action,
 	named::{JobName, TaskName},
 	read_filter::Kind as ReadFilterKind,
 };
 use fetcher_core::{
-	auth as c_auth, read_filter::ReadFilter as CReadFilter, task::entry_to_msg_map::EntryToMsgMap,
+	auth as c_auth,
+	read_filter::{ReadFilter as CReadFilter, ReadListLimits as CReadListLimits},
+	source::{gemini::KnownHosts as CKnownHosts, reddit::RedditAuth as CRedditAuth},
+	task::{dead_letters::SaveDeadLetter, entry_to_msg_map::EntryToMsgMap},
 	utils::DisplayDebug,
 };
 
@@ -19,7 +26,10 @@ use std::{
 	fmt::{Debug, Display},
 	io,
 	path::Path,
+	sync::Arc,
 };
+use tokio::sync::Mutex;
+use url::Url;
 
 pub enum ExternalDataResult<T, E = ExternalDataError> {
 	Ok(T),
@@ -30,7 +40,10 @@ pub enum ExternalDataResult<T, E = ExternalDataError> {
 pub trait ProvideExternalData {
 	type ReadFilter: CReadFilter + 'static;
 
-	fn google_oauth2(&self) -> ExternalDataResult<c_auth::Google> {
+	fn google_oauth2(&self) -> ExternalDataResult<Arc<Mutex<c_auth::Google>>> {
+		ExternalDataResult::Unavailable
+	}
+	fn microsoft_oauth2(&self) -> ExternalDataResult<Arc<Mutex<c_auth::Microsoft>>> {
 		ExternalDataResult::Unavailable
 	}
 	fn email_password(&self) -> ExternalDataResult<String> {
@@ -42,12 +55,24 @@ pub trait ProvideExternalData {
 	fn discord_bot_token(&self) -> ExternalDataResult<String> {
 		ExternalDataResult::Unavailable
 	}
+	/// webhook URL for slack sinks without one of their own
+	fn slack_webhook_url(&self) -> ExternalDataResult<Url> {
+		ExternalDataResult::Unavailable
+	}
+	fn reddit_auth(&self) -> ExternalDataResult<CRedditAuth> {
+		ExternalDataResult::Unavailable
+	}
+	/// known hosts shared between all gemini sources
+	fn gemini_known_hosts(&self) -> ExternalDataResult<Arc<Mutex<CKnownHosts>>> {
+		ExternalDataResult::Unavailable
+	}
 
 	fn read_filter(
 		&self,
 		_job: &JobName,
 		_task: Option<&TaskName>,
 		_expected_rf: ReadFilterKind,
+		_read_list_limits: CReadListLimits,
 	) -> ExternalDataResult<Self::ReadFilter> {
 		ExternalDataResult::Unavailable
 	}
@@ -60,6 +85,14 @@ pub trait ProvideExternalData {
 		ExternalDataResult::Unavailable
 	}
 
+	fn dead_letters(
+		&self,
+		_job: &JobName,
+		_task: Option<&TaskName>,
+	) -> ExternalDataResult<Box<dyn SaveDeadLetter>> {
+		ExternalDataResult::Unavailable
+	}
+
 	/// import action `name`
 	fn import(&self, _name: &str) -> ExternalDataResult<Vec<Action>> {
 		ExternalDataResult::Unavailable
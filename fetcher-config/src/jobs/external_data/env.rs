@@ -0,0 +1,221 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains [`EnvExternalData`] that provides external data from environment variables
+
+use std::{
+	env::{self, VarError},
+	io,
+	sync::Arc,
+};
+use tokio::sync::Mutex;
+use url::Url;
+
+use super::{ExternalDataError, ExternalDataResult, ProvideExternalData};
+use crate::jobs::{
+	named::{JobName, TaskName},
+	read_filter::Kind as ReadFilterKind,
+};
+use fetcher_core::{
+	auth as c_auth,
+	read_filter::{
+		Newer as CNewer, NotPresent as CNotPresent, ReadFilter as CReadFilter,
+		ReadListLimits as CReadListLimits,
+	},
+	source::reddit::RedditAuth as CRedditAuth,
+};
+
+pub const GOOGLE_CLIENT_ID: &str = "FETCHER_GOOGLE_CLIENT_ID";
+pub const GOOGLE_CLIENT_SECRET: &str = "FETCHER_GOOGLE_CLIENT_SECRET";
+pub const GOOGLE_REFRESH_TOKEN: &str = "FETCHER_GOOGLE_REFRESH_TOKEN";
+pub const MICROSOFT_CLIENT_ID: &str = "FETCHER_MICROSOFT_CLIENT_ID";
+pub const MICROSOFT_CLIENT_SECRET: &str = "FETCHER_MICROSOFT_CLIENT_SECRET";
+pub const MICROSOFT_REFRESH_TOKEN: &str = "FETCHER_MICROSOFT_REFRESH_TOKEN";
+pub const EMAIL_PASSWORD: &str = "FETCHER_EMAIL_PASSWORD";
+pub const TELEGRAM_TOKEN: &str = "FETCHER_TELEGRAM_TOKEN";
+pub const DISCORD_TOKEN: &str = "FETCHER_DISCORD_TOKEN";
+pub const SLACK_WEBHOOK_URL: &str = "FETCHER_SLACK_WEBHOOK_URL";
+pub const REDDIT_CLIENT_ID: &str = "FETCHER_REDDIT_CLIENT_ID";
+pub const REDDIT_CLIENT_SECRET: &str = "FETCHER_REDDIT_CLIENT_SECRET";
+pub const REDDIT_USERNAME: &str = "FETCHER_REDDIT_USERNAME";
+pub const REDDIT_PASSWORD: &str = "FETCHER_REDDIT_PASSWORD";
+
+/// Provides login credentials from the environment variables above, e.g. [`TELEGRAM_TOKEN`], for when there's no data directory to keep them in.
+///
+/// Credentials, whose environment variables aren't set or are empty, are unavailable.
+/// A Microsoft refresh token that has been replaced with a new one is kept only in memory.
+/// Read filters start out empty and are kept only in memory, thus everything is considered unread again after a restart.
+/// Entry to message maps, dead letters, and imports are unavailable
+#[derive(Clone, Copy, Default, Debug)]
+pub struct EnvExternalData;
+
+impl ProvideExternalData for EnvExternalData {
+	type ReadFilter = Box<dyn CReadFilter>;
+
+	fn google_oauth2(&self) -> ExternalDataResult<Arc<Mutex<c_auth::Google>>> {
+		let auth = || {
+			let (Some(client_id), Some(client_secret), Some(refresh_token)) = (
+				var(GOOGLE_CLIENT_ID)?,
+				var(GOOGLE_CLIENT_SECRET)?,
+				var(GOOGLE_REFRESH_TOKEN)?,
+			) else {
+				return Ok(None);
+			};
+
+			let auth = c_auth::Google::new(client_id, client_secret, refresh_token);
+			Ok(Some(Arc::new(Mutex::new(auth))))
+		};
+
+		to_external_data_result(auth())
+	}
+
+	fn microsoft_oauth2(&self) -> ExternalDataResult<Arc<Mutex<c_auth::Microsoft>>> {
+		let auth = || {
+			let (Some(client_id), Some(refresh_token)) =
+				(var(MICROSOFT_CLIENT_ID)?, var(MICROSOFT_REFRESH_TOKEN)?)
+			else {
+				return Ok(None);
+			};
+
+			// public clients don't have a client secret
+			let client_secret = var(MICROSOFT_CLIENT_SECRET)?;
+
+			let auth = c_auth::Microsoft::new(client_id, client_secret, refresh_token);
+			Ok(Some(Arc::new(Mutex::new(auth))))
+		};
+
+		to_external_data_result(auth())
+	}
+
+	fn email_password(&self) -> ExternalDataResult<String> {
+		to_external_data_result(var(EMAIL_PASSWORD))
+	}
+
+	fn telegram_bot_token(&self) -> ExternalDataResult<String> {
+		to_external_data_result(var(TELEGRAM_TOKEN))
+	}
+
+	fn discord_bot_token(&self) -> ExternalDataResult<String> {
+		to_external_data_result(var(DISCORD_TOKEN))
+	}
+
+	fn slack_webhook_url(&self) -> ExternalDataResult<Url> {
+		let url = || {
+			let Some(url) = var(SLACK_WEBHOOK_URL)? else {
+				return Ok(None);
+			};
+
+			// don't include the URL itself in the error, it's a secret
+			let url = Url::parse(&url).map_err(|e| ExternalDataError::Io {
+				source: io::Error::new(io::ErrorKind::InvalidData, e),
+				payload: Some(Box::new(format!(
+					"environment variable: {SLACK_WEBHOOK_URL}"
+				))),
+			})?;
+
+			Ok(Some(url))
+		};
+
+		to_external_data_result(url())
+	}
+
+	fn reddit_auth(&self) -> ExternalDataResult<CRedditAuth> {
+		let auth = || {
+			let (Some(client_id), Some(client_secret), Some(username), Some(password)) = (
+				var(REDDIT_CLIENT_ID)?,
+				var(REDDIT_CLIENT_SECRET)?,
+				var(REDDIT_USERNAME)?,
+				var(REDDIT_PASSWORD)?,
+			) else {
+				return Ok(None);
+			};
+
+			Ok(Some(CRedditAuth {
+				client_id,
+				client_secret,
+				username,
+				password,
+			}))
+		};
+
+		to_external_data_result(auth())
+	}
+
+	fn read_filter(
+		&self,
+		_job: &JobName,
+		_task: Option<&TaskName>,
+		expected_rf: ReadFilterKind,
+		read_list_limits: CReadListLimits,
+	) -> ExternalDataResult<Self::ReadFilter> {
+		match expected_rf {
+			ReadFilterKind::NewerThanRead => ExternalDataResult::Ok(Box::new(CNewer::new())),
+			ReadFilterKind::NotPresentInReadList => {
+				ExternalDataResult::Ok(Box::new(CNotPresent::with_limits(read_list_limits)))
+			}
+			ReadFilterKind::NotOlderThan => ExternalDataResult::Unavailable,
+		}
+	}
+}
+
+/// Get the value of the environment variable `name`, or None if it's not set or is empty
+fn var(name: &str) -> Result<Option<String>, ExternalDataError> {
+	non_empty(name, env::var(name))
+}
+
+/// Treat an unset and an empty environment variable `name` the same, as missing
+fn non_empty(
+	name: &str,
+	val: Result<String, VarError>,
+) -> Result<Option<String>, ExternalDataError> {
+	match val {
+		Ok(val) if !val.is_empty() => Ok(Some(val)),
+		Ok(_) | Err(VarError::NotPresent) => Ok(None),
+		Err(e @ VarError::NotUnicode(_)) => Err(ExternalDataError::Io {
+			source: io::Error::new(io::ErrorKind::InvalidData, e),
+			payload: Some(Box::new(format!("environment variable: {name}"))),
+		}),
+	}
+}
+
+fn to_external_data_result<T>(res: Result<Option<T>, ExternalDataError>) -> ExternalDataResult<T> {
+	match res {
+		Ok(Some(v)) => ExternalDataResult::Ok(v),
+		Ok(None) => ExternalDataResult::Unavailable,
+		Err(e) => ExternalDataResult::Err(e),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn unset_and_empty_are_unavailable() {
+		assert!(matches!(
+			to_external_data_result(var("FETCHER_TEST_UNSET_VARIABLE")),
+			ExternalDataResult::Unavailable
+		));
+
+		assert!(matches!(
+			to_external_data_result(non_empty(TELEGRAM_TOKEN, Err(VarError::NotPresent))),
+			ExternalDataResult::Unavailable
+		));
+
+		assert!(matches!(
+			to_external_data_result(non_empty(TELEGRAM_TOKEN, Ok(String::new()))),
+			ExternalDataResult::Unavailable
+		));
+	}
+
+	#[test]
+	fn set_is_ok() {
+		assert!(matches!(
+			to_external_data_result(non_empty(TELEGRAM_TOKEN, Ok("token".to_owned()))),
+			ExternalDataResult::Ok(token) if token == "token"
+		));
+	}
+}
@@ -4,11 +4,13 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+pub mod backoff;
+pub mod heartbeat;
 pub mod timepoint;
 
-use std::{collections::HashMap, ops::Not};
+use std::{collections::HashMap, path::PathBuf};
 
-use self::timepoint::TimePoint;
+use self::{backoff::Backoff, heartbeat::Heartbeat, timepoint::TimePoint};
 use super::{
 	action::Action,
 	external_data::ProvideExternalData,
@@ -16,37 +18,111 @@ use super::{
 	read_filter,
 	sink::Sink,
 	source::Source,
-	task::Task,
+	task::{Task, lint::TaskSettings},
 };
-use crate::FetcherConfigError;
+use crate::{FetcherConfigError, serde_extentions::exact_one_or_many::ExactOneOrMany};
 use fetcher_core::{job::Job as CJob, utils::OptionExt};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_with::{OneOrMany, serde_as};
 
 pub type DisabledField = Option<bool>;
 pub type TemplatesField = Option<Vec<String>>;
+pub type IncludeField = Option<Vec<PathBuf>>;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[serde_as]
+#[derive(Deserialize, JsonSchema, Serialize, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct Job {
 	#[serde(rename = "read_filter_type")]
-	pub read_filter_kind: Option<read_filter::Kind>,
+	#[serde_as(deserialize_as = "Option<OneOrMany<_>>")]
+	#[serde(default)]
+	pub read_filter_kind: Option<Vec<read_filter::Kind>>,
+	pub max_entry_age: Option<String>,
+	pub read_list: Option<read_filter::ReadListLimits>,
 	pub tag: Option<String>,
 	pub source: Option<Source>,
 	#[serde(rename = "process")]
 	pub actions: Option<Vec<Action>>,
 	pub entry_to_msg_map_enabled: Option<bool>,
-	pub sink: Option<Sink>,
+	pub update_in_place: Option<bool>,
+	pub delete_removed: Option<bool>,
+	pub timeout: Option<String>,
+	pub max_entries: Option<usize>,
+	pub websub: Option<bool>,
+	#[serde_as(as = "Option<ExactOneOrMany>")]
+	#[serde(default)]
+	pub sink: Option<Vec<Sink>>,
 
 	pub tasks: Option<HashMap<TaskName, Task>>,
 	pub refresh: Option<TimePoint>,
+	pub backoff: Option<Backoff>,
+	pub heartbeat: Option<Heartbeat>,
 
 	// these are meant to be used externally and are unused here
 	pub disabled: DisabledField,
 	pub templates: TemplatesField,
+	pub include: IncludeField,
 }
 
 impl Job {
+	/// JSON Schema of the job config format, for editors to validate and autocomplete job configs with
+	#[must_use]
+	pub fn schema() -> schemars::Schema {
+		schemars::schema_for!(Self)
+	}
+
+	/// Find settings of the job and its tasks that are valid on their own but don't make sense together.
+	/// Settings the tasks inherit from the job are taken into account
+	#[must_use]
+	pub fn lint(&self) -> Vec<String> {
+		match &self.tasks {
+			Some(tasks) if !tasks.is_empty() => tasks
+				.iter()
+				.filter(|(_, task)| !task.disabled.unwrap_or(false))
+				.flat_map(|(name, task)| {
+					let task = TaskSettings {
+						read_filter_kind: task
+							.read_filter_kind
+							.as_deref()
+							.or(self.read_filter_kind.as_deref()),
+						max_entry_age: task
+							.max_entry_age
+							.as_deref()
+							.or(self.max_entry_age.as_deref()),
+						has_read_list: task.read_list.is_some() || self.read_list.is_some(),
+						actions: task.actions.as_deref().or(self.actions.as_deref()),
+						entry_to_msg_map_enabled: task
+							.entry_to_msg_map_enabled
+							.or(self.entry_to_msg_map_enabled),
+						update_in_place: task.update_in_place.or(self.update_in_place),
+						delete_removed: task.delete_removed.or(self.delete_removed),
+						sink: task.sink.as_deref().or(self.sink.as_deref()),
+					};
+
+					task.lint()
+						.into_iter()
+						.map(move |problem| format!("task {name}: {problem}"))
+				})
+				.collect(),
+			_ => TaskSettings {
+				read_filter_kind: self.read_filter_kind.as_deref(),
+				max_entry_age: self.max_entry_age.as_deref(),
+				has_read_list: self.read_list.is_some(),
+				actions: self.actions.as_deref(),
+				entry_to_msg_map_enabled: self.entry_to_msg_map_enabled,
+				update_in_place: self.update_in_place,
+				delete_removed: self.delete_removed,
+				sink: self.sink.as_deref(),
+			}
+			.lint()
+			.into_iter()
+			.map(ToOwned::to_owned)
+			.collect(),
+		}
+	}
+
 	pub fn decode_from_conf<D>(
 		mut self,
 		name: JobName,
@@ -63,23 +139,54 @@ impl Job {
 			_ => {
 				// copy paste all values from the job to a dummy task, i.e. create a single task with all the values from the job
 				let task = Task {
+					disabled: None,
 					read_filter_kind: self.read_filter_kind,
+					max_entry_age: self.max_entry_age,
+					read_list: self.read_list,
 					tag: self.tag,
 					source: self.source,
 					actions: self.actions,
 					entry_to_msg_map_enabled: self.entry_to_msg_map_enabled,
+					update_in_place: self.update_in_place,
+					delete_removed: self.delete_removed,
+					timeout: self.timeout,
+					max_entries: self.max_entries,
+					// the job's refresh time is used for the whole job instead
+					refresh: None,
+					websub: self.websub,
 					sink: self.sink,
 				};
 
+				let websub_feeds = task
+					.websub_feed()?
+					.map(|url| (0, url))
+					.into_iter()
+					.collect();
+
 				let job = CJob {
+					name: Some(name.as_str().to_owned()),
 					tasks: vec![task.decode_from_conf(&name, None, external)?],
 					refresh_time: self.refresh.try_map(TimePoint::decode_from_conf)?,
+					backoff: self
+						.backoff
+						.try_map(Backoff::decode_from_conf)?
+						.unwrap_or_default(),
+					task_limit: None,
+					heartbeat: self
+						.heartbeat
+						.map(Heartbeat::decode_from_conf)
+						.unwrap_or_default(),
+					trigger: None,
 				};
 
-				Ok((name, JobWithTaskNames {
-					inner: job,
-					task_names: None,
-				}))
+				Ok((
+					name,
+					JobWithTaskNames {
+						inner: job,
+						task_names: None,
+						websub_feeds,
+					},
+				))
 			}
 		}
 	}
@@ -96,9 +203,28 @@ impl Job {
 	{
 		tracing::trace!("Parsing job {name:?} with tasks {tasks:#?}");
 
+		tasks.retain(|task_name, task| {
+			let disabled = task.disabled.unwrap_or(false);
+			if disabled {
+				tracing::info!("Task {task_name} of job {name} is disabled, skipping...");
+			}
+
+			!disabled
+		});
+
 		// append values from the job if they are not present in the tasks
 		for task in tasks.values_mut() {
-			task.read_filter_kind = task.read_filter_kind.or(self.read_filter_kind);
+			if task.read_filter_kind.is_none() {
+				task.read_filter_kind.clone_from(&self.read_filter_kind);
+			}
+
+			if task.max_entry_age.is_none() {
+				task.max_entry_age.clone_from(&self.max_entry_age);
+			}
+
+			if task.read_list.is_none() {
+				task.read_list.clone_from(&self.read_list);
+			}
 
 			if task.tag.is_none() {
 				task.tag.clone_from(&self.tag);
@@ -116,34 +242,44 @@ impl Job {
 				task.entry_to_msg_map_enabled = self.entry_to_msg_map_enabled;
 			}
 
-			if task.sink.is_none() {
-				task.sink.clone_from(&self.sink);
+			if task.update_in_place.is_none() {
+				task.update_in_place = self.update_in_place;
 			}
-		}
 
-		// FIXME: broken. Filtering can remove tasks from the tasks map. Then, when checking if we should pass the task name as a tag, we ignore the fact that we could've had more tasks in the job and skip the tag which we shouldn't do
-		// TODO: add disabled field to the task and enable that instead of removing tasks when filtering outright. Then the code below will work again
-		/*
-		// NOTE: if the tasks map only has one task, don't provide the task its name to avoid it automatically adding a tag
-		// a tag should only be automatically added if there are more then 1 task in the tasks map
-		let single_task = tasks.len() == 1;
+			if task.delete_removed.is_none() {
+				task.delete_removed = self.delete_removed;
+			}
+
+			if task.timeout.is_none() {
+				task.timeout.clone_from(&self.timeout);
+			}
 
-		if single_task {
-			tracing::trace!("Not setting task name as tag in a tasks map with a single element");
+			if task.max_entries.is_none() {
+				task.max_entries = self.max_entries;
+			}
+
+			if task.websub.is_none() {
+				task.websub = self.websub;
+			}
+
+			if task.sink.is_none() {
+				task.sink.clone_from(&self.sink);
+			}
 		}
-		*/
-		let single_task = false; // remove when above is fixed
 
+		let mut websub_feeds = HashMap::new();
 		let tasks_and_task_name_map_iter =
 			tasks
 				.into_iter()
 				.enumerate()
 				.map(|(id, (task_name, task))| {
-					let task = task.decode_from_conf(
-						&name,
-						single_task.not().then_some(&task_name),
-						external,
-					)?;
+					if let Some(feed) = task.websub_feed()? {
+						websub_feeds.insert(id, feed);
+					}
+
+					// always pass the name, even if there is only a single task in the map, e.g. when the rest are disabled.
+					// The read filter and the other saved state of the task are saved under it and it's used as the tag
+					let task = task.decode_from_conf(&name, Some(&task_name), external)?;
 
 					Ok::<_, FetcherConfigError>((task, (id, task_name)))
 				});
@@ -154,13 +290,28 @@ impl Job {
 			itertools::process_results(tasks_and_task_name_map_iter, |iter| iter.unzip())?;
 
 		let job = CJob {
+			name: Some(name.as_str().to_owned()),
 			tasks,
 			refresh_time: self.refresh.try_map(TimePoint::decode_from_conf)?,
+			backoff: self
+				.backoff
+				.try_map(Backoff::decode_from_conf)?
+				.unwrap_or_default(),
+			task_limit: None,
+			heartbeat: self
+				.heartbeat
+				.map(Heartbeat::decode_from_conf)
+				.unwrap_or_default(),
+			trigger: None,
 		};
 
-		Ok((name, JobWithTaskNames {
-			inner: job,
-			task_names: Some(task_names),
-		}))
+		Ok((
+			name,
+			JobWithTaskNames {
+				inner: job,
+				task_names: Some(task_names),
+				websub_feeds,
+			},
+		))
 	}
 }
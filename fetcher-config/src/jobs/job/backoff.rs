@@ -0,0 +1,35 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::FetcherConfigError;
+use fetcher_core::job::backoff::Backoff as CBackoff;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, JsonSchema, Serialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Backoff {
+	pub base: Option<String>,
+	pub max: Option<String>,
+}
+
+impl Backoff {
+	pub fn decode_from_conf(self) -> Result<CBackoff, FetcherConfigError> {
+		let default = CBackoff::default();
+
+		let parse = |dur: Option<String>| {
+			dur.map(duration_str::parse_std)
+				.transpose()
+				.map_err(FetcherConfigError::BadBackoffDuration)
+		};
+
+		Ok(CBackoff {
+			base: parse(self.base)?.unwrap_or(default.base),
+			max: parse(self.max)?.unwrap_or(default.max),
+		})
+	}
+}
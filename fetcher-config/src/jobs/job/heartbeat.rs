@@ -0,0 +1,28 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use fetcher_core::job::heartbeat::Heartbeat as CHeartbeat;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+#[derive(Deserialize, JsonSchema, Serialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Heartbeat {
+	pub success: Option<Url>,
+	pub failure: Option<Url>,
+}
+
+impl Heartbeat {
+	#[must_use]
+	pub fn decode_from_conf(self) -> CHeartbeat {
+		CHeartbeat {
+			success_url: self.success,
+			failure_url: self.failure,
+		}
+	}
+}
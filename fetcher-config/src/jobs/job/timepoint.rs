@@ -7,13 +7,15 @@
 use crate::FetcherConfigError;
 use fetcher_core::job::timepoint::TimePoint as CTimePoint;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum TimePoint {
 	Every(String),
 	At(String),
+	Cron(String),
 }
 
 impl TimePoint {
@@ -26,6 +28,20 @@ impl TimePoint {
 				let time = chrono::NaiveTime::parse_from_str(&at, "%H:%M")?;
 				CTimePoint::Time(time)
 			}
+			TimePoint::Cron(cron) => {
+				// support the common 5 field format without seconds, too
+				let full_cron = if cron.split_whitespace().count() == 5 {
+					format!("0 {cron}")
+				} else {
+					cron.clone()
+				};
+
+				let schedule = full_cron
+					.parse()
+					.map_err(|e| FetcherConfigError::BadCronSchedule(e, cron))?;
+
+				CTimePoint::Cron(Box::new(schedule))
+			}
 		})
 	}
 }
@@ -15,9 +15,13 @@ pub use self::{
 use fetcher_core::job::Job;
 
 use std::collections::HashMap;
+use url::Url;
 
 #[derive(Debug)]
 pub struct JobWithTaskNames {
 	pub inner: Job,
 	pub task_names: Option<HashMap<usize, TaskName>>,
+
+	/// URLs of the feeds to subscribe to through `WebSub` by the index of their task
+	pub websub_feeds: HashMap<usize, Url>,
 }
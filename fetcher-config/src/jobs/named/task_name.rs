@@ -4,6 +4,7 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::{
 	borrow::Borrow,
@@ -12,7 +13,7 @@ use std::{
 	ops::Deref,
 };
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Clone, PartialEq, Eq, Hash, Debug)]
 #[serde(transparent)]
 pub struct TaskName(pub String);
 
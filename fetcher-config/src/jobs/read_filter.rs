@@ -7,42 +7,54 @@
 use std::fmt::Display;
 
 use chrono::Utc;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use fetcher_core::{
 	entry::EntryId as CEntryId,
 	external_save::ExternalSave as CExternalSave,
 	read_filter::{
-		ExternalSaveRFWrapper as CExternalSaveRFWrapper, Newer as CNewer,
-		NotPresent as CNotPresent, ReadFilter as CReadFilter,
+		DEFAULT_MAX_LIST_LEN, ExternalSaveRFWrapper as CExternalSaveRFWrapper, Newer as CNewer,
+		NotPresent as CNotPresent, ReadFilter as CReadFilter, ReadListLimits as CReadListLimits,
 	},
 };
 
-#[derive(Deserialize, Serialize, Debug)]
+use crate::FetcherConfigError;
+
+#[derive(Deserialize, JsonSchema, Serialize, Debug)]
 #[serde(transparent)]
 pub struct EntryId(pub String);
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Debug)]
 #[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
 pub enum ReadFilter {
 	NewerThanRead(Newer),
 	NotPresentInReadList(NotPresent),
 }
 
-#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
 #[serde(rename_all = "snake_case", deny_unknown_fields)]
 pub enum Kind {
 	NewerThanRead,
 	NotPresentInReadList,
+	NotOlderThan,
+}
+
+/// Limits of the `not_present_in_read_list` read filter
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ReadListLimits {
+	pub max_len: Option<usize>,
+	pub max_age: Option<String>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct Newer {
 	last_read_id: EntryId,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct NotPresent {
 	read_list: Vec<(EntryId, chrono::DateTime<Utc>)>,
@@ -61,7 +73,11 @@ impl EntryId {
 }
 
 impl ReadFilter {
-	pub fn decode_from_conf<S>(self, external_save: S) -> Box<dyn CReadFilter>
+	pub fn decode_from_conf<S>(
+		self,
+		external_save: S,
+		limits: CReadListLimits,
+	) -> Box<dyn CReadFilter>
 	where
 		S: CExternalSave + 'static,
 	{
@@ -71,7 +87,7 @@ impl ReadFilter {
 				external_save: Some(external_save),
 			}),
 			ReadFilter::NotPresentInReadList(rf) => Box::new(CExternalSaveRFWrapper {
-				rf: rf.decode_from_conf(),
+				rf: rf.decode_from_conf(limits),
 				external_save: Some(external_save),
 			}),
 		}
@@ -104,7 +120,18 @@ impl ReadFilter {
 }
 
 impl Kind {
-	pub fn new_from_kind<S>(self, external_save: S) -> Box<dyn CReadFilter>
+	/// Whether the read filter keeps track of the entries read and thus should be saved and restored externally
+	#[must_use]
+	pub fn is_stateful(self) -> bool {
+		match self {
+			Self::NewerThanRead | Self::NotPresentInReadList => true,
+			Self::NotOlderThan => false,
+		}
+	}
+
+	/// # Panics
+	/// if the kind isn't [stateful](`Kind::is_stateful`)
+	pub fn new_from_kind<S>(self, external_save: S, limits: CReadListLimits) -> Box<dyn CReadFilter>
 	where
 		S: CExternalSave + 'static,
 	{
@@ -114,9 +141,14 @@ impl Kind {
 				external_save: Some(external_save),
 			}),
 			Self::NotPresentInReadList => Box::new(CExternalSaveRFWrapper {
-				rf: CNotPresent::new(),
+				rf: CNotPresent::with_limits(limits),
 				external_save: Some(external_save),
 			}),
+			Self::NotOlderThan => {
+				unreachable!(
+					"{self} read filter has no state and is never created from an external save"
+				)
+			}
 		}
 	}
 }
@@ -137,13 +169,30 @@ impl Newer {
 	}
 }
 
+impl ReadListLimits {
+	pub fn decode_from_conf(self) -> Result<CReadListLimits, FetcherConfigError> {
+		Ok(CReadListLimits {
+			max_len: self.max_len.unwrap_or(DEFAULT_MAX_LIST_LEN),
+			max_age: self
+				.max_age
+				.map(duration_str::parse_std)
+				.transpose()
+				.map_err(FetcherConfigError::BadReadListMaxAge)?,
+		})
+	}
+}
+
 impl NotPresent {
 	#[must_use]
-	pub fn decode_from_conf(self) -> CNotPresent {
-		self.read_list
-			.into_iter()
-			.map(|(id, time)| (id.decode_from_conf(), time))
-			.collect()
+	pub fn decode_from_conf(self, limits: CReadListLimits) -> CNotPresent {
+		let mut rf = CNotPresent::with_limits(limits);
+		rf.extend(
+			self.read_list
+				.into_iter()
+				.map(|(id, time)| (id.decode_from_conf(), time)),
+		);
+
+		rf
 	}
 
 	#[must_use]
@@ -167,6 +216,7 @@ impl Display for Kind {
 		f.write_str(match self {
 			Self::NewerThanRead => "newer that the last one read",
 			Self::NotPresentInReadList => "not present in the marked as read list",
+			Self::NotOlderThan => "not older than the max entry age",
 		})
 	}
 }
@@ -4,35 +4,119 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+#![expect(
+	clippy::same_name_method,
+	reason = "the derived (de)serialization of Sink with remote = Self is wrapped by the manual impls"
+)]
+
 mod discord;
 mod exec;
+mod file;
+mod slack;
+mod stdout;
+mod tag_format;
 mod telegram;
 
-use self::{discord::Discord, exec::Exec, telegram::Telegram};
-use crate::{FetcherConfigError, jobs::external_data::ProvideExternalData};
-use fetcher_core::sink::{Sink as CSink, Stdout as CStdout};
+use self::{
+	discord::Discord, exec::Exec, file::File, slack::Slack, stdout::Stdout, telegram::Telegram,
+};
+use crate::{
+	FetcherConfigError,
+	jobs::external_data::ProvideExternalData,
+	serde_extentions::shorthand::{self, Shorthands},
+};
+use fetcher_core::sink::{RateLimited as CRateLimited, Sink as CSink, Stdout as CStdout};
 
-use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
-#[serde(rename_all = "snake_case", deny_unknown_fields)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(rename_all = "snake_case", deny_unknown_fields, remote = "Self")]
+#[schemars(transform = shorthand::transform_schema::<Sink>)]
 pub enum Sink {
 	Telegram(Telegram),
 	Discord(Discord),
+	Slack(Slack),
 	Exec(Exec),
-	Stdout,
+	File(File),
+	Stdout(Option<Stdout>),
+}
+
+// stdout can be written without a payload, the same as stdout: ~
+impl Shorthands for Sink {
+	const SHORTHANDS: &'static [&'static str] = &["stdout"];
+
+	fn deserialize_tagged<'de, D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		Self::deserialize(deserializer)
+	}
+}
+
+impl<'de> Deserialize<'de> for Sink {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		shorthand::deserialize(deserializer)
+	}
+}
+
+impl Serialize for Sink {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		Self::serialize(self, serializer)
+	}
 }
 
 impl Sink {
+	/// Whether the sink returns ids of the messages it has sent, which are needed for the entry to message map
+	#[must_use]
+	pub const fn supports_message_ids(&self) -> bool {
+		matches!(self, Self::Telegram(_) | Self::Discord(_))
+	}
+
 	pub fn decode_from_conf<D>(self, external: &D) -> Result<Box<dyn CSink>, FetcherConfigError>
 	where
 		D: ProvideExternalData + ?Sized,
 	{
 		Ok(match self {
-			Self::Telegram(x) => Box::new(x.decode_from_conf(external)?),
-			Self::Discord(x) => Box::new(x.decode_from_conf(external)?),
+			Self::Telegram(x) => {
+				let min_send_interval = x.min_send_interval.clone();
+				rate_limited(x.decode_from_conf(external)?, min_send_interval)?
+			}
+			Self::Discord(x) => {
+				let min_send_interval = x.min_send_interval.clone();
+				rate_limited(x.decode_from_conf(external)?, min_send_interval)?
+			}
+			Self::Slack(x) => {
+				let min_send_interval = x.min_send_interval.clone();
+				rate_limited(x.decode_from_conf(external)?, min_send_interval)?
+			}
 			Self::Exec(x) => Box::new(x.decode_from_conf()),
-			Self::Stdout => Box::new(CStdout {}),
+			Self::File(x) => Box::new(x.decode_from_conf()),
+			Self::Stdout(x) => Box::new(x.map_or_else(CStdout::default, Stdout::decode_from_conf)),
 		})
 	}
 }
+
+/// Wrap `sink` into [`CRateLimited`] if `min_send_interval` is set
+fn rate_limited<S>(
+	sink: S,
+	min_send_interval: Option<String>,
+) -> Result<Box<dyn CSink>, FetcherConfigError>
+where
+	S: CSink + 'static,
+{
+	let Some(min_send_interval) = min_send_interval else {
+		return Ok(Box::new(sink));
+	};
+
+	let min_interval = duration_str::parse_std(min_send_interval)
+		.map_err(FetcherConfigError::BadMinSendInterval)?;
+
+	Ok(Box::new(CRateLimited::new(sink, min_interval)))
+}
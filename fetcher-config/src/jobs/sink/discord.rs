@@ -4,21 +4,26 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+use super::tag_format::TagFormat;
 use crate::{
 	FetcherConfigError as ConfigError,
 	jobs::external_data::{ExternalDataResult, ProvideExternalData},
 };
 use fetcher_core::sink::discord::{Discord as CDiscord, Target as CTarget};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
-#[serde(transparent)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 pub struct Discord {
+	#[serde(flatten)]
 	pub target: Target,
+	pub use_embeds: Option<bool>,
+	pub tag_format: Option<TagFormat>,
+	pub min_send_interval: Option<String>,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum Target {
 	User(u64),
@@ -36,7 +41,17 @@ impl Discord {
 			ExternalDataResult::Err(e) => return Err(e.into()),
 		};
 
-		Ok(CDiscord::new(&token, self.target.decode_from_conf()))
+		let mut discord = CDiscord::new(
+			&token,
+			self.target.decode_from_conf(),
+			self.use_embeds.unwrap_or(true),
+		);
+
+		if let Some(tag_format) = self.tag_format {
+			discord = discord.with_tag_format(tag_format.decode_from_conf());
+		}
+
+		Ok(discord)
 	}
 }
 
@@ -5,9 +5,10 @@
  */
 
 use fetcher_core::sink::Exec as CExec;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(transparent)]
 pub struct Exec {
 	pub cmd: String,
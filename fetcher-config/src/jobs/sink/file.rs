@@ -0,0 +1,47 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use fetcher_core::sink::{File as CFile, file::Format as CFormat};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct File {
+	pub path: PathBuf,
+	pub format: Option<Format>,
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Format {
+	JsonLines,
+	Text,
+}
+
+impl File {
+	#[must_use]
+	pub fn decode_from_conf(self) -> CFile {
+		CFile {
+			path: self.path,
+			format: self
+				.format
+				.map_or_else(CFormat::default, Format::decode_from_conf),
+		}
+	}
+}
+
+impl Format {
+	#[must_use]
+	pub fn decode_from_conf(self) -> CFormat {
+		match self {
+			Format::JsonLines => CFormat::JsonLines,
+			Format::Text => CFormat::Text,
+		}
+	}
+}
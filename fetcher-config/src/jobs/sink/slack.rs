@@ -0,0 +1,48 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::tag_format::TagFormat;
+use crate::{
+	FetcherConfigError as ConfigError,
+	jobs::external_data::{ExternalDataResult, ProvideExternalData},
+};
+use fetcher_core::sink::slack::Slack as CSlack;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Slack {
+	pub webhook_url: Option<Url>,
+	pub tag_format: Option<TagFormat>,
+	pub min_send_interval: Option<String>,
+}
+
+impl Slack {
+	pub fn decode_from_conf<D>(self, external: &D) -> Result<CSlack, ConfigError>
+	where
+		D: ProvideExternalData + ?Sized,
+	{
+		let webhook_url = match self.webhook_url {
+			Some(url) => url,
+			None => match external.slack_webhook_url() {
+				ExternalDataResult::Ok(v) => v,
+				ExternalDataResult::Unavailable => return Err(ConfigError::SlackWebhookUrlMissing),
+				ExternalDataResult::Err(e) => return Err(e.into()),
+			},
+		};
+
+		let mut slack = CSlack::new(webhook_url);
+
+		if let Some(tag_format) = self.tag_format {
+			slack = slack.with_tag_format(tag_format.decode_from_conf());
+		}
+
+		Ok(slack)
+	}
+}
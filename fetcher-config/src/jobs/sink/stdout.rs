@@ -0,0 +1,72 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use fetcher_core::sink::{
+	Stdout as CStdout,
+	stdout::{Color as CColor, Format as CFormat},
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Stdout {
+	pub format: Option<Format>,
+	pub color: Option<Color>,
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Format {
+	Pretty,
+	Json,
+	JsonPretty,
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Color {
+	Auto,
+	Always,
+	Never,
+}
+
+impl Stdout {
+	#[must_use]
+	pub fn decode_from_conf(self) -> CStdout {
+		CStdout {
+			format: self
+				.format
+				.map_or_else(CFormat::default, Format::decode_from_conf),
+			color: self
+				.color
+				.map_or_else(CColor::default, Color::decode_from_conf),
+		}
+	}
+}
+
+impl Format {
+	#[must_use]
+	pub fn decode_from_conf(self) -> CFormat {
+		match self {
+			Format::Pretty => CFormat::Pretty,
+			Format::Json => CFormat::Json,
+			Format::JsonPretty => CFormat::JsonPretty,
+		}
+	}
+}
+
+impl Color {
+	#[must_use]
+	pub fn decode_from_conf(self) -> CColor {
+		match self {
+			Color::Auto => CColor::Auto,
+			Color::Always => CColor::Always,
+			Color::Never => CColor::Never,
+		}
+	}
+}
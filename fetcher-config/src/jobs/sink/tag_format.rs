@@ -0,0 +1,28 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use fetcher_core::sink::TagFormat as CTagFormat;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum TagFormat {
+	Hashtag,
+	Bracketed,
+	Bold,
+}
+
+impl TagFormat {
+	pub fn decode_from_conf(self) -> CTagFormat {
+		match self {
+			TagFormat::Hashtag => CTagFormat::Hashtag,
+			TagFormat::Bracketed => CTagFormat::Bracketed,
+			TagFormat::Bold => CTagFormat::Bold,
+		}
+	}
+}
@@ -4,27 +4,51 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use super::tag_format::TagFormat;
 use crate::{
 	FetcherConfigError as ConfigError,
 	jobs::external_data::{ExternalDataResult, ProvideExternalData},
 };
-use fetcher_core::sink::{Telegram as CTelegram, telegram::LinkLocation as CLinkLocation};
+use fetcher_core::sink::{
+	Telegram as CTelegram,
+	telegram::{CorrectOption as CCorrectOption, LinkLocation as CLinkLocation, Poll as CPoll},
+};
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct Telegram {
 	pub chat_id: i64,
 	pub link_location: Option<LinkLocation>,
+	pub web_page_preview: Option<bool>,
+	pub tag_format: Option<TagFormat>,
+	pub min_send_interval: Option<String>,
+	pub poll: Option<Poll>,
 }
 
 /// Refer to [`crate::sink::message::LinkLocation`]
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(rename_all = "snake_case", deny_unknown_fields)]
 pub enum LinkLocation {
 	PreferTitle,
 	Bottom,
+	PreviewOnly,
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Poll {
+	pub separator: Option<String>,
+	pub quiz: Option<Quiz>,
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Quiz {
+	CorrectOption(u8),
+	CorrectMarker(String),
 }
 
 impl Telegram {
@@ -38,12 +62,23 @@ impl Telegram {
 			ExternalDataResult::Err(e) => return Err(e.into()),
 		};
 
-		Ok(CTelegram::new(
+		let mut telegram = CTelegram::new(
 			token,
 			self.chat_id,
 			self.link_location
 				.map_or(CLinkLocation::PreferTitle, LinkLocation::decode_from_conf),
-		))
+			self.web_page_preview.unwrap_or(false),
+		);
+
+		if let Some(tag_format) = self.tag_format {
+			telegram = telegram.with_tag_format(tag_format.decode_from_conf());
+		}
+
+		if let Some(poll) = self.poll {
+			telegram = telegram.with_poll(poll.decode_from_conf());
+		}
+
+		Ok(telegram)
 	}
 }
 
@@ -52,6 +87,27 @@ impl LinkLocation {
 		match self {
 			LinkLocation::PreferTitle => CLinkLocation::PreferTitle,
 			LinkLocation::Bottom => CLinkLocation::Bottom,
+			LinkLocation::PreviewOnly => CLinkLocation::PreviewOnly,
+		}
+	}
+}
+
+impl Poll {
+	pub fn decode_from_conf(self) -> CPoll {
+		let default = CPoll::default();
+
+		CPoll {
+			separator: self.separator.unwrap_or(default.separator),
+			quiz: self.quiz.map(Quiz::decode_from_conf),
+		}
+	}
+}
+
+impl Quiz {
+	pub fn decode_from_conf(self) -> CCorrectOption {
+		match self {
+			Quiz::CorrectOption(idx) => CCorrectOption::Index(idx),
+			Quiz::CorrectMarker(marker) => CCorrectOption::Marker(marker),
 		}
 	}
 }
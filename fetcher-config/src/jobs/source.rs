@@ -7,12 +7,15 @@
 pub mod email;
 pub mod exec;
 pub mod file;
+pub mod gemini;
+pub mod graphql;
 pub mod http;
 pub mod reddit;
 pub mod string;
 
 use self::{
-	email::Email, exec::Exec, file::File, http::Http, reddit::Reddit, string::StringSource,
+	email::Email, exec::Exec, file::File, gemini::Gemini, graphql::GraphQl, http::Http,
+	reddit::Reddit, string::StringSource,
 };
 use crate::{FetcherConfigError, jobs::external_data::ProvideExternalData};
 use fetcher_core::{
@@ -23,18 +26,22 @@ use fetcher_core::{
 	},
 };
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[allow(clippy::large_enum_variant)]
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(rename_all = "snake_case", deny_unknown_fields)]
 pub enum Source {
 	// with shared read filter
 	String(StringSource),
 	Http(Http),
+	#[serde(rename = "graphql")]
+	GraphQl(GraphQl),
 	File(File),
 	Reddit(Reddit),
 	Exec(Exec),
+	Gemini(Gemini),
 
 	// with custom read filter
 	Email(Email),
@@ -65,9 +72,11 @@ impl Source {
 			// with shared read filter
 			Self::String(x) => with_read_filter!(x.decode_from_conf()),
 			Self::Http(x) => with_read_filter!(x.decode_from_conf()?),
+			Self::GraphQl(x) => with_read_filter!(x.decode_from_conf()?),
 			Self::File(x) => with_read_filter!(x.decode_from_conf()),
-			Self::Reddit(x) => with_read_filter!(x.decode_from_conf()),
+			Self::Reddit(x) => with_read_filter!(x.decode_from_conf(external)?),
 			Self::Exec(x) => with_read_filter!(x.decode_from_conf()),
+			Self::Gemini(x) => with_read_filter!(x.decode_from_conf(external)?),
 
 			// with custom read filter
 			Self::Email(x) => Box::new(x.decode_from_conf(external)?),
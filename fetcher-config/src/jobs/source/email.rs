@@ -6,25 +6,32 @@
 
 mod auth;
 mod filters;
+mod tls_mode;
 mod view_mode;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use self::{auth::Auth, filters::Filters, view_mode::ViewMode};
+use self::{auth::Auth, filters::Filters, tls_mode::TlsMode, view_mode::ViewMode};
 use crate::{
 	FetcherConfigError as ConfigError,
 	jobs::external_data::{ExternalDataResult, ProvideExternalData},
 };
 use fetcher_core::source::Email as CEmail;
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct Email {
 	imap: Option<String>,
+	port: Option<u16>,
+	tls: Option<TlsMode>,
+	allow_insecure_connection: Option<bool>,
+	danger_accept_invalid_certs: Option<bool>,
 	email: String,
 	auth: Auth,
 	filters: Filters,
 	view_mode: ViewMode,
+	max_body_len: Option<usize>,
 }
 
 impl Email {
@@ -32,7 +39,14 @@ impl Email {
 	where
 		D: ProvideExternalData + ?Sized,
 	{
-		Ok(match self.auth {
+		let tls = match self.tls {
+			Some(TlsMode::None) if self.allow_insecure_connection != Some(true) => {
+				return Err(ConfigError::EmailInsecureConnectionNotAllowed);
+			}
+			tls => tls.map(TlsMode::decode_from_conf),
+		};
+
+		let mut email = match self.auth {
 			Auth::GmailOAuth2 => {
 				if self.imap.is_some() {
 					tracing::warn!("The imap address field is ignored in Gmail mode");
@@ -53,6 +67,26 @@ impl Email {
 					self.view_mode.decode_from_conf(),
 				)
 			}
+			Auth::OutlookOAuth2 => {
+				if self.imap.is_some() {
+					tracing::warn!("The imap address field is ignored in Outlook mode");
+				}
+
+				let oauth = match external.microsoft_oauth2() {
+					ExternalDataResult::Ok(v) => v,
+					ExternalDataResult::Unavailable => {
+						return Err(ConfigError::MicrosoftOAuth2TokenMissing);
+					}
+					ExternalDataResult::Err(e) => return Err(e.into()),
+				};
+
+				CEmail::new_outlook(
+					self.email,
+					oauth,
+					self.filters.decode_from_conf(),
+					self.view_mode.decode_from_conf(),
+				)
+			}
 			Auth::Password => {
 				let passwd = match external.email_password() {
 					ExternalDataResult::Ok(v) => v,
@@ -70,6 +104,24 @@ impl Email {
 					self.view_mode.decode_from_conf(),
 				)
 			}
-		})
+		};
+
+		if let Some(port) = self.port {
+			email = email.with_port(port);
+		}
+
+		if let Some(tls) = tls {
+			email = email.with_tls(tls);
+		}
+
+		if self.danger_accept_invalid_certs == Some(true) {
+			email = email.danger_accept_invalid_certs();
+		}
+
+		if let Some(max_body_len) = self.max_body_len {
+			email = email.with_max_body_len(max_body_len);
+		}
+
+		Ok(email)
 	}
 }
@@ -4,12 +4,15 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Copy, Debug)]
 #[serde(rename_all = "snake_case", deny_unknown_fields)]
 pub enum Auth {
 	#[serde(rename = "gmail_oauth2")]
 	GmailOAuth2,
+	#[serde(rename = "outlook_oauth2")]
+	OutlookOAuth2,
 	Password,
 }
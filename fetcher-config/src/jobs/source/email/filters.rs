@@ -6,9 +6,10 @@
 
 use fetcher_core::source::email::Filters as CFilters;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct Filters {
 	sender: Option<String>,
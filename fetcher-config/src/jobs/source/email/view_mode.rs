@@ -6,9 +6,10 @@
 
 use fetcher_core::source::email::ViewMode as CViewMode;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Copy, Debug)]
 #[serde(rename_all = "snake_case", deny_unknown_fields)]
 pub enum ViewMode {
 	ReadOnly,
@@ -6,11 +6,12 @@
 
 use fetcher_core::source::Exec as CExec;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::{OneOrMany, serde_as};
 
 #[serde_as]
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(transparent)]
 pub struct Exec {
 	#[serde_as(deserialize_as = "OneOrMany<_>")]
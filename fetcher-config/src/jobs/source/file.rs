@@ -6,12 +6,13 @@
 
 use fetcher_core::source::File as CFile;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::{OneOrMany, serde_as};
 use std::path::PathBuf;
 
 #[serde_as]
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(transparent)]
 pub struct File(#[serde_as(deserialize_as = "OneOrMany<_>")] pub Vec<PathBuf>);
 
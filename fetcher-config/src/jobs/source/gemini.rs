@@ -0,0 +1,111 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::{
+	FetcherConfigError,
+	jobs::external_data::{ExternalDataResult, ProvideExternalData},
+};
+use fetcher_core::source::{
+	Gemini as CGemini,
+	gemini::{
+		CertificateCheck as CCertificateCheck, Fingerprint as CFingerprint, Items as CItems,
+		KnownHosts as CKnownHosts,
+	},
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use url::Url;
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Gemini {
+	url: Url,
+	items: Option<Items>,
+	certificate: Option<CertificateCheck>,
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Items {
+	Document,
+	Links,
+	Headings,
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum CertificateCheck {
+	Tofu,
+	Pinned(Vec<String>),
+	DangerAcceptAny,
+}
+
+impl Gemini {
+	pub fn decode_from_conf<D>(self, external: &D) -> Result<CGemini, FetcherConfigError>
+	where
+		D: ProvideExternalData + ?Sized,
+	{
+		let mut gemini = CGemini::new(self.url)
+			.map_err(|e| FetcherConfigError::FetcherCoreSource(Box::new(e.into())))?;
+
+		if let Some(items) = self.items {
+			gemini = gemini.with_items(items.decode_from_conf());
+		}
+
+		let certificate = self
+			.certificate
+			.unwrap_or(CertificateCheck::Tofu)
+			.decode_from_conf(external)?;
+
+		Ok(gemini.with_certificate_check(certificate))
+	}
+}
+
+impl Items {
+	#[must_use]
+	pub fn decode_from_conf(self) -> CItems {
+		match self {
+			Items::Document => CItems::Document,
+			Items::Links => CItems::Links,
+			Items::Headings => CItems::Headings,
+		}
+	}
+}
+
+impl CertificateCheck {
+	pub fn decode_from_conf<D>(self, external: &D) -> Result<CCertificateCheck, FetcherConfigError>
+	where
+		D: ProvideExternalData + ?Sized,
+	{
+		Ok(match self {
+			CertificateCheck::Tofu => {
+				let known_hosts = match external.gemini_known_hosts() {
+					ExternalDataResult::Ok(v) => v,
+					ExternalDataResult::Unavailable => {
+						tracing::warn!(
+							"Gemini known hosts can't be saved, trusted certificates are going to be forgotten after a restart"
+						);
+
+						Arc::new(Mutex::new(CKnownHosts::default()))
+					}
+					ExternalDataResult::Err(e) => return Err(e.into()),
+				};
+
+				CCertificateCheck::Tofu(known_hosts)
+			}
+			CertificateCheck::Pinned(fingerprints) => CCertificateCheck::Pinned(
+				fingerprints
+					.iter()
+					.map(|fingerprint| fingerprint.parse::<CFingerprint>())
+					.collect::<Result<_, _>>()?,
+			),
+			CertificateCheck::DangerAcceptAny => CCertificateCheck::DangerAcceptAny,
+		})
+	}
+}
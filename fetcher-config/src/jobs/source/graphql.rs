@@ -0,0 +1,40 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::http::Auth;
+use crate::FetcherConfigError;
+use fetcher_core::source::GraphQl as CGraphQl;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use url::Url;
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct GraphQl {
+	pub url: Url,
+	pub query: String,
+	pub variables: Option<Map<String, Value>>,
+	pub proxy: Option<Url>,
+	pub auth: Option<Auth>,
+}
+
+impl GraphQl {
+	pub fn decode_from_conf(self) -> Result<CGraphQl, FetcherConfigError> {
+		let graphql = CGraphQl::new(self.url, &self.query, self.variables.map(Value::Object))?;
+
+		let graphql = match self.auth {
+			Some(auth) => graphql.with_auth(auth.decode_from_conf()),
+			None => graphql,
+		};
+
+		Ok(match self.proxy {
+			Some(proxy) => graphql.with_proxy(&proxy)?,
+			None => graphql,
+		})
+	}
+}
@@ -4,46 +4,181 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use fetcher_core::source::{Http as CHttp, http::HttpError as CHttpError};
+use crate::FetcherConfigError;
+use fetcher_core::source::{
+	Http as CHttp,
+	http::{Auth as CAuth, PostBody as CPostBody},
+};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::{OneOrMany, serde_as};
+use std::collections::HashMap;
 use url::Url;
 
 #[serde_as]
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(transparent)]
 pub struct Http(#[serde_as(deserialize_as = "OneOrMany<_>")] pub Vec<Request>);
 
 // treat http: url the same as http: {get: url}
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[allow(clippy::large_enum_variant)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(untagged)]
 pub enum Request {
 	Untagged(Url),
 	Tagged(TaggedRequest),
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TaggedRequest {
+	#[serde(flatten)]
+	pub method: Method,
+	pub proxy: Option<Url>,
+	pub auth: Option<Auth>,
+	pub danger_accept_invalid_certs: Option<bool>,
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(rename_all = "snake_case", deny_unknown_fields)]
-pub enum TaggedRequest {
+pub enum Method {
 	Get(Url),
-	Post { url: Url, body: String },
+	Post {
+		url: Url,
+		body: Option<String>,
+		content_type: Option<ContentType>,
+		form: Option<HashMap<String, String>>,
+	},
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentType {
+	Json,
+	Form,
+	#[serde(untagged)]
+	Other(String),
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Auth {
+	Basic {
+		username: String,
+		password: Option<String>,
+	},
+	Bearer(String),
 }
 
 impl Http {
-	pub fn decode_from_conf(self) -> Result<Vec<CHttp>, CHttpError> {
+	/// The URL of the page this source gets, if it's a single GET request
+	#[must_use]
+	pub fn feed_url(&self) -> Option<&Url> {
+		match self.0.as_slice() {
+			[
+				Request::Untagged(url)
+				| Request::Tagged(TaggedRequest {
+					method: Method::Get(url),
+					..
+				}),
+			] => Some(url),
+			_ => None,
+		}
+	}
+
+	pub fn decode_from_conf(self) -> Result<Vec<CHttp>, FetcherConfigError> {
 		self.0
 			.into_iter()
 			.map(Request::decode_from_conf)
-			.collect::<Result<_, CHttpError>>()
+			.collect::<Result<_, _>>()
 	}
 }
 
 impl Request {
-	pub fn decode_from_conf(self) -> Result<CHttp, CHttpError> {
+	pub fn decode_from_conf(self) -> Result<CHttp, FetcherConfigError> {
+		match self {
+			Self::Untagged(url) => Ok(CHttp::new_get(url)?),
+			Self::Tagged(TaggedRequest {
+				method,
+				proxy,
+				auth,
+				danger_accept_invalid_certs,
+			}) => {
+				let http = match method {
+					Method::Get(url) => CHttp::new_get(url)?,
+					Method::Post {
+						url,
+						body,
+						content_type,
+						form,
+					} => decode_post(url, body, content_type, form)?,
+				};
+
+				let http = match auth {
+					Some(auth) => http.with_auth(auth.decode_from_conf()),
+					None => http,
+				};
+
+				let http = match proxy {
+					Some(proxy) => http.with_proxy(&proxy)?,
+					None => http,
+				};
+
+				if danger_accept_invalid_certs == Some(true) {
+					Ok(http.danger_accept_invalid_certs()?)
+				} else {
+					Ok(http)
+				}
+			}
+		}
+	}
+}
+
+fn decode_post(
+	url: Url,
+	body: Option<String>,
+	content_type: Option<ContentType>,
+	form: Option<HashMap<String, String>>,
+) -> Result<CHttp, FetcherConfigError> {
+	let body = match (body, content_type, form) {
+		// the body is JSON by default
+		(Some(body), None | Some(ContentType::Json), None) => {
+			return Ok(CHttp::new_post(url, &body)?);
+		}
+		(Some(body), Some(content_type), None) => CPostBody::Raw {
+			body,
+			content_type: content_type.as_str().to_owned(),
+		},
+		(None, None | Some(ContentType::Form), Some(form)) => {
+			CPostBody::Form(form.into_iter().collect())
+		}
+		(None, _, None) => return Err(FetcherConfigError::HttpPostBodyMissing),
+		(Some(_), _, Some(_)) | (None, Some(_), Some(_)) => {
+			return Err(FetcherConfigError::HttpPostBodyConflict);
+		}
+	};
+
+	Ok(CHttp::new_post_with_body(url, body)?)
+}
+
+impl Auth {
+	#[must_use]
+	pub fn decode_from_conf(self) -> CAuth {
+		match self {
+			Self::Basic { username, password } => CAuth::Basic { username, password },
+			Self::Bearer(token) => CAuth::Bearer(token),
+		}
+	}
+}
+
+impl ContentType {
+	#[must_use]
+	pub fn as_str(&self) -> &str {
 		match self {
-			Self::Untagged(url) | Self::Tagged(TaggedRequest::Get(url)) => CHttp::new_get(url),
-			Self::Tagged(TaggedRequest::Post { url, body }) => CHttp::new_post(url, &body),
+			Self::Json => "application/json",
+			Self::Form => "application/x-www-form-urlencoded",
+			Self::Other(s) => s,
 		}
 	}
 }
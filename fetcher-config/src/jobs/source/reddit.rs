@@ -4,23 +4,30 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+use crate::{
+	FetcherConfigError as ConfigError,
+	jobs::external_data::{ExternalDataResult, ProvideExternalData},
+};
 use fetcher_core::source::{Reddit as CReddit, reddit::Sort as CSort};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(transparent)]
 pub struct Reddit(pub HashMap<String, Inner>);
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct Inner {
-	sort: Sort,
+	sort: Option<Sort>,
 	score_threshold: Option<u32>,
+	limit: Option<u32>,
+	auth: Option<bool>,
 }
 
-#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Copy, Debug)]
 #[serde(rename_all = "snake_case", deny_unknown_fields)]
 #[rustfmt::skip]	// to put new and latest side by side
 pub enum Sort {
@@ -30,7 +37,7 @@ pub enum Sort {
 	Top(TimePeriod),
 }
 
-#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Copy, Debug)]
 #[serde(rename_all = "snake_case", deny_unknown_fields)]
 pub enum TimePeriod {
 	Today,
@@ -41,23 +48,59 @@ pub enum TimePeriod {
 }
 
 impl Reddit {
-	#[must_use]
-	pub fn decode_from_conf(self) -> Vec<CReddit> {
+	pub fn decode_from_conf<D>(self, external: &D) -> Result<Vec<CReddit>, ConfigError>
+	where
+		D: ProvideExternalData + ?Sized,
+	{
 		self.0
 			.into_iter()
-			.map(|(subreddit, inner)| inner.decode_from_conf(&subreddit))
+			.map(|(name, inner)| inner.decode_from_conf(&name, external))
 			.collect()
 	}
 }
 
 impl Inner {
-	#[must_use]
-	pub fn decode_from_conf(self, subreddit: &str) -> CReddit {
-		CReddit::new(
-			subreddit,
-			self.sort.decode_from_conf(),
-			self.score_threshold,
-		)
+	pub fn decode_from_conf<D>(self, name: &str, external: &D) -> Result<CReddit, ConfigError>
+	where
+		D: ProvideExternalData + ?Sized,
+	{
+		// either a subreddit name, optionally prefixed with "r/", or a username prefixed with "u/"
+		let reddit = match name.strip_prefix("u/") {
+			Some(user) => {
+				// posts of users are always sorted by newest and can only be fetched anonymously
+				if self.sort.is_some() {
+					return Err(ConfigError::RedditUserUnsupported("sort"));
+				}
+
+				if self.auth.is_some() {
+					return Err(ConfigError::RedditUserUnsupported("auth"));
+				}
+
+				CReddit::new_user(user, self.score_threshold)
+			}
+			None => CReddit::new(
+				name.strip_prefix("r/").unwrap_or(name),
+				self.sort.unwrap_or(Sort::Hot).decode_from_conf(),
+				self.score_threshold,
+			),
+		};
+
+		let reddit = match self.limit {
+			Some(limit) => reddit.with_limit(limit),
+			None => reddit,
+		};
+
+		if self.auth != Some(true) {
+			return Ok(reddit);
+		}
+
+		let auth = match external.reddit_auth() {
+			ExternalDataResult::Ok(v) => v,
+			ExternalDataResult::Unavailable => return Err(ConfigError::RedditAuthMissing),
+			ExternalDataResult::Err(e) => return Err(e.into()),
+		};
+
+		Ok(reddit.with_auth(auth))
 	}
 }
 
@@ -5,37 +5,79 @@
  */
 
 pub mod entry_to_msg_map;
+pub mod lint;
 
+use itertools::Itertools;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_with::{OneOrMany, serde_as};
 use std::sync::Arc;
-use tap::TapOptional;
 use tokio::sync::RwLock;
+use url::Url;
 
 use super::{
 	action::Action,
 	external_data::{ExternalDataResult, ProvideExternalData},
+	job::timepoint::TimePoint,
 	named::{JobName, TaskName},
 	read_filter,
 	sink::Sink,
 	source::Source,
 };
-use crate::FetcherConfigError;
-use fetcher_core::{action::Action as CAction, task::Task as CTask, utils::OptionExt};
+use crate::{FetcherConfigError, serde_extentions::exact_one_or_many::ExactOneOrMany};
+use fetcher_core::{
+	action::Action as CAction,
+	read_filter::{
+		NotOlder as CNotOlder, ReadFilter as CReadFilter, ReadListLimits as CReadListLimits,
+	},
+	task::Task as CTask,
+	utils::OptionExt,
+};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[serde_as]
+#[derive(Deserialize, JsonSchema, Serialize, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct Task {
+	pub disabled: Option<bool>,
 	#[serde(rename = "read_filter_type")]
-	pub read_filter_kind: Option<read_filter::Kind>,
+	#[serde_as(deserialize_as = "Option<OneOrMany<_>>")]
+	#[serde(default)]
+	pub read_filter_kind: Option<Vec<read_filter::Kind>>,
+	pub max_entry_age: Option<String>,
+	pub read_list: Option<read_filter::ReadListLimits>,
 	pub tag: Option<String>,
 	pub source: Option<Source>,
 	#[serde(rename = "process")]
 	pub actions: Option<Vec<Action>>,
 	pub entry_to_msg_map_enabled: Option<bool>,
-	pub sink: Option<Sink>,
+	pub update_in_place: Option<bool>,
+	pub delete_removed: Option<bool>,
+	pub timeout: Option<String>,
+	pub max_entries: Option<usize>,
+	pub refresh: Option<TimePoint>,
+	pub websub: Option<bool>,
+	#[serde_as(as = "Option<ExactOneOrMany>")]
+	#[serde(default)]
+	pub sink: Option<Vec<Sink>>,
 }
 
 impl Task {
+	/// The URL of the feed to subscribe to through `WebSub`, if it's enabled
+	pub fn websub_feed(&self) -> Result<Option<Url>, FetcherConfigError> {
+		if self.websub != Some(true) {
+			return Ok(None);
+		}
+
+		match &self.source {
+			Some(Source::Http(http)) => http
+				.feed_url()
+				.cloned()
+				.map(Some)
+				.ok_or(FetcherConfigError::WebSubSourceNotHttpGet),
+			_ => Err(FetcherConfigError::WebSubSourceNotHttpGet),
+		}
+	}
+
 	#[tracing::instrument(level = "debug", skip(self, external))]
 	pub fn decode_from_conf<D>(
 		self,
@@ -48,45 +90,97 @@ impl Task {
 	{
 		tracing::trace!("Parsing task config");
 
-		let rf = match self.read_filter_kind {
-			Some(expected_rf_type) => {
-				match external.read_filter(job, task_name, expected_rf_type) {
-					ExternalDataResult::Ok(rf) => Some(Arc::new(RwLock::new(rf))),
-					ExternalDataResult::Unavailable => {
-						tracing::info!("Read filter is unavailable, skipping");
-						None
-					}
-					ExternalDataResult::Err(e) => return Err(e.into()),
-				}
+		let read_list_limits = self
+			.read_list
+			.try_map(read_filter::ReadListLimits::decode_from_conf)?
+			.unwrap_or_default();
+
+		let rf = self
+			.read_filter_kind
+			.try_and_then(|kinds| {
+				decode_read_filter(
+					kinds,
+					self.max_entry_age,
+					read_list_limits,
+					job,
+					task_name,
+					external,
+				)
+			})?
+			.map(|rf| Arc::new(RwLock::new(rf)));
+
+		// the tag is needed by the templates in the actions
+		let tag = match (self.tag, task_name) {
+			(Some(tag_override), Some(task_name)) => {
+				tracing::debug!(
+					"Overriding tag from task name {task_name:?} with {tag_override:?}"
+				);
+				Some(tag_override)
 			}
-			None => None,
+			(Some(tag), None) => {
+				tracing::debug!("Setting custom tag {tag:?}");
+				Some(tag)
+			}
+			(None, Some(task_name)) => {
+				tracing::trace!("Using task name as tag");
+				Some(task_name.as_str().to_owned())
+			}
+			(None, None) => None,
 		};
 
 		let actions = self.actions.try_map(|acts| {
 			let mut acts = itertools::process_results(
-				acts.into_iter()
-					.filter_map(|act| act.decode_from_conf(rf.clone(), external).transpose()),
+				acts.into_iter().filter_map(|act| {
+					act.decode_from_conf(rf.clone(), tag.as_deref(), external)
+						.transpose()
+				}),
 				|i| i.flatten().collect::<Vec<_>>(),
 			)?;
 
-			if let Some(sink) = self.sink {
+			// every sink gets all the entries, one after another
+			for sink in self.sink.into_iter().flatten() {
 				acts.push(CAction::Sink(sink.decode_from_conf(external)?));
 			}
 
 			Ok::<_, FetcherConfigError>(acts)
 		})?;
 
-		let entry_to_msg_map_enabled = self
-			.entry_to_msg_map_enabled
-			.tap_some(|b| {
-				// TODO: include task name
-				tracing::info!(
-					"Overriding entry_to_msg_map_enabled for {} from the default to {}",
-					job,
-					b
-				);
-			})
-			.unwrap_or_else(|| self.source.as_ref().is_some_and(Source::supports_replies));
+		let timeout = self
+			.timeout
+			.map(duration_str::parse_std)
+			.transpose()
+			.map_err(FetcherConfigError::BadTimeout)?;
+
+		let update_in_place = self.update_in_place.unwrap_or(false);
+		let delete_removed = self.delete_removed.unwrap_or(false);
+
+		// editing and deleting sent messages requires knowing which message an entry was sent as
+		let entry_to_msg_map_required = if update_in_place {
+			Some("update_in_place")
+		} else if delete_removed {
+			Some("delete_removed")
+		} else {
+			None
+		};
+
+		let entry_to_msg_map_enabled =
+			match (self.entry_to_msg_map_enabled, entry_to_msg_map_required) {
+				(Some(false), Some(required_by)) => {
+					return Err(FetcherConfigError::EntryToMsgMapDisabled(required_by));
+				}
+				(Some(enabled), _) => {
+					// TODO: include task name
+					tracing::info!(
+						"Overriding entry_to_msg_map_enabled for {} from the default to {}",
+						job,
+						enabled
+					);
+
+					enabled
+				}
+				(None, Some(_)) => true,
+				(None, None) => self.source.as_ref().is_some_and(Source::supports_replies),
+			};
 
 		let entry_to_msg_map = if entry_to_msg_map_enabled {
 			match external.entry_to_msg_map(job, task_name) {
@@ -101,25 +195,17 @@ impl Task {
 			None
 		};
 
-		let tag = match (self.tag, task_name) {
-			(Some(tag_override), Some(task_name)) => {
-				tracing::debug!(
-					"Overriding tag from task name {task_name:?} with {tag_override:?}"
-				);
-				Some(tag_override)
-			}
-			(Some(tag), None) => {
-				tracing::debug!("Setting custom tag {tag:?}");
-				Some(tag)
-			}
-			(None, Some(task_name)) => {
-				tracing::trace!("Using task name as tag");
-				Some(task_name.as_str().to_owned())
+		let dead_letters = match external.dead_letters(job, task_name) {
+			ExternalDataResult::Ok(v) => Some(v),
+			ExternalDataResult::Unavailable => {
+				tracing::trace!("Dead letter save location is unavailable, skipping...");
+				None
 			}
-			(None, None) => None,
+			ExternalDataResult::Err(e) => return Err(e.into()),
 		};
 
 		Ok(CTask {
+			name: task_name.map(|name| name.as_str().to_owned()),
 			tag,
 			source: self
 				.source
@@ -127,6 +213,97 @@ impl Task {
 				.transpose()?,
 			actions,
 			entry_to_msg_map,
+			update_in_place,
+			delete_removed,
+			timeout,
+			max_entries: self.max_entries,
+			dead_letters,
+			refresh_time: self.refresh.try_map(TimePoint::decode_from_conf)?,
 		})
 	}
 }
+
+/// Create a read filter out of all the read filter kinds set for the task.
+/// At most one of them can keep track of the entries read, the rest are composed on top of it
+fn decode_read_filter<D>(
+	kinds: Vec<read_filter::Kind>,
+	max_entry_age: Option<String>,
+	read_list_limits: CReadListLimits,
+	job: &JobName,
+	task_name: Option<&TaskName>,
+	external: &D,
+) -> Result<Option<Box<dyn CReadFilter>>, FetcherConfigError>
+where
+	D: ProvideExternalData + ?Sized,
+{
+	let (stateful, stateless): (Vec<_>, Vec<_>) = kinds
+		.into_iter()
+		.unique()
+		.partition(|kind| kind.is_stateful());
+
+	let rf = match *stateful.as_slice() {
+		[] => None,
+		[expected_rf_type] => {
+			match external.read_filter(job, task_name, expected_rf_type, read_list_limits) {
+				ExternalDataResult::Ok(rf) => Some(Box::new(rf) as Box<dyn CReadFilter>),
+				ExternalDataResult::Unavailable => {
+					tracing::info!("Read filter is unavailable, skipping");
+					None
+				}
+				ExternalDataResult::Err(e) => return Err(e.into()),
+			}
+		}
+		_ => return Err(FetcherConfigError::MultipleStatefulReadFilters),
+	};
+
+	// not_older_than is the only stateless read filter kind
+	if stateless.is_empty() {
+		return Ok(rf);
+	}
+
+	let max_age = max_entry_age.ok_or(FetcherConfigError::MaxEntryAgeMissing)?;
+	let max_age = duration_str::parse_std(max_age).map_err(FetcherConfigError::BadMaxEntryAge)?;
+
+	Ok(Some(Box::new(CNotOlder { max_age, inner: rf })))
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+
+	use super::*;
+
+	use crate::jobs::external_data::env::EnvExternalData;
+
+	fn decode(task: serde_json::Value) -> Result<CTask, FetcherConfigError> {
+		serde_json::from_value::<Task>(task)
+			.unwrap()
+			.decode_from_conf(&JobName::from("job"), None, &EnvExternalData)
+	}
+
+	#[test]
+	fn entry_to_msg_map_explicitly_disabled() {
+		let res = decode(serde_json::json!({
+			"entry_to_msg_map_enabled": false,
+			"delete_removed": true,
+		}));
+
+		assert!(matches!(
+			res,
+			Err(FetcherConfigError::EntryToMsgMapDisabled("delete_removed"))
+		));
+	}
+
+	#[test]
+	fn entry_to_msg_map_required() {
+		let task = decode(serde_json::json!({ "update_in_place": true })).unwrap();
+		assert!(task.update_in_place);
+
+		let task = decode(serde_json::json!({
+			"entry_to_msg_map_enabled": true,
+			"update_in_place": true,
+		}))
+		.unwrap();
+		assert!(task.update_in_place);
+	}
+}
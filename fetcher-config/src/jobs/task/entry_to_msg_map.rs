@@ -4,22 +4,43 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use fetcher_core::{entry::EntryId as CEntryId, sink::message::MessageId as CMessageId};
+use fetcher_core::{
+	entry::EntryId as CEntryId,
+	sink::message::{ContentHash as CContentHash, MessageId as CMessageId},
+	task::entry_to_msg_map::SentMessage as CSentMessage,
+};
 
-#[derive(Deserialize, Serialize, PartialEq, Eq, Hash, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, PartialEq, Eq, Hash, Debug)]
 #[serde(transparent)]
 pub struct EntryId(pub String);
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, JsonSchema, Serialize, Debug)]
 #[serde(transparent)]
 pub struct MessageId(pub i64);
 
-#[derive(Deserialize, Serialize, Debug)]
-#[serde(transparent)]
-pub struct EntryToMsgMap(pub HashMap<EntryId, MessageId>);
+#[derive(Deserialize, JsonSchema, Serialize, Debug)]
+#[serde(untagged)]
+pub enum SentMessage {
+	/// Just the id of the message. This is also the format messages were saved in before the hashes of their contents were kept
+	Id(MessageId),
+	WithHash {
+		id: MessageId,
+		hash: i64,
+	},
+}
+
+/// Entry to message maps of every sink of a task
+#[derive(Deserialize, JsonSchema, Serialize, Debug)]
+#[serde(untagged)]
+pub enum EntryToMsgMap {
+	/// The map of the only sink of the task. This is also the format maps were saved in before multiple sinks were supported
+	Single(HashMap<EntryId, SentMessage>),
+	PerSink(Vec<HashMap<EntryId, SentMessage>>),
+}
 
 impl EntryId {
 	#[must_use]
@@ -45,26 +66,87 @@ impl MessageId {
 	}
 }
 
-impl EntryToMsgMap {
+impl SentMessage {
 	#[must_use]
-	pub fn decode_from_conf(self) -> HashMap<CEntryId, CMessageId> {
-		self.0
-			.into_iter()
-			.map(|(eid, msgid)| (eid.decode_from_conf(), msgid.decode_from_conf()))
-			.collect()
+	pub fn decode_from_conf(self) -> CSentMessage {
+		match self {
+			Self::Id(id) => CSentMessage {
+				id: id.decode_from_conf(),
+				hash: None,
+			},
+			Self::WithHash { id, hash } => CSentMessage {
+				id: id.decode_from_conf(),
+				hash: Some(CContentHash(hash)),
+			},
+		}
 	}
 
 	#[must_use]
-	pub fn encode_into_conf(map: HashMap<CEntryId, CMessageId>) -> Self {
-		Self(
+	pub fn encode_into_conf(msg: CSentMessage) -> Self {
+		let id = MessageId::encode_into_conf(msg.id);
+
+		match msg.hash {
+			Some(CContentHash(hash)) => Self::WithHash { id, hash },
+			None => Self::Id(id),
+		}
+	}
+}
+
+impl EntryToMsgMap {
+	#[must_use]
+	pub fn decode_from_conf(self) -> Vec<HashMap<CEntryId, CSentMessage>> {
+		let decode_map = |map: HashMap<EntryId, SentMessage>| {
 			map.into_iter()
-				.map(|(eid, msgid)| {
-					(
-						EntryId::encode_into_conf(eid),
-						MessageId::encode_into_conf(msgid),
-					)
-				})
-				.collect(),
-		)
+				.map(|(eid, msg)| (eid.decode_from_conf(), msg.decode_from_conf()))
+				.collect()
+		};
+
+		match self {
+			Self::Single(map) => vec![decode_map(map)],
+			Self::PerSink(maps) => maps.into_iter().map(decode_map).collect(),
+		}
+	}
+
+	#[must_use]
+	pub fn encode_into_conf(maps: Vec<HashMap<CEntryId, CSentMessage>>) -> Self {
+		let mut maps = maps
+			.into_iter()
+			.map(|map| {
+				map.into_iter()
+					.map(|(eid, msg)| {
+						(
+							EntryId::encode_into_conf(eid),
+							SentMessage::encode_into_conf(msg),
+						)
+					})
+					.collect::<HashMap<_, _>>()
+			})
+			.collect::<Vec<_>>();
+
+		// keep the old format for tasks with a single sink
+		if maps.len() <= 1 {
+			Self::Single(maps.pop().unwrap_or_default())
+		} else {
+			Self::PerSink(maps)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+	use super::*;
+
+	#[test]
+	fn decode_with_and_without_hashes() {
+		let conf: EntryToMsgMap =
+			serde_json::from_str(r#"{"old": 1, "new": {"id": 2, "hash": 3}}"#).unwrap();
+		let maps = conf.decode_from_conf();
+
+		let old = maps[0][&CEntryId("old".to_owned())];
+		assert_eq!((old.id.0, old.hash), (1, None));
+
+		let new = maps[0][&CEntryId("new".to_owned())];
+		assert_eq!((new.id.0, new.hash), (2, Some(CContentHash(3))));
 	}
 }
@@ -0,0 +1,81 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::jobs::{action::Action, read_filter::Kind, sink::Sink};
+
+/// All settings of a task that are checked for combinations that don't make sense,
+/// borrowed either from the task itself or from the job it inherits them from
+#[derive(Clone, Copy, Debug)]
+pub struct TaskSettings<'a> {
+	pub read_filter_kind: Option<&'a [Kind]>,
+	pub max_entry_age: Option<&'a str>,
+	pub has_read_list: bool,
+	pub actions: Option<&'a [Action]>,
+	pub entry_to_msg_map_enabled: Option<bool>,
+	pub update_in_place: Option<bool>,
+	pub delete_removed: Option<bool>,
+	pub sink: Option<&'a [Sink]>,
+}
+
+impl TaskSettings<'_> {
+	/// Find settings that are valid on their own but don't make sense together
+	#[must_use]
+	pub fn lint(&self) -> Vec<&'static str> {
+		let mut problems = Vec::new();
+
+		let kinds = self.read_filter_kind.unwrap_or_default();
+		let sinks = self.sink.unwrap_or_default();
+
+		if sinks.is_empty() {
+			problems.push("no sink is set, entries won't be sent anywhere");
+		} else if self.actions.is_none() {
+			problems.push(
+				"sink is set but process isn't, so the sinks are ignored. Set process to an empty list if nothing needs to be processed",
+			);
+		}
+
+		if self.entry_to_msg_map_enabled == Some(true)
+			&& !sinks.iter().any(Sink::supports_message_ids)
+		{
+			problems.push(
+				"entry_to_msg_map_enabled is set but none of the sinks support message ids, so the map will stay empty",
+			);
+		}
+
+		if self.update_in_place == Some(true) && !sinks.iter().any(Sink::supports_message_ids) {
+			problems.push(
+				"update_in_place is set but none of the sinks support message ids, so nothing can be edited",
+			);
+		}
+
+		if self.update_in_place == Some(true)
+			&& kinds.iter().any(|kind| *kind != Kind::NotOlderThan)
+		{
+			problems.push(
+				"update_in_place is set together with a read filter that keeps track of read entries, so sent entries are filtered out before they can be updated",
+			);
+		}
+
+		if self.delete_removed == Some(true) && !sinks.iter().any(Sink::supports_message_ids) {
+			problems.push(
+				"delete_removed is set but none of the sinks support message ids, so nothing can be deleted",
+			);
+		}
+
+		if self.max_entry_age.is_some() && !kinds.contains(&Kind::NotOlderThan) {
+			problems
+				.push("max_entry_age is set but is only used by the not_older_than read filter");
+		}
+
+		if self.has_read_list && !kinds.contains(&Kind::NotPresentInReadList) {
+			problems.push(
+				"read_list is set but is only used by the not_present_in_read_list read filter",
+			);
+		}
+
+		problems
+	}
+}
@@ -13,5 +13,6 @@ pub mod error;
 pub mod jobs;
 mod serde_extentions;
 pub mod settings;
+pub mod suggestion;
 
 pub use self::error::FetcherConfigError;
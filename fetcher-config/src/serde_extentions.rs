@@ -74,3 +74,199 @@ pub mod tuple {
 		}
 	}
 }
+
+/// Deserialize an externally tagged enum that also accepts the bare names of some of its variants with an optional payload,
+/// e.g. `auto` as a shorthand for `auto: ~`
+pub mod shorthand {
+	use schemars::Schema;
+	use serde::{
+		Deserializer,
+		de::{
+			IntoDeserializer, MapAccess, Visitor,
+			value::{MapAccessDeserializer, MapDeserializer},
+		},
+	};
+	use std::{fmt, iter, marker::PhantomData};
+
+	/// An enum that derives its (de)serialization with `#[serde(remote = "Self")]`
+	pub trait Shorthands: Sized {
+		/// Names of the variants with an `Option` payload that can be written without it
+		const SHORTHANDS: &'static [&'static str];
+
+		/// The derived deserialization of the enum itself
+		fn deserialize_tagged<'de, D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: Deserializer<'de>;
+	}
+
+	pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+	where
+		D: Deserializer<'de>,
+		T: Shorthands,
+	{
+		deserializer.deserialize_any(ShorthandVisitor { _t: PhantomData })
+	}
+
+	/// Add the shorthands to the string variants of the derived schema of `T`
+	pub fn transform_schema<T: Shorthands>(schema: &mut Schema) {
+		let Some(variants) = schema
+			.get_mut("oneOf")
+			.and_then(|one_of| one_of.as_array_mut())
+		else {
+			return;
+		};
+
+		let string_variants = variants
+			.iter_mut()
+			.find_map(|variant| variant.get_mut("enum").and_then(|e| e.as_array_mut()));
+
+		match string_variants {
+			Some(string_variants) => {
+				string_variants.extend(T::SHORTHANDS.iter().map(|&name| name.into()));
+			}
+			None => variants.insert(
+				0,
+				serde_json::json!({
+					"type": "string",
+					"enum": T::SHORTHANDS,
+				}),
+			),
+		}
+	}
+
+	struct ShorthandVisitor<T> {
+		_t: PhantomData<T>,
+	}
+
+	impl<'de, T> Visitor<'de> for ShorthandVisitor<T>
+	where
+		T: Shorthands,
+	{
+		type Value = T;
+
+		fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+			f.write_str("a variant name or a map with a single variant")
+		}
+
+		fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+		where
+			E: serde::de::Error,
+		{
+			if T::SHORTHANDS.contains(&v) {
+				// same as `v: ~`
+				return T::deserialize_tagged(MapAccessDeserializer::new(MapDeserializer::new(
+					iter::once((v, ())),
+				)));
+			}
+
+			T::deserialize_tagged(v.into_deserializer())
+		}
+
+		fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+		where
+			A: MapAccess<'de>,
+		{
+			T::deserialize_tagged(MapAccessDeserializer::new(map))
+		}
+	}
+}
+
+/// Like [`serde_with::OneOrMany`] but reports the error of a single value as is
+/// instead of hiding it behind "could not deserialize any variant", to be able to suggest the right name for a misspelled one
+pub mod exact_one_or_many {
+	use schemars::{JsonSchema, Schema, SchemaGenerator};
+	use serde::{
+		Deserialize, Deserializer, Serialize, Serializer,
+		de::{
+			IntoDeserializer, MapAccess, SeqAccess, Visitor,
+			value::{MapAccessDeserializer, StrDeserializer},
+		},
+	};
+	use serde_with::{DeserializeAs, OneOrMany, Same, SerializeAs, schemars_1::JsonSchemaAs};
+	use std::{borrow::Cow, fmt, marker::PhantomData};
+
+	pub struct ExactOneOrMany;
+
+	impl<'de, T> DeserializeAs<'de, Vec<T>> for ExactOneOrMany
+	where
+		T: Deserialize<'de>,
+	{
+		fn deserialize_as<D>(deserializer: D) -> Result<Vec<T>, D::Error>
+		where
+			D: Deserializer<'de>,
+		{
+			deserializer.deserialize_any(OneOrManyVisitor { _t: PhantomData })
+		}
+	}
+
+	impl<T> SerializeAs<Vec<T>> for ExactOneOrMany
+	where
+		T: Serialize,
+	{
+		fn serialize_as<S>(source: &Vec<T>, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: Serializer,
+		{
+			<OneOrMany<Same> as SerializeAs<Vec<T>>>::serialize_as(source, serializer)
+		}
+	}
+
+	impl<T> JsonSchemaAs<Vec<T>> for ExactOneOrMany
+	where
+		T: JsonSchema,
+	{
+		fn schema_name() -> Cow<'static, str> {
+			<OneOrMany<Same> as JsonSchemaAs<Vec<T>>>::schema_name()
+		}
+
+		fn schema_id() -> Cow<'static, str> {
+			<OneOrMany<Same> as JsonSchemaAs<Vec<T>>>::schema_id()
+		}
+
+		fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+			<OneOrMany<Same> as JsonSchemaAs<Vec<T>>>::json_schema(generator)
+		}
+	}
+
+	struct OneOrManyVisitor<T> {
+		_t: PhantomData<T>,
+	}
+
+	impl<'de, T> Visitor<'de> for OneOrManyVisitor<T>
+	where
+		T: Deserialize<'de>,
+	{
+		type Value = Vec<T>;
+
+		fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+			f.write_str("a single value or a list of them")
+		}
+
+		fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+		where
+			E: serde::de::Error,
+		{
+			let de: StrDeserializer<'_, E> = v.into_deserializer();
+			Ok(vec![T::deserialize(de)?])
+		}
+
+		fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+		where
+			A: MapAccess<'de>,
+		{
+			Ok(vec![T::deserialize(MapAccessDeserializer::new(map))?])
+		}
+
+		fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+		where
+			A: SeqAccess<'de>,
+		{
+			let mut values = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+			while let Some(value) = seq.next_element()? {
+				values.push(value);
+			}
+
+			Ok(values)
+		}
+	}
+}
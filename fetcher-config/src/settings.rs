@@ -7,9 +7,15 @@
 pub mod discord;
 pub mod email_password;
 pub mod google;
+pub mod microsoft;
+pub mod reddit;
+pub mod slack;
 pub mod telegram;
 
 pub use self::discord::Discord;
 pub use self::email_password::EmailPassword;
 pub use self::google::Google;
+pub use self::microsoft::Microsoft;
+pub use self::reddit::Reddit;
+pub use self::slack::Slack;
 pub use self::telegram::Telegram;
@@ -0,0 +1,54 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use fetcher_core::source::reddit::RedditAuth as CRedditAuth;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Reddit {
+	client_id: String,
+	client_secret: String,
+	username: String,
+	password: String,
+}
+
+impl Reddit {
+	#[must_use]
+	pub fn decode_from_conf(self) -> CRedditAuth {
+		let Self {
+			client_id,
+			client_secret,
+			username,
+			password,
+		} = self;
+
+		CRedditAuth {
+			client_id,
+			client_secret,
+			username,
+			password,
+		}
+	}
+
+	#[must_use]
+	pub fn encode_into_conf(auth: CRedditAuth) -> Self {
+		let CRedditAuth {
+			client_id,
+			client_secret,
+			username,
+			password,
+		} = auth;
+
+		Self {
+			client_id,
+			client_secret,
+			username,
+			password,
+		}
+	}
+}
@@ -0,0 +1,28 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Slack {
+	pub webhook_url: Url,
+}
+
+impl Slack {
+	#[must_use]
+	pub fn decode_from_conf(self) -> Url {
+		let Self { webhook_url } = self;
+
+		webhook_url
+	}
+
+	#[must_use]
+	pub fn encode_into_conf(webhook_url: Url) -> Self {
+		Self { webhook_url }
+	}
+}
@@ -0,0 +1,78 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Suggestions of the right key or variant name for a misspelled one found in a config
+
+/// Find the name in `expected` that `found` was most likely meant to be, e.g. `title_query` for `titel_query`.
+///
+/// Returns None if none of them is close enough to be a plausible typo
+#[must_use]
+pub fn did_you_mean<'a>(found: &str, expected: &[&'a str]) -> Option<&'a str> {
+	// allow about one typo per every 3 characters but at least one
+	let max_distance = (found.chars().count() / 3).max(1);
+
+	expected
+		.iter()
+		.map(|name| (*name, edit_distance(found, name)))
+		.filter(|(_, distance)| *distance <= max_distance)
+		.min_by_key(|(_, distance)| *distance)
+		.map(|(name, _)| name)
+}
+
+/// Levenshtein distance, i.e. the number of single character insertions, deletions, and substitutions to turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+	let b = b.chars().collect::<Vec<_>>();
+
+	// distances from the current prefix of `a` to every prefix of `b`
+	let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+	for (i, a_ch) in a.chars().enumerate() {
+		let mut diagonal = row[0];
+		row[0] = i + 1;
+
+		for (j, b_ch) in b.iter().enumerate() {
+			let substitution = diagonal + usize::from(a_ch != *b_ch);
+			diagonal = row[j + 1];
+			row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+		}
+	}
+
+	row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn edit_distance_counts_edits() {
+		assert_eq!(edit_distance("", ""), 0);
+		assert_eq!(edit_distance("refresh", "refresh"), 0);
+		assert_eq!(edit_distance("", "abc"), 3);
+		assert_eq!(edit_distance("abc", ""), 3);
+		assert_eq!(edit_distance("refrsh", "refresh"), 1);
+		assert_eq!(edit_distance("refreshh", "refresh"), 1);
+		assert_eq!(edit_distance("refresj", "refresh"), 1);
+		assert_eq!(edit_distance("titel", "title"), 2);
+		assert_eq!(edit_distance("kitten", "sitting"), 3);
+	}
+
+	#[test]
+	fn edit_distance_counts_chars_not_bytes() {
+		assert_eq!(edit_distance("héllo", "hello"), 1);
+		assert_eq!(edit_distance("日本", "日本語"), 1);
+	}
+
+	#[test]
+	fn did_you_mean_closest() {
+		let expected = ["title_query", "text_query", "link", "id"];
+
+		assert_eq!(did_you_mean("titel_query", &expected), Some("title_query"));
+		assert_eq!(did_you_mean("lnk", &expected), Some("link"));
+		assert_eq!(did_you_mean("titel", &expected), None);
+		assert_eq!(did_you_mean("body", &expected), None);
+	}
+}
@@ -8,6 +8,7 @@
 
 pub mod filter;
 pub mod transform;
+pub mod when;
 
 use crate::sink::Sink;
 
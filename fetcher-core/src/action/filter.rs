@@ -7,9 +7,12 @@
 //! This module contains the [`Filter`] trait that can be implemented in filters as well as all types that implement it
 
 pub mod contains;
+pub mod dedup;
+pub mod rules;
+pub mod sort;
 pub mod take;
 
-pub use self::{contains::Contains, take::Take};
+pub use self::{contains::Contains, dedup::Dedup, rules::Rules, sort::Sort, take::Take};
 
 use crate::entry::Entry;
 
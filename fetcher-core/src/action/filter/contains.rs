@@ -7,7 +7,7 @@
 //! This module contains the [`Contains`] filter
 
 use async_trait::async_trait;
-use regex::Regex;
+use regex::{Regex, RegexBuilder};
 use std::borrow::Cow;
 
 use super::Filter;
@@ -29,31 +29,101 @@ impl Contains {
 	/// # Errors
 	/// if the regex is invalid
 	pub fn new(regex: &str, field: Field) -> Result<Self, BadRegexError> {
+		Self::with_options(regex, field, true, true)
+	}
+
+	/// Create a new [`Contains`] that matches `pattern` against `field`.
+	///
+	/// `pattern` is treated as a regular expression if `is_regex` is set, or as plain text that should be contained somewhere in the field otherwise.
+	/// If `case_sensitive` isn't set, the case of the letters is ignored when matching
+	///
+	/// # Errors
+	/// if `is_regex` is set and the regex is invalid
+	pub fn with_options(
+		pattern: &str,
+		field: Field,
+		is_regex: bool,
+		case_sensitive: bool,
+	) -> Result<Self, BadRegexError> {
+		let pattern = if is_regex {
+			Cow::Borrowed(pattern)
+		} else {
+			Cow::Owned(regex::escape(pattern))
+		};
+
 		Ok(Self {
-			re: Regex::new(regex)?,
+			re: RegexBuilder::new(&pattern)
+				.case_insensitive(!case_sensitive)
+				.build()?,
 			field,
 		})
 	}
+
+	/// Check if the field of the `entry` is set and matches the regular expression
+	#[must_use]
+	pub fn matches(&self, entry: &Entry) -> bool {
+		match self.field.get_from(entry) {
+			Some(field) => self.re.is_match(&field),
+			None => false,
+		}
+	}
 }
 
 #[async_trait]
 impl Filter for Contains {
 	/// Filter out some entries out of the `entries` vector
 	async fn filter(&self, entries: &mut Vec<Entry>) {
-		entries.retain(|ent| {
-			let field = match self.field {
-				Field::Title => ent.msg.title.as_deref().map(Cow::Borrowed),
-				Field::Body => ent.msg.body.as_deref().map(Cow::Borrowed),
-				Field::Link => ent.msg.link.as_ref().map(|s| Cow::Owned(s.to_string())),
-				Field::Id => ent.id.as_ref().map(|id| Cow::Borrowed(id.0.as_str())),
-				Field::ReplyTo => ent.reply_to.as_ref().map(|id| Cow::Borrowed(id.0.as_str())),
-				Field::RawContets => ent.raw_contents.as_deref().map(Cow::Borrowed),
-			};
-
-			match field {
-				Some(field) => self.re.is_match(&field),
-				None => false,
-			}
-		});
+		entries.retain(|ent| self.matches(ent));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+	use super::*;
+	use crate::sink::message::Message;
+
+	fn titled(titles: &[&str]) -> Vec<Entry> {
+		titles
+			.iter()
+			.map(|title| Entry {
+				msg: Message {
+					title: Some((*title).to_owned()),
+					..Default::default()
+				},
+				..Default::default()
+			})
+			.collect()
+	}
+
+	fn titles(entries: &[Entry]) -> Vec<&str> {
+		entries
+			.iter()
+			.filter_map(|e| e.msg.title.as_deref())
+			.collect()
+	}
+
+	#[tokio::test]
+	async fn regex_case_insensitive() {
+		let mut entries = titled(&["[Release] v1", "[release] v2", "Re: [Release] v1"]);
+
+		Contains::with_options(r"^\[release\]", Field::Title, true, false)
+			.unwrap()
+			.filter(&mut entries)
+			.await;
+
+		assert_eq!(titles(&entries), ["[Release] v1", "[release] v2"]);
+	}
+
+	#[tokio::test]
+	async fn plain_text() {
+		let mut entries = titled(&["v1.0 (stable)", "v1a0", "V1.0 (STABLE)"]);
+
+		Contains::with_options("1.0 (stable)", Field::Title, false, true)
+			.unwrap()
+			.filter(&mut entries)
+			.await;
+
+		assert_eq!(titles(&entries), ["v1.0 (stable)"]);
 	}
 }
@@ -0,0 +1,119 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`Dedup`] filter
+
+use async_trait::async_trait;
+use std::{borrow::Cow, collections::HashSet};
+
+use super::Filter;
+use crate::{action::transform::field::Field, entry::Entry};
+
+/// Filter out all entries whose fields are identical to the ones of an entry earlier in the list.
+///
+/// Unlike a [`ReadFilter`](`crate::read_filter::ReadFilter`), this only works within a single batch of entries
+/// and compares their contents instead of their ids
+#[derive(Clone, Debug)]
+pub struct Dedup {
+	/// The fields that together make up the key two entries are compared by.
+	/// Entries that have none of these fields set are never considered duplicates
+	pub fields: Vec<Field>,
+}
+
+impl Default for Dedup {
+	/// Compare entries by their title and body
+	fn default() -> Self {
+		Self {
+			fields: vec![Field::Title, Field::Body],
+		}
+	}
+}
+
+#[async_trait]
+impl Filter for Dedup {
+	async fn filter(&self, entries: &mut Vec<Entry>) {
+		let num_og_entries = entries.len();
+		let mut seen = HashSet::new();
+
+		entries.retain(|ent| {
+			let key = self
+				.fields
+				.iter()
+				.map(|field| field.get_from(ent).map(Cow::into_owned))
+				.collect::<Vec<_>>();
+
+			if key.iter().all(Option::is_none) {
+				return true;
+			}
+
+			seen.insert(key)
+		});
+
+		let num_removed = num_og_entries - entries.len();
+		if num_removed > 0 {
+			tracing::debug!("Removed {num_removed} duplicate entries");
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sink::message::Message;
+
+	fn entry(title: Option<&str>, body: Option<&str>) -> Entry {
+		Entry {
+			msg: Message {
+				title: title.map(ToOwned::to_owned),
+				body: body.map(ToOwned::to_owned),
+				..Default::default()
+			},
+			..Default::default()
+		}
+	}
+
+	fn titles(entries: &[Entry]) -> Vec<Option<&str>> {
+		entries.iter().map(|e| e.msg.title.as_deref()).collect()
+	}
+
+	#[tokio::test]
+	async fn by_title_and_body() {
+		let mut entries = vec![
+			entry(Some("a"), Some("1")),
+			entry(Some("a"), Some("2")),
+			entry(Some("a"), Some("1")),
+			entry(Some("b"), Some("1")),
+		];
+
+		Dedup::default().filter(&mut entries).await;
+		assert_eq!(titles(&entries), [Some("a"), Some("a"), Some("b")]);
+		assert_eq!(entries[1].msg.body.as_deref(), Some("2"));
+	}
+
+	#[tokio::test]
+	async fn by_single_field() {
+		let mut entries = vec![
+			entry(Some("a"), Some("1")),
+			entry(Some("a"), Some("2")),
+			entry(Some("b"), Some("1")),
+		];
+
+		Dedup {
+			fields: vec![Field::Title],
+		}
+		.filter(&mut entries)
+		.await;
+		assert_eq!(titles(&entries), [Some("a"), Some("b")]);
+	}
+
+	#[tokio::test]
+	async fn empty_entries_are_kept() {
+		let mut entries = vec![entry(None, None), entry(None, None)];
+
+		Dedup::default().filter(&mut entries).await;
+		assert_eq!(entries.len(), 2);
+	}
+}
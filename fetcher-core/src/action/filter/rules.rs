@@ -0,0 +1,112 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`Rules`] filter, as well as the [`Rule`]s it consists of and the [`Verdict`] they come to
+
+use async_trait::async_trait;
+
+use super::{Contains, Filter};
+use crate::entry::Entry;
+
+/// Keep or drop entries according to a list of rules, e.g. drop everything sponsored but keep the rest if it mentions a keyword.
+///
+/// The rules are checked in order and the first one that matches decides what happens to the entry
+#[derive(Clone, Debug)]
+pub struct Rules {
+	/// The rules to check, in order
+	pub rules: Vec<Rule>,
+
+	/// What to do with the entries none of the rules matched
+	pub otherwise: Verdict,
+}
+
+/// A single rule of [`Rules`]
+#[derive(Clone, Debug)]
+pub struct Rule {
+	/// The regular expression and the field it should match for the rule to apply
+	pub condition: Contains,
+
+	/// What to do with the entry if the rule applies
+	pub verdict: Verdict,
+}
+
+/// Whether to keep or to drop an entry
+#[expect(missing_docs, reason = "names are self-documenting")]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Verdict {
+	Keep,
+	Drop,
+}
+
+impl Rules {
+	fn verdict(&self, entry: &Entry) -> Verdict {
+		self.rules
+			.iter()
+			.find(|rule| rule.condition.matches(entry))
+			.map_or(self.otherwise, |rule| rule.verdict)
+	}
+}
+
+#[async_trait]
+impl Filter for Rules {
+	async fn filter(&self, entries: &mut Vec<Entry>) {
+		entries.retain(|ent| self.verdict(ent) == Verdict::Keep);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+	use super::*;
+	use crate::{action::transform::field::Field, sink::message::Message};
+
+	#[tokio::test]
+	async fn first_matching_rule_wins() {
+		let mut entries = [
+			("Sponsored: keyword inside", "keyword"),
+			("News", "keyword"),
+			("News", "nothing"),
+		]
+		.into_iter()
+		.map(|(title, body)| Entry {
+			msg: Message {
+				title: Some(title.to_owned()),
+				body: Some(body.to_owned()),
+				..Default::default()
+			},
+			..Default::default()
+		})
+		.collect::<Vec<_>>();
+
+		Rules {
+			rules: vec![
+				Rule {
+					condition: Contains::with_options("sponsored", Field::Title, false, false)
+						.unwrap(),
+					verdict: Verdict::Drop,
+				},
+				Rule {
+					condition: Contains::new("keyword", Field::Body).unwrap(),
+					verdict: Verdict::Keep,
+				},
+			],
+			otherwise: Verdict::Drop,
+		}
+		.filter(&mut entries)
+		.await;
+
+		let kept = entries
+			.iter()
+			.map(|e| {
+				(
+					e.msg.title.as_deref().unwrap(),
+					e.msg.body.as_deref().unwrap(),
+				)
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(kept, [("News", "keyword")]);
+	}
+}
@@ -0,0 +1,175 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`Sort`] filter, as well as [`SortBy`] and [`SortOrder`] enums that specify how entries should be ordered
+
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset};
+use std::cmp::Ordering;
+
+use super::Filter;
+use crate::{
+	action::transform::field::{Field, parse_date::parse_date},
+	entry::Entry,
+};
+
+/// Reorder entries by the value of a field. Doesn't remove any entries.
+///
+/// The sort is stable, i.e. entries that compare equal keep their relative order.
+/// Entries that don't have the field set (or whose field couldn't be parsed) are always put last
+#[derive(Clone, Debug)]
+pub struct Sort {
+	/// The field to sort by
+	pub field: Field,
+
+	/// What to compare the field by
+	pub by: SortBy,
+
+	/// Sort in ascending or descending order
+	pub order: SortOrder,
+}
+
+/// What to compare the value of the field by
+#[derive(Clone, Copy, Default, Debug)]
+pub enum SortBy {
+	/// The text itself, lexicographically
+	#[default]
+	Value,
+
+	/// The length of the text in characters
+	Length,
+
+	/// The date contained in the text.
+	///
+	/// Supports RFC 3339 (`2024-01-03T10:00:00+00:00`), RFC 2822 (`Wed, 03 Jan 2024 10:00:00 +0000`),
+	/// and plain `2024-01-03 10:00:00` and `2024-01-03` dates
+	Date,
+}
+
+#[expect(missing_docs, reason = "names are self-documenting")]
+#[derive(Clone, Copy, Default, Debug)]
+pub enum SortOrder {
+	#[default]
+	Ascending,
+	Descending,
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum SortKey {
+	Value(String),
+	Length(usize),
+	Date(DateTime<FixedOffset>),
+}
+
+#[async_trait]
+impl Filter for Sort {
+	async fn filter(&self, entries: &mut Vec<Entry>) {
+		let mut keyed = entries
+			.drain(..)
+			.map(|ent| (self.key_of(&ent), ent))
+			.collect::<Vec<_>>();
+
+		// sort_by is stable
+		keyed.sort_by(|(a, _), (b, _)| match (a, b) {
+			(Some(a), Some(b)) => match self.order {
+				SortOrder::Ascending => a.cmp(b),
+				SortOrder::Descending => b.cmp(a),
+			},
+			(Some(_), None) => Ordering::Less,
+			(None, Some(_)) => Ordering::Greater,
+			(None, None) => Ordering::Equal,
+		});
+
+		entries.extend(keyed.into_iter().map(|(_, ent)| ent));
+	}
+}
+
+impl Sort {
+	fn key_of(&self, entry: &Entry) -> Option<SortKey> {
+		let val = self.field.get_from(entry)?;
+
+		Some(match self.by {
+			SortBy::Value => SortKey::Value(val.into_owned()),
+			SortBy::Length => SortKey::Length(val.chars().count()),
+			SortBy::Date => SortKey::Date(parse_date(&val)?),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sink::message::Message;
+
+	fn entries(bodies: &[Option<&str>]) -> Vec<Entry> {
+		bodies
+			.iter()
+			.enumerate()
+			.map(|(i, body)| Entry {
+				id: Some(i.to_string().into()),
+				msg: Message {
+					body: body.map(ToOwned::to_owned),
+					..Default::default()
+				},
+				..Default::default()
+			})
+			.collect()
+	}
+
+	fn ids(entries: &[Entry]) -> Vec<&str> {
+		entries.iter().filter_map(|e| e.id.as_deref()).collect()
+	}
+
+	#[tokio::test]
+	async fn by_value_stable() {
+		let mut ents = entries(&[Some("b"), Some("a"), None, Some("b"), Some("a")]);
+
+		Sort {
+			field: Field::Body,
+			by: SortBy::Value,
+			order: SortOrder::Ascending,
+		}
+		.filter(&mut ents)
+		.await;
+
+		assert_eq!(ids(&ents), ["1", "4", "0", "3", "2"]);
+	}
+
+	#[tokio::test]
+	async fn by_length_descending() {
+		let mut ents = entries(&[Some("aa"), None, Some("a"), Some("aaa"), Some("bb")]);
+
+		Sort {
+			field: Field::Body,
+			by: SortBy::Length,
+			order: SortOrder::Descending,
+		}
+		.filter(&mut ents)
+		.await;
+
+		assert_eq!(ids(&ents), ["3", "0", "4", "2", "1"]);
+	}
+
+	#[tokio::test]
+	async fn by_date() {
+		let mut ents = entries(&[
+			Some("Wed, 03 Jan 2024 10:00:00 +0000"),
+			Some("not a date"),
+			Some("2023-12-31"),
+			Some("2024-01-02T10:00:00+00:00"),
+		]);
+
+		Sort {
+			field: Field::Body,
+			by: SortBy::Date,
+			order: SortOrder::Ascending,
+		}
+		.filter(&mut ents)
+		.await;
+
+		assert_eq!(ids(&ents), ["2", "3", "0", "1"]);
+	}
+}
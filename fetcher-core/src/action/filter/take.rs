@@ -4,7 +4,9 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-//! This module contains the [`Take`] filter and the [`TakeFrom`] enum that specifies where the [`Take`] filter should take the entries from
+//! This module contains the [`Take`] filter
+//!
+//! As well as the [`TakeFrom`] and [`TakeAmount`] enums that specify where the [`Take`] filter should take the entries from and how many of them
 
 use async_trait::async_trait;
 
@@ -16,8 +18,8 @@ use crate::entry::Entry;
 pub struct Take {
 	/// Take from the Beginning or the end of the list?
 	pub from: TakeFrom,
-	/// Take this number of entries
-	pub num: usize,
+	/// Take this many entries
+	pub amount: TakeAmount,
 }
 
 #[expect(missing_docs, reason = "names are self-documenting")]
@@ -27,17 +29,93 @@ pub enum TakeFrom {
 	End,
 }
 
+/// How many entries to take
+#[derive(Clone, Copy, Debug)]
+pub enum TakeAmount {
+	/// Take this exact number of entries, or all of them if there are fewer
+	Count(usize),
+
+	/// Take this percentage (0-100) of all entries, rounded down
+	Percent(u8),
+}
+
 #[async_trait]
 impl Filter for Take {
 	async fn filter(&self, entries: &mut Vec<Entry>) {
+		let num = self.amount.of(entries.len());
+
 		match self.from {
 			TakeFrom::Beginning => {
-				entries.truncate(self.num);
+				entries.truncate(num);
 			}
 			TakeFrom::End => {
-				let first = entries.len() - self.num;
+				let first = entries.len() - num;
 				entries.drain(..first);
 			}
 		}
 	}
 }
+
+impl TakeAmount {
+	/// Get the number of entries to take out of `len` entries. Never more than `len`
+	fn of(self, len: usize) -> usize {
+		match self {
+			TakeAmount::Count(num) => num.min(len),
+			TakeAmount::Percent(percent) => len * usize::from(percent.min(100)) / 100,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn entries(num: usize) -> Vec<Entry> {
+		(0..num)
+			.map(|i| Entry {
+				id: Some(i.to_string().into()),
+				..Default::default()
+			})
+			.collect()
+	}
+
+	fn ids(entries: &[Entry]) -> Vec<&str> {
+		entries.iter().filter_map(|e| e.id.as_deref()).collect()
+	}
+
+	async fn take(from: TakeFrom, amount: TakeAmount, num_entries: usize) -> Vec<Entry> {
+		let mut entries = entries(num_entries);
+		Take { from, amount }.filter(&mut entries).await;
+		entries
+	}
+
+	#[tokio::test]
+	async fn from_beginning() {
+		let entries = take(TakeFrom::Beginning, TakeAmount::Count(2), 4).await;
+		assert_eq!(ids(&entries), ["0", "1"]);
+	}
+
+	#[tokio::test]
+	async fn from_end() {
+		let entries = take(TakeFrom::End, TakeAmount::Count(2), 4).await;
+		assert_eq!(ids(&entries), ["2", "3"]);
+	}
+
+	#[tokio::test]
+	async fn more_than_available() {
+		let entries = take(TakeFrom::Beginning, TakeAmount::Count(10), 3).await;
+		assert_eq!(ids(&entries), ["0", "1", "2"]);
+
+		let entries = take(TakeFrom::End, TakeAmount::Count(10), 3).await;
+		assert_eq!(ids(&entries), ["0", "1", "2"]);
+	}
+
+	#[tokio::test]
+	async fn percent() {
+		let entries = take(TakeFrom::Beginning, TakeAmount::Percent(50), 5).await;
+		assert_eq!(ids(&entries), ["0", "1"]);
+
+		let entries = take(TakeFrom::End, TakeAmount::Percent(100), 3).await;
+		assert_eq!(ids(&entries), ["0", "1", "2"]);
+	}
+}
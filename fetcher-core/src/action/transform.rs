@@ -9,12 +9,16 @@
 pub mod entry;
 pub mod field;
 pub mod result;
+pub mod template;
 
 pub mod error;
 
 pub use self::{
-	entry::{feed::Feed, html::Html, http::Http, json::Json, print::DebugPrint, use_as::Use},
-	field::{caps::Caps, set::Set, shorten::Shorten, trim::Trim},
+	entry::{
+		feed::Feed, html::Html, http::Http, json::Json, print::DebugPrint, translate::Translate,
+		use_as::Use, xml::Xml,
+	},
+	field::{case::Case, set::Set, shorten::Shorten, trim::Trim},
 };
 
 use self::error::TransformError;
@@ -26,7 +30,7 @@ use std::fmt::Debug;
 /// Transform an [`Entry`] into one or more new (entries)[`Entry`].
 ///
 /// For example, a [`Json`] transform parses the contents of the [`Entry`] as JSON and returns new entries from it,
-/// while the [`Caps`] field transform just makes a field uppercase
+/// while the [`Case`] field transform just changes the case of a field
 #[async_trait]
 pub trait Transform: Debug + Send + Sync {
 	/// Transform an [`Entry`] to one or more entries
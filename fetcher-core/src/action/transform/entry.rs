@@ -6,12 +6,19 @@
 
 //! This module contains the [`TransformEntry`] trait as well as every type that implement it
 
+pub mod auto;
+pub mod extract_fields;
 pub mod feed;
 pub mod html;
 pub mod http;
 pub mod json;
+pub mod json_feed;
+pub mod open_graph;
 pub mod print;
+pub mod split;
+pub mod translate;
 pub mod use_as;
+pub mod xml;
 
 use async_trait::async_trait;
 
@@ -0,0 +1,233 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`Auto`] transform that detects the format of the raw contents and parses them accordingly
+
+use super::{
+	TransformEntry,
+	feed::{Feed, FeedError},
+	html::{Html, HtmlError},
+	json::{Json, JsonError},
+	json_feed::{self, JsonFeed, JsonFeedError},
+};
+use crate::{
+	action::transform::{
+		error::RawContentsNotSetError,
+		result::{OptionUnwrapTransformResultExt, TransformedEntry, TransformedMessage},
+	},
+	entry::Entry,
+};
+
+use async_trait::async_trait;
+use soup_kuchiki::{NodeExt, QueryBuilderExt, Soup};
+
+/// Detects whether the raw contents of an entry are an RSS/Atom feed, a JSON Feed, other JSON, or HTML and parses them with the matching transform.
+///
+/// The format is detected from the contents themselves since the content type of the response isn't preserved
+#[derive(Default, Debug)]
+pub struct Auto {
+	/// Parser for RSS and Atom feeds
+	pub feed: Feed,
+
+	/// Parser for JSON that isn't a JSON Feed. Such JSON is an error if it's not set
+	pub json: Option<Json>,
+
+	/// Parser for HTML. If it's not set, the title and the description of the page are used instead
+	pub html: Option<Html>,
+}
+
+/// The format of the raw contents of an entry
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+	/// An RSS or Atom feed
+	Feed,
+	/// A feed in the [JSON Feed](https://jsonfeed.org) format
+	JsonFeed,
+	/// Any other JSON
+	Json,
+	/// HTML, or anything else that can't be recognized
+	Html,
+}
+
+#[expect(missing_docs, reason = "error message is self-documenting")]
+#[derive(thiserror::Error, Debug)]
+pub enum AutoError {
+	#[error(transparent)]
+	RawContentsNotSet(#[from] RawContentsNotSetError),
+
+	#[error("Got JSON that isn't a JSON Feed but no json query to parse it with is set")]
+	JsonQueryMissing,
+
+	#[error(transparent)]
+	Feed(#[from] FeedError),
+
+	#[error(transparent)]
+	JsonFeed(#[from] JsonFeedError),
+
+	#[error(transparent)]
+	Json(#[from] JsonError),
+
+	#[error(transparent)]
+	Html(#[from] HtmlError),
+}
+
+#[async_trait]
+impl TransformEntry for Auto {
+	type Err = AutoError;
+
+	async fn transform_entry(&self, entry: Entry) -> Result<Vec<TransformedEntry>, Self::Err> {
+		let format = detect(
+			entry
+				.raw_contents
+				.as_deref()
+				.ok_or(RawContentsNotSetError)?,
+		);
+		tracing::debug!("Detected the format of the entry as {format:?}");
+
+		Ok(match format {
+			Format::Feed => self.feed.transform_entry(entry).await?,
+			Format::JsonFeed => JsonFeed.transform_entry(entry).await?,
+			Format::Json => match &self.json {
+				Some(json) => json.transform_entry(entry).await?,
+				None => return Err(AutoError::JsonQueryMissing),
+			},
+			Format::Html => match &self.html {
+				Some(html) => html.transform_entry(entry).await?,
+				None => vec![page_summary(&entry)],
+			},
+		})
+	}
+}
+
+/// Detect the format of `contents` by sniffing its beginning
+#[must_use]
+pub fn detect(contents: &str) -> Format {
+	let contents = contents.trim_start_matches(['\u{feff}', ' ', '\t', '\r', '\n']);
+
+	if contents.starts_with(['{', '[']) {
+		let is_json_feed = serde_json::from_str::<serde_json::Value>(contents)
+			.ok()
+			.as_ref()
+			.and_then(|json| json.get("version")?.as_str())
+			.is_some_and(|version| version.starts_with(json_feed::VERSION_PREFIX));
+
+		return if is_json_feed {
+			Format::JsonFeed
+		} else {
+			Format::Json
+		};
+	}
+
+	// the root element of a feed should be somewhere at the very beginning, after the XML declaration and maybe some comments
+	let head = contents
+		.char_indices()
+		.nth(1024)
+		.map_or(contents, |(end, _)| &contents[..end]);
+
+	if ["<rss", "<feed", "<rdf:RDF"]
+		.iter()
+		.any(|root| head.contains(root))
+	{
+		Format::Feed
+	} else {
+		Format::Html
+	}
+}
+
+/// A single entry with the title and the description of the page, like a link preview
+fn page_summary(entry: &Entry) -> TransformedEntry {
+	let dom = Soup::new(entry.raw_contents.as_deref().unwrap_or_default()).get_handle();
+
+	let title = dom
+		.tag("title")
+		.find()
+		.map(|title| title.text().trim().to_owned())
+		.filter(|title| !title.is_empty());
+
+	let description = dom
+		.tag("meta")
+		.find_all()
+		.find(|meta| {
+			meta.get("name").as_deref() == Some("description")
+				|| meta.get("property").as_deref() == Some("og:description")
+		})
+		.and_then(|meta| meta.get("content"))
+		.map(|description| description.trim().to_owned())
+		.filter(|description| !description.is_empty());
+
+	TransformedEntry {
+		msg: TransformedMessage {
+			title: title.unwrap_or_prev(),
+			body: description.unwrap_or_prev(),
+			..Default::default()
+		},
+		..Default::default()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+	use super::*;
+
+	#[test]
+	fn detect_rss() {
+		assert_eq!(
+			detect("<?xml version=\"1.0\"?>\n<rss version=\"2.0\"><channel></channel></rss>"),
+			Format::Feed
+		);
+	}
+
+	#[test]
+	fn detect_atom() {
+		assert_eq!(
+			detect("<feed xmlns=\"http://www.w3.org/2005/Atom\"></feed>"),
+			Format::Feed
+		);
+	}
+
+	#[test]
+	fn detect_json_feed() {
+		assert_eq!(
+			detect(r#" {"version": "https://jsonfeed.org/version/1.1", "items": []}"#),
+			Format::JsonFeed
+		);
+	}
+
+	#[test]
+	fn detect_json() {
+		assert_eq!(detect(r#"{"items": []}"#), Format::Json);
+	}
+
+	#[test]
+	fn detect_html() {
+		assert_eq!(
+			detect("<!DOCTYPE html><html><head><title>Page</title></head></html>"),
+			Format::Html
+		);
+	}
+
+	#[tokio::test]
+	async fn html_without_query() {
+		let entry = Entry {
+			raw_contents: Some(
+				r#"<html><head><title> Page </title><meta name="description" content="About the page"></head><body>Hi</body></html>"#
+					.to_owned(),
+			),
+			..Default::default()
+		};
+
+		let entries = Auto::default().transform_entry(entry).await.unwrap();
+		let entry = entries
+			.into_iter()
+			.next()
+			.unwrap()
+			.into_entry(&Entry::default());
+
+		assert_eq!(entry.msg.title.as_deref(), Some("Page"));
+		assert_eq!(entry.msg.body.as_deref(), Some("About the page"));
+	}
+}
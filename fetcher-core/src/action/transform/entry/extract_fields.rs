@@ -0,0 +1,211 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`ExtractFields`] transform that splits a field into several using named capture groups of a regular expression
+
+use async_trait::async_trait;
+use regex::Regex;
+use url::Url;
+
+use super::TransformEntry;
+use crate::{
+	action::transform::{
+		field::Field,
+		result::{TransformResult, TransformedEntry},
+	},
+	entry::Entry,
+	error::{BadRegexError, InvalidUrlError},
+};
+
+/// Match a regular expression against a field and put the contents of every named capture group into the field with the same name.
+///
+/// For example, `(?P<title>.+?) - (?P<link>https?://\S+)` puts everything before the dash into the title and the URL after it into the link.
+/// Valid group names are `title`, `body`, `link`, `id`, `reply_to`, and `raw_contents`. Fields without a group are left as is
+#[derive(Debug)]
+pub struct ExtractFields {
+	/// The field to match the regular expression against
+	pub from_field: Field,
+
+	re: Regex,
+
+	/// The named capture groups of `re` and the fields they are put into
+	groups: Vec<(String, Field)>,
+
+	/// Leave the entry as is if the regex didn't match
+	passthrough_if_not_found: bool,
+}
+
+#[expect(missing_docs, reason = "error message is self-documenting")]
+#[derive(thiserror::Error, Debug)]
+pub enum ExtractFieldsError {
+	#[error(transparent)]
+	BadRegex(#[from] BadRegexError),
+
+	#[error(
+		"Capture group {0:?} isn't the name of a field. Valid names are title, body, link, id, reply_to, and raw_contents"
+	)]
+	UnknownCaptureGroup(String),
+
+	#[error("The regex doesn't contain any named capture groups")]
+	NoNamedCaptureGroups,
+
+	#[error("The regex didn't match {0} but passthrough_if_not_found is not set")]
+	NotMatched(Field),
+
+	#[error("Capture group {0:?} didn't match anything")]
+	CaptureGroupMissing(String),
+
+	#[error("Capture group \"link\" is not a valid URL")]
+	InvalidUrl(#[from] InvalidUrlError),
+}
+
+impl ExtractFields {
+	/// Create a new [`ExtractFields`] that matches `re` against `from_field`
+	///
+	/// # Errors
+	/// * if the regex is invalid
+	/// * if the regex doesn't contain any named capture groups or one of them isn't the name of a field
+	pub fn new(
+		from_field: Field,
+		re: &str,
+		passthrough_if_not_found: bool,
+	) -> Result<Self, ExtractFieldsError> {
+		let re = Regex::new(re).map_err(BadRegexError)?;
+
+		let groups = re
+			.capture_names()
+			.flatten()
+			.map(|name| {
+				let field = match name {
+					"title" => Field::Title,
+					"body" => Field::Body,
+					"link" => Field::Link,
+					"id" => Field::Id,
+					"reply_to" => Field::ReplyTo,
+					"raw_contents" => Field::RawContets,
+					_ => return Err(ExtractFieldsError::UnknownCaptureGroup(name.to_owned())),
+				};
+
+				Ok((name.to_owned(), field))
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+
+		if groups.is_empty() {
+			return Err(ExtractFieldsError::NoNamedCaptureGroups);
+		}
+
+		Ok(Self {
+			from_field,
+			re,
+			groups,
+			passthrough_if_not_found,
+		})
+	}
+}
+
+#[async_trait]
+impl TransformEntry for ExtractFields {
+	type Err = ExtractFieldsError;
+
+	async fn transform_entry(&self, entry: Entry) -> Result<Vec<TransformedEntry>, Self::Err> {
+		let value = self.from_field.get_from(&entry).unwrap_or_default();
+
+		let Some(captures) = self.re.captures(&value) else {
+			if self.passthrough_if_not_found {
+				return Ok(vec![TransformedEntry::default()]);
+			}
+
+			return Err(ExtractFieldsError::NotMatched(self.from_field));
+		};
+
+		let mut transformed = TransformedEntry::default();
+
+		for (name, field) in &self.groups {
+			let capture = captures
+				.name(name)
+				.ok_or_else(|| ExtractFieldsError::CaptureGroupMissing(name.clone()))?
+				.as_str()
+				.to_owned();
+
+			match field {
+				Field::Title => transformed.msg.title = TransformResult::New(capture),
+				Field::Body => transformed.msg.body = TransformResult::New(capture),
+				Field::Link => {
+					let link = Url::try_from(capture.as_str())
+						.map_err(|e| InvalidUrlError(e, capture.clone()))?;
+					transformed.msg.link = TransformResult::New(link);
+				}
+				Field::Id => transformed.id = TransformResult::New(capture.into()),
+				Field::ReplyTo => transformed.reply_to = TransformResult::New(capture.into()),
+				Field::RawContets => transformed.raw_contents = TransformResult::New(capture),
+			}
+		}
+
+		Ok(vec![transformed])
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+	use super::*;
+	use crate::{action::transform::Transform, sink::message::Message};
+
+	fn entry(body: &str) -> Entry {
+		Entry {
+			msg: Message {
+				body: Some(body.to_owned()),
+				..Default::default()
+			},
+			..Default::default()
+		}
+	}
+
+	#[tokio::test]
+	async fn title_and_link() {
+		let extract = ExtractFields::new(
+			Field::Body,
+			r"(?P<title>.+?) - (?P<link>https?://\S+)",
+			false,
+		)
+		.unwrap();
+
+		let entry = extract
+			.transform(entry("Hello - https://example.com/hello"))
+			.await
+			.unwrap()
+			.remove(0);
+
+		assert_eq!(entry.msg.title.as_deref(), Some("Hello"));
+		assert_eq!(
+			entry.msg.link.as_ref().map(Url::as_str),
+			Some("https://example.com/hello")
+		);
+		assert_eq!(
+			entry.msg.body.as_deref(),
+			Some("Hello - https://example.com/hello")
+		);
+	}
+
+	#[tokio::test]
+	async fn group_missing() {
+		let extract =
+			ExtractFields::new(Field::Body, r"(?P<title>[^-]+)(?: - (?P<id>\d+))?", false).unwrap();
+
+		assert!(matches!(
+			extract.transform_entry(entry("Hello")).await,
+			Err(ExtractFieldsError::CaptureGroupMissing(name)) if name == "id"
+		));
+	}
+
+	#[test]
+	fn unknown_group() {
+		assert!(matches!(
+			ExtractFields::new(Field::Body, "(?P<author>.+)", false),
+			Err(ExtractFieldsError::UnknownCaptureGroup(name)) if name == "author"
+		));
+	}
+}
@@ -6,6 +6,8 @@
 
 //! This module contains the [`Feed`] transform that can parse RSS and Atom feeds
 
+pub use crate::action::transform::template::{Template, TemplateError};
+
 use super::TransformEntry;
 use crate::{
 	action::transform::{
@@ -13,6 +15,7 @@ use crate::{
 		result::{OptionUnwrapTransformResultExt, TransformedEntry, TransformedMessage},
 	},
 	entry::Entry,
+	sink::message::Media,
 };
 
 use async_trait::async_trait;
@@ -20,8 +23,11 @@ use tap::{TapFallible, TapOptional};
 use url::Url;
 
 /// RSS or Atom feed parser
-#[derive(Debug)]
-pub struct Feed;
+#[derive(Default, Debug)]
+pub struct Feed {
+	/// Render the body of every entry using this template instead of just using the summary of the entry
+	pub template: Option<Template>,
+}
 
 #[expect(missing_docs, reason = "error message is self-documenting")]
 #[derive(thiserror::Error, Debug)]
@@ -53,7 +59,7 @@ impl TransformEntry for Feed {
 		let entries = feed
 			.entries
 			.into_iter()
-			.map(|mut feed_entry| {
+			.map(|feed_entry| {
 				let title = feed_entry
 					.title
 					.tap_none(|| tracing::error!("Feed entry doesn't contain a title"))
@@ -67,19 +73,49 @@ impl TransformEntry for Feed {
 					.map(|x| x.content);
 
 				let id = Some(feed_entry.id);
-
-				let link = Url::try_from(feed_entry.links.remove(0).href.as_str())
-					.tap_err(|e| tracing::warn!("A feed entry's link is not a valid URL: {e:?}"))
-					.ok();
+				let published = feed_entry.published.or(feed_entry.updated);
+
+				let link = feed_entry.links.first().and_then(|link| {
+					Url::try_from(link.href.as_str())
+						.tap_err(|e| {
+							tracing::warn!("A feed entry's link is not a valid URL: {e:?}");
+						})
+						.ok()
+				});
+
+				// enclosures, e.g. podcast episodes, as well as Media RSS content
+				let media = feed_entry
+					.media
+					.iter()
+					.flat_map(|media| &media.content)
+					.filter_map(|content| {
+						Media::from_mime_type(
+							content.url.clone()?,
+							content.content_type.as_ref()?.as_str(),
+						)
+					})
+					.collect::<Vec<_>>();
+				let media = (!media.is_empty()).then_some(media);
+
+				let rendered_body = match &self.template {
+					Some(template) => Some(template.render(
+						title.as_deref(),
+						body.as_deref(),
+						link.as_ref(),
+						id.as_deref(),
+					)),
+					None => body.clone(),
+				};
 
 				TransformedEntry {
 					id: id.map(Into::into).unwrap_or_prev(),
-					raw_contents: body.clone().unwrap_or_prev(),
+					raw_contents: body.unwrap_or_prev(),
+					published: published.unwrap_or_prev(),
 					msg: TransformedMessage {
 						title: title.unwrap_or_prev(),
-						body: body.unwrap_or_prev(),
+						body: rendered_body.unwrap_or_prev(),
 						link: link.unwrap_or_prev(),
-						..Default::default()
+						media: media.unwrap_or_prev(),
 					},
 					..Default::default()
 				}
@@ -89,3 +125,38 @@ impl TransformEntry for Feed {
 		Ok(entries)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+	use super::*;
+	use crate::action::transform::Transform;
+
+	#[tokio::test]
+	async fn podcast_enclosure() {
+		let entry = Entry {
+			raw_contents: Some(
+				r#"<?xml version="1.0"?>
+				<rss version="2.0">
+					<channel>
+						<title>Podcast</title>
+						<item>
+							<title>Episode 1</title>
+							<guid>1</guid>
+							<enclosure url="https://example.com/1.mp3" length="1000" type="audio/mpeg"/>
+						</item>
+					</channel>
+				</rss>"#
+					.to_owned(),
+			),
+			..Default::default()
+		};
+
+		let entries = Feed::default().transform(entry).await.unwrap();
+
+		assert!(matches!(
+			entries[0].msg.media.as_deref(),
+			Some([Media::Audio(url)]) if url.as_str() == "https://example.com/1.mp3"
+		));
+	}
+}
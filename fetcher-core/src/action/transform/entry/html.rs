@@ -30,19 +30,32 @@ use soup_kuchiki::{Handle as HtmlNode, NodeExt, QueryBuilderExt, Soup};
 use std::iter;
 use url::Url;
 
+/// The string the pieces of text of an item are joined with by default
+pub const DEFAULT_TEXT_SEPARATOR: &str = "\n\n";
+
 /// HTML parser
 #[derive(Debug)]
 pub struct Html {
+	/// Query to find the single element of the page to search in, e.g. the main content without the sidebar.
+	/// If it matches several elements, the first one is used. None means to search in the entire page
+	pub container: Option<Vec<ElementQuery>>,
+
 	/// Query to find an item/entry/article in a list on the page. None means to thread the entire page as a single item
 	pub item: Option<Vec<ElementQuery>>,
 
 	/// Query to find the title of an item
 	pub title: Option<ElementDataQuery>,
 
-	/// One or more query to find the text of an item. If more than one, then they all get joined with "\n\n" in-between and put into the [`Message.body`] field
+	/// One or more query to find the text of an item. If more than one, then they all get joined with [`text_separator`](`Html::text_separator`) in-between and put into the [`Message.body`] field
 	pub text: Option<Vec<ElementDataQuery>>, // allow to find multiple paragraphs and join them together
 
-	/// Query to find the id of an item
+	/// The string to put between the pieces of text found with [`text`](`Html::text`). [`DEFAULT_TEXT_SEPARATOR`] if None
+	pub text_separator: Option<String>,
+
+	/// Query to find the id of an item.
+	///
+	/// If it's optional and doesn't match anything, a hash of the title, link, and body is used as the id instead.
+	/// The same happens if it's not set at all, unless the entire page is the item and it already has an id
 	pub id: Option<ElementDataQuery>,
 
 	/// Query to find the link to an item
@@ -88,6 +101,10 @@ impl TransformEntry for Html {
 	async fn transform_entry(&self, entry: Entry) -> Result<Vec<TransformedEntry>, Self::Err> {
 		tracing::debug!("Parsing HTML");
 
+		// without an id query, every item of a page would otherwise get the same id, if any.
+		// Entries that were already distinct and had an id, e.g. ones from a feed whose link was fetched, keep it
+		let hash_missing_id = self.item.is_some() || entry.id.is_none();
+
 		let dom =
 			Soup::new(entry.raw_contents.as_ref().ok_or(RawContentsNotSetError)?).get_handle();
 
@@ -101,6 +118,16 @@ impl TransformEntry for Html {
 				dom
 			});
 
+		let body = match self.container.as_ref() {
+			Some(containerq) => find_chain(&body, containerq)
+				.map_err(|i| HtmlError::ElementNotFound {
+					num: i,
+					elem_list: containerq.clone(),
+				})?
+				.swap_remove(0),
+			None => body,
+		};
+
 		if body.text().trim().is_empty() {
 			tracing::warn!("HTML body is completely empty");
 
@@ -120,7 +147,7 @@ impl TransformEntry for Html {
 		};
 
 		let entries = items
-			.map(|item| self.extract_entry(&item))
+			.map(|item| self.extract_entry(&item, hash_missing_id))
 			.collect::<Result<Vec<_>, _>>()?;
 
 		tracing::debug!("Found {num} HTML articles total", num = entries.len());
@@ -132,14 +159,25 @@ impl TransformEntry for Html {
 // TODO: make sure (and add tests!) that it errors if no item was found
 // Won't remove this one till I add these goddamned tests >:(
 impl Html {
-	fn extract_entry(&self, html: &HtmlNode) -> Result<TransformedEntry, HtmlError> {
+	fn extract_entry(
+		&self,
+		html: &HtmlNode,
+		hash_missing_id: bool,
+	) -> Result<TransformedEntry, HtmlError> {
 		let title = self
 			.title
 			.as_ref()
 			.try_and_then(|q| extract_title(html, q))?;
 
-		let body = self.text.as_ref().try_map(|q| extract_body(html, q))?;
-		let id = self.id.as_ref().try_and_then(|q| extract_id(html, q))?;
+		let body = self.text.as_ref().try_map(|q| {
+			extract_body(
+				html,
+				q,
+				self.text_separator
+					.as_deref()
+					.unwrap_or(DEFAULT_TEXT_SEPARATOR),
+			)
+		})?;
 
 		let link = self
 			.link
@@ -150,6 +188,18 @@ impl Html {
 					.expect("iterator shouldn't be empty, otherwise it would've been None before")
 			})?;
 
+		let id = match self.id.as_ref().try_and_then(|q| extract_id(html, q))? {
+			Some(id) => Some(id),
+			// the id query is either optional and didn't match anything or there's no id query at all.
+			// Use a hash of the contents instead to still be able to tell the entries apart, e.g. in the read filter
+			None if self.id.is_some() || hash_missing_id => content_hash(&[
+				title.as_deref(),
+				link.as_ref().map(Url::as_str),
+				body.as_deref(),
+			]),
+			None => None,
+		};
+
 		let img = self.img.as_ref().try_and_then(|q| extract_imgs(html, q))?;
 
 		Ok(TransformedEntry {
@@ -166,6 +216,34 @@ impl Html {
 	}
 }
 
+/// A hash of all `parts` that stays the same between runs and versions of fetcher, formatted as a hex string.
+/// Returns `None` if all parts are empty.
+///
+/// Uses 64-bit FNV-1a since [`DefaultHasher`](`std::hash::DefaultHasher`) isn't guaranteed to be stable
+pub(crate) fn content_hash(parts: &[Option<&str>]) -> Option<String> {
+	const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+	const PRIME: u64 = 0x0100_0000_01b3;
+	// can't be a part of a valid UTF-8 string, thus "ab" + "c" and "a" + "bc" hash differently
+	const SEPARATOR: u8 = 0xff;
+
+	if parts.iter().flatten().all(|part| part.is_empty()) {
+		return None;
+	}
+
+	let hash = parts
+		.iter()
+		.flat_map(|part| {
+			part.unwrap_or_default()
+				.bytes()
+				.chain(iter::once(SEPARATOR))
+		})
+		.fold(OFFSET_BASIS, |hash, byte| {
+			(hash ^ u64::from(byte)).wrapping_mul(PRIME)
+		});
+
+	Some(format!("{hash:016x}"))
+}
+
 /// Extract data from the provided HTML tags
 fn extract_data<'a>(
 	html: &HtmlNode,
@@ -178,6 +256,10 @@ fn extract_data<'a>(
 				match &data_query.data_location {
 					DataLocation::Text => Some(html.text()),
 					DataLocation::Attr(v) => html.get(v),
+					DataLocation::AttrAny(attrs) => attrs.iter().find_map(|attr| html.get(attr)),
+					DataLocation::InnerHtml => {
+						Some(html.children().map(|child| child.to_string()).collect())
+					}
 				}
 				.map(|s| s.trim().to_owned())
 			})
@@ -222,7 +304,11 @@ fn extract_title(
 	Ok(extract_data(html, data_query)?.map(|mut it| it.join("\n\n"))) // concat string with "\n\n" as sep
 }
 
-fn extract_body(html: &HtmlNode, data_queries: &[ElementDataQuery]) -> Result<String, HtmlError> {
+fn extract_body(
+	html: &HtmlNode,
+	data_queries: &[ElementDataQuery],
+	separator: &str,
+) -> Result<String, HtmlError> {
 	Ok(data_queries
 		.iter()
 		.map(|query| extract_data(html, query))
@@ -230,7 +316,7 @@ fn extract_body(html: &HtmlNode, data_queries: &[ElementDataQuery]) -> Result<St
 		.into_iter()
 		.flatten() // flatten options, ignore none's
 		.flatten() // flatten inner iterator
-		.join("\n\n"))
+		.join(separator))
 }
 
 fn extract_id(html: &HtmlNode, data_query: &ElementDataQuery) -> Result<Option<String>, HtmlError> {
@@ -313,3 +399,237 @@ fn find(html: HtmlNode, elem_query: &ElementQuery) -> impl Iterator<Item = HtmlN
 		true
 	})
 }
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+
+	use super::*;
+	use crate::action::transform::{entry::html::query::ElementKind, result::TransformResult};
+
+	fn query(optional: bool, class: &str) -> ElementDataQuery {
+		ElementDataQuery {
+			optional,
+			query: vec![ElementQuery {
+				kind: ElementKind::Class(class.to_owned()),
+				ignore: None,
+			}],
+			data_location: DataLocation::Text,
+			regex: None,
+		}
+	}
+
+	#[tokio::test]
+	async fn missing_optional_id_is_hash() {
+		let html = Html {
+			container: None,
+			item: Some(vec![ElementQuery {
+				kind: ElementKind::Class("item".to_owned()),
+				ignore: None,
+			}]),
+			title: Some(query(true, "title")),
+			text: Some(vec![query(false, "text")]),
+			text_separator: None,
+			id: Some(query(true, "id")),
+			link: None,
+			img: None,
+		};
+
+		let entry = Entry {
+			raw_contents: Some(
+				r#"<body>
+					<div class="item"><p class="id">1</p><p class="text">First</p></div>
+					<div class="item"><p class="title">Second</p><p class="text">Second</p></div>
+					<div class="item"><p class="text">Second</p></div>
+				</body>"#
+					.to_owned(),
+			),
+			..Default::default()
+		};
+
+		let entries = html
+			.transform_entry(entry)
+			.await
+			.unwrap()
+			.into_iter()
+			.map(|e| e.id)
+			.collect::<Vec<_>>();
+
+		let hash_id =
+			|parts: &[Option<&str>]| TransformResult::New(content_hash(parts).unwrap().into());
+		assert_eq!(
+			entries,
+			[
+				TransformResult::New("1".to_owned().into()),
+				hash_id(&[Some("Second"), None, Some("Second")]),
+				hash_id(&[None, None, Some("Second")]),
+			]
+		);
+		assert_ne!(entries[1], entries[2]);
+	}
+
+	#[tokio::test]
+	async fn no_id_query() {
+		let html = |item| Html {
+			container: None,
+			item,
+			title: None,
+			text: Some(vec![query(false, "text")]),
+			text_separator: None,
+			id: None,
+			link: None,
+			img: None,
+		};
+		let entry = |id: Option<&str>| Entry {
+			id: id.map(|id| id.to_owned().into()),
+			raw_contents: Some(r#"<body><p class="text">Text</p></body>"#.to_owned()),
+			..Default::default()
+		};
+
+		let id_of =
+			async |html: Html, entry| html.transform_entry(entry).await.unwrap().remove(0).id;
+
+		let hash_id =
+			TransformResult::New(content_hash(&[None, None, Some("Text")]).unwrap().into());
+		assert_eq!(id_of(html(None), entry(None)).await, hash_id);
+		assert_eq!(
+			id_of(html(None), entry(Some("feed id"))).await,
+			TransformResult::Previous
+		);
+
+		let item = Some(vec![ElementQuery {
+			kind: ElementKind::Tag("body".to_owned()),
+			ignore: None,
+		}]);
+		assert_eq!(id_of(html(item), entry(Some("feed id"))).await, hash_id);
+	}
+
+	#[tokio::test]
+	async fn container() {
+		let html = Html {
+			container: Some(vec![ElementQuery {
+				kind: ElementKind::Attr {
+					name: "id".to_owned(),
+					value: "main".to_owned(),
+				},
+				ignore: None,
+			}]),
+			item: Some(vec![ElementQuery {
+				kind: ElementKind::Class("item".to_owned()),
+				ignore: None,
+			}]),
+			title: None,
+			text: Some(vec![query(false, "text")]),
+			text_separator: None,
+			id: None,
+			link: None,
+			img: None,
+		};
+
+		let entry = Entry {
+			raw_contents: Some(
+				r#"<body>
+					<div id="sidebar"><div class="item"><p class="text">Sidebar</p></div></div>
+					<div id="main"><div class="item"><p class="text">Main</p></div></div>
+				</body>"#
+					.to_owned(),
+			),
+			..Default::default()
+		};
+
+		let bodies = html
+			.transform_entry(entry)
+			.await
+			.unwrap()
+			.into_iter()
+			.map(|e| e.msg.body)
+			.collect::<Vec<_>>();
+
+		assert_eq!(bodies, [TransformResult::New("Main".to_owned())]);
+	}
+
+	#[tokio::test]
+	async fn text_separator() {
+		let html = Html {
+			container: None,
+			item: None,
+			title: None,
+			text: Some(vec![query(false, "text"), query(false, "more")]),
+			text_separator: Some(" | ".to_owned()),
+			id: None,
+			link: None,
+			img: None,
+		};
+
+		let entry = Entry {
+			raw_contents: Some(
+				r#"<body><p class="text">One</p><p class="text">Two</p><p class="more">Three</p></body>"#
+					.to_owned(),
+			),
+			..Default::default()
+		};
+
+		let body = html
+			.transform_entry(entry)
+			.await
+			.unwrap()
+			.remove(0)
+			.msg
+			.body;
+		assert_eq!(body, TransformResult::New("One | Two | Three".to_owned()));
+	}
+
+	#[tokio::test]
+	async fn attr_any_and_inner_html() {
+		let html = Html {
+			container: None,
+			item: None,
+			title: None,
+			text: Some(vec![ElementDataQuery {
+				data_location: DataLocation::InnerHtml,
+				..query(false, "text")
+			}]),
+			text_separator: None,
+			id: None,
+			link: Some(ElementDataQuery {
+				data_location: DataLocation::AttrAny(vec!["data-src".to_owned(), "src".to_owned()]),
+				..query(false, "link")
+			}),
+			img: None,
+		};
+
+		let entry = Entry {
+			raw_contents: Some(
+				r#"<body>
+					<p class="text">Some <b>bold</b> &amp; <a href="https://example.com/">linked</a> text</p>
+					<img class="link" src="https://example.com/small.png" data-src="https://example.com/full.png">
+				</body>"#
+					.to_owned(),
+			),
+			..Default::default()
+		};
+
+		let msg = html.transform_entry(entry).await.unwrap().remove(0).msg;
+		assert_eq!(
+			msg.body,
+			TransformResult::New(
+				r#"Some <b>bold</b> &amp; <a href="https://example.com/">linked</a> text"#
+					.to_owned()
+			)
+		);
+		assert_eq!(
+			msg.link,
+			TransformResult::New(Url::parse("https://example.com/full.png").unwrap())
+		);
+	}
+
+	#[test]
+	fn content_hash_is_stable() {
+		assert_eq!(content_hash(&[Some("a")]).unwrap(), "089bc907b544c769");
+		assert_eq!(content_hash(&[None, Some("")]), None);
+		assert_ne!(
+			content_hash(&[Some("ab"), Some("c")]),
+			content_hash(&[Some("a"), Some("bc")])
+		);
+	}
+}
@@ -34,6 +34,10 @@ pub enum DataLocation {
 	Text,
 	/// In an attribute
 	Attr(String),
+	/// In the first of these attributes that is present on the tag, e.g. `data-src` and then `src`
+	AttrAny(Vec<String>),
+	/// In the HTML inside the tag, with all the markup of the children kept as is
+	InnerHtml,
 }
 
 /// A query for an HTML tag
@@ -8,6 +8,8 @@
 
 use async_trait::async_trait;
 use reqwest::Client;
+use std::time::Duration;
+use tokio::time::sleep;
 use url::Url;
 
 use super::TransformEntry;
@@ -22,11 +24,21 @@ use crate::{
 	utils::OptionExt,
 };
 
+/// The delay before the first retry if it's not set explicitly
+pub const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(1);
+
 /// A transform that fetches the page from URL in `from_field` and returns it in [`Entry::raw_contents`]
 #[derive(Debug)]
 pub struct Http {
 	/// The field to get the URL from
 	pub from_field: Field,
+
+	/// How many more times to try to fetch the page if it failed because of the connection or the server
+	pub retries: u32,
+
+	/// How long to wait before the first retry. Doubles after every retry
+	pub retry_delay: Duration,
+
 	client: Client,
 }
 
@@ -49,16 +61,41 @@ impl Http {
 	/// # Errors
 	/// This method fails if TLS couldn't be initialized
 	pub fn new(from_field: Field) -> Result<Self, SourceHttpError> {
-		let client = source::http::CLIENT
-			.get_or_try_init(|| {
-				reqwest::ClientBuilder::new()
-					.timeout(std::time::Duration::from_secs(30))
-					.build()
-					.map_err(SourceHttpError::TlsInitFailed)
-			})?
-			.clone();
-
-		Ok(Self { from_field, client })
+		Ok(Self {
+			from_field,
+			retries: 0,
+			retry_delay: DEFAULT_RETRY_DELAY,
+			client: source::http::client()?,
+		})
+	}
+
+	/// Retry fetching the page up to `retries` times, waiting `delay` before the first retry and twice as long before every next one
+	#[must_use]
+	pub fn with_retries(mut self, retries: u32, delay: Duration) -> Self {
+		self.retries = retries;
+		self.retry_delay = delay;
+		self
+	}
+
+	async fn fetch(&self, url: &Url) -> Result<String, SourceHttpError> {
+		let mut delay = self.retry_delay;
+		let mut retries_left = self.retries;
+
+		loop {
+			match source::http::send_request(&self.client, &Request::Get, None, url).await {
+				Err(e) if retries_left > 0 && e.is_connection_err() => {
+					tracing::warn!(
+						"Failed to fetch {:?}, retrying in {delay:?} ({retries_left} retries left): {e}",
+						url.as_str()
+					);
+
+					sleep(delay).await;
+					delay = delay.saturating_mul(2);
+					retries_left -= 1;
+				}
+				res => return res,
+			}
+		}
 	}
 }
 
@@ -98,7 +135,7 @@ impl TransformEntry for Http {
 
 		let url = url.ok_or_else(|| HttpError::MissingUrl(self.from_field))?;
 
-		let new_page = source::http::send_request(&self.client, &Request::Get, &url).await?;
+		let new_page = self.fetch(&url).await?;
 
 		Ok(vec![TransformedEntry {
 			raw_contents: TransformResult::New(new_page),
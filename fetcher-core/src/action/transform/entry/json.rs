@@ -21,8 +21,14 @@ use crate::{
 
 use async_trait::async_trait;
 use either::Either;
+use itertools::Itertools;
 use serde_json::Value;
-use std::{borrow::Cow, ops::ControlFlow};
+use serde_json_path::JsonPath;
+use std::{
+	borrow::Cow,
+	fmt::{self, Display},
+	ops::ControlFlow,
+};
 use url::Url;
 
 /// JSON parser
@@ -38,8 +44,34 @@ pub struct Json {
 	pub id: Option<StringQuery>,
 	/// Query to find the link to an item
 	pub link: Option<StringQuery>,
-	/// Query to find the image of that item
+	/// Queries to find the images of that item. Each query can resolve to either a single URL or to an array of them
 	pub img: Option<Vec<StringQuery>>, // nested
+	/// Skip items that match any of these filters
+	pub ignore: Option<Vec<ItemFilter>>,
+	/// Skip items that don't match all of these filters
+	pub only: Option<Vec<ItemFilter>>,
+}
+
+/// A condition on the value of a field of an item, used to skip some items before they become entries
+#[derive(Debug)]
+pub struct ItemFilter {
+	/// The location of the value in the item
+	pub query: QueryKind,
+	/// What the value should be like for the item to match
+	pub condition: ItemCondition,
+}
+
+/// What the value of a field of an item should be like for the item to match an [`ItemFilter`].
+///
+/// If the value is an array, it's enough for any one of its elements to match
+#[derive(Debug)]
+pub enum ItemCondition {
+	/// The field exists and isn't null
+	Present,
+	/// The field is a string, number, or bool equal to this string
+	Equals(String),
+	/// The field is a string, number, or bool containing this string
+	Contains(String),
 }
 
 /// JSON key
@@ -65,12 +97,22 @@ pub struct StringQuery {
 /// A query to get the value of a JSON field
 #[derive(Debug)]
 pub struct Query {
-	/// a chain of JSON keys that are needed to be traversed to get to this key
-	pub keys: Keys,
+	/// where to find the value
+	pub kind: QueryKind,
 	/// whether this query is fine to be ignored if not found
 	pub optional: bool,
 }
 
+/// The location of a JSON value
+#[derive(Clone, Debug)]
+pub enum QueryKind {
+	/// a chain of JSON keys that are needed to be traversed to get to this key
+	Keys(Keys),
+	/// a `JSONPath` expression, e.g. `$.items[0].tags[*].name`.
+	/// If it matches more than one value, they are all put into an array
+	JsonPath(JsonPath),
+}
+
 #[expect(missing_docs, reason = "error message is self-documenting")]
 #[derive(thiserror::Error, Debug)]
 pub enum JsonError {
@@ -83,9 +125,16 @@ pub enum JsonError {
 	#[error("JSON key #{num} not found. From query list: {key_list:?}")]
 	KeyNotFound { num: usize, key_list: Keys },
 
-	#[error("JSON key {key:?} wrong type: expected {expected_type}, found {found_type}")]
+	#[error("JSONPath segment #{num} ({segment}) not found. From path: {path}")]
+	PathSegmentNotFound {
+		num: usize,
+		segment: String,
+		path: String,
+	},
+
+	#[error("JSON key {key} wrong type: expected {expected_type}, found {found_type}")]
 	KeyWrongType {
-		key: Keys,
+		key: QueryKind,
 		expected_type: &'static str,
 		found_type: String,
 	},
@@ -109,7 +158,7 @@ impl TransformEntry for Json {
 				None => return Ok(Vec::new()),
 			},
 			// use JSON root if item query is not set
-			None => &json,
+			None => Cow::Borrowed(&json),
 		};
 
 		let items = if let Some(items) = items.as_array() {
@@ -119,7 +168,10 @@ impl TransformEntry for Json {
 			Either::Right(items.iter().map(|(_, v)| v))
 		} else {
 			return Err(JsonError::KeyWrongType {
-				key: self.item.as_ref().map_or_else(Vec::new, |v| v.keys.clone()),
+				key: self
+					.item
+					.as_ref()
+					.map_or_else(|| QueryKind::Keys(Vec::new()), |v| v.kind.clone()),
 				expected_type: "iterator (array, map)",
 				found_type: format!("{items:?}"),
 			});
@@ -127,12 +179,37 @@ impl TransformEntry for Json {
 
 		items
 			.into_iter()
+			.filter(|item| self.should_keep(item))
 			.map(|item| self.extract_entry(item))
 			.collect::<Result<Vec<_>, _>>()
 	}
 }
 
 impl Json {
+	fn should_keep(&self, item: &Value) -> bool {
+		if let Some(filter) = self
+			.ignore
+			.iter()
+			.flatten()
+			.find(|filter| filter.matches(item))
+		{
+			tracing::debug!("Ignoring an item matching {}", filter.query);
+			return false;
+		}
+
+		if let Some(filter) = self
+			.only
+			.iter()
+			.flatten()
+			.find(|filter| !filter.matches(item))
+		{
+			tracing::debug!("Ignoring an item not matching {}", filter.query);
+			return false;
+		}
+
+		true
+	}
+
 	fn extract_entry(&self, item: &Value) -> Result<TransformedEntry, JsonError> {
 		let title = self
 			.title
@@ -144,7 +221,8 @@ impl Json {
 
 		let img = self.img.as_ref().try_map(|v| {
 			v.iter()
-				.filter_map(|q| extract_url(item, q).transpose())
+				.map(|q| extract_urls(item, q))
+				.flatten_ok()
 				.collect::<Result<Vec<_>, _>>()
 		})?;
 
@@ -171,8 +249,13 @@ impl Json {
 	}
 }
 
-fn extract_data<'a>(json: &'a Value, query: &Query) -> Result<Option<&'a Value>, JsonError> {
-	let data = query.keys.iter().enumerate().try_fold(json, |val, (i, q)| {
+fn extract_data<'a>(json: &'a Value, query: &Query) -> Result<Option<Cow<'a, Value>>, JsonError> {
+	let keys = match &query.kind {
+		QueryKind::Keys(keys) => keys,
+		QueryKind::JsonPath(path) => return extract_data_with_path(json, path, query.optional),
+	};
+
+	let data = keys.iter().enumerate().try_fold(json, |val, (i, q)| {
 		let res_val = match q {
 			Key::String(s) => val.get(s),
 			Key::Usize(u) => val.get(u),
@@ -190,12 +273,96 @@ fn extract_data<'a>(json: &'a Value, query: &Query) -> Result<Option<&'a Value>,
 		ControlFlow::Break(key) => {
 			return Err(JsonError::KeyNotFound {
 				num: key,
-				key_list: query.keys.clone(),
+				key_list: keys.clone(),
 			});
 		}
 	};
 
-	Ok(Some(data))
+	Ok(Some(Cow::Borrowed(data)))
+}
+
+fn extract_data_with_path<'a>(
+	json: &'a Value,
+	path: &JsonPath,
+	optional: bool,
+) -> Result<Option<Cow<'a, Value>>, JsonError> {
+	let nodes = path.query(json).all();
+
+	match nodes.as_slice() {
+		[] if optional => Ok(None),
+		[] => {
+			let path = path.to_string();
+			let segments = split_path_segments(&path);
+
+			// find the first segment that doesn't match anything
+			let num = (1..=segments.len())
+				.find(|&len| {
+					JsonPath::parse(&format!("${}", segments[..len].concat()))
+						.is_ok_and(|prefix| prefix.query(json).is_empty())
+				})
+				.map_or_else(|| segments.len().saturating_sub(1), |len| len - 1);
+
+			Err(JsonError::PathSegmentNotFound {
+				num,
+				segment: segments.get(num).copied().unwrap_or_default().to_owned(),
+				path,
+			})
+		}
+		[single] => Ok(Some(Cow::Borrowed(single))),
+		multiple => Ok(Some(Cow::Owned(Value::Array(
+			multiple.iter().map(|&v| v.clone()).collect(),
+		)))),
+	}
+}
+
+/// Split a normalized `JSONPath`, e.g. `$['items'][0].name`, into its segments, e.g. `['items']`, `[0]`, and `.name`
+fn split_path_segments(path: &str) -> Vec<&str> {
+	let path = path.strip_prefix('$').unwrap_or(path);
+	let bytes = path.as_bytes();
+
+	let mut segments = Vec::new();
+	let mut i = 0;
+
+	while i < bytes.len() {
+		let start = i;
+
+		// skip the leading . or .. of the segment
+		while i < bytes.len() && bytes[i] == b'.' {
+			i += 1;
+		}
+
+		if bytes.get(i) == Some(&b'[') {
+			let mut depth = 0;
+			let mut in_quotes = None;
+
+			while i < bytes.len() {
+				match (bytes[i], in_quotes) {
+					(b'\\', Some(_)) => i += 1,
+					(q @ (b'\'' | b'"'), None) => in_quotes = Some(q),
+					(q, Some(quote)) if q == quote => in_quotes = None,
+					(b'[', None) => depth += 1,
+					(b']', None) => {
+						depth -= 1;
+						if depth == 0 {
+							i += 1;
+							break;
+						}
+					}
+					_ => (),
+				}
+
+				i += 1;
+			}
+		} else {
+			while i < bytes.len() && bytes[i] != b'.' && bytes[i] != b'[' {
+				i += 1;
+			}
+		}
+
+		segments.push(&path[start..i.min(path.len())]);
+	}
+
+	segments
 }
 
 fn extract_string(item: &Value, str_query: &StringQuery) -> Result<Option<String>, JsonError> {
@@ -205,20 +372,41 @@ fn extract_string(item: &Value, str_query: &StringQuery) -> Result<Option<String
 		Err(e) => return Err(e),
 	};
 
-	let s = data.as_str().ok_or_else(|| JsonError::KeyWrongType {
-		key: str_query.query.keys.clone(),
-		expected_type: "string",
+	let wrong_type = || JsonError::KeyWrongType {
+		key: str_query.query.kind.clone(),
+		expected_type: "string/number/bool",
 		found_type: format!("{data:?}"),
-	})?;
+	};
+
+	let s = match &*data {
+		// e.g. a JSONPath matched several strings
+		Value::Array(arr) => Cow::Owned(
+			arr.iter()
+				.map(|v| scalar_to_string(v).ok_or_else(wrong_type))
+				.collect::<Result<Vec<_>, _>>()?
+				.join("\n\n"),
+		),
+		other => scalar_to_string(other).ok_or_else(wrong_type)?,
+	};
 
 	let s = match str_query.regex.as_ref() {
-		Some(r) => r.replace(s),
-		None => Cow::Borrowed(s),
+		Some(r) => Cow::Owned(r.replace(&s).into_owned()),
+		None => s,
 	};
 
 	Ok(Some(s.trim().to_owned()))
 }
 
+/// Get the string representation of a string, number, or bool. Objects, arrays, and null aren't considered a string
+fn scalar_to_string(val: &Value) -> Option<Cow<'_, str>> {
+	match val {
+		Value::String(s) => Some(Cow::Borrowed(s)),
+		Value::Number(n) => Some(Cow::Owned(n.to_string())),
+		Value::Bool(b) => Some(Cow::Owned(b.to_string())),
+		Value::Null | Value::Array(_) | Value::Object(_) => None,
+	}
+}
+
 fn extract_body(item: &Value, bodyq: &[StringQuery]) -> Result<Option<String>, JsonError> {
 	let body = bodyq
 		.iter()
@@ -248,7 +436,7 @@ fn extract_id(item: &Value, query: &StringQuery) -> Result<Option<String>, JsonE
 		id.to_string()
 	} else {
 		return Err(JsonError::KeyWrongType {
-			key: query.query.keys.clone(),
+			key: query.query.kind.clone(),
 			expected_type: "string/i64/u64",
 			found_type: format!("{id_val:?}"),
 		});
@@ -273,3 +461,263 @@ fn extract_url(item: &Value, query: &StringQuery) -> Result<Option<Url>, JsonErr
 
 	Ok(Some(url))
 }
+
+/// Extract either a single URL or an array of them.
+///
+/// If the query is optional, invalid URLs are skipped with a warning instead of failing the entire entry
+fn extract_urls(item: &Value, query: &StringQuery) -> Result<Vec<Url>, JsonError> {
+	let data = match extract_data(item, &query.query) {
+		Ok(Some(v)) => v,
+		Ok(None) => return Ok(Vec::new()),
+		Err(e) => return Err(e),
+	};
+
+	let url_vals = match &*data {
+		Value::Array(arr) => Either::Left(arr.iter()),
+		single => Either::Right(std::iter::once(single)),
+	};
+
+	url_vals
+		.filter_map(|url_val| {
+			let Some(url_str) = url_val.as_str() else {
+				return Some(Err(JsonError::KeyWrongType {
+					key: query.query.kind.clone(),
+					expected_type: "string/array of strings",
+					found_type: format!("{url_val:?}"),
+				}));
+			};
+
+			let url_str = match query.regex.as_ref() {
+				Some(r) => r.replace(url_str),
+				None => Cow::Borrowed(url_str),
+			};
+			let url_str = url_str.trim();
+
+			match Url::try_from(url_str) {
+				Ok(url) => Some(Ok(url)),
+				Err(e) if query.query.optional => {
+					tracing::warn!("Skipping invalid image URL {url_str:?}: {e}");
+					None
+				}
+				Err(e) => Some(Err(InvalidUrlError(e, url_str.to_owned()).into())),
+			}
+		})
+		.collect()
+}
+
+impl ItemFilter {
+	/// Whether the `item` matches the filter. A missing value never matches
+	#[must_use]
+	pub fn matches(&self, item: &Value) -> bool {
+		let query = Query {
+			kind: self.query.clone(),
+			optional: true,
+		};
+
+		// optional queries never error, they just don't find anything
+		match extract_data(item, &query) {
+			Ok(Some(val)) => self.condition.matches(&val),
+			Ok(None) | Err(_) => false,
+		}
+	}
+}
+
+impl ItemCondition {
+	fn matches(&self, val: &Value) -> bool {
+		if let Value::Array(arr) = val {
+			return arr.iter().any(|val| self.matches(val));
+		}
+
+		match self {
+			Self::Present => !val.is_null(),
+			Self::Equals(s) => scalar_to_string(val).is_some_and(|val| val == s.as_str()),
+			Self::Contains(s) => scalar_to_string(val).is_some_and(|val| val.contains(s.as_str())),
+		}
+	}
+}
+
+impl Display for QueryKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Keys(keys) => write!(f, "{keys:?}"),
+			Self::JsonPath(path) => write!(f, "{path}"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+
+	use super::*;
+	use crate::action::transform::result::TransformResult;
+	use assert_matches::assert_matches;
+	use serde_json::json;
+
+	fn path_query(path: &str) -> StringQuery {
+		StringQuery {
+			query: Query {
+				kind: QueryKind::JsonPath(JsonPath::parse(path).unwrap()),
+				optional: false,
+			},
+			regex: None,
+		}
+	}
+
+	#[test]
+	fn json_path_index() {
+		let json = json!({ "items": [{ "name": "first" }, { "name": "second" }] });
+
+		assert_eq!(
+			extract_string(&json, &path_query("$.items[1].name")).unwrap(),
+			Some("second".to_owned())
+		);
+	}
+
+	#[test]
+	fn json_path_multiple_matches() {
+		let json = json!({ "items": [{ "tags": [{ "name": "a" }, { "name": "b" }] }] });
+
+		assert_eq!(
+			extract_string(&json, &path_query("$.items[0].tags[*].name")).unwrap(),
+			Some("a\n\nb".to_owned())
+		);
+	}
+
+	#[test]
+	fn numbers_and_bools_as_strings() {
+		let json = json!({ "score": 42, "ratio": 0.5, "nsfw": false, "tags": ["a", 1, true] });
+
+		assert_eq!(
+			extract_string(&json, &path_query("$.score")).unwrap(),
+			Some("42".to_owned())
+		);
+		assert_eq!(
+			extract_string(&json, &path_query("$.ratio")).unwrap(),
+			Some("0.5".to_owned())
+		);
+		assert_eq!(
+			extract_string(&json, &path_query("$.nsfw")).unwrap(),
+			Some("false".to_owned())
+		);
+		assert_eq!(
+			extract_string(&json, &path_query("$.tags[*]")).unwrap(),
+			Some("a\n\n1\n\ntrue".to_owned())
+		);
+	}
+
+	#[test]
+	fn null_and_objects_are_not_strings() {
+		let json = json!({ "null": null, "obj": { "a": "b" } });
+
+		assert_matches!(
+			extract_string(&json, &path_query("$.null")),
+			Err(JsonError::KeyWrongType { .. })
+		);
+		assert_matches!(
+			extract_string(&json, &path_query("$.obj")),
+			Err(JsonError::KeyWrongType { .. })
+		);
+	}
+
+	#[test]
+	fn json_path_not_found_reports_segment() {
+		let json = json!({ "items": [{ "name": "first" }] });
+
+		assert_matches!(
+			extract_string(&json, &path_query("$.items[0].title")),
+			Err(JsonError::PathSegmentNotFound { num: 2, segment, .. }) if segment == ".title"
+		);
+	}
+
+	#[test]
+	fn json_path_optional() {
+		let json = json!({ "items": [] });
+		let mut query = path_query("$.items[0].name");
+		query.query.optional = true;
+
+		assert_eq!(extract_string(&json, &query).unwrap(), None);
+	}
+
+	#[test]
+	fn multiple_images() {
+		let json = json!({ "gallery": ["https://example.com/1.png", "not a url", "https://example.com/2.png"] });
+		let mut query = path_query("$.gallery");
+
+		assert_matches!(extract_urls(&json, &query), Err(JsonError::InvalidUrl(_)));
+
+		query.query.optional = true;
+		assert_eq!(
+			extract_urls(&json, &query)
+				.unwrap()
+				.iter()
+				.map(Url::as_str)
+				.collect::<Vec<_>>(),
+			["https://example.com/1.png", "https://example.com/2.png"]
+		);
+	}
+
+	#[test]
+	fn single_image() {
+		let json = json!({ "img": "https://example.com/1.png" });
+
+		assert_eq!(extract_urls(&json, &path_query("$.img")).unwrap().len(), 1);
+	}
+
+	#[test]
+	fn split_segments() {
+		assert_eq!(
+			split_path_segments("$['it.ems'][0]..['na]me'][*]"),
+			["['it.ems']", "[0]", "..['na]me']", "[*]"]
+		);
+		assert_eq!(split_path_segments("$.items.*"), [".items", ".*"]);
+	}
+
+	#[tokio::test]
+	async fn ignore_and_only_items() {
+		let filter = |path: &str, condition| ItemFilter {
+			query: QueryKind::JsonPath(JsonPath::parse(path).unwrap()),
+			condition,
+		};
+
+		let json = Json {
+			item: None,
+			title: Some(path_query("$.title")),
+			text: None,
+			id: None,
+			link: None,
+			img: None,
+			ignore: Some(vec![
+				filter("$.type", ItemCondition::Equals("ad".to_owned())),
+				filter("$.pinned", ItemCondition::Present),
+			]),
+			only: Some(vec![filter(
+				"$.tags",
+				ItemCondition::Contains("rust".to_owned()),
+			)]),
+		};
+
+		let entry = Entry {
+			raw_contents: Some(
+				json!([
+					{ "title": "ad", "type": "ad", "tags": ["rust"] },
+					{ "title": "pinned", "pinned": true, "tags": ["rust"] },
+					{ "title": "untagged", "type": "post" },
+					{ "title": "post", "type": "post", "tags": ["news", "rustlang"] },
+				])
+				.to_string(),
+			),
+			..Default::default()
+		};
+
+		let titles = json
+			.transform_entry(entry)
+			.await
+			.unwrap()
+			.into_iter()
+			.map(|e| e.msg.title)
+			.collect::<Vec<_>>();
+
+		assert_eq!(titles, [TransformResult::New("post".to_owned())]);
+	}
+}
@@ -0,0 +1,255 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`JsonFeed`] transform that can parse feeds in the [JSON Feed](https://jsonfeed.org) format
+
+use super::TransformEntry;
+use crate::{
+	action::transform::{
+		error::RawContentsNotSetError,
+		field::replace::HTML_TAG_RE,
+		result::{OptionUnwrapTransformResultExt, TransformedEntry, TransformedMessage},
+	},
+	entry::Entry,
+	sink::message::Media,
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use tap::TapFallible;
+use url::Url;
+
+/// The prefix of the `version` field every JSON Feed starts with, e.g. `https://jsonfeed.org/version/1.1`
+pub(crate) const VERSION_PREFIX: &str = "https://jsonfeed.org/version/";
+
+/// JSON Feed parser. Maps the fields of every item of the feed directly to the fields of an entry.
+///
+/// The body is the plain text content of the item if it has one, otherwise its HTML content with the tags removed.
+/// Images, videos, and audio among the attachments of an item are added as media
+#[derive(Debug)]
+pub struct JsonFeed;
+
+#[expect(missing_docs, reason = "error message is self-documenting")]
+#[derive(thiserror::Error, Debug)]
+pub enum JsonFeedError {
+	#[error(transparent)]
+	RawContentsNotSet(#[from] RawContentsNotSetError),
+
+	#[error("Not a valid JSON Feed")]
+	Invalid(#[from] serde_json::Error),
+
+	#[error("Not a JSON Feed: version should start with {VERSION_PREFIX:?} but it's {0:?}")]
+	UnsupportedVersion(String),
+
+	#[error("Not a JSON Feed: it doesn't contain a version")]
+	VersionMissing,
+}
+
+#[derive(Deserialize, Debug)]
+struct Feed {
+	version: Option<String>,
+	#[serde(default)]
+	items: Vec<Item>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Item {
+	id: Option<serde_json::Value>,
+	url: Option<String>,
+	title: Option<String>,
+	content_text: Option<String>,
+	content_html: Option<String>,
+	summary: Option<String>,
+	image: Option<String>,
+	banner_image: Option<String>,
+	date_published: Option<String>,
+	date_modified: Option<String>,
+	#[serde(default)]
+	attachments: Vec<Attachment>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Attachment {
+	url: String,
+	mime_type: String,
+}
+
+#[async_trait]
+impl TransformEntry for JsonFeed {
+	type Err = JsonFeedError;
+
+	async fn transform_entry(&self, entry: Entry) -> Result<Vec<TransformedEntry>, Self::Err> {
+		tracing::trace!("Parsing JSON Feed items");
+
+		let feed: Feed =
+			serde_json::from_str(entry.raw_contents.as_ref().ok_or(RawContentsNotSetError)?)?;
+
+		match feed.version {
+			Some(version) if version.starts_with(VERSION_PREFIX) => (),
+			Some(version) => return Err(JsonFeedError::UnsupportedVersion(version)),
+			None => return Err(JsonFeedError::VersionMissing),
+		}
+
+		tracing::debug!("Got {num} JSON Feed items total", num = feed.items.len());
+
+		Ok(feed.items.into_iter().map(Item::into_entry).collect())
+	}
+}
+
+impl Item {
+	fn into_entry(self) -> TransformedEntry {
+		// the spec requires the id to be a string but some feeds use numbers anyways
+		let id = self.id.and_then(|id| match id {
+			serde_json::Value::String(s) => Some(s),
+			serde_json::Value::Number(n) => Some(n.to_string()),
+			_ => None,
+		});
+
+		let body = self
+			.content_text
+			.or_else(|| self.content_html.as_deref().map(remove_html))
+			.or(self.summary);
+
+		let link = self.url.and_then(|url| {
+			Url::try_from(url.as_str())
+				.tap_err(|e| tracing::warn!("A JSON Feed item's url is not a valid URL: {e:?}"))
+				.ok()
+		});
+
+		let media = self
+			.image
+			.or(self.banner_image)
+			.and_then(|img| Url::try_from(img.as_str()).ok())
+			.map(Media::Photo)
+			.into_iter()
+			.chain(self.attachments.iter().filter_map(Attachment::to_media))
+			.collect::<Vec<_>>();
+		let media = (!media.is_empty()).then_some(media);
+
+		let published = self
+			.date_published
+			.or(self.date_modified)
+			.and_then(|date| DateTime::parse_from_rfc3339(&date).ok())
+			.map(|date| date.with_timezone(&Utc));
+
+		TransformedEntry {
+			id: id.map(Into::into).unwrap_or_prev(),
+			raw_contents: body.clone().unwrap_or_prev(),
+			published: published.unwrap_or_prev(),
+			msg: TransformedMessage {
+				title: self.title.unwrap_or_prev(),
+				body: body.unwrap_or_prev(),
+				link: link.unwrap_or_prev(),
+				media: media.unwrap_or_prev(),
+			},
+			..Default::default()
+		}
+	}
+}
+
+impl Attachment {
+	/// Images, videos, and audio, e.g. podcast episodes, are media, the rest of the attachments are ignored
+	fn to_media(&self) -> Option<Media> {
+		let url = Url::try_from(self.url.as_str())
+			.tap_err(|e| tracing::warn!("A JSON Feed attachment's url is not a valid URL: {e:?}"))
+			.ok()?;
+
+		Media::from_mime_type(url, &self.mime_type)
+	}
+}
+
+/// Remove all HTML tags and decode HTML entities
+fn remove_html(html: &str) -> String {
+	static HTML_TAG: Lazy<Regex> =
+		Lazy::new(|| Regex::new(HTML_TAG_RE).expect("hardcoded regex is valid"));
+
+	let text = HTML_TAG.replace_all(html, "");
+	html_escape::decode_html_entities(text.trim()).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+	use super::*;
+	use crate::action::transform::Transform;
+
+	#[tokio::test]
+	async fn items() {
+		let entry = Entry {
+			raw_contents: Some(
+				r#"{
+					"version": "https://jsonfeed.org/version/1.1",
+					"title": "Feed",
+					"items": [
+						{
+							"id": "1",
+							"url": "https://example.com/1",
+							"title": "First",
+							"content_html": "<p>Hello &amp; welcome</p>",
+							"image": "https://example.com/1.png",
+							"date_published": "2024-01-03T00:00:00Z",
+							"attachments": [
+								{ "url": "https://example.com/1.mp4", "mime_type": "video/mp4" },
+								{ "url": "https://example.com/1.mp3", "mime_type": "audio/mpeg" }
+							]
+						},
+						{
+							"id": 2,
+							"content_text": "Text",
+							"content_html": "<p>Text</p>"
+						}
+					]
+				}"#
+				.to_owned(),
+			),
+			..Default::default()
+		};
+
+		let entries = JsonFeed.transform(entry).await.unwrap();
+		assert_eq!(entries.len(), 2);
+
+		assert_eq!(entries[0].id.as_deref(), Some("1"));
+		assert_eq!(entries[0].msg.title.as_deref(), Some("First"));
+		assert_eq!(entries[0].msg.body.as_deref(), Some("Hello & welcome"));
+		assert_eq!(
+			entries[0].msg.link.as_ref().map(Url::as_str),
+			Some("https://example.com/1")
+		);
+		assert!(matches!(
+			entries[0].msg.media.as_deref(),
+			Some([Media::Photo(img), Media::Video(video), Media::Audio(audio)])
+				if img.as_str() == "https://example.com/1.png"
+					&& video.as_str() == "https://example.com/1.mp4"
+					&& audio.as_str() == "https://example.com/1.mp3"
+		));
+		assert_eq!(
+			entries[0]
+				.published
+				.map(|date| date.to_rfc3339())
+				.as_deref(),
+			Some("2024-01-03T00:00:00+00:00")
+		);
+
+		assert_eq!(entries[1].id.as_deref(), Some("2"));
+		assert_eq!(entries[1].msg.body.as_deref(), Some("Text"));
+	}
+
+	#[tokio::test]
+	async fn not_a_json_feed() {
+		let entry = Entry {
+			raw_contents: Some(r#"{"version": "1.0", "items": []}"#.to_owned()),
+			..Default::default()
+		};
+
+		assert!(matches!(
+			JsonFeed.transform_entry(entry).await,
+			Err(JsonFeedError::UnsupportedVersion(version)) if version == "1.0"
+		));
+	}
+}
@@ -0,0 +1,182 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`OpenGraph`] transform that makes a link preview out of a web page
+
+use super::TransformEntry;
+use crate::{
+	action::transform::{
+		error::RawContentsNotSetError,
+		result::{OptionUnwrapTransformResultExt, TransformedEntry, TransformedMessage},
+	},
+	entry::Entry,
+	sink::message::Media,
+};
+
+use async_trait::async_trait;
+use soup_kuchiki::{Handle as HtmlNode, NodeExt, QueryBuilderExt, Soup};
+use url::Url;
+
+/// Parses the raw contents of an entry as a web page and uses its [Open Graph](https://ogp.me) metadata as the message.
+///
+/// `og:title` is used as the title, `og:description` as the body, `og:url` as the link, and every `og:image` as a photo.
+/// Pages without these tags fall back to the `<title>`, the `description` meta tag, and the first `<img>` of the page.
+/// Relative URLs are resolved against the link of the entry, e.g. the page it has been fetched from
+#[derive(Debug)]
+pub struct OpenGraph;
+
+#[async_trait]
+impl TransformEntry for OpenGraph {
+	type Err = RawContentsNotSetError;
+
+	async fn transform_entry(&self, entry: Entry) -> Result<Vec<TransformedEntry>, Self::Err> {
+		let dom = Soup::new(
+			entry
+				.raw_contents
+				.as_deref()
+				.ok_or(RawContentsNotSetError)?,
+		)
+		.get_handle();
+
+		let url = |s: String| match &entry.msg.link {
+			Some(base) => base.join(&s).ok(),
+			None => Url::parse(&s).ok(),
+		};
+
+		let title = meta(&dom, "og:title").or_else(|| {
+			dom.tag("title")
+				.find()
+				.map(|title| title.text().trim().to_owned())
+				.filter(|title| !title.is_empty())
+		});
+
+		let description = meta(&dom, "og:description").or_else(|| meta(&dom, "description"));
+
+		let link = meta(&dom, "og:url").and_then(url);
+
+		let mut images = metas(&dom, "og:image").filter_map(url).collect::<Vec<_>>();
+		if images.is_empty() {
+			images.extend(
+				dom.tag("img")
+					.find_all()
+					.filter_map(|img| img.get("src"))
+					.filter_map(url)
+					// skip inline images that can't be sent as a link
+					.filter(|img| matches!(img.scheme(), "http" | "https"))
+					.take(1),
+			);
+		}
+
+		let media = (!images.is_empty()).then(|| images.into_iter().map(Media::Photo).collect());
+
+		Ok(vec![TransformedEntry {
+			msg: TransformedMessage {
+				title: title.unwrap_or_prev(),
+				body: description.unwrap_or_prev(),
+				link: link.unwrap_or_prev(),
+				media: media.unwrap_or_prev(),
+			},
+			..Default::default()
+		}])
+	}
+}
+
+/// The non-empty contents of every `<meta>` tag with the `property`, e.g. `og:image`.
+/// The `name` attribute is checked, too, since some pages use it instead
+fn metas<'a>(dom: &HtmlNode, property: &'a str) -> impl Iterator<Item = String> + use<'a> {
+	dom.tag("meta")
+		.find_all()
+		.filter(move |meta| {
+			meta.get("property").as_deref() == Some(property)
+				|| meta.get("name").as_deref() == Some(property)
+		})
+		.filter_map(|meta| meta.get("content"))
+		.map(|content| content.trim().to_owned())
+		.filter(|content| !content.is_empty())
+}
+
+/// The contents of the first `<meta>` tag with the `property`
+fn meta(dom: &HtmlNode, property: &str) -> Option<String> {
+	metas(dom, property).next()
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+	use super::*;
+
+	async fn transform(html: &str) -> Entry {
+		let entry = Entry {
+			raw_contents: Some(html.to_owned()),
+			msg: crate::sink::message::Message {
+				link: Some(Url::parse("https://example.com/posts/1").unwrap()),
+				..Default::default()
+			},
+			..Default::default()
+		};
+
+		OpenGraph
+			.transform_entry(entry.clone())
+			.await
+			.unwrap()
+			.remove(0)
+			.into_entry(&entry)
+	}
+
+	#[tokio::test]
+	async fn open_graph_tags() {
+		let entry = transform(
+			r#"<html><head>
+				<title>Page title</title>
+				<meta property="og:title" content="OG title">
+				<meta property="og:description" content="OG description">
+				<meta property="og:url" content="https://example.com/canonical">
+				<meta property="og:image" content="/first.png">
+				<meta property="og:image" content="https://cdn.example.com/second.png">
+			</head><body><img src="/ignored.png"></body></html>"#,
+		)
+		.await;
+
+		assert_eq!(entry.msg.title.as_deref(), Some("OG title"));
+		assert_eq!(entry.msg.body.as_deref(), Some("OG description"));
+		assert_eq!(
+			entry.msg.link.unwrap().as_str(),
+			"https://example.com/canonical"
+		);
+		assert!(matches!(
+			&entry.msg.media.unwrap()[..],
+			[Media::Photo(first), Media::Photo(second)]
+				if first.as_str() == "https://example.com/first.png"
+					&& second.as_str() == "https://cdn.example.com/second.png"
+		));
+	}
+
+	#[tokio::test]
+	async fn fallback() {
+		let entry = transform(
+			r#"<html><head>
+				<title> Page title </title>
+				<meta name="description" content="Description">
+			</head><body><img src="data:image/png;base64,AAAA"><img src="img/photo.jpg"><img src="/second.jpg"></body></html>"#,
+		)
+		.await;
+
+		assert_eq!(entry.msg.title.as_deref(), Some("Page title"));
+		assert_eq!(entry.msg.body.as_deref(), Some("Description"));
+		assert_eq!(
+			entry.msg.link.unwrap().as_str(),
+			"https://example.com/posts/1",
+			"the link should be kept"
+		);
+		assert!(
+			matches!(
+				&entry.msg.media.unwrap()[..],
+				[Media::Photo(img)] if img.as_str() == "https://example.com/posts/img/photo.jpg"
+			),
+			"only the first image that can be linked to should be used"
+		);
+	}
+}
@@ -38,7 +38,7 @@ impl TransformEntry for DebugPrint {
 			Some(body)
 		};
 
-		Stdout
+		Stdout::default()
 			.send(&msg, None, Some("debug print"))
 			.await
 			.expect("stdout is unavailable");
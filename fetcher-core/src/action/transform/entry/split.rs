@@ -0,0 +1,153 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`Split`] transform that splits a single entry into several ones
+
+use async_trait::async_trait;
+use regex::Regex;
+use url::Url;
+
+use super::{TransformEntry, html::content_hash};
+use crate::{
+	action::transform::{
+		error::TransformErrorKind,
+		field::Field,
+		result::{TransformResult, TransformedEntry},
+	},
+	entry::Entry,
+	error::{BadRegexError, InvalidUrlError},
+};
+
+/// Split a field at every match of a regular expression and make a separate entry out of every part, e.g. to split a digest email into the items it consists of
+///
+/// All other fields are copied from the original entry. Parts that are empty after trimming whitespace are skipped.
+/// Every entry gets an id made of the id of the original entry and a hash of the part, to be able to tell them apart, e.g. in the read filter
+#[derive(Debug)]
+pub struct Split {
+	/// The field to split
+	pub field: Field,
+
+	separator: Regex,
+}
+
+impl Split {
+	/// Create a new [`Split`] that splits `field` at every match of the regular expression `separator`
+	///
+	/// # Errors
+	/// if the regex is invalid
+	pub fn new(field: Field, separator: &str) -> Result<Self, BadRegexError> {
+		Ok(Self {
+			field,
+			separator: Regex::new(separator)?,
+		})
+	}
+
+	/// Create a new [`Split`] that splits `field` at every occurrence of the literal text `separator`
+	#[must_use]
+	#[expect(clippy::missing_panics_doc, reason = "doesn't actually panic")]
+	pub fn new_literal(field: Field, separator: &str) -> Self {
+		Self {
+			field,
+			separator: Regex::new(&regex::escape(separator))
+				.expect("an escaped string should always be a valid regular expression"),
+		}
+	}
+}
+
+#[async_trait]
+impl TransformEntry for Split {
+	type Err = TransformErrorKind;
+
+	async fn transform_entry(&self, entry: Entry) -> Result<Vec<TransformedEntry>, Self::Err> {
+		let Some(value) = self.field.get_from(&entry) else {
+			return Ok(vec![TransformedEntry::default()]);
+		};
+
+		let original_id = entry.id.as_ref().map(|id| id.0.as_str());
+
+		self.separator
+			.split(&value)
+			.map(str::trim)
+			.filter(|part| !part.is_empty())
+			.map(|part| {
+				let mut transformed = TransformedEntry::default();
+
+				// parts are never empty, thus the hash is always there
+				let hash = content_hash(&[Some(part)]).unwrap_or_default();
+				let id = match original_id {
+					Some(original_id) => format!("{original_id}#{hash}"),
+					None => hash,
+				};
+				transformed.id = TransformResult::New(id.into());
+
+				let part = part.to_owned();
+				match self.field {
+					Field::Title => transformed.msg.title = TransformResult::New(part),
+					Field::Body => transformed.msg.body = TransformResult::New(part),
+					Field::Link => {
+						let link = Url::try_from(part.as_str()).map_err(|e| {
+							TransformErrorKind::FieldLinkTransformInvalidUrl(InvalidUrlError(
+								e, part,
+							))
+						})?;
+						transformed.msg.link = TransformResult::New(link);
+					}
+					// the part is already unique in this case
+					Field::Id => transformed.id = TransformResult::New(part.into()),
+					Field::ReplyTo => transformed.reply_to = TransformResult::New(part.into()),
+					Field::RawContets => transformed.raw_contents = TransformResult::New(part),
+				}
+
+				Ok(transformed)
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+	use super::*;
+	use crate::{action::transform::Transform, sink::message::Message};
+
+	#[tokio::test]
+	async fn split_body() {
+		let entry = Entry {
+			id: Some("digest".into()),
+			msg: Message {
+				title: Some("Daily digest".to_owned()),
+				body: Some("First item\n---\n\nSecond item\n---\n".to_owned()),
+				..Default::default()
+			},
+			..Default::default()
+		};
+
+		let entries = Split::new(Field::Body, r"\n-{3,}\n")
+			.unwrap()
+			.transform(entry)
+			.await
+			.unwrap();
+
+		let bodies = entries
+			.iter()
+			.map(|e| e.msg.body.as_deref().unwrap())
+			.collect::<Vec<_>>();
+		assert_eq!(bodies, ["First item", "Second item"]);
+
+		assert!(
+			entries
+				.iter()
+				.all(|e| e.msg.title.as_deref() == Some("Daily digest"))
+		);
+
+		let ids = entries
+			.iter()
+			.map(|e| e.id.as_ref().unwrap().0.as_str())
+			.collect::<Vec<_>>();
+		assert!(ids.iter().all(|id| id.starts_with("digest#")));
+		assert_ne!(ids[0], ids[1]);
+	}
+}
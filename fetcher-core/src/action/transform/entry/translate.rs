@@ -0,0 +1,218 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`Translate`] transform and the [`TranslationBackend`] trait that all translation services implement,
+//! as well as the [`DeepL`] and [`LibreTranslate`] backends
+
+pub mod deepl;
+pub mod libre_translate;
+
+pub use self::{deepl::DeepL, libre_translate::LibreTranslate};
+
+use async_trait::async_trait;
+use std::{
+	collections::HashMap,
+	fmt::Debug,
+	hash::{DefaultHasher, Hash, Hasher},
+	sync::Mutex,
+};
+use url::Url;
+
+use super::TransformEntry;
+use crate::{
+	action::transform::{
+		error::TransformErrorKind,
+		field::Field,
+		result::{TransformResult, TransformedEntry},
+	},
+	entry::Entry,
+	error::InvalidUrlError,
+};
+
+/// Forget all cached translations after this many have been cached to avoid growing the cache indefinitely
+const MAX_CACHED_TRANSLATIONS: usize = 1000;
+
+/// A service that can translate text
+#[async_trait]
+pub trait TranslationBackend: Debug + Send + Sync {
+	/// Translate `text` into the `target_lang` language. The source language is detected automatically
+	///
+	/// # Errors
+	/// if the translation service couldn't be reached or returned an error
+	async fn translate(&self, text: &str, target_lang: &str) -> Result<String, TranslateError>;
+}
+
+/// Translate the contents of fields into a different language, replacing them with the translation
+#[derive(Debug)]
+pub struct Translate {
+	/// The service to translate with
+	pub backend: Box<dyn TranslationBackend>,
+
+	/// The language to translate into, e.g. "en" or "de"
+	pub target_lang: String,
+
+	/// The fields to translate
+	pub fields: Vec<Field>,
+
+	/// Translations of previously seen text, keyed by the hash of the text
+	cache: Mutex<HashMap<u64, String>>,
+}
+
+#[expect(missing_docs, reason = "error message is self-documenting")]
+#[derive(thiserror::Error, Debug)]
+pub enum TranslateError {
+	#[error("Can't send a request to the translation service")]
+	Request(#[from] reqwest::Error),
+
+	#[error("The translation service returned an error: {0}")]
+	Service(String),
+}
+
+impl Translate {
+	/// Create a new [`Translate`] transform that translates `fields` into `target_lang` using `backend`
+	#[must_use]
+	pub fn new<B>(backend: B, target_lang: String, fields: Vec<Field>) -> Self
+	where
+		B: TranslationBackend + 'static,
+	{
+		Self {
+			backend: Box::new(backend),
+			target_lang,
+			fields,
+			cache: Mutex::default(),
+		}
+	}
+
+	async fn translate_cached(&self, text: &str) -> Result<String, TranslateError> {
+		let hash = {
+			let mut hasher = DefaultHasher::new();
+			text.hash(&mut hasher);
+			hasher.finish()
+		};
+
+		if let Some(translated) = self.lock_cache().get(&hash) {
+			tracing::trace!("Using cached translation of {text:?}");
+			return Ok(translated.clone());
+		}
+
+		let translated = self.backend.translate(text, &self.target_lang).await?;
+
+		{
+			let mut cache = self.lock_cache();
+			if cache.len() >= MAX_CACHED_TRANSLATIONS {
+				cache.clear();
+			}
+			cache.insert(hash, translated.clone());
+		}
+
+		Ok(translated)
+	}
+
+	fn lock_cache(&self) -> std::sync::MutexGuard<'_, HashMap<u64, String>> {
+		// the cache can't be left in an invalid state, so it's safe to use even if poisoned
+		self.cache
+			.lock()
+			.unwrap_or_else(std::sync::PoisonError::into_inner)
+	}
+}
+
+#[async_trait]
+impl TransformEntry for Translate {
+	type Err = TransformErrorKind;
+
+	async fn transform_entry(&self, entry: Entry) -> Result<Vec<TransformedEntry>, Self::Err> {
+		let mut transformed = TransformedEntry::default();
+
+		for &field in &self.fields {
+			let Some(text) = field.get_from(&entry) else {
+				continue;
+			};
+
+			let translated = self.translate_cached(&text).await?;
+
+			match field {
+				Field::Title => transformed.msg.title = TransformResult::New(translated),
+				Field::Body => transformed.msg.body = TransformResult::New(translated),
+				Field::Link => {
+					let link = Url::try_from(translated.as_str()).map_err(|e| {
+						TransformErrorKind::FieldLinkTransformInvalidUrl(InvalidUrlError(
+							e, translated,
+						))
+					})?;
+
+					transformed.msg.link = TransformResult::New(link);
+				}
+				Field::Id => transformed.id = TransformResult::New(translated.into()),
+				Field::ReplyTo => transformed.reply_to = TransformResult::New(translated.into()),
+				Field::RawContets => transformed.raw_contents = TransformResult::New(translated),
+			}
+		}
+
+		Ok(vec![transformed])
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+	use super::*;
+	use crate::{action::transform::Transform, sink::message::Message};
+
+	use std::sync::{
+		Arc,
+		atomic::{AtomicUsize, Ordering},
+	};
+
+	#[derive(Default, Debug)]
+	struct Reverse {
+		calls: Arc<AtomicUsize>,
+	}
+
+	#[async_trait]
+	impl TranslationBackend for Reverse {
+		async fn translate(&self, text: &str, target_lang: &str) -> Result<String, TranslateError> {
+			self.calls.fetch_add(1, Ordering::SeqCst);
+			Ok(format!(
+				"{target_lang}:{}",
+				text.chars().rev().collect::<String>()
+			))
+		}
+	}
+
+	fn entry(title: &str, body: &str) -> Entry {
+		Entry {
+			msg: Message {
+				title: Some(title.to_owned()),
+				body: Some(body.to_owned()),
+				..Default::default()
+			},
+			..Default::default()
+		}
+	}
+
+	#[tokio::test]
+	async fn translates_only_requested_fields() {
+		let tr = Translate::new(Reverse::default(), "en".to_owned(), vec![Field::Body]);
+
+		let entries = tr.transform(entry("title", "body")).await.unwrap();
+		assert_eq!(entries[0].msg.title.as_deref(), Some("title"));
+		assert_eq!(entries[0].msg.body.as_deref(), Some("en:ydob"));
+	}
+
+	#[tokio::test]
+	async fn caches_translations() {
+		let backend = Reverse::default();
+		let calls = Arc::clone(&backend.calls);
+		let tr = Translate::new(backend, "en".to_owned(), vec![Field::Title, Field::Body]);
+
+		tr.transform(entry("same", "same")).await.unwrap();
+		let entries = tr.transform(entry("same", "other")).await.unwrap();
+
+		assert_eq!(entries[0].msg.title.as_deref(), Some("en:emas"));
+		assert_eq!(entries[0].msg.body.as_deref(), Some("en:rehto"));
+		assert_eq!(calls.load(Ordering::SeqCst), 2);
+	}
+}
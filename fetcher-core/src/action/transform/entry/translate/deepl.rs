@@ -0,0 +1,94 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`DeepL`] translation backend
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{TranslateError, TranslationBackend};
+use crate::source::http::HttpError;
+
+const FREE_API_URL: &str = "https://api-free.deepl.com/v2/translate";
+const PRO_API_URL: &str = "https://api.deepl.com/v2/translate";
+
+/// The [DeepL](https://www.deepl.com/pro-api) translation API. Supports both free and pro API keys
+pub struct DeepL {
+	api_key: String,
+	client: Client,
+}
+
+#[derive(Deserialize)]
+struct Response {
+	translations: Vec<Translation>,
+}
+
+#[derive(Deserialize)]
+struct Translation {
+	text: String,
+}
+
+impl DeepL {
+	/// Create a new [`DeepL`] backend that authenticates with `api_key`
+	///
+	/// # Errors
+	/// This method fails if TLS couldn't be initialized
+	pub fn new(api_key: String) -> Result<Self, HttpError> {
+		Ok(Self {
+			api_key,
+			client: crate::source::http::client()?,
+		})
+	}
+}
+
+#[async_trait]
+impl TranslationBackend for DeepL {
+	async fn translate(&self, text: &str, target_lang: &str) -> Result<String, TranslateError> {
+		// free API keys end with ":fx" and can only be used with the free API endpoint
+		let url = if self.api_key.ends_with(":fx") {
+			FREE_API_URL
+		} else {
+			PRO_API_URL
+		};
+
+		tracing::trace!("Translating {text:?} into {target_lang:?} with DeepL");
+		let response = self
+			.client
+			.post(url)
+			.header(
+				reqwest::header::AUTHORIZATION,
+				format!("DeepL-Auth-Key {}", self.api_key),
+			)
+			.json(&json!({
+				"text": [text],
+				"target_lang": target_lang.to_uppercase(),
+			}))
+			.send()
+			.await?;
+
+		let status = response.status();
+		if !status.is_success() {
+			return Err(TranslateError::Service(status.to_string()));
+		}
+
+		response
+			.json::<Response>()
+			.await?
+			.translations
+			.into_iter()
+			.next()
+			.map(|translation| translation.text)
+			.ok_or_else(|| TranslateError::Service("no translations returned".to_owned()))
+	}
+}
+
+impl std::fmt::Debug for DeepL {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("DeepL").finish_non_exhaustive()
+	}
+}
@@ -0,0 +1,137 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`LibreTranslate`] translation backend
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use url::Url;
+
+use super::{TranslateError, TranslationBackend};
+use crate::source::http::HttpError;
+
+/// A [LibreTranslate](https://libretranslate.com) instance
+pub struct LibreTranslate {
+	/// URL of the instance, e.g. `https://libretranslate.com`
+	pub url: Url,
+
+	/// API key, if the instance requires one
+	pub api_key: Option<String>,
+
+	client: Client,
+}
+
+#[derive(Deserialize)]
+struct Response {
+	#[serde(rename = "translatedText")]
+	translated_text: String,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+	error: String,
+}
+
+impl LibreTranslate {
+	/// Create a new [`LibreTranslate`] backend that uses the instance at `url`
+	///
+	/// # Errors
+	/// This method fails if TLS couldn't be initialized
+	pub fn new(url: Url, api_key: Option<String>) -> Result<Self, HttpError> {
+		Ok(Self {
+			url,
+			api_key,
+			client: crate::source::http::client()?,
+		})
+	}
+}
+
+#[async_trait]
+impl TranslationBackend for LibreTranslate {
+	async fn translate(&self, text: &str, target_lang: &str) -> Result<String, TranslateError> {
+		let url = translate_url(&self.url);
+
+		let mut body = json!({
+			"q": text,
+			"source": "auto",
+			"target": target_lang,
+			"format": "text",
+		});
+
+		if let Some(api_key) = &self.api_key {
+			body["api_key"] = api_key.as_str().into();
+		}
+
+		tracing::trace!("Translating {text:?} into {target_lang:?} with LibreTranslate");
+		let response = self.client.post(url).json(&body).send().await?;
+
+		let status = response.status();
+		if !status.is_success() {
+			// the body contains the reason only if it's the instance itself that has returned the error and not e.g. a proxy in front of it
+			let reason = match response.json::<ErrorResponse>().await {
+				Ok(ErrorResponse { error }) => format!("{status}: {error}"),
+				Err(_) => status.to_string(),
+			};
+
+			return Err(TranslateError::Service(reason));
+		}
+
+		Ok(response.json::<Response>().await?.translated_text)
+	}
+}
+
+/// The translate endpoint of the instance at `url`.
+///
+/// Unlike [`Url::join()`], it keeps the whole path of the `url`, even if it doesn't end with a slash,
+/// e.g. `https://example.com/libretranslate` becomes `https://example.com/libretranslate/translate`
+fn translate_url(url: &Url) -> Url {
+	let mut url = url.clone();
+
+	if let Ok(mut segments) = url.path_segments_mut() {
+		segments.pop_if_empty().push("translate");
+	}
+
+	url
+}
+
+impl std::fmt::Debug for LibreTranslate {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("LibreTranslate")
+			.field("url", &self.url.as_str())
+			.field("api_key.is_some()", &self.api_key.is_some())
+			.finish_non_exhaustive()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+	use super::*;
+
+	#[test]
+	fn translate_url_keeps_path() {
+		for (url, expected) in [
+			("https://example.com", "https://example.com/translate"),
+			("https://example.com/", "https://example.com/translate"),
+			(
+				"https://example.com/libretranslate",
+				"https://example.com/libretranslate/translate",
+			),
+			(
+				"https://example.com/libretranslate/",
+				"https://example.com/libretranslate/translate",
+			),
+		] {
+			assert_eq!(
+				translate_url(&Url::parse(url).unwrap()).as_str(),
+				expected,
+				"{url}"
+			);
+		}
+	}
+}
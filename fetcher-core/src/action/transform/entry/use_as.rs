@@ -11,7 +11,7 @@ use crate::{
 	action::transform::{
 		error::TransformErrorKind,
 		field::Field,
-		result::{OptionUnwrapTransformResultExt, TransformedEntry},
+		result::{OptionUnwrapTransformResultExt, TransformResult, TransformedEntry},
 	},
 	entry::Entry,
 	error::InvalidUrlError,
@@ -21,13 +21,18 @@ use crate::{
 use async_trait::async_trait;
 use url::Url;
 
-/// Use the value of a field as the value of a different field
+/// Use the value of a field as the value of a different field,
+/// e.g. to put the [`raw_contents`](`Entry::raw_contents`) of an entry from a source that only sets them into the body of its message
 #[derive(Debug)]
 pub struct Use {
 	/// use the value from this field
 	pub field: Field,
 	/// put the value into this field
 	pub as_field: Field,
+	/// empty the field the value was taken from afterwards, i.e. move the value instead of copying it
+	pub clear: bool,
+	/// replace the value of [`as_field`](`Use::as_field`) if it's already set. If false, the entry is left as is in that case
+	pub overwrite: bool,
 }
 
 #[async_trait]
@@ -35,6 +40,11 @@ impl TransformEntry for Use {
 	type Err = TransformErrorKind;
 
 	async fn transform_entry(&self, ent: Entry) -> Result<Vec<TransformedEntry>, Self::Err> {
+		if !self.overwrite && is_set(&ent, self.as_field) {
+			tracing::trace!("{:?} is already set, not overwriting it", self.as_field);
+			return Ok(vec![TransformedEntry::default()]);
+		}
+
 		let val = match self.field {
 			Field::Title => ent.msg.title,
 			Field::Body => ent.msg.body,
@@ -62,6 +72,95 @@ impl TransformEntry for Use {
 			Field::RawContets => ent.raw_contents = val.unwrap_or_empty(),
 		}
 
+		if self.clear && self.field != self.as_field {
+			match self.field {
+				Field::Title => ent.msg.title = TransformResult::Empty,
+				Field::Body => ent.msg.body = TransformResult::Empty,
+				Field::Link => ent.msg.link = TransformResult::Empty,
+				Field::Id => ent.id = TransformResult::Empty,
+				Field::ReplyTo => ent.reply_to = TransformResult::Empty,
+				Field::RawContets => ent.raw_contents = TransformResult::Empty,
+			}
+		}
+
 		Ok(vec![ent])
 	}
 }
+
+fn is_set(ent: &Entry, field: Field) -> bool {
+	match field {
+		Field::Title => ent.msg.title.is_some(),
+		Field::Body => ent.msg.body.is_some(),
+		Field::Link => ent.msg.link.is_some(),
+		Field::Id => ent.id.is_some(),
+		Field::ReplyTo => ent.reply_to.is_some(),
+		Field::RawContets => ent.raw_contents.is_some(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+	use super::*;
+	use crate::{action::transform::Transform, sink::message::Message};
+
+	#[tokio::test]
+	async fn move_raw_contents_to_body() {
+		let entry = Entry {
+			raw_contents: Some("output".to_owned()),
+			..Default::default()
+		};
+
+		let entry = Use {
+			field: Field::RawContets,
+			as_field: Field::Body,
+			clear: true,
+			overwrite: true,
+		}
+		.transform(entry)
+		.await
+		.unwrap()
+		.remove(0);
+
+		assert_eq!(entry.msg.body.as_deref(), Some("output"));
+		assert_eq!(entry.raw_contents, None);
+	}
+
+	#[tokio::test]
+	async fn dont_overwrite() {
+		let entry = Entry {
+			msg: Message {
+				title: Some("title".to_owned()),
+				body: Some("body".to_owned()),
+				..Default::default()
+			},
+			..Default::default()
+		};
+
+		let use_body_as_title = |overwrite| Use {
+			field: Field::Body,
+			as_field: Field::Title,
+			clear: false,
+			overwrite,
+		};
+
+		let title_of = async |use_as: Use| {
+			use_as
+				.transform(entry.clone())
+				.await
+				.unwrap()
+				.remove(0)
+				.msg
+				.title
+		};
+
+		assert_eq!(
+			title_of(use_body_as_title(false)).await.as_deref(),
+			Some("title")
+		);
+		assert_eq!(
+			title_of(use_body_as_title(true)).await.as_deref(),
+			Some("body")
+		);
+	}
+}
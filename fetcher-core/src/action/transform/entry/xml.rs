@@ -0,0 +1,372 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`Xml`] parser that uses `XPath` expressions to query arbitrary (non-RSS/Atom) XML
+
+use super::TransformEntry;
+use crate::{
+	action::transform::{
+		error::RawContentsNotSetError,
+		field::Replace,
+		result::{OptionUnwrapTransformResultExt, TransformedEntry, TransformedMessage},
+	},
+	entry::Entry,
+	error::InvalidUrlError,
+	sink::message::Media,
+	utils::OptionExt,
+};
+
+use async_trait::async_trait;
+use std::borrow::Cow;
+use sxd_document::{dom::Document, parser as xml_parser};
+use sxd_xpath::{Context, ExecutionError, Factory, ParserError, Value, XPath, nodeset::Node};
+use url::Url;
+
+/// XML parser
+#[derive(Debug)]
+pub struct Xml {
+	/// Query to find an item/entry/article in the document. None means to treat the entire document as a single item
+	pub item: Option<Query>,
+	/// Query to find the title of an item
+	pub title: Option<StringQuery>,
+	/// One or more query to find the text of an item. If more than one, then they all get joined with "\n\n" in-between and put into the [`Message.body`] field
+	pub text: Option<Vec<StringQuery>>,
+	/// Query to find the id of an item
+	pub id: Option<StringQuery>,
+	/// Query to find the link to an item
+	pub link: Option<StringQuery>,
+	/// Queries to find the images of that item. Every node a query matches is considered a separate image
+	pub img: Option<Vec<StringQuery>>,
+}
+
+/// A query to find a value in an XML document
+#[derive(Debug)]
+pub struct Query {
+	/// The `XPath` expression, relative to the item if it's a query for a field of the item
+	xpath: String,
+	/// whether this query is fine to be ignored if not found
+	pub optional: bool,
+}
+
+/// All data needed to query, extract, and finalize a string from XML
+#[derive(Debug)]
+pub struct StringQuery {
+	/// a query to get the string from
+	pub query: Query,
+	/// a regex to finalize the string
+	pub regex: Option<Replace>,
+}
+
+#[expect(missing_docs, reason = "error message is self-documenting")]
+#[derive(thiserror::Error, Debug)]
+pub enum XmlError {
+	#[error(transparent)]
+	RawContentsNotSet(#[from] RawContentsNotSetError),
+
+	#[error("Invalid XML")]
+	Invalid(#[from] xml_parser::Error),
+
+	#[error("Invalid XPath expression {1:?}")]
+	BadXPath(#[source] ParserError, String),
+
+	#[error("Error evaluating XPath expression {1:?}")]
+	Evaluation(#[source] ExecutionError, String),
+
+	#[error("XPath expression {0:?} didn't match anything")]
+	NotFound(String),
+
+	#[error("XPath expression {0:?} should match a list of nodes")]
+	NotANodeset(String),
+
+	#[error(transparent)]
+	InvalidUrl(#[from] InvalidUrlError),
+}
+
+impl Query {
+	/// Create a new [`Query`] from an `XPath` expression
+	///
+	/// # Errors
+	/// if the `XPath` expression is invalid
+	pub fn new(xpath: String, optional: bool) -> Result<Self, XmlError> {
+		// make sure the expression is valid early. It's compiled again on use because XPath isn't Send
+		compile(&xpath)?;
+
+		Ok(Self { xpath, optional })
+	}
+
+	/// The `XPath` expression of the query
+	#[must_use]
+	pub fn xpath(&self) -> &str {
+		&self.xpath
+	}
+
+	/// Evaluate the query relative to `node`. Returns None if nothing was found and the query is optional
+	fn evaluate<'d>(&self, node: Node<'d>) -> Result<Option<Value<'d>>, XmlError> {
+		let val = compile(&self.xpath)?
+			.evaluate(&Context::new(), node)
+			.map_err(|e| XmlError::Evaluation(e, self.xpath.clone()))?;
+
+		let found = match &val {
+			Value::Nodeset(nodes) => nodes.size() > 0,
+			Value::String(s) => !s.is_empty(),
+			Value::Number(_) | Value::Boolean(_) => true,
+		};
+
+		if found {
+			Ok(Some(val))
+		} else if self.optional {
+			Ok(None)
+		} else {
+			Err(XmlError::NotFound(self.xpath.clone()))
+		}
+	}
+}
+
+#[async_trait]
+impl TransformEntry for Xml {
+	type Err = XmlError;
+
+	async fn transform_entry(&self, entry: Entry) -> Result<Vec<TransformedEntry>, Self::Err> {
+		self.parse(entry.raw_contents.as_ref().ok_or(RawContentsNotSetError)?)
+	}
+}
+
+impl Xml {
+	fn parse(&self, xml: &str) -> Result<Vec<TransformedEntry>, XmlError> {
+		let package = xml_parser::parse(xml)?;
+		let doc = package.as_document();
+
+		self.find_items(&doc)?
+			.into_iter()
+			.map(|item| self.extract_entry(item))
+			.collect()
+	}
+
+	fn find_items<'d>(&self, doc: &Document<'d>) -> Result<Vec<Node<'d>>, XmlError> {
+		let Some(query) = &self.item else {
+			return Ok(vec![doc.root().into()]);
+		};
+
+		match query.evaluate(doc.root().into())? {
+			Some(Value::Nodeset(nodes)) => Ok(nodes.document_order()),
+			// don't continue if the items query is optional and wasn't found
+			None => Ok(Vec::new()),
+			Some(_) => Err(XmlError::NotANodeset(query.xpath.clone())),
+		}
+	}
+
+	fn extract_entry(&self, item: Node<'_>) -> Result<TransformedEntry, XmlError> {
+		let title = self
+			.title
+			.as_ref()
+			.try_and_then(|q| extract_string(item, q))?;
+		let body = self.text.as_ref().try_and_then(|v| extract_body(item, v))?;
+		let id = self.id.as_ref().try_and_then(|q| extract_string(item, q))?;
+		let link = self.link.as_ref().try_and_then(|q| extract_url(item, q))?;
+
+		let img = self.img.as_ref().try_map(|v| {
+			v.iter()
+				.map(|q| extract_urls(item, q))
+				.collect::<Result<Vec<_>, _>>()
+				.map(|urls| {
+					urls.into_iter()
+						.flatten()
+						.map(Media::Photo)
+						.collect::<Vec<_>>()
+				})
+		})?;
+
+		// make it none if it's empty
+		let img = img.filter(|v| !v.is_empty());
+
+		Ok(TransformedEntry {
+			id: id.map(Into::into).unwrap_or_prev(),
+			raw_contents: body.clone().unwrap_or_prev(),
+			msg: TransformedMessage {
+				title: title.unwrap_or_prev(),
+				body: body.unwrap_or_prev(),
+				link: link.unwrap_or_prev(),
+				media: img.unwrap_or_prev(),
+			},
+			..Default::default()
+		})
+	}
+}
+
+fn compile(xpath: &str) -> Result<XPath, XmlError> {
+	Factory::new()
+		.build(xpath)
+		.map_err(|e| XmlError::BadXPath(e, xpath.to_owned()))?
+		// the expression was empty
+		.ok_or_else(|| XmlError::NotFound(xpath.to_owned()))
+}
+
+/// Get the strings of all values the query matched
+fn extract_strings(item: Node<'_>, query: &StringQuery) -> Result<Option<Vec<String>>, XmlError> {
+	let Some(val) = query.query.evaluate(item)? else {
+		return Ok(None);
+	};
+
+	let strings = match val {
+		Value::Nodeset(nodes) => nodes
+			.document_order()
+			.into_iter()
+			.map(|node| node.string_value())
+			.collect(),
+		Value::String(s) => vec![s],
+		Value::Number(n) => vec![n.to_string()],
+		Value::Boolean(b) => vec![b.to_string()],
+	};
+
+	Ok(Some(
+		strings
+			.into_iter()
+			.map(|s| {
+				let s = match query.regex.as_ref() {
+					Some(r) => r.replace(&s),
+					None => Cow::Borrowed(s.as_str()),
+				};
+
+				s.trim().to_owned()
+			})
+			.collect(),
+	))
+}
+
+fn extract_string(item: Node<'_>, query: &StringQuery) -> Result<Option<String>, XmlError> {
+	Ok(extract_strings(item, query)?.map(|v| v.join("\n\n")))
+}
+
+fn extract_body(item: Node<'_>, bodyq: &[StringQuery]) -> Result<Option<String>, XmlError> {
+	let body = bodyq
+		.iter()
+		.filter_map(|query| extract_string(item, query).transpose())
+		.collect::<Result<Vec<String>, XmlError>>()?
+		.join("\n\n");
+
+	if body.is_empty() {
+		Ok(None)
+	} else {
+		Ok(Some(body))
+	}
+}
+
+fn extract_url(item: Node<'_>, query: &StringQuery) -> Result<Option<Url>, XmlError> {
+	let Some(url) = extract_string(item, query)? else {
+		return Ok(None);
+	};
+
+	let url = Url::try_from(url.as_str()).map_err(|e| InvalidUrlError(e, url))?;
+	Ok(Some(url))
+}
+
+/// Extract a URL out of every node the query matched.
+///
+/// If the query is optional, invalid URLs are skipped with a warning instead of failing the entire entry
+fn extract_urls(item: Node<'_>, query: &StringQuery) -> Result<Vec<Url>, XmlError> {
+	let Some(urls) = extract_strings(item, query)? else {
+		return Ok(Vec::new());
+	};
+
+	urls.into_iter()
+		.filter_map(|url| match Url::try_from(url.as_str()) {
+			Ok(url) => Some(Ok(url)),
+			Err(e) if query.query.optional => {
+				tracing::warn!("Skipping invalid image URL {url:?}: {e}");
+				None
+			}
+			Err(e) => Some(Err(InvalidUrlError(e, url).into())),
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+
+	use super::*;
+	use crate::action::transform::result::TransformResult;
+	use assert_matches::assert_matches;
+
+	const XML: &str = r#"<?xml version="1.0"?>
+<response>
+	<status>ok</status>
+	<results>
+		<result id="1">
+			<name>First</name>
+			<description>Hello</description>
+			<url>https://example.com/1</url>
+			<images>
+				<image>https://example.com/1a.png</image>
+				<image>https://example.com/1b.png</image>
+			</images>
+		</result>
+		<result id="2">
+			<name>Second</name>
+			<description>World</description>
+			<url>https://example.com/2</url>
+		</result>
+	</results>
+</response>"#;
+
+	fn q(xpath: &str) -> StringQuery {
+		StringQuery {
+			query: Query::new(xpath.to_owned(), false).unwrap(),
+			regex: None,
+		}
+	}
+
+	fn parser() -> Xml {
+		Xml {
+			item: Some(Query::new("/response/results/result".to_owned(), false).unwrap()),
+			title: Some(q("name")),
+			text: Some(vec![q("description")]),
+			id: Some(q("@id")),
+			link: Some(q("url")),
+			img: Some(vec![StringQuery {
+				query: Query::new("images/image".to_owned(), true).unwrap(),
+				regex: None,
+			}]),
+		}
+	}
+
+	#[test]
+	fn parse_items() {
+		let entries = parser().parse(XML).unwrap();
+		assert_eq!(entries.len(), 2);
+
+		let first = &entries[0];
+		assert_matches!(&first.id, TransformResult::New(id) if id.0 == "1");
+		assert_matches!(&first.msg.title, TransformResult::New(t) if t == "First");
+		assert_matches!(&first.msg.body, TransformResult::New(b) if b == "Hello");
+		assert_matches!(&first.msg.link, TransformResult::New(l) if l.as_str() == "https://example.com/1");
+		assert_matches!(&first.msg.media, TransformResult::New(m) if m.len() == 2);
+
+		// optional image query didn't find anything
+		assert_matches!(&entries[1].msg.media, TransformResult::Previous);
+	}
+
+	#[test]
+	fn required_not_found() {
+		let mut xml = parser();
+		xml.title = Some(q("title"));
+
+		assert_matches!(xml.parse(XML), Err(XmlError::NotFound(xpath)) if xpath == "title");
+	}
+
+	#[test]
+	fn bad_xpath() {
+		assert_matches!(
+			Query::new("///[".to_owned(), false),
+			Err(XmlError::BadXPath(..))
+		);
+	}
+
+	#[test]
+	fn invalid_xml() {
+		assert_matches!(parser().parse("<a><b></a>"), Err(XmlError::Invalid(_)));
+	}
+}
@@ -8,8 +8,12 @@
 
 use crate::{
 	action::transform::{
-		entry::{feed::FeedError, html::HtmlError, http::HttpError, json::JsonError},
-		field::extract::ExtractError,
+		entry::{
+			auto::AutoError, extract_fields::ExtractFieldsError, feed::FeedError, html::HtmlError,
+			http::HttpError, json::JsonError, json_feed::JsonFeedError, translate::TranslateError,
+			xml::XmlError,
+		},
+		field::{extract::ExtractError, parse_date::ParseDateError},
 	},
 	entry::Entry,
 	error::InvalidUrlError,
@@ -29,6 +33,9 @@ pub struct TransformError {
 #[expect(missing_docs, reason = "error message is self-documenting")]
 #[derive(thiserror::Error, Debug)]
 pub enum TransformErrorKind {
+	#[error(transparent)]
+	RawContentsNotSet(#[from] RawContentsNotSetError),
+
 	#[error("Message link is not a valid URL after transforming")]
 	FieldLinkTransformInvalidUrl(#[source] InvalidUrlError),
 
@@ -44,8 +51,26 @@ pub enum TransformErrorKind {
 	#[error("JSON parsing error")]
 	Json(#[from] JsonError),
 
+	#[error("JSON Feed parsing error")]
+	JsonFeed(#[from] JsonFeedError),
+
+	#[error("Error parsing the entry in the detected format")]
+	Auto(#[from] AutoError),
+
+	#[error("XML parsing error")]
+	Xml(#[from] XmlError),
+
 	#[error("Extraction error")]
 	Extract(#[from] ExtractError),
+
+	#[error("Error extracting fields")]
+	ExtractFields(#[from] ExtractFieldsError),
+
+	#[error("Date parsing error")]
+	ParseDate(#[from] ParseDateError),
+
+	#[error("Translation error")]
+	Translate(#[from] TranslateError),
 }
 
 #[expect(missing_docs, reason = "error message is self-documenting")]
@@ -62,7 +87,8 @@ impl From<Infallible> for TransformErrorKind {
 impl TransformError {
 	pub(crate) fn is_connection_err(&self) -> Option<&(dyn StdError + Send + Sync)> {
 		match &self.kind {
-			TransformErrorKind::Http(HttpError::Other(_)) => Some(self),
+			TransformErrorKind::Http(HttpError::Other(e)) if e.is_connection_err() => Some(self),
+			TransformErrorKind::Translate(TranslateError::Request(_)) => Some(self),
 			_ => None,
 		}
 	}
@@ -9,20 +9,27 @@
 //!
 //! [Message]: crate::sink::message::Message
 
-pub mod caps;
+pub mod affix;
+pub mod case;
 pub mod decode_html;
 pub mod extract;
+pub mod parse_date;
 pub mod replace;
 pub mod set;
 pub mod shorten;
 pub mod trim;
+pub mod truncate_after;
 
 pub use self::{
-	caps::Caps, extract::Extract, replace::Replace, set::Set, shorten::Shorten, trim::Trim,
+	affix::Affix, case::Case, extract::Extract, parse_date::ParseDate, replace::Replace, set::Set,
+	shorten::Shorten, trim::Trim, truncate_after::TruncateAfter,
 };
 
 use async_trait::async_trait;
-use std::fmt::{self, Debug};
+use std::{
+	borrow::Cow,
+	fmt::{self, Debug},
+};
 use url::Url;
 
 use super::{Transform, result::TransformResult};
@@ -30,7 +37,6 @@ use crate::{
 	action::transform::error::{TransformError, TransformErrorKind},
 	entry::Entry,
 	error::InvalidUrlError,
-	sink::message::Message,
 	utils::OptionExt,
 };
 
@@ -44,6 +50,21 @@ pub trait TransformField: Debug + Send + Sync {
 	/// # Errors
 	/// Refer to implementator's docs. Most of them never error but some do
 	fn transform_field(&self, old_val: Option<&str>) -> Result<TransformResult<String>, Self::Err>;
+
+	/// Same as [`transform_field`](`TransformField::transform_field`) but with access to the entire `entry` the field is a part of, e.g. to use the values of other fields.
+	/// The field itself still has its old value in the `entry`.
+	///
+	/// Only transformators that need access to the rest of the entry have to implement it
+	///
+	/// # Errors
+	/// Refer to implementator's docs
+	fn transform_field_in_entry(
+		&self,
+		old_val: Option<&str>,
+		_entry: &Entry,
+	) -> Result<TransformResult<String>, Self::Err> {
+		self.transform_field(old_val)
+	}
 }
 
 // TODO: make a new name
@@ -69,83 +90,39 @@ where
 	T: TransformField,
 {
 	async fn transform(&self, mut entry: Entry) -> Result<Vec<Entry>, TransformError> {
-		// old value of the field
-		let old_val = match self.field {
-			Field::Title => entry.msg.title.take(),
-			Field::Body => entry.msg.body.take(),
-			Field::Link => entry.msg.link.take().map(|u| u.to_string()),
-			Field::Id => entry.id.take().map(|id| id.0),
-			Field::ReplyTo => entry.reply_to.take().map(|id| id.0),
-			Field::RawContets => entry.raw_contents.take(),
-		};
-
 		let new_val = self
 			.transformator
-			.transform_field(old_val.as_deref())
+			.transform_field_in_entry(self.field.get_from(&entry).as_deref(), &entry)
 			.map_err(|kind| TransformError {
 				kind: kind.into(),
 				original_entry: entry.clone(),
 			})?;
 
+		// old value of the field
+		let old_val = self.field.take_from(&mut entry);
+
 		// finalized value of the field. It's the new value that can get replaced with the old value if requested
 		let final_val = new_val.get(|| old_val);
 
-		let new_entry = match self.field {
-			Field::Title => Entry {
-				msg: Message {
-					title: final_val,
-					..entry.msg
-				},
-				..entry
-			},
-			Field::Body => Entry {
-				msg: Message {
-					body: final_val,
-					..entry.msg
-				},
-				..entry
-			},
-			Field::Link => {
-				let link = final_val.try_map(|s| {
-					Url::try_from(s.as_str()).map_err(|e| TransformError {
-						kind: TransformErrorKind::FieldLinkTransformInvalidUrl(InvalidUrlError(
-							e, s,
-						)),
-						original_entry: entry.clone(),
-					})
-				})?;
-
-				Entry {
-					msg: Message { link, ..entry.msg },
-					..entry
-				}
-			}
-			Field::Id => Entry {
-				id: final_val.map(Into::into),
-				..entry
-			},
-			Field::ReplyTo => Entry {
-				reply_to: final_val.map(Into::into),
-				..entry
-			},
-			Field::RawContets => Entry {
-				raw_contents: final_val,
-				..entry
-			},
-		};
+		self.field
+			.set_in(&mut entry, final_val)
+			.map_err(|e| TransformError {
+				kind: TransformErrorKind::FieldLinkTransformInvalidUrl(e),
+				original_entry: entry.clone(),
+			})?;
 
-		Ok(vec![new_entry])
+		Ok(vec![entry])
 	}
 }
 
 /// List of all available fields for transformations
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Field {
-	/// [`Message::title`] field
+	/// [`Message::title`](`crate::sink::message::Message::title`) field
 	Title,
-	/// [`Message::body`] field
+	/// [`Message::body`](`crate::sink::message::Message::body`) field
 	Body,
-	/// [`Message::link`] field
+	/// [`Message::link`](`crate::sink::message::Message::link`) field
 	Link,
 	/// [`Entry::id`] field
 	Id,
@@ -155,6 +132,60 @@ pub enum Field {
 	RawContets,
 }
 
+impl Field {
+	/// Get the value of this field from the `entry`, if it's set
+	#[must_use]
+	pub fn get_from(self, entry: &Entry) -> Option<Cow<'_, str>> {
+		match self {
+			Field::Title => entry.msg.title.as_deref().map(Cow::Borrowed),
+			Field::Body => entry.msg.body.as_deref().map(Cow::Borrowed),
+			Field::Link => entry.msg.link.as_ref().map(|s| Cow::Owned(s.to_string())),
+			Field::Id => entry.id.as_ref().map(|id| Cow::Borrowed(id.0.as_str())),
+			Field::ReplyTo => entry
+				.reply_to
+				.as_ref()
+				.map(|id| Cow::Borrowed(id.0.as_str())),
+			Field::RawContets => entry.raw_contents.as_deref().map(Cow::Borrowed),
+		}
+	}
+
+	/// Take the value of this field out of the `entry`, leaving it unset
+	pub(crate) fn take_from(self, entry: &mut Entry) -> Option<String> {
+		match self {
+			Field::Title => entry.msg.title.take(),
+			Field::Body => entry.msg.body.take(),
+			Field::Link => entry.msg.link.take().map(|u| u.to_string()),
+			Field::Id => entry.id.take().map(|id| id.0),
+			Field::ReplyTo => entry.reply_to.take().map(|id| id.0),
+			Field::RawContets => entry.raw_contents.take(),
+		}
+	}
+
+	/// Set this field of the `entry` to `value`
+	///
+	/// # Errors
+	/// if this is [`Field::Link`] and the `value` isn't a valid URL. The field is left as it was in that case
+	pub(crate) fn set_in(
+		self,
+		entry: &mut Entry,
+		value: Option<String>,
+	) -> Result<(), InvalidUrlError> {
+		match self {
+			Field::Title => entry.msg.title = value,
+			Field::Body => entry.msg.body = value,
+			Field::Link => {
+				entry.msg.link = value
+					.try_map(|s| Url::try_from(s.as_str()).map_err(|e| InvalidUrlError(e, s)))?;
+			}
+			Field::Id => entry.id = value.map(Into::into),
+			Field::ReplyTo => entry.reply_to = value.map(Into::into),
+			Field::RawContets => entry.raw_contents = value,
+		}
+
+		Ok(())
+	}
+}
+
 impl fmt::Display for Field {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		let name = match self {
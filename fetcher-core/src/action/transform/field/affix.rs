@@ -0,0 +1,72 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`Affix`] field transform
+
+use super::TransformField;
+use crate::action::transform::result::TransformResult;
+
+use std::convert::Infallible;
+
+/// Add text to the beginning and/or to the end of a field, keeping its original contents in between.
+///
+/// An empty field is treated as an empty string, i.e. it's set to [`prepend`](`Affix::prepend`) followed by [`append`](`Affix::append`)
+#[derive(Debug)]
+pub struct Affix {
+	/// The string to add to the beginning of the field
+	pub prepend: Option<String>,
+
+	/// The string to add to the end of the field
+	pub append: Option<String>,
+}
+
+impl TransformField for Affix {
+	type Err = Infallible;
+
+	fn transform_field(&self, old_val: Option<&str>) -> Result<TransformResult<String>, Self::Err> {
+		if self.prepend.is_none() && self.append.is_none() {
+			return Ok(TransformResult::Previous);
+		}
+
+		let new_val = [self.prepend.as_deref(), old_val, self.append.as_deref()]
+			.into_iter()
+			.flatten()
+			.collect::<String>();
+
+		Ok(TransformResult::New(new_val))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn prepend_and_append() {
+		let affix = Affix {
+			prepend: Some("Source: ".to_owned()),
+			append: Some("\n#news".to_owned()),
+		};
+
+		assert_eq!(
+			affix.transform_field(Some("Hello, World!")).unwrap(),
+			TransformResult::New("Source: Hello, World!\n#news".to_owned())
+		);
+	}
+
+	#[test]
+	fn empty_field() {
+		let affix = Affix {
+			prepend: None,
+			append: Some("footer".to_owned()),
+		};
+
+		assert_eq!(
+			affix.transform_field(None).unwrap(),
+			TransformResult::New("footer".to_owned())
+		);
+	}
+}
@@ -0,0 +1,106 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`Case`] field transform and the [`CaseMode`] enum that specifies how the case of the text should be changed
+
+use std::convert::Infallible;
+
+use super::TransformField;
+use crate::action::transform::result::{OptionUnwrapTransformResultExt, TransformResult};
+
+/// Change the case of the letters of a field
+#[derive(Default, Debug)]
+pub struct Case {
+	/// How to change the case
+	pub mode: CaseMode,
+}
+
+/// How to change the case of the text. All modes are Unicode-aware
+#[derive(Clone, Copy, Default, Debug)]
+pub enum CaseMode {
+	/// MAKE ALL TEXT UPPERCASE
+	#[default]
+	Upper,
+
+	/// make all text lowercase
+	Lower,
+
+	/// Capitalize The First Letter Of Every Word, leaving all other letters as is
+	Title,
+
+	/// Capitalize the first letter of every sentence. Leave all other letters as is
+	Sentence,
+}
+
+impl TransformField for Case {
+	type Err = Infallible;
+
+	// Infallible
+	fn transform_field(&self, field: Option<&str>) -> Result<TransformResult<String>, Self::Err> {
+		Ok(field.map(|s| self.mode.apply(s)).unwrap_or_empty())
+	}
+}
+
+impl CaseMode {
+	/// Change the case of `s` according to this mode
+	#[must_use]
+	pub fn apply(self, s: &str) -> String {
+		match self {
+			CaseMode::Upper => s.to_uppercase(),
+			CaseMode::Lower => s.to_lowercase(),
+			CaseMode::Title => capitalize_after(s, char::is_whitespace),
+			CaseMode::Sentence => capitalize_after(s, |c| matches!(c, '.' | '!' | '?')),
+		}
+	}
+}
+
+/// Uppercase the first letter of the string and the first letter after every char `is_boundary` returns true for
+fn capitalize_after(s: &str, is_boundary: impl Fn(char) -> bool) -> String {
+	let mut capitalized = String::with_capacity(s.len());
+	let mut capitalize_next = true;
+
+	for c in s.chars() {
+		if capitalize_next && c.is_alphanumeric() {
+			capitalized.extend(c.to_uppercase());
+			capitalize_next = false;
+		} else {
+			capitalized.push(c);
+		}
+
+		if is_boundary(c) {
+			capitalize_next = true;
+		}
+	}
+
+	capitalized
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn upper_lower() {
+		assert_eq!(CaseMode::Upper.apply("Straße ß"), "STRASSE SS");
+		assert_eq!(CaseMode::Lower.apply("ПРИВЕТ, Мир"), "привет, мир");
+	}
+
+	#[test]
+	fn title() {
+		assert_eq!(
+			CaseMode::Title.apply("hello, wORLD!\nпривет  мир"),
+			"Hello, WORLD!\nПривет  Мир"
+		);
+	}
+
+	#[test]
+	fn sentence() {
+		assert_eq!(
+			CaseMode::Sentence.apply("first one. second iPhone!  «third» one? 4th"),
+			"First one. Second iPhone!  «Third» one? 4th"
+		);
+	}
+}
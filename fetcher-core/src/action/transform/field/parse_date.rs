@@ -0,0 +1,251 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`ParseDate`] field transform, as well as all errors that can happen while executing it
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::TransformField;
+use crate::action::transform::result::TransformResult;
+
+/// Parse a date in a field and replace it with the same date in the RFC3339 format, e.g. "2024-01-03T00:00:00+00:00"
+///
+/// Common formats like RFC3339, RFC2822, and `%Y-%m-%d` are always tried, in addition to the user-provided [`formats`](`ParseDate::formats`).
+/// Dates without a timezone are assumed to be in UTC
+#[derive(Debug)]
+pub struct ParseDate {
+	/// A list of [`chrono` format strings](https://docs.rs/chrono/latest/chrono/format/strftime/index.html) to try, in order
+	pub formats: Vec<String>,
+
+	/// Whether to parse relative dates like "2 hours ago", "yesterday", or "just now"
+	pub relative: bool,
+}
+
+#[expect(missing_docs, reason = "error message is self-documenting")]
+#[derive(thiserror::Error, Debug)]
+#[error("Can't parse \"{0}\" as a date using any of the provided formats")]
+pub struct ParseDateError(pub String);
+
+impl TransformField for ParseDate {
+	type Err = ParseDateError;
+
+	fn transform_field(&self, old_val: Option<&str>) -> Result<TransformResult<String>, Self::Err> {
+		let Some(field) = old_val else {
+			return Ok(TransformResult::Previous);
+		};
+
+		let date = self
+			.parse(field, Utc::now())
+			.ok_or_else(|| ParseDateError(field.to_owned()))?;
+
+		Ok(TransformResult::New(date.to_rfc3339()))
+	}
+}
+
+impl ParseDate {
+	fn parse(&self, s: &str, now: DateTime<Utc>) -> Option<DateTime<FixedOffset>> {
+		let s = s.trim();
+
+		parse_date(s)
+			.or_else(|| {
+				self.formats
+					.iter()
+					.find_map(|fmt| parse_with_format(s, fmt))
+			})
+			.or_else(|| {
+				if self.relative {
+					parse_relative(s, now)
+				} else {
+					None
+				}
+			})
+	}
+}
+
+/// Parse a date in one of the common formats: RFC3339, RFC2822, `%Y-%m-%d %H:%M:%S`, or `%Y-%m-%d`
+pub(crate) fn parse_date(s: &str) -> Option<DateTime<FixedOffset>> {
+	let s = s.trim();
+
+	DateTime::parse_from_rfc3339(s)
+		.or_else(|_| DateTime::parse_from_rfc2822(s))
+		.ok()
+		.or_else(|| parse_with_format(s, "%Y-%m-%d %H:%M:%S"))
+		.or_else(|| parse_with_format(s, "%Y-%m-%d"))
+}
+
+fn parse_with_format(s: &str, fmt: &str) -> Option<DateTime<FixedOffset>> {
+	DateTime::parse_from_str(s, fmt).ok().or_else(|| {
+		NaiveDateTime::parse_from_str(s, fmt)
+			.ok()
+			.or_else(|| {
+				NaiveDate::parse_from_str(s, fmt)
+					.ok()
+					.and_then(|d| d.and_hms_opt(0, 0, 0))
+			})
+			.map(|dt| dt.and_utc().fixed_offset())
+	})
+}
+
+fn parse_relative(s: &str, now: DateTime<Utc>) -> Option<DateTime<FixedOffset>> {
+	static RELATIVE_RE: Lazy<Regex> = Lazy::new(|| {
+		Regex::new(r"(?i)^(\d+|an?|one)\s*([a-z]+?)s?\s+ago$").expect("hardcoded regex is valid")
+	});
+
+	let now = now.fixed_offset();
+
+	match s.to_lowercase().as_str() {
+		"now" | "just now" => return Some(now),
+		"today" => {
+			return now
+				.date_naive()
+				.and_hms_opt(0, 0, 0)
+				.map(|d| d.and_utc().fixed_offset());
+		}
+		"yesterday" => {
+			return (now - Duration::days(1))
+				.date_naive()
+				.and_hms_opt(0, 0, 0)
+				.map(|d| d.and_utc().fixed_offset());
+		}
+		_ => (),
+	}
+
+	let caps = RELATIVE_RE.captures(s)?;
+
+	let amount = match &caps[1] {
+		"a" | "an" | "one" => 1,
+		num => num.parse::<i64>().ok()?,
+	};
+
+	let unit = match caps[2].to_lowercase().as_str() {
+		"s" | "sec" | "second" => Duration::seconds(1),
+		"m" | "min" | "minute" => Duration::minutes(1),
+		"h" | "hr" | "hour" => Duration::hours(1),
+		"d" | "day" => Duration::days(1),
+		"w" | "wk" | "week" => Duration::weeks(1),
+		"mo" | "month" => Duration::days(30),
+		"y" | "yr" | "year" => Duration::days(365),
+		_ => return None,
+	};
+
+	// a count too large for a date is just as unparseable as an unknown unit
+	let ago = unit.checked_mul(i32::try_from(amount).ok()?)?;
+	now.checked_sub_signed(ago)
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+
+	use super::*;
+
+	fn parser(formats: &[&str]) -> ParseDate {
+		ParseDate {
+			formats: formats.iter().map(|&s| s.to_owned()).collect(),
+			relative: true,
+		}
+	}
+
+	fn now() -> DateTime<Utc> {
+		DateTime::parse_from_rfc3339("2024-01-10T12:00:00+00:00")
+			.unwrap()
+			.to_utc()
+	}
+
+	fn parse(p: &ParseDate, s: &str) -> Option<String> {
+		p.parse(s, now()).map(|d| d.to_rfc3339())
+	}
+
+	#[test]
+	fn common_formats() {
+		let p = parser(&[]);
+
+		assert_eq!(
+			parse(&p, "2024-01-03T10:00:00+02:00").as_deref(),
+			Some("2024-01-03T10:00:00+02:00")
+		);
+		assert_eq!(
+			parse(&p, "Wed, 03 Jan 2024 10:00:00 +0000").as_deref(),
+			Some("2024-01-03T10:00:00+00:00")
+		);
+		assert_eq!(
+			parse(&p, "2024-01-03").as_deref(),
+			Some("2024-01-03T00:00:00+00:00")
+		);
+	}
+
+	#[test]
+	fn custom_formats() {
+		let p = parser(&["%d.%m.%Y %H:%M", "%b %d, %Y"]);
+
+		assert_eq!(
+			parse(&p, "Jan 3, 2024").as_deref(),
+			Some("2024-01-03T00:00:00+00:00")
+		);
+		assert_eq!(
+			parse(&p, "03.01.2024 15:30").as_deref(),
+			Some("2024-01-03T15:30:00+00:00")
+		);
+	}
+
+	#[test]
+	fn relative() {
+		let p = parser(&[]);
+
+		assert_eq!(
+			parse(&p, "2 hours ago").as_deref(),
+			Some("2024-01-10T10:00:00+00:00")
+		);
+		assert_eq!(
+			parse(&p, "an hour ago").as_deref(),
+			Some("2024-01-10T11:00:00+00:00")
+		);
+		assert_eq!(
+			parse(&p, "5m ago").as_deref(),
+			Some("2024-01-10T11:55:00+00:00")
+		);
+		assert_eq!(
+			parse(&p, "Yesterday").as_deref(),
+			Some("2024-01-09T00:00:00+00:00")
+		);
+		assert_eq!(
+			parse(&p, "just now").as_deref(),
+			Some("2024-01-10T12:00:00+00:00")
+		);
+	}
+
+	#[test]
+	fn relative_overflow() {
+		let p = parser(&[]);
+
+		assert_eq!(parse(&p, "2000000000 years ago"), None);
+		assert_eq!(parse(&p, "99999999999999999999 seconds ago"), None);
+		assert_eq!(parse(&p, "2147483647 weeks ago"), None);
+	}
+
+	#[test]
+	fn relative_disabled() {
+		let p = ParseDate {
+			formats: Vec::new(),
+			relative: false,
+		};
+
+		assert_eq!(parse(&p, "2 hours ago"), None);
+	}
+
+	#[test]
+	fn unparseable_errors() {
+		let p = parser(&["%b %d, %Y"]);
+
+		assert!(p.transform_field(Some("not a date")).is_err());
+		assert!(matches!(
+			p.transform_field(None),
+			Ok(TransformResult::Previous)
+		));
+	}
+}
@@ -18,13 +18,14 @@ use crate::{
 /// Replace this with "" when you want to remove all HTML tags
 pub const HTML_TAG_RE: &str = "<[^>]*>";
 
-/// Replace the first regular expression match with a string
+/// Replace all regular expression matches with a string
 #[derive(Debug)]
 pub struct Replace {
 	/// The regular expression to match
 	pub re: Regex,
 
-	/// The string to replace the matched part with
+	/// The string to replace the matched part with.
+	/// May reference capture groups of [`Replace::re`] as `$1` or `${name}`
 	pub with: String,
 }
 
@@ -39,6 +40,18 @@ impl Replace {
 			with,
 		})
 	}
+
+	/// Create a new [`Replace`] that replaces all occurrences of the literal text `text` with the literal string `with`,
+	/// i.e. neither of them are treated as regular expressions or may reference capture groups
+	#[must_use]
+	#[expect(clippy::missing_panics_doc, reason = "doesn't actually panic")]
+	pub fn new_literal(text: &str, with: &str) -> Self {
+		Self {
+			re: Regex::new(&regex::escape(text))
+				.expect("an escaped string should always be a valid regular expression"),
+			with: with.replace('$', "$$"),
+		}
+	}
 }
 
 impl TransformField for Replace {
@@ -58,3 +71,30 @@ impl Replace {
 		self.re.replace_all(text, &self.with)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+	use super::*;
+
+	#[test]
+	fn capture_groups() {
+		let replace = Replace::new(r"Episode (\d+):", "#$1".to_owned()).unwrap();
+		assert_eq!(replace.replace("Episode 12: Title"), "#12 Title");
+	}
+
+	#[test]
+	fn named_capture_groups() {
+		let replace = Replace::new(r"(?P<num>\d+)\.", "${num})".to_owned()).unwrap();
+		assert_eq!(replace.replace("1. One 2. Two"), "1) One 2) Two");
+	}
+
+	#[test]
+	fn literal() {
+		let replace = Replace::new_literal("(1.5$)", "$1");
+		assert_eq!(
+			replace.replace("Price: (1.5$) or (105$)"),
+			"Price: $1 or (105$)"
+		);
+	}
+}
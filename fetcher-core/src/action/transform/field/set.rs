@@ -4,27 +4,140 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-//! This module contains the [`Set`] field transform
+//! This module contains the [`Set`] field transform, as well as [`SetFields`] that sets several fields at once
 
+use async_trait::async_trait;
 use rand::seq::SliceRandom;
 use std::convert::Infallible;
 
-use super::TransformField;
-use crate::action::transform::result::{OptionUnwrapTransformResultExt, TransformResult as TrRes};
+use super::{Field, TransformField};
+use crate::{
+	action::transform::{
+		Transform,
+		error::{TransformError, TransformErrorKind},
+		result::TransformResult as TrRes,
+		template::Template,
+	},
+	entry::Entry,
+};
 
-/// Set a field to a hardcoded value
+/// Set a field to a value rendered from a [`Template`] using the other fields of the entry, e.g. `[{id}] {title}`.
+///
+/// If several templates are provided, a random one is chosen. The field is cleared if there are none or if the rendered value is empty
 #[derive(Debug)]
-pub struct Set(pub Option<Vec<String>>);
+pub struct Set(pub Option<Vec<Template>>);
 
 impl TransformField for Set {
 	type Err = Infallible;
 
-	fn transform_field(&self, _old_field: Option<&str>) -> Result<TrRes<String>, Self::Err> {
-		Ok(self
-			.0
+	fn transform_field(&self, old_field: Option<&str>) -> Result<TrRes<String>, Self::Err> {
+		self.transform_field_in_entry(old_field, &Entry::default())
+	}
+
+	fn transform_field_in_entry(
+		&self,
+		_old_field: Option<&str>,
+		entry: &Entry,
+	) -> Result<TrRes<String>, Self::Err> {
+		Ok(match self.render(entry) {
+			Some(value) => TrRes::New(value),
+			None => TrRes::Empty,
+		})
+	}
+}
+
+impl Set {
+	fn render(&self, entry: &Entry) -> Option<String> {
+		self.0
 			.as_ref()
 			.and_then(|v| v.choose(&mut rand::thread_rng()))
-			.cloned()
-			.unwrap_or_empty())
+			.map(|template| template.render_entry(entry))
+			.filter(|value| !value.is_empty())
+	}
+}
+
+/// Set several fields at once.
+///
+/// All templates are rendered using the values the fields had before any of them were set,
+/// so that e.g. the title and the body can be swapped
+#[derive(Debug)]
+pub struct SetFields(pub Vec<(Field, Set)>);
+
+#[async_trait]
+impl Transform for SetFields {
+	async fn transform(&self, entry: Entry) -> Result<Vec<Entry>, TransformError> {
+		let mut new_entry = entry.clone();
+
+		for (field, set) in &self.0 {
+			field
+				.set_in(&mut new_entry, set.render(&entry))
+				.map_err(|e| TransformError {
+					kind: TransformErrorKind::FieldLinkTransformInvalidUrl(e),
+					original_entry: entry.clone(),
+				})?;
+		}
+
+		Ok(vec![new_entry])
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+
+	use super::*;
+	use crate::sink::message::Message;
+	use assert_matches::assert_matches;
+
+	#[test]
+	fn set_from_other_fields() {
+		let set = Set(Some(vec![Template::parse("{body}!").unwrap()]));
+		let entry = Entry {
+			msg: Message {
+				title: Some("Title".to_owned()),
+				body: Some("Body".to_owned()),
+				..Default::default()
+			},
+			..Default::default()
+		};
+
+		assert_matches!(
+			set.transform_field_in_entry(Some("Title"), &entry),
+			Ok(TrRes::New(s)) if s == "Body!"
+		);
+		assert_matches!(
+			Set(Some(vec![Template::parse("{link}").unwrap()]))
+				.transform_field_in_entry(None, &entry),
+			Ok(TrRes::Empty)
+		);
+	}
+
+	#[tokio::test]
+	async fn swap_fields() {
+		let set = SetFields(vec![
+			(
+				Field::Title,
+				Set(Some(vec![Template::parse("{body}").unwrap()])),
+			),
+			(
+				Field::Body,
+				Set(Some(vec![Template::parse("{title}").unwrap()])),
+			),
+			(Field::Id, Set(None)),
+		]);
+		let entry = Entry {
+			id: Some("id".to_owned().into()),
+			msg: Message {
+				title: Some("Title".to_owned()),
+				body: Some("Body".to_owned()),
+				..Default::default()
+			},
+			..Default::default()
+		};
+
+		let entry = set.transform(entry).await.unwrap().remove(0);
+		assert_eq!(entry.msg.title.as_deref(), Some("Body"));
+		assert_eq!(entry.msg.body.as_deref(), Some("Title"));
+		assert_eq!(entry.id, None);
 	}
 }
@@ -9,13 +9,34 @@
 use super::TransformField;
 use crate::action::transform::result::{OptionUnwrapTransformResultExt, TransformResult};
 
-use std::{convert::Infallible, iter::repeat};
+use std::convert::Infallible;
 
-/// Shorten a field to [`len`](`Shorten::len`). Makes the field completely empty if [`len`](`Shorten::len`) is 0, or trims the field to [`len`](`Shorten::len`) and adds "..." to the end
+/// The suffix [`Shorten`] appends to a shortened field by default
+pub const DEFAULT_SUFFIX: &str = "…";
+
+/// Shorten a field to [`len`](`Shorten::len`) characters.
+///
+/// Makes the field completely empty if [`len`](`Shorten::len`) is 0.
+/// Otherwise cuts the field at the last whitespace that fits into [`len`](`Shorten::len`) (or right at [`len`](`Shorten::len`) if there is none)
+/// and appends [`suffix`](`Shorten::suffix`) to the end
 #[derive(Debug)]
 pub struct Shorten {
-	/// The maximum length of the field string
+	/// The maximum length of the field string in characters, not including the suffix
 	pub len: usize,
+
+	/// The string to append to the end of the field if it was shortened
+	pub suffix: String,
+}
+
+impl Shorten {
+	/// Create a new [`Shorten`] that shortens a field to `len` characters and appends the [`DEFAULT_SUFFIX`]
+	#[must_use]
+	pub fn new(len: usize) -> Self {
+		Self {
+			len,
+			suffix: DEFAULT_SUFFIX.to_owned(),
+		}
+	}
 }
 
 impl TransformField for Shorten {
@@ -25,24 +46,61 @@ impl TransformField for Shorten {
 		// len == 0 means we should unset the field. Same effect as Set with value: None here
 		let new_val = if self.len == 0 {
 			None
-		} else if let Some(field) = field {
-			// pass-through the field if it's shorter than max len
-			if field.chars().count() < self.len {
-				Some(field.to_owned())
-			} else {
-				// take self.len chars from field and append "..."
-				Some(
-					field
-						.chars()
-						.take(self.len)
-						.chain(repeat('.').take(3))
-						.collect::<String>(),
-				)
-			}
 		} else {
-			None
+			field.map(|field| shorten(field, self.len, &self.suffix))
 		};
 
 		Ok(new_val.unwrap_or_empty())
 	}
 }
+
+fn shorten(s: &str, len: usize, suffix: &str) -> String {
+	// the byte index of the first char that doesn't fit into len
+	let Some((cut_at, _)) = s.char_indices().nth(len) else {
+		// pass-through the field if it's not longer than max len
+		return s.to_owned();
+	};
+
+	let fits = &s[..cut_at];
+
+	// don't cut in the middle of a word if we can help it
+	let fits = if s[cut_at..].starts_with(char::is_whitespace) {
+		fits
+	} else {
+		fits.rfind(char::is_whitespace)
+			.map_or(fits, |last_whitespace| &fits[..last_whitespace])
+	};
+
+	let mut shortened = fits.trim_end().to_owned();
+	shortened.push_str(suffix);
+	shortened
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn not_longer_than_len() {
+		assert_eq!(shorten("Hello", 10, "…"), "Hello");
+		assert_eq!(shorten("Hello", 5, "…"), "Hello");
+	}
+
+	#[test]
+	fn word_boundary() {
+		assert_eq!(shorten("Hello, World!", 9, "…"), "Hello,…");
+		assert_eq!(shorten("Hello, World!", 6, "…"), "Hello,…");
+		assert_eq!(shorten("Hello, World!", 7, "..."), "Hello,...");
+	}
+
+	#[test]
+	fn single_long_word() {
+		assert_eq!(shorten("Supercalifragilistic", 5, "…"), "Super…");
+	}
+
+	#[test]
+	fn multibyte() {
+		assert_eq!(shorten("Привет, мир", 8, "…"), "Привет,…");
+		assert_eq!(shorten("日本語のテキスト", 3, ""), "日本語");
+	}
+}
@@ -4,7 +4,7 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-//! This module contains the [`Trim`] field transform
+//! This module contains the [`Trim`] field transform and the [`TrimMode`] enum that specifies what should be trimmed
 
 use itertools::Itertools;
 use std::convert::Infallible;
@@ -12,19 +12,61 @@ use std::convert::Infallible;
 use super::TransformField;
 use crate::action::transform::result::{OptionUnwrapTransformResultExt, TransformResult};
 
-/// Trim whitespace from a field
-#[derive(Debug)]
-pub struct Trim;
+/// Chars some websites put into text that take up no space, e.g. to prevent copying or to allow line breaks inside words
+const ZERO_WIDTH_CHARS: &[char] = &[
+	'\u{200B}', // zero width space
+	'\u{200C}', // zero width non-joiner
+	'\u{200D}', // zero width joiner
+	'\u{2060}', // word joiner
+	'\u{FEFF}', // zero width no-break space
+];
+
+/// Trim whitespace or other unwanted characters from a field
+#[derive(Default, Debug)]
+pub struct Trim {
+	/// What to trim
+	pub mode: TrimMode,
+}
+
+/// What to trim from the text
+#[derive(Clone, Default, Debug)]
+pub enum TrimMode {
+	/// Remove whitespace to the left and to the right of every line, as well as empty lines at the start and at the end
+	#[default]
+	Lines,
+
+	/// Replace every run of whitespace, including newlines, with a single space and remove whitespace at the start and at the end
+	CollapseWhitespace,
+
+	/// Remove all of these characters at the start and at the end
+	Chars(Vec<char>),
+
+	/// Remove all zero width characters anywhere in the text
+	ZeroWidth,
+}
 
 impl TransformField for Trim {
 	type Err = Infallible;
 
 	fn transform_field(&self, old_val: Option<&str>) -> Result<TransformResult<String>, Self::Err> {
-		Ok(old_val.map(trim).unwrap_or_empty())
+		Ok(old_val.map(|s| self.mode.apply(s)).unwrap_or_empty())
+	}
+}
+
+impl TrimMode {
+	/// Trim `s` according to this mode
+	#[must_use]
+	pub fn apply(&self, s: &str) -> String {
+		match self {
+			TrimMode::Lines => trim_lines(s),
+			TrimMode::CollapseWhitespace => s.split_whitespace().join(" "),
+			TrimMode::Chars(chars) => s.trim_matches(chars.as_slice()).to_owned(),
+			TrimMode::ZeroWidth => s.replace(ZERO_WIDTH_CHARS, ""),
+		}
 	}
 }
 
-fn trim(s: &str) -> String {
+fn trim_lines(s: &str) -> String {
 	s.trim()
 		.lines()
 		.map(|line| line.trim().to_owned())
@@ -38,12 +80,33 @@ mod tests {
 	#[test]
 	fn one_line() {
 		const S: &str = "\n\n\n   \nHello, World!      \n    \n";
-		assert_eq!(trim(S), "Hello, World!");
+		assert_eq!(trim_lines(S), "Hello, World!");
 	}
 
 	#[test]
 	fn multi_line() {
 		const S: &str = "\n\n\n   \nHello, \n   World!      \n    \n";
-		assert_eq!(trim(S), "Hello,\nWorld!");
+		assert_eq!(trim_lines(S), "Hello,\nWorld!");
+	}
+
+	#[test]
+	fn collapse_whitespace() {
+		const S: &str = "  Hello,\n\n  \t World!  ";
+		assert_eq!(TrimMode::CollapseWhitespace.apply(S), "Hello, World!");
+	}
+
+	#[test]
+	fn chars() {
+		const S: &str = "-*- Hello, World! *-";
+		assert_eq!(
+			TrimMode::Chars(vec!['-', '*', ' ']).apply(S),
+			"Hello, World!"
+		);
+	}
+
+	#[test]
+	fn zero_width() {
+		const S: &str = "Hel\u{200B}lo,\u{FEFF} World!";
+		assert_eq!(TrimMode::ZeroWidth.apply(S), "Hello, World!");
 	}
 }
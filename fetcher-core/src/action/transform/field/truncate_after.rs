@@ -0,0 +1,71 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`TruncateAfter`] field transform
+
+use super::TransformField;
+use crate::action::transform::result::TransformResult;
+
+use std::convert::Infallible;
+
+/// Remove everything starting with the first occurence of [`marker`](`TruncateAfter::marker`) in a field, e.g. to remove footers and unsubscribe boilerplate from emails.
+///
+/// The field is left as is if it doesn't contain the marker
+#[derive(Debug)]
+pub struct TruncateAfter {
+	/// The text to cut the field at. It's removed as well
+	pub marker: String,
+}
+
+impl TransformField for TruncateAfter {
+	type Err = Infallible;
+
+	fn transform_field(&self, old_val: Option<&str>) -> Result<TransformResult<String>, Self::Err> {
+		let Some(idx) = old_val.and_then(|s| s.find(&self.marker)) else {
+			return Ok(TransformResult::Previous);
+		};
+
+		// old_val is always Some here since the marker has been found in it
+		let new_val = old_val.map(|s| s[..idx].to_owned()).unwrap_or_default();
+
+		Ok(TransformResult::New(new_val))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn cut_at_first_marker() {
+		let truncate = TruncateAfter {
+			marker: "--".to_owned(),
+		};
+
+		assert_eq!(
+			truncate
+				.transform_field(Some("Hello, World!\n--\nUnsubscribe\n--\n"))
+				.unwrap(),
+			TransformResult::New("Hello, World!\n".to_owned())
+		);
+	}
+
+	#[test]
+	fn marker_not_found() {
+		let truncate = TruncateAfter {
+			marker: "Unsubscribe".to_owned(),
+		};
+
+		assert_eq!(
+			truncate.transform_field(Some("Hello, World!")).unwrap(),
+			TransformResult::Previous
+		);
+		assert_eq!(
+			truncate.transform_field(None).unwrap(),
+			TransformResult::Previous
+		);
+	}
+}
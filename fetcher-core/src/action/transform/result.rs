@@ -11,6 +11,7 @@ use crate::{
 	sink::message::{Media, Message},
 };
 
+use chrono::{DateTime, Utc};
 use url::Url;
 
 /// An [`Entry`] mirror that can be converted to [`Entry`] but whose fields can be chosen to inherit old entry's values on [`None`]
@@ -24,6 +25,7 @@ pub struct TransformedEntry {
 	pub id: TransformResult<EntryId>,
 	pub reply_to: TransformResult<EntryId>,
 	pub raw_contents: TransformResult<String>,
+	pub published: TransformResult<DateTime<Utc>>,
 	pub msg: TransformedMessage,
 }
 
@@ -42,7 +44,7 @@ pub struct TransformedMessage {
 }
 
 /// Specify whether to use previous/old, empty, or a new value
-#[derive(Debug)]
+#[derive(PartialEq, Eq, Debug)]
 pub enum TransformResult<T> {
 	/// Keep the previous value
 	Previous,
@@ -71,6 +73,7 @@ impl TransformedEntry {
 			id: self.id.get(|| old_entry.id.clone()),
 			reply_to: self.reply_to.get(|| old_entry.reply_to.clone()),
 			raw_contents: self.raw_contents.get(|| old_entry.raw_contents.clone()),
+			published: self.published.get(|| old_entry.published),
 			msg: self.msg.into_message(&old_entry.msg),
 		}
 	}
@@ -0,0 +1,237 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`Template`] that formats a string using the fields of an [`Entry`]
+
+use crate::entry::Entry;
+
+use std::fmt::Write as _;
+use url::Url;
+
+/// A template to format an entry with, e.g. `"{title}\n\n{body}\n{link}"`.
+///
+/// Supported placeholders are `{title}`, `{body}`, `{link}`, `{id}`, and `{tag}`. Use `{{` and `}}` for literal braces.
+/// Placeholders of fields that aren't set are replaced with an empty string.
+/// `{tag}` isn't a field of the entry and is replaced with the tag set with [`Template::with_tag()`]
+#[derive(Clone, Debug)]
+pub struct Template(Vec<Segment>);
+
+#[derive(Clone, Debug)]
+enum Segment {
+	Literal(String),
+	Title,
+	Body,
+	Link,
+	Id,
+	Tag,
+}
+
+#[expect(missing_docs, reason = "error message is self-documenting")]
+#[derive(thiserror::Error, Debug)]
+pub enum TemplateError {
+	#[error(
+		"Unknown placeholder {{{0}}}. Available placeholders: {{title}}, {{body}}, {{link}}, {{id}}, {{tag}}"
+	)]
+	UnknownPlaceholder(String),
+
+	#[error("Placeholder isn't closed, use {{{{ for a literal brace")]
+	Unclosed,
+
+	#[error("Unexpected }}, use }}}} for a literal brace")]
+	UnexpectedClosingBrace,
+}
+
+impl Template {
+	/// Parse a template string
+	///
+	/// # Errors
+	/// if the template contains an unknown placeholder or unbalanced braces
+	pub fn parse(template: &str) -> Result<Self, TemplateError> {
+		let mut segments = Vec::new();
+		let mut literal = String::new();
+		let mut chars = template.chars().peekable();
+
+		while let Some(c) = chars.next() {
+			match c {
+				'{' if chars.next_if_eq(&'{').is_some() => literal.push('{'),
+				'}' if chars.next_if_eq(&'}').is_some() => literal.push('}'),
+				'{' => {
+					let mut name = String::new();
+					loop {
+						match chars.next() {
+							Some('}') => break,
+							Some(c) => name.push(c),
+							None => return Err(TemplateError::Unclosed),
+						}
+					}
+
+					let segment = match name.trim() {
+						"title" => Segment::Title,
+						"body" => Segment::Body,
+						"link" => Segment::Link,
+						"id" => Segment::Id,
+						"tag" => Segment::Tag,
+						_ => return Err(TemplateError::UnknownPlaceholder(name)),
+					};
+
+					if !literal.is_empty() {
+						segments.push(Segment::Literal(std::mem::take(&mut literal)));
+					}
+
+					segments.push(segment);
+				}
+				'}' => return Err(TemplateError::UnexpectedClosingBrace),
+				c => literal.push(c),
+			}
+		}
+
+		if !literal.is_empty() {
+			segments.push(Segment::Literal(literal));
+		}
+
+		Ok(Self(segments))
+	}
+
+	/// A template that always renders to `literal` as is, without any placeholders
+	#[must_use]
+	pub fn literal(literal: impl Into<String>) -> Self {
+		Self(vec![Segment::Literal(literal.into())])
+	}
+
+	/// Replace the `{tag}` placeholders with the `tag`, or with an empty string if there's none
+	#[must_use]
+	pub fn with_tag(self, tag: Option<&str>) -> Self {
+		let segments = self
+			.0
+			.into_iter()
+			.filter_map(|segment| match segment {
+				Segment::Tag => tag.map(|tag| Segment::Literal(tag.to_owned())),
+				other => Some(other),
+			})
+			.collect();
+
+		Self(segments)
+	}
+
+	/// Render the template using the fields of the `entry`
+	#[must_use]
+	pub fn render_entry(&self, entry: &Entry) -> String {
+		self.render(
+			entry.msg.title.as_deref(),
+			entry.msg.body.as_deref(),
+			entry.msg.link.as_ref(),
+			entry.id.as_ref().map(|id| id.0.as_str()),
+		)
+	}
+
+	pub(crate) fn render(
+		&self,
+		title: Option<&str>,
+		body: Option<&str>,
+		link: Option<&Url>,
+		id: Option<&str>,
+	) -> String {
+		let mut out = String::new();
+
+		for segment in &self.0 {
+			match segment {
+				Segment::Literal(s) => out.push_str(s),
+				Segment::Title => out.push_str(title.unwrap_or_default()),
+				Segment::Body => out.push_str(body.unwrap_or_default()),
+				Segment::Link => {
+					if let Some(link) = link {
+						_ = write!(out, "{link}");
+					}
+				}
+				Segment::Id => out.push_str(id.unwrap_or_default()),
+				// the tag hasn't been set with with_tag()
+				Segment::Tag => (),
+			}
+		}
+
+		out
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+
+	use super::*;
+	use crate::sink::message::Message;
+	use assert_matches::assert_matches;
+
+	#[test]
+	fn render_template() {
+		let template = Template::parse("{title}: {body} {{{ link }}}").unwrap();
+		let link = Url::parse("https://example.com/").unwrap();
+
+		assert_eq!(
+			template.render(Some("Hello"), Some("World"), Some(&link), None),
+			"Hello: World {https://example.com/}"
+		);
+		assert_eq!(
+			template.render(None, Some("World"), None, None),
+			": World {}"
+		);
+	}
+
+	#[test]
+	fn render_template_from_entry() {
+		let template = Template::parse("[{id}] {title}").unwrap();
+		let entry = Entry {
+			id: Some("1".to_owned().into()),
+			msg: Message {
+				title: Some("Hello".to_owned()),
+				..Default::default()
+			},
+			..Default::default()
+		};
+
+		assert_eq!(template.render_entry(&entry), "[1] Hello");
+	}
+
+	#[test]
+	fn render_tag() {
+		let entry = Entry {
+			msg: Message {
+				title: Some("Hello".to_owned()),
+				..Default::default()
+			},
+			..Default::default()
+		};
+
+		let template = Template::parse("[{tag}] {title}").unwrap();
+		assert_eq!(
+			template.clone().with_tag(Some("news")).render_entry(&entry),
+			"[news] Hello"
+		);
+		assert_eq!(template.with_tag(None).render_entry(&entry), "[] Hello");
+	}
+
+	#[test]
+	fn literal() {
+		assert_eq!(
+			Template::literal("{title} }")
+				.with_tag(Some("news"))
+				.render_entry(&Entry::default()),
+			"{title} }"
+		);
+	}
+
+	#[test]
+	fn bad_template() {
+		assert_matches!(
+			Template::parse("{title} {author}"),
+			Err(TemplateError::UnknownPlaceholder(p)) if p == "author"
+		);
+		assert_matches!(Template::parse("{title"), Err(TemplateError::Unclosed));
+		assert_matches!(
+			Template::parse("title}"),
+			Err(TemplateError::UnexpectedClosingBrace)
+		);
+	}
+}
@@ -0,0 +1,150 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`When`] action that runs other actions only on the entries that match a condition
+
+use async_trait::async_trait;
+
+use super::{
+	Action,
+	filter::Contains,
+	transform::{Transform, error::TransformError},
+};
+use crate::entry::Entry;
+
+/// Run the [`then`](`When::then`) actions on every entry that matches the [`condition`](`When::condition`)
+/// and pass the rest of the entries through unchanged.
+///
+/// Every entry goes through the actions on its own, e.g. a [`Take`](`super::filter::Take`) inside only limits the entries that a single entry has been transformed into.
+/// Sinks aren't supported inside and are skipped
+#[derive(Debug)]
+pub struct When {
+	/// All of these have to match for the actions to run. A field that isn't set is matched as if it were empty
+	pub condition: Vec<Contains>,
+
+	/// The actions to run the matching entries through, in order
+	pub then: Vec<Action>,
+}
+
+impl When {
+	/// Check if all conditions match the `entry`
+	#[must_use]
+	pub fn matches(&self, entry: &Entry) -> bool {
+		self.condition.iter().all(|cond| {
+			cond.re
+				.is_match(&cond.field.get_from(entry).unwrap_or_default())
+		})
+	}
+}
+
+#[async_trait]
+impl Transform for When {
+	async fn transform(&self, entry: Entry) -> Result<Vec<Entry>, TransformError> {
+		if !self.matches(&entry) {
+			return Ok(vec![entry]);
+		}
+
+		let mut entries = vec![entry];
+		for act in &self.then {
+			match act {
+				Action::Filter(f) => f.filter(&mut entries).await,
+				Action::Transform(tr) => {
+					let mut transformed = Vec::new();
+
+					for entry in entries {
+						transformed.extend(tr.transform(entry).await?);
+					}
+
+					entries = transformed;
+				}
+				Action::Sink(_) => {
+					tracing::warn!(
+						"Sinks can't be used inside of a conditional action, skipping..."
+					);
+				}
+			}
+		}
+
+		Ok(entries)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+	use super::*;
+	use crate::{
+		action::transform::{
+			Case,
+			field::{Field, TransformFieldWrapper},
+		},
+		sink::message::Message,
+	};
+
+	fn entry(title: &str, body: Option<&str>) -> Entry {
+		Entry {
+			msg: Message {
+				title: Some(title.to_owned()),
+				body: body.map(ToOwned::to_owned),
+				..Default::default()
+			},
+			..Default::default()
+		}
+	}
+
+	async fn run(when: &When, entries: Vec<Entry>) -> Vec<Entry> {
+		let mut out = Vec::new();
+		for entry in entries {
+			out.extend(when.transform(entry).await.unwrap());
+		}
+
+		out
+	}
+
+	#[tokio::test]
+	async fn only_matching_entries() {
+		let when = When {
+			condition: vec![Contains::new("keyword", Field::Title).unwrap()],
+			then: vec![
+				Action::Transform(Box::new(TransformFieldWrapper {
+					field: Field::Title,
+					transformator: Case::default(),
+				})),
+				Action::Filter(Box::new(Contains::new("keep", Field::Body).unwrap())),
+			],
+		};
+
+		let entries = run(
+			&when,
+			vec![
+				entry("keyword kept", Some("keep")),
+				entry("keyword dropped", Some("drop")),
+				entry("other", Some("drop")),
+			],
+		)
+		.await;
+
+		assert_eq!(
+			entries
+				.iter()
+				.map(|e| e.msg.title.as_deref().unwrap())
+				.collect::<Vec<_>>(),
+			["KEYWORD KEPT", "other"]
+		);
+	}
+
+	#[test]
+	fn missing_field_is_empty() {
+		let when = When {
+			condition: vec![Contains::new("^$", Field::Body).unwrap()],
+			then: Vec::new(),
+		};
+
+		assert!(when.matches(&entry("no body", None)));
+		assert!(when.matches(&entry("empty body", Some(""))));
+		assert!(!when.matches(&entry("with body", Some("body"))));
+	}
+}
@@ -7,11 +7,16 @@
 // I can avoid the clippy::doc_markdown lint this way :P
 #![doc = "This module contains the Google authenticator that can access Google services via OAuth2"]
 
+use crate::source::http::{self, HttpError};
+
 use serde::Deserialize;
 use std::time::{Duration, Instant};
 
 const GOOGLE_AUTH_URL: &str = "https://accounts.google.com/o/oauth2/token";
 
+/// The default value of [`Google::expiry_margin`]
+pub const DEFAULT_EXPIRY_MARGIN: Duration = Duration::from_mins(1);
+
 #[expect(clippy::doc_markdown, reason = "false positive")]
 /// An OAuth2 access token. It can be used to actually access stuff via OAuth2
 #[derive(Clone, Debug)]
@@ -29,6 +34,11 @@ struct AccessTokenResponce {
 	expires_in: u64,
 }
 
+#[derive(Deserialize)]
+struct ErrorResponse {
+	error: String,
+}
+
 #[expect(clippy::doc_markdown, reason = "false positive")]
 /// Google OAuth2 authenticator
 // TODO: link docs to the oauth2 spec
@@ -43,6 +53,9 @@ pub struct Google {
 	/// OAuth2 refresh token. It doesn't expire and is used to get new shortlived access tokens
 	pub refresh_token: String,
 
+	/// Refresh the access token this long before it actually expires, so that it doesn't expire while it's being used
+	pub expiry_margin: Duration,
+
 	/// OAuth2 access token. It's used for the actual accessing of the data
 	access_token: Option<AccessToken>,
 }
@@ -50,6 +63,9 @@ pub struct Google {
 #[expect(missing_docs, reason = "error message is self-documenting")]
 #[derive(thiserror::Error, Debug)]
 pub enum GoogleOAuth2Error {
+	#[error("Can't create an HTTP client")]
+	Client(#[source] HttpError),
+
 	#[error("Error contacting Google servers for authentication")]
 	Post(#[source] reqwest::Error),
 
@@ -58,6 +74,11 @@ pub enum GoogleOAuth2Error {
 
 	#[error("Can't get a new OAuth2 access token from Google: {0}")]
 	AccessToken(String),
+
+	#[error(
+		"Google OAuth2 refresh token has expired or has been revoked. Go through the OAuth2 setup again to get a new one"
+	)]
+	RefreshTokenRevoked,
 }
 
 impl Google {
@@ -69,6 +90,7 @@ impl Google {
 			client_id,
 			client_secret,
 			refresh_token,
+			expiry_margin: DEFAULT_EXPIRY_MARGIN,
 			access_token: None,
 		}
 	}
@@ -117,15 +139,14 @@ impl Google {
 
 			access_token_doesnt_exist
 		} || {
-			// or if if has expired
+			// or if if has expired or is about to
 			let is_expired = self
 				.access_token
 				.as_ref()
-				.and_then(|x| Instant::now().checked_duration_since(x.expires))
-				.is_some();
+				.is_some_and(|x| x.expires_within(self.expiry_margin, Instant::now()));
 
 			if is_expired {
-				tracing::trace!("Access token has expired");
+				tracing::trace!("Access token has expired or is about to expire");
 			}
 
 			is_expired
@@ -151,6 +172,14 @@ impl Google {
 	}
 }
 
+impl AccessToken {
+	/// Whether the token has already expired or expires in less than `margin` from `now`
+	pub(crate) fn expires_within(&self, margin: Duration, now: Instant) -> bool {
+		now.checked_add(margin)
+			.is_none_or(|deadline| deadline >= self.expires)
+	}
+}
+
 impl GoogleOAuth2Error {
 	pub(crate) fn is_connection_err(&self) -> Option<&(dyn std::error::Error + Send + Sync)> {
 		// #[expect(
@@ -192,7 +221,8 @@ pub async fn generate_refresh_token(
 		("grant_type", "authorization_code"),
 	];
 
-	let resp = reqwest::Client::new()
+	let resp = http::client()
+		.map_err(GoogleOAuth2Error::Client)?
 		.post(GOOGLE_AUTH_URL)
 		.form(&body)
 		.send()
@@ -227,7 +257,8 @@ async fn generate_access_token(
 		("grant_type", "refresh_token"),
 	];
 
-	let resp = reqwest::Client::new()
+	let resp = http::client()
+		.map_err(GoogleOAuth2Error::Client)?
 		.post(GOOGLE_AUTH_URL)
 		.form(&body)
 		.send()
@@ -239,5 +270,31 @@ async fn generate_access_token(
 
 	tracing::debug!("Got {resp:?} from the Google OAuth2 endpoint");
 
-	serde_json::from_str(&resp).map_err(|_| GoogleOAuth2Error::AccessToken(resp))
+	serde_json::from_str(&resp).map_err(|_| {
+		// Google responds with "invalid_grant" if the refresh token is no longer valid
+		match serde_json::from_str::<ErrorResponse>(&resp) {
+			Ok(ErrorResponse { error }) if error == "invalid_grant" => {
+				GoogleOAuth2Error::RefreshTokenRevoked
+			}
+			_ => GoogleOAuth2Error::AccessToken(resp),
+		}
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn token_is_refreshed_early() {
+		let now = Instant::now();
+		let token = AccessToken {
+			token: String::new(),
+			expires: now + Duration::from_secs(30),
+		};
+
+		assert!(!token.expires_within(Duration::ZERO, now));
+		assert!(token.expires_within(DEFAULT_EXPIRY_MARGIN, now));
+		assert!(token.expires_within(Duration::ZERO, now + Duration::from_secs(30)));
+	}
 }
@@ -0,0 +1,285 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the Microsoft authenticator that can access Outlook and Office 365 mailboxes via `OAuth2`
+
+use super::google::{AccessToken, DEFAULT_EXPIRY_MARGIN};
+use crate::{
+	external_save::ExternalSaveError,
+	source::http::{self, HttpError},
+};
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::{
+	fmt,
+	time::{Duration, Instant},
+};
+
+/// The URL the user should open to grant access and get an access code
+pub const MICROSOFT_AUTHORIZE_URL: &str =
+	"https://login.microsoftonline.com/common/oauth2/v2.0/authorize";
+const MICROSOFT_TOKEN_URL: &str = "https://login.microsoftonline.com/common/oauth2/v2.0/token";
+
+/// The redirect URI for native apps. The access code is passed to it as the `code` query parameter
+pub const MICROSOFT_REDIRECT_URI: &str =
+	"https://login.microsoftonline.com/common/oauth2/nativeclient";
+
+/// Scopes required to read a mailbox via IMAP and to get a refresh token
+pub const MICROSOFT_IMAP_SCOPE: &str =
+	"offline_access https://outlook.office.com/IMAP.AccessAsUser.All";
+
+#[derive(Deserialize)]
+struct TokenResponse {
+	access_token: String,
+	expires_in: u64,
+	refresh_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+	error: String,
+}
+
+#[expect(clippy::doc_markdown, reason = "false positive")]
+/// Microsoft identity platform OAuth2 authenticator
+#[derive(Debug)]
+pub struct Microsoft {
+	/// OAuth2 client (application) id
+	pub client_id: String,
+
+	/// OAuth2 client secret. Not needed for public clients
+	pub client_secret: Option<String>,
+
+	/// OAuth2 refresh token. It's used to get new shortlived access tokens and may be replaced with a new one while doing so
+	pub refresh_token: String,
+
+	/// Refresh the access token this long before it actually expires, so that it doesn't expire while it's being used
+	pub expiry_margin: Duration,
+
+	/// External save location for the refresh token.
+	/// It's called every time Microsoft replaces the refresh token with a new one
+	pub external_save: Option<Box<dyn SaveRefreshToken>>,
+
+	/// OAuth2 access token. It's used for the actual accessing of the data
+	access_token: Option<AccessToken>,
+}
+
+/// A place to save the refresh token to, to keep using the newest one across restarts after the old one has been replaced
+#[async_trait]
+pub trait SaveRefreshToken: fmt::Debug + Send + Sync {
+	/// Save the new `refresh_token` externally
+	///
+	/// # Errors
+	/// It may return an error if there has been issues saving, e.g. writing to disk
+	async fn save_refresh_token(&mut self, refresh_token: &str) -> Result<(), ExternalSaveError>;
+}
+
+#[expect(missing_docs, reason = "error message is self-documenting")]
+#[derive(thiserror::Error, Debug)]
+pub enum MicrosoftOAuth2Error {
+	#[error("Can't create an HTTP client")]
+	Client(#[source] HttpError),
+
+	#[error("Error contacting Microsoft servers for authentication")]
+	Post(#[source] reqwest::Error),
+
+	#[error("Can't get a new OAuth2 refresh token from Microsoft: {0}")]
+	RefreshToken(String),
+
+	#[error("Can't get a new OAuth2 access token from Microsoft: {0}")]
+	AccessToken(String),
+
+	#[error(
+		"Microsoft OAuth2 refresh token has expired or has been revoked. Go through the OAuth2 setup again to get a new one"
+	)]
+	RefreshTokenRevoked,
+}
+
+impl Microsoft {
+	#[expect(clippy::doc_markdown, reason = "false positive")]
+	/// Creates a new Microsoft OAuth2 authenticator
+	#[must_use]
+	pub const fn new(
+		client_id: String,
+		client_secret: Option<String>,
+		refresh_token: String,
+	) -> Self {
+		Self {
+			client_id,
+			client_secret,
+			refresh_token,
+			expiry_margin: DEFAULT_EXPIRY_MARGIN,
+			external_save: None,
+			access_token: None,
+		}
+	}
+
+	/// Save the refresh token to `external_save` every time it's replaced with a new one
+	#[must_use]
+	pub fn with_external_save<E>(mut self, external_save: E) -> Self
+	where
+		E: SaveRefreshToken + 'static,
+	{
+		self.external_save = Some(Box::new(external_save));
+		self
+	}
+
+	/// Force fetch a new access token and overwrite the old one
+	///
+	/// # Errors
+	/// * if there was a network connection error
+	/// * if the responce isn't a valid access token
+	/// * if the refresh token has been revoked
+	pub async fn get_new_access_token(&mut self) -> Result<&AccessToken, MicrosoftOAuth2Error> {
+		let mut body = vec![
+			("client_id", self.client_id.as_str()),
+			("refresh_token", self.refresh_token.as_str()),
+			("scope", MICROSOFT_IMAP_SCOPE),
+			("grant_type", "refresh_token"),
+		];
+
+		if let Some(client_secret) = &self.client_secret {
+			body.push(("client_secret", client_secret));
+		}
+
+		let TokenResponse {
+			access_token,
+			expires_in,
+			refresh_token,
+		} = request_token(&body).await.map_err(|e| match e {
+			TokenRequestError::Other(e) => e,
+			TokenRequestError::InvalidGrant => MicrosoftOAuth2Error::RefreshTokenRevoked,
+			TokenRequestError::BadResponse(resp) => MicrosoftOAuth2Error::AccessToken(resp),
+		})?;
+
+		tracing::debug!("New access token expires in {expires_in}s");
+
+		// Microsoft may rotate refresh tokens, the new one should be used from now on
+		if let Some(refresh_token) = refresh_token
+			&& refresh_token != self.refresh_token
+		{
+			tracing::debug!("Got a new refresh token");
+
+			// the access token has been gotten already, so only log the error.
+			// The old refresh token should keep working for a while anyways
+			if let Some(ext_save) = &mut self.external_save
+				&& let Err(e) = ext_save.save_refresh_token(&refresh_token).await
+			{
+				tracing::error!("Failed to save the new Microsoft OAuth2 refresh token: {e}");
+			}
+
+			self.refresh_token = refresh_token;
+		}
+
+		Ok(self.access_token.insert(AccessToken {
+			token: access_token,
+			expires: Instant::now() + Duration::from_secs(expires_in),
+		}))
+	}
+
+	/// Return a previously gotten `access_token` or fetch a new one if it has expired or is about to
+	///
+	/// # Errors
+	/// * if there was a network connection error
+	/// * if the responce isn't a valid access token
+	/// * if the refresh token has been revoked
+	#[tracing::instrument(name = "microsoft_oauth2_access_token", skip_all)]
+	pub async fn access_token(&mut self) -> Result<&str, MicrosoftOAuth2Error> {
+		let is_valid = self
+			.access_token
+			.as_ref()
+			.is_some_and(|x| !x.expires_within(self.expiry_margin, Instant::now()));
+
+		if is_valid {
+			tracing::trace!("Access token is still valid");
+		} else {
+			tracing::trace!("Access token doesn't exist or is about to expire");
+			self.get_new_access_token().await?;
+		}
+
+		Ok(self
+			.access_token
+			.as_ref()
+			.map(|x| x.token.as_str())
+			.unwrap_or_default())
+	}
+}
+
+#[expect(clippy::doc_markdown, reason = "false positive")]
+/// Generate and return a new Microsoft OAuth2 refresh token using the `client_id`, `client_secret`, and `access_code`
+///
+/// The access code can be gotten after opening [`MICROSOFT_AUTHORIZE_URL`] with [`MICROSOFT_IMAP_SCOPE`] and [`MICROSOFT_REDIRECT_URI`]
+///
+/// # Errors
+/// * if there was a network connection error
+/// * if the responce isn't a valid refresh_token
+pub async fn generate_refresh_token(
+	client_id: &str,
+	client_secret: Option<&str>,
+	access_code: &str,
+) -> Result<String, MicrosoftOAuth2Error> {
+	tracing::debug!(
+		"Generating a new OAuth2 refresh token from client_id: {client_id:?}, client_secret: {client_secret:?}, and access_code: {access_code:?}"
+	);
+
+	let mut body = vec![
+		("client_id", client_id),
+		("code", access_code),
+		("redirect_uri", MICROSOFT_REDIRECT_URI),
+		("scope", MICROSOFT_IMAP_SCOPE),
+		("grant_type", "authorization_code"),
+	];
+
+	if let Some(client_secret) = client_secret {
+		body.push(("client_secret", client_secret));
+	}
+
+	let resp = request_token(&body).await.map_err(|e| match e {
+		TokenRequestError::Other(e) => e,
+		TokenRequestError::InvalidGrant => {
+			MicrosoftOAuth2Error::RefreshToken("invalid access code".to_owned())
+		}
+		TokenRequestError::BadResponse(resp) => MicrosoftOAuth2Error::RefreshToken(resp),
+	})?;
+
+	resp.refresh_token.ok_or_else(|| {
+		MicrosoftOAuth2Error::RefreshToken(
+			"no refresh token in the response, is the offline_access scope missing?".to_owned(),
+		)
+	})
+}
+
+enum TokenRequestError {
+	Other(MicrosoftOAuth2Error),
+	InvalidGrant,
+	BadResponse(String),
+}
+
+async fn request_token(body: &[(&str, &str)]) -> Result<TokenResponse, TokenRequestError> {
+	let resp = http::client()
+		.map_err(|e| TokenRequestError::Other(MicrosoftOAuth2Error::Client(e)))?
+		.post(MICROSOFT_TOKEN_URL)
+		.form(body)
+		.send()
+		.await
+		.map_err(|e| TokenRequestError::Other(MicrosoftOAuth2Error::Post(e)))?
+		.text()
+		.await
+		.map_err(|e| TokenRequestError::Other(MicrosoftOAuth2Error::Post(e)))?;
+
+	tracing::debug!("Got {resp:?} from the Microsoft OAuth2 endpoint");
+
+	serde_json::from_str(&resp).map_err(|_| {
+		// Microsoft responds with "invalid_grant" if the refresh token or the access code is no longer valid
+		match serde_json::from_str::<ErrorResponse>(&resp) {
+			Ok(ErrorResponse { error }) if error == "invalid_grant" => {
+				TokenRequestError::InvalidGrant
+			}
+			_ => TokenRequestError::BadResponse(resp),
+		}
+	})
+}
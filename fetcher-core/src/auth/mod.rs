@@ -4,8 +4,10 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-//! This module contains all external manual authentication implementations. For now it's just [`Google OAuth2`](`Google`)
+//! This module contains all external manual authentication implementations: [`Google OAuth2`](`Google`) and [`Microsoft OAuth2`](`Microsoft`)
 
 pub mod google;
+pub mod microsoft;
 
 pub use google::Google;
+pub use microsoft::Microsoft;
@@ -9,6 +9,7 @@
 
 use crate::sink::message::Message;
 
+use chrono::{DateTime, Utc};
 use std::{fmt::Debug, ops::Deref};
 
 // TODO: make generic over String/i64/other types of id
@@ -33,6 +34,11 @@ pub struct Entry {
 	/// It's used to compose a message using [`transformators`](`crate::action::transform::Transform`).
 	pub raw_contents: Option<String>,
 
+	/// When the entry was published, if the source or a parser provided it
+	///
+	/// A [`NotOlder`](`crate::read_filter::NotOlder`) read filter uses it to filter out entries that are too old
+	pub published: Option<DateTime<Utc>>,
+
 	/// The message itself
 	pub msg: Message,
 }
@@ -63,6 +69,7 @@ impl Debug for Entry {
 			.field("id", &self.id)
 			.field("reply_to", &self.reply_to)
 			.field("raw_contents.is_some()", &self.raw_contents.is_some())
+			.field("published", &self.published)
 			.field("msg", &self.msg)
 			.finish()
 	}
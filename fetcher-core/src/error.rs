@@ -11,7 +11,7 @@ use crate::{
 	external_save::ExternalSaveError, sink::error::SinkError, source::error::SourceError,
 };
 
-use std::error::Error as StdError;
+use std::{error::Error as StdError, time::Duration};
 
 #[expect(missing_docs, reason = "error message is self-documenting")]
 #[derive(thiserror::Error, Debug)]
@@ -30,6 +30,33 @@ pub enum FetcherError {
 
 	#[error("Error writing to the external save location")]
 	ExternalSave(#[source] ExternalSaveError),
+
+	#[error("Took longer than {0:?} and was cancelled")]
+	Timeout(Duration),
+
+	#[error(transparent)]
+	TooManyEntries(#[from] TooManyEntriesError),
+}
+
+/// A [`FetcherError`] that happened while running a task, together with the name of that task
+#[expect(missing_docs, reason = "error message is self-documenting")]
+#[derive(thiserror::Error, Debug)]
+#[error("Task {}", .task.as_deref().unwrap_or("without a name"))]
+pub struct TaskError {
+	pub task: Option<String>,
+
+	#[source]
+	pub error: FetcherError,
+}
+
+#[expect(missing_docs, reason = "error message is self-documenting")]
+#[derive(thiserror::Error, Debug)]
+#[error(
+	"Got {count} entries to send, more than the max_entries limit of {max}. Nothing has been sent"
+)]
+pub struct TooManyEntriesError {
+	pub count: usize,
+	pub max: usize,
 }
 
 #[expect(missing_docs, reason = "error message is self-documenting")]
@@ -47,15 +74,13 @@ impl FetcherError {
 	#[must_use]
 	pub fn is_connection_error(&self) -> Option<&(dyn StdError + Send + Sync)> {
 		// I know it will match any future variants automatically but I actually want it to do that anyways
-		#[expect(
-			clippy::match_wildcard_for_single_variants,
-			reason = "all other branches are ignored, no matter how many there are"
-		)]
 		match self {
 			Self::Source(e) => e.is_connection_err(),
 			FetcherError::Transform(e) => e.is_connection_err(),
 			FetcherError::Sink(e) => e.is_connection_err(),
 			FetcherError::GoogleOAuth2(e) => e.is_connection_err(),
+			// a task usually takes too long because of a slow or unresponsive server
+			FetcherError::Timeout(_) => Some(self),
 			_ => None,
 		}
 	}
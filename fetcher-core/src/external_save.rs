@@ -14,7 +14,8 @@ use std::{
 };
 
 use crate::{
-	entry::EntryId, read_filter::ReadFilter, sink::message::MessageId, utils::DisplayDebug,
+	entry::EntryId, read_filter::ReadFilter, task::entry_to_msg_map::SentMessage,
+	utils::DisplayDebug,
 };
 
 /// This trait represent some kind of external save destination.
@@ -30,10 +31,10 @@ pub trait ExternalSave: Debug + Send + Sync {
 		read_filter: &dyn ReadFilter,
 	) -> Result<(), ExternalSaveError>;
 
-	/// Save the entry id to message id map (see [`Task.entry_to_msg_map`]) enternally
+	/// Save the entry id to message id maps of every sink (see [`Task.entry_to_msg_map`]) enternally
 	async fn save_entry_to_msg_map(
 		&mut self,
-		map: &HashMap<EntryId, MessageId>,
+		maps: &[HashMap<EntryId, SentMessage>],
 	) -> Result<(), ExternalSaveError>;
 }
 
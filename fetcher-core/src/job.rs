@@ -6,58 +6,494 @@
 
 //! This module contains the [`Job`] struct and the entryway to the library
 
+pub mod backoff;
+pub mod heartbeat;
 pub mod timepoint;
 
 use futures::future::join_all;
-use tokio::time::sleep;
+use std::{error::Error as StdError, ops::ControlFlow, sync::Arc, time::Instant};
+use tokio::{
+	sync::{Semaphore, mpsc},
+	time::{Instant as TokioInstant, sleep_until, timeout_at},
+};
 
-use self::timepoint::TimePoint;
-use crate::{error::FetcherError, task::Task};
+use self::{
+	backoff::{Backoff, FailureState},
+	heartbeat::Heartbeat,
+	timepoint::TimePoint,
+};
+use crate::{error::TaskError, task::Task};
 
 /// A single job, containing a single or a couple [`tasks`](`Task`), possibly refetching every set amount of time
 #[derive(Debug)]
 pub struct Job {
+	/// Name of the job. Used to tell the metrics of tasks of different jobs apart
+	pub name: Option<String>,
+
 	/// The tasks to run
 	pub tasks: Vec<Task>,
 
-	/// Refresh/refetch/redo the job every "this" point of the day
+	/// Refresh/refetch/redo the job every "this" point of the day.
+	/// Tasks with their own [`refresh_time`](`Task::refresh_time`) run on that one instead
 	pub refresh_time: Option<TimePoint>,
+
+	/// For how long to skip a task of a refreshed job that keeps failing
+	pub backoff: Backoff,
+
+	/// Limits how many tasks can run at the same time. Can be shared between several jobs to limit all of them at once
+	pub task_limit: Option<Arc<Semaphore>>,
+
+	/// URLs to ping after every run of the job to let an external monitor know it's still running
+	pub heartbeat: Heartbeat,
+
+	/// Receives indices of tasks to run right away, e.g. when a webhook has been called.
+	/// A job with a trigger keeps waiting for it even if it isn't refreshed
+	pub trigger: Option<mpsc::Receiver<usize>>,
 }
 
 impl Job {
-	/// Run this job to completion or return early on an error
+	/// Run this job to completion or return early on an error.
+	///
+	/// Every task runs right away the first time. After that, each one runs on its own [`refresh_time`](`Task::refresh_time`),
+	/// or on the one of the job if it doesn't have one.
+	///
+	/// An error in a task doesn't stop the other tasks of the job.
+	/// If the job is refreshed, the failed tasks are logged and skipped for the duration of the [`backoff`](`Job::backoff`),
+	/// unless all of them have failed, in which case the errors are returned
+	///
+	/// Between the runs, the tasks sent through the [`trigger`](`Job::trigger`) are run one by one, ignoring the backoff.
+	/// Their errors are only logged
 	///
 	/// # Errors
-	/// if any of the inner tasks return an error, refer to [`Task`] documentation
-	pub async fn run(&mut self) -> Result<(), Vec<FetcherError>> {
+	/// if any of the inner tasks return an error and the job isn't refreshed, or all of them return an error. Refer to [`Task`] documentation
+	pub async fn run(&mut self) -> Result<(), Vec<TaskError>> {
+		let mut failure_states = vec![FailureState::default(); self.tasks.len()];
+
+		// the index of the task to run instead of all of them if the run has been triggered
+		let mut triggered = None;
+
+		// when the next scheduled run is due. Triggered runs in between don't push it back
+		let mut next_run = None;
+
+		// same as `next_run` but for each of the tasks with their own refresh time
+		let mut next_task_runs = vec![None; self.tasks.len()];
+
+		let mut first_run = true;
+		let refreshed = self.is_refreshed();
+
 		loop {
-			let tasks = self.tasks.iter_mut().map(Task::run);
+			let now = Instant::now();
+
+			// which tasks are due on their schedule. Only the triggered task runs if the run has been triggered
+			let due = match triggered {
+				Some(_) => Vec::new(),
+				None => self.due_tasks(first_run, &mut next_run, &mut next_task_runs),
+			};
+			let due_count = due.iter().filter(|due| **due).count();
+			first_run = false;
+
+			let tasks = self
+				.tasks
+				.iter_mut()
+				.zip(&mut failure_states)
+				.enumerate()
+				.filter(|(idx, _)| match triggered {
+					Some(triggered) => triggered == *idx,
+					None => due[*idx],
+				})
+				.map(|(_, task_and_state)| task_and_state)
+				.filter(|(task, state)| {
+					let skip = triggered.is_none() && state.should_skip(now);
+					if skip {
+						tracing::debug!(
+							"Skipping task {} because it has failed too many times recently",
+							task.name.as_deref().unwrap_or("without a name")
+						);
+					}
+
+					!skip
+				})
+				.map(|(task, state)| async {
+					// a closed semaphore isn't limiting anything anymore
+					let _permit = match &self.task_limit {
+						Some(limit) => limit.acquire().await.ok(),
+						None => None,
+					};
+
+					let res = task
+						.run_in_job(self.name.as_deref())
+						.await
+						.map_err(|error| TaskError {
+							task: task.name.clone(),
+							error,
+						});
+
+					match &res {
+						Ok(()) => state.succeed(),
+						Err(e) => {
+							let delay = state.fail(&self.backoff, Instant::now());
+
+							if refreshed {
+								tracing::info!(
+									"{e} is going to be skipped for {}m",
+									delay.as_secs() / 60
+								);
+							}
+						}
+					}
+
+					res
+				});
 			let results = join_all(tasks).await;
 
+			let task_count = self.tasks.len();
+			let run_count = results.len();
 			let errors = results
 				.into_iter()
-				.filter_map(|r| match r {
-					Ok(()) => None,
-					Err(e) => Some(e),
-				})
+				.filter_map(Result::err)
 				.collect::<Vec<_>>();
 
+			if triggered.is_none() {
+				tracing::info!(
+					"Tasks finished: {} succeeded, {} failed, {} skipped",
+					run_count - errors.len(),
+					errors.len(),
+					due_count - run_count,
+				);
+
+				// a task that is skipped because it keeps failing is still failing
+				self.heartbeat
+					.ping(errors.is_empty() && run_count == due_count)
+					.await;
+			}
+
 			if !errors.is_empty() {
-				return Err(errors);
+				// nothing is left running, let the caller decide what to do with the errors
+				if triggered.is_none() && (!refreshed || errors.len() == task_count) {
+					return Err(errors);
+				}
+
+				for err in &errors {
+					tracing::error!("{}", display_chain(err));
+				}
+			}
+
+			match self
+				.wait_for_next_run(&mut next_run, &mut next_task_runs)
+				.await
+			{
+				ControlFlow::Continue(next) => triggered = next,
+				ControlFlow::Break(()) => return Ok(()),
+			}
+		}
+	}
+
+	/// Whether the job or any of its tasks is run repeatedly on a schedule
+	fn is_refreshed(&self) -> bool {
+		self.refresh_time.is_some() || self.tasks.iter().any(|task| task.refresh_time.is_some())
+	}
+
+	/// Find out which tasks are due to run on their schedule, either on their own or on the one of the job,
+	/// and forget the deadlines that have passed to compute the next ones later on.
+	/// Every task is due on the `first_run`
+	fn due_tasks(
+		&self,
+		first_run: bool,
+		next_run: &mut Option<TokioInstant>,
+		next_task_runs: &mut [Option<TokioInstant>],
+	) -> Vec<bool> {
+		let now = TokioInstant::now();
+		let passed = |deadline: &mut Option<TokioInstant>| {
+			let passed = first_run || deadline.is_some_and(|deadline| deadline <= now);
+			if passed {
+				*deadline = None;
 			}
 
-			match &self.refresh_time {
-				Some(refresh_time) => {
-					let remaining_time = refresh_time.remaining_from_now();
+			passed
+		};
+
+		let job_due = passed(next_run);
+		self.tasks
+			.iter()
+			.zip(next_task_runs)
+			.map(|(task, next_task_run)| match task.refresh_time {
+				Some(_) => passed(next_task_run),
+				None => job_due,
+			})
+			.collect()
+	}
+
+	/// The earliest of the next scheduled runs of the job and of its tasks with their own refresh time.
+	/// The deadlines that haven't been computed yet are computed from now
+	fn next_deadline(
+		&self,
+		next_run: &mut Option<TokioInstant>,
+		next_task_runs: &mut [Option<TokioInstant>],
+	) -> Option<TokioInstant> {
+		let schedule = |refresh_time: &TimePoint, deadline: &mut Option<TokioInstant>| {
+			if deadline.is_none() {
+				// a deadline too far in the future to be represented never comes
+				*deadline = TokioInstant::now().checked_add(refresh_time.remaining_from_now());
+			}
+
+			*deadline
+		};
+
+		// the job's schedule doesn't matter if every task has its own
+		let job_deadline = self
+			.refresh_time
+			.as_ref()
+			.filter(|_| self.tasks.iter().any(|task| task.refresh_time.is_none()))
+			.and_then(|refresh_time| schedule(refresh_time, next_run));
+
+		let task_deadlines =
+			self.tasks
+				.iter()
+				.zip(next_task_runs)
+				.filter_map(|(task, next_task_run)| {
+					schedule(task.refresh_time.as_ref()?, next_task_run)
+				});
+
+		let deadline = job_deadline.into_iter().chain(task_deadlines).min()?;
+		tracing::debug!(
+			"Putting job to sleep for {}m",
+			deadline
+				.saturating_duration_since(TokioInstant::now())
+				.as_secs() / 60
+		);
+
+		Some(deadline)
+	}
+
+	/// Wait until either the job or some of its tasks should be refreshed, or a task has been triggered, returning its index.
+	/// Breaks if neither can happen anymore.
+	///
+	/// `next_run` and `next_task_runs` are the deadlines of the next scheduled runs of the job and of the tasks with their own refresh time.
+	/// They are computed once and kept across triggered runs
+	async fn wait_for_next_run(
+		&mut self,
+		next_run: &mut Option<TokioInstant>,
+		next_task_runs: &mut [Option<TokioInstant>],
+	) -> ControlFlow<(), Option<usize>> {
+		loop {
+			let deadline = self.next_deadline(next_run, next_task_runs);
+
+			let Some(trigger) = &mut self.trigger else {
+				return match deadline {
+					Some(deadline) => {
+						sleep_until(deadline).await;
+						ControlFlow::Continue(None)
+					}
+					None => ControlFlow::Break(()),
+				};
+			};
 
-					tracing::debug!(
-						"Putting job to sleep for {}m",
-						remaining_time.as_secs() / 60
+			let received = match deadline {
+				Some(deadline) => match timeout_at(deadline, trigger.recv()).await {
+					Ok(received) => received,
+					Err(_elapsed) => return ControlFlow::Continue(None),
+				},
+				None => trigger.recv().await,
+			};
+
+			match received {
+				Some(idx) if idx < self.tasks.len() => {
+					tracing::info!(
+						"Task {} has been triggered, running it right away",
+						self.tasks[idx].name.as_deref().unwrap_or("without a name")
 					);
-					sleep(remaining_time).await;
+					return ControlFlow::Continue(Some(idx));
 				}
-				None => return Ok(()),
+				Some(idx) => {
+					tracing::warn!(
+						"Task #{idx} has been triggered but it doesn't exist, ignoring..."
+					);
+				}
+				// nothing can trigger the job anymore, continue as if it never could
+				None => self.trigger = None,
 			}
 		}
 	}
 }
+
+/// Format the error and all of its sources on a single line
+fn display_chain(err: &dyn StdError) -> String {
+	let mut output = err.to_string();
+
+	let mut source = err.source();
+	while let Some(err) = source {
+		output.push_str(": ");
+		output.push_str(&err.to_string());
+		source = err.source();
+	}
+
+	output
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+
+	use super::*;
+	use crate::{
+		entry::{Entry, EntryId},
+		error::FetcherError,
+		read_filter::MarkAsRead,
+		source::{Fetch, Source, error::SourceError},
+	};
+	use async_trait::async_trait;
+	use std::{
+		sync::atomic::{AtomicUsize, Ordering},
+		time::Duration,
+	};
+
+	#[derive(Debug)]
+	struct FailingSource;
+
+	#[async_trait]
+	impl Fetch for FailingSource {
+		async fn fetch(&mut self) -> Result<Vec<Entry>, SourceError> {
+			Err(SourceError::Debug)
+		}
+	}
+
+	#[async_trait]
+	impl MarkAsRead for FailingSource {
+		async fn mark_as_read(&mut self, _id: &EntryId) -> Result<(), FetcherError> {
+			Ok(())
+		}
+
+		async fn set_read_only(&mut self) {}
+	}
+
+	impl Source for FailingSource {}
+
+	/// Counts how many times it has been fetched from
+	#[derive(Debug)]
+	struct CountingSource(Arc<AtomicUsize>);
+
+	#[async_trait]
+	impl Fetch for CountingSource {
+		async fn fetch(&mut self) -> Result<Vec<Entry>, SourceError> {
+			self.0.fetch_add(1, Ordering::SeqCst);
+			Ok(Vec::new())
+		}
+	}
+
+	#[async_trait]
+	impl MarkAsRead for CountingSource {
+		async fn mark_as_read(&mut self, _id: &EntryId) -> Result<(), FetcherError> {
+			Ok(())
+		}
+
+		async fn set_read_only(&mut self) {}
+	}
+
+	impl Source for CountingSource {}
+
+	fn task(name: &str, source: Option<Box<dyn Source>>) -> Task {
+		Task {
+			name: Some(name.to_owned()),
+			tag: None,
+			source,
+			actions: None,
+			entry_to_msg_map: None,
+			update_in_place: false,
+			delete_removed: false,
+			timeout: None,
+			max_entries: None,
+			dead_letters: None,
+			refresh_time: None,
+		}
+	}
+
+	#[tokio::test]
+	async fn errors_have_task_names() {
+		let mut job = Job {
+			name: None,
+			tasks: vec![
+				task("ok", None),
+				task("failing", Some(Box::new(FailingSource))),
+			],
+			refresh_time: None,
+			backoff: Backoff::default(),
+			task_limit: None,
+			heartbeat: Heartbeat::default(),
+			trigger: None,
+		};
+
+		let errors = job.run().await.expect_err("one of the tasks should fail");
+		assert_eq!(errors.len(), 1);
+		assert_eq!(errors[0].task.as_deref(), Some("failing"));
+		assert_eq!(errors[0].to_string(), "Task failing");
+	}
+
+	#[tokio::test]
+	async fn triggered_task() {
+		let first = Arc::new(AtomicUsize::new(0));
+		let second = Arc::new(AtomicUsize::new(0));
+		let (trigger_tx, trigger_rx) = mpsc::channel(1);
+
+		let mut job = Job {
+			name: None,
+			tasks: vec![
+				task("first", Some(Box::new(CountingSource(Arc::clone(&first))))),
+				task(
+					"second",
+					Some(Box::new(CountingSource(Arc::clone(&second)))),
+				),
+			],
+			refresh_time: None,
+			backoff: Backoff::default(),
+			task_limit: None,
+			heartbeat: Heartbeat::default(),
+			trigger: Some(trigger_rx),
+		};
+
+		trigger_tx.send(1).await.unwrap();
+		drop(trigger_tx);
+
+		job.run().await.unwrap();
+		assert_eq!(first.load(Ordering::SeqCst), 1);
+		assert_eq!(second.load(Ordering::SeqCst), 2);
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn task_refresh_time() {
+		let on_job_schedule = Arc::new(AtomicUsize::new(0));
+		let on_own_schedule = Arc::new(AtomicUsize::new(0));
+
+		let mut own_schedule_task = task(
+			"own schedule",
+			Some(Box::new(CountingSource(Arc::clone(&on_own_schedule)))),
+		);
+		own_schedule_task.refresh_time = Some(TimePoint::Duration(Duration::from_secs(25)));
+
+		let mut job = Job {
+			name: None,
+			tasks: vec![
+				task(
+					"job schedule",
+					Some(Box::new(CountingSource(Arc::clone(&on_job_schedule)))),
+				),
+				own_schedule_task,
+			],
+			refresh_time: Some(TimePoint::Duration(Duration::from_secs(10))),
+			backoff: Backoff::default(),
+			task_limit: None,
+			heartbeat: Heartbeat::default(),
+			trigger: None,
+		};
+
+		// a refreshed job never finishes on its own
+		tokio::time::timeout(Duration::from_secs(55), job.run())
+			.await
+			.expect_err("the job should still be running");
+
+		// at 0s, 10s, 20s, 30s, 40s, and 50s
+		assert_eq!(on_job_schedule.load(Ordering::SeqCst), 6);
+
+		// at 0s, 25s, and 50s
+		assert_eq!(on_own_schedule.load(Ordering::SeqCst), 3);
+	}
+}
@@ -0,0 +1,106 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`Backoff`] settings of a [`Job`](`super::Job`) that decide for how long to skip a repeatedly failing task
+
+use std::time::{Duration, Instant};
+
+/// Skip a failing task for an exponentially increasing amount of time, beginning with [`base`](`Backoff::base`) and capped at [`max`](`Backoff::max`).
+///
+/// The task is retried on the first refresh of the job after the delay has passed
+/// and all failures are forgotten as soon as it finishes successfully
+#[derive(Clone, Copy, Debug)]
+pub struct Backoff {
+	/// The delay after the first failure. It's doubled with every failure after that
+	pub base: Duration,
+
+	/// The maximum delay
+	pub max: Duration,
+}
+
+/// Keeps track of the consecutive failures of a single task
+#[derive(Clone, Default, Debug)]
+pub(crate) struct FailureState {
+	failures: u32,
+	retry_at: Option<Instant>,
+}
+
+impl Default for Backoff {
+	fn default() -> Self {
+		Self {
+			base: Duration::from_mins(1),
+			max: Duration::from_hours(6),
+		}
+	}
+}
+
+impl Backoff {
+	/// The delay after `failures` consecutive failures
+	#[must_use]
+	pub fn delay(&self, failures: u32) -> Duration {
+		let multiplier = 2u32.saturating_pow(failures.saturating_sub(1));
+
+		self.base.saturating_mul(multiplier).min(self.max)
+	}
+}
+
+impl FailureState {
+	/// Whether the task is still being backed off from at `now`
+	pub(crate) fn should_skip(&self, now: Instant) -> bool {
+		self.retry_at.is_some_and(|retry_at| now < retry_at)
+	}
+
+	/// Record a failure and return for how long the task is going to be skipped
+	pub(crate) fn fail(&mut self, backoff: &Backoff, now: Instant) -> Duration {
+		self.failures = self.failures.saturating_add(1);
+
+		let delay = backoff.delay(self.failures);
+		self.retry_at = now.checked_add(delay);
+
+		delay
+	}
+
+	pub(crate) fn succeed(&mut self) {
+		*self = Self::default();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn delay_is_capped() {
+		let backoff = Backoff {
+			base: Duration::from_mins(1),
+			max: Duration::from_mins(10),
+		};
+
+		assert_eq!(backoff.delay(1), Duration::from_mins(1));
+		assert_eq!(backoff.delay(2), Duration::from_mins(2));
+		assert_eq!(backoff.delay(4), Duration::from_mins(8));
+		assert_eq!(backoff.delay(5), Duration::from_mins(10));
+		assert_eq!(backoff.delay(u32::MAX), Duration::from_mins(10));
+	}
+
+	#[test]
+	fn reset_on_success() {
+		let backoff = Backoff::default();
+		let now = Instant::now();
+		let mut state = FailureState::default();
+
+		assert!(!state.should_skip(now));
+
+		state.fail(&backoff, now);
+		assert_eq!(state.fail(&backoff, now), Duration::from_mins(2));
+		assert!(state.should_skip(now + Duration::from_mins(1)));
+		assert!(!state.should_skip(now + Duration::from_mins(2)));
+
+		state.succeed();
+		assert!(!state.should_skip(now));
+		assert_eq!(state.fail(&backoff, now), Duration::from_mins(1));
+	}
+}
@@ -0,0 +1,62 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`Heartbeat`] of a [`Job`](`super::Job`) that lets an external monitor know the job is still running
+
+use url::Url;
+
+use crate::source::http::{self, HttpError};
+
+/// URLs to send a GET request to after every run of a job, e.g. to a dead man's switch like healthchecks.io
+/// that alerts when the pings stop coming
+#[derive(Clone, Default, Debug)]
+pub struct Heartbeat {
+	/// Pinged after a run in which all tasks have finished successfully
+	pub success_url: Option<Url>,
+
+	/// Pinged after a run in which any of the tasks has failed or has been skipped because of the backoff
+	pub failure_url: Option<Url>,
+}
+
+impl Heartbeat {
+	/// Ping the URL for a successful or a failed run. Errors are only logged to not fail the job because of an unreachable monitor
+	pub(crate) async fn ping(&self, success: bool) {
+		let url = if success {
+			self.success_url.as_ref()
+		} else {
+			self.failure_url.as_ref()
+		};
+
+		let Some(url) = url else {
+			return;
+		};
+
+		tracing::debug!("Sending a heartbeat to {:?}", url.as_str());
+
+		if let Err(e) = send(url).await {
+			tracing::warn!("Failed to send a heartbeat: {e}");
+		}
+	}
+}
+
+async fn send(url: &Url) -> Result<(), HttpError> {
+	let response = http::client()?
+		.get(url.as_str())
+		.send()
+		.await
+		.map_err(|e| HttpError::BadRequest(e, url.to_string()))?;
+
+	let status = response.status();
+	if !status.is_success() {
+		return Err(HttpError::BadStatus {
+			url: url.to_string(),
+			status,
+			body: String::new(),
+		});
+	}
+
+	Ok(())
+}
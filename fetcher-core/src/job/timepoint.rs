@@ -4,9 +4,10 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-//! This module defines the [`TimePoint`] enum that specifies either a duration or a time of day a job should be refreshed
+//! This module defines the [`TimePoint`] enum that specifies either a duration, a time of day, or a cron schedule a job should be refreshed at
 
 use chrono::{NaiveDateTime, NaiveTime, offset::Local as LocalTime};
+use cron::Schedule;
 use std::time::Duration;
 
 /// A point in time of a day
@@ -17,6 +18,9 @@ pub enum TimePoint {
 
 	/// A point in time of a day
 	Time(NaiveTime),
+
+	/// A cron schedule, in local time
+	Cron(Box<Schedule>),
 }
 
 impl TimePoint {
@@ -55,6 +59,17 @@ impl TimePoint {
 						),
 				}
 			}
+			TimePoint::Cron(schedule) => {
+				// pretend local time is UTC to match the schedule against the wall clock time
+				let now = now.and_utc();
+
+				schedule
+					.after(&now)
+					.next()
+					.and_then(|next| (next - now).to_std().ok())
+					// the schedule will never fire again, e.g. a year in the past was specified
+					.unwrap_or(Duration::MAX)
+			}
 		}
 	}
 }
@@ -98,4 +113,12 @@ mod tests {
 
 		assert_eq!(at_10_am.remaining_from(*NOW), HOUR * 22);
 	}
+
+	#[test]
+	fn cron() {
+		// 2000-01-01 is a Saturday
+		let weekdays_at_9_am = TimePoint::Cron(Box::new("0 0 9 * * Mon-Fri".parse().unwrap()));
+
+		assert_eq!(weekdays_at_9_am.remaining_from(*NOW), HOUR * (24 + 21));
+	}
 }
@@ -20,3 +20,4 @@ pub mod sink;
 pub mod source;
 pub mod task;
 pub mod utils;
+pub mod websub;
@@ -9,12 +9,16 @@
 
 mod external_save_wrapper;
 mod newer;
+mod not_older;
 mod not_present;
 
 mod external_implementations;
 
 pub use self::{
-	external_save_wrapper::ExternalSaveRFWrapper, newer::Newer, not_present::NotPresent,
+	external_save_wrapper::ExternalSaveRFWrapper,
+	newer::Newer,
+	not_older::NotOlder,
+	not_present::{DEFAULT_MAX_LIST_LEN, NotPresent, ReadListLimits},
 };
 
 use crate::{action::filter::Filter, entry::EntryId, error::FetcherError};
@@ -0,0 +1,169 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`NotOlder`] read filter that filters out entries that were published too long ago
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::{any::Any, time::Duration};
+
+use super::{MarkAsRead, ReadFilter};
+use crate::{
+	action::filter::Filter,
+	entry::{Entry, EntryId},
+	error::FetcherError,
+};
+
+/// Read Filter that removes all entries that were [published](`Entry::published`) longer than [`max_age`](`NotOlder::max_age`) ago.
+///
+/// It doesn't keep track of what was read by itself and can optionally be composed with another read filter that does,
+/// e.g. to avoid getting the entire backlog of a feed when it's first added.
+/// Entries without a publication date are always kept
+#[derive(Debug)]
+pub struct NotOlder {
+	/// Max age of an entry, measured from the moment the filter is run
+	pub max_age: Duration,
+
+	/// The read filter to run after the old entries have been removed and to pass all entries marked as read to
+	pub inner: Option<Box<dyn ReadFilter>>,
+}
+
+impl NotOlder {
+	/// Creates a new [`NotOlder`] read filter without an inner read filter
+	#[must_use]
+	pub const fn new(max_age: Duration) -> Self {
+		Self {
+			max_age,
+			inner: None,
+		}
+	}
+
+	fn is_too_old(&self, entry: &Entry, now: DateTime<Utc>) -> bool {
+		let Some(published) = entry.published else {
+			return false;
+		};
+
+		// an age that doesn't fit into chrono's duration is never exceeded
+		chrono::Duration::from_std(self.max_age)
+			.ok()
+			.and_then(|max_age| now.checked_sub_signed(max_age))
+			.is_some_and(|oldest_allowed| published < oldest_allowed)
+	}
+
+	fn filter_at(&self, entries: &mut Vec<Entry>, now: DateTime<Utc>) {
+		let len_before = entries.len();
+		entries.retain(|ent| !self.is_too_old(ent, now));
+
+		tracing::debug!(
+			"Removed {} entries older than {:?}",
+			len_before - entries.len(),
+			self.max_age
+		);
+	}
+}
+
+#[async_trait]
+impl ReadFilter for NotOlder {
+	async fn as_any(&self) -> Box<dyn Any> {
+		match &self.inner {
+			Some(inner) => inner.as_any().await,
+			// there's nothing to save
+			None => Box::new(()),
+		}
+	}
+}
+
+#[async_trait]
+impl MarkAsRead for NotOlder {
+	async fn mark_as_read(&mut self, id: &EntryId) -> Result<(), FetcherError> {
+		if let Some(inner) = &mut self.inner {
+			inner.mark_as_read(id).await?;
+		}
+
+		Ok(())
+	}
+
+	async fn set_read_only(&mut self) {
+		if let Some(inner) = &mut self.inner {
+			inner.set_read_only().await;
+		}
+	}
+}
+
+#[async_trait]
+impl Filter for NotOlder {
+	#[tracing::instrument(level = "debug", name = "filter_old", skip_all)]
+	async fn filter(&self, entries: &mut Vec<Entry>) {
+		self.filter_at(entries, Utc::now());
+
+		if let Some(inner) = &self.inner {
+			inner.filter(entries).await;
+		}
+	}
+
+	fn is_readfilter(&self) -> bool {
+		true
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+
+	use super::*;
+	use crate::read_filter::Newer;
+
+	fn entry(id: &str, published: Option<&str>) -> Entry {
+		Entry {
+			id: Some(id.into()),
+			published: published.map(|s| DateTime::parse_from_rfc3339(s).unwrap().to_utc()),
+			..Default::default()
+		}
+	}
+
+	fn now() -> DateTime<Utc> {
+		DateTime::parse_from_rfc3339("2024-01-10T12:00:00+00:00")
+			.unwrap()
+			.to_utc()
+	}
+
+	#[test]
+	fn remove_old() {
+		let rf = NotOlder::new(Duration::from_hours(7 * 24));
+
+		let mut entries = vec![
+			entry("0", Some("2024-01-10T11:00:00+00:00")),
+			entry("1", Some("2023-12-01T00:00:00+00:00")),
+			entry("2", None),
+			entry("3", Some("2024-01-04T00:00:00+00:00")),
+			entry("4", Some("2024-01-02T00:00:00+00:00")),
+		];
+
+		rf.filter_at(&mut entries, now());
+
+		let ids = entries
+			.iter()
+			.map(|e| e.id.as_deref().unwrap())
+			.collect::<Vec<_>>();
+		assert_eq!(ids, ["0", "2", "3"]);
+	}
+
+	#[tokio::test]
+	async fn composed() {
+		let mut rf = NotOlder {
+			max_age: Duration::MAX,
+			inner: Some(Box::new(Newer::new())),
+		};
+
+		rf.mark_as_read(&"1".into()).await.unwrap();
+
+		let mut entries = vec![entry("0", None), entry("1", None), entry("2", None)];
+		rf.filter(&mut entries).await;
+
+		assert_eq!(entries.len(), 1);
+		assert_eq!(entries[0].id.as_deref(), Some("0"));
+	}
+}
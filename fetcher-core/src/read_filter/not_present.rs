@@ -13,22 +13,55 @@ use crate::{
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use std::{any::Any, collections::VecDeque};
+use std::{
+	any::Any,
+	collections::{HashSet, VecDeque},
+	time::Duration,
+};
 
-const MAX_LIST_LEN: usize = 500;
+/// The default [`ReadListLimits::max_len`]
+pub const DEFAULT_MAX_LIST_LEN: usize = 500;
 
 /// Read Filter that stores a list of all entries read
 #[derive(Clone, Debug)]
 pub struct NotPresent {
+	/// all read entries in the order they were read in. Doesn't contain duplicates
 	read_list: VecDeque<(EntryId, DateTime<Utc>)>,
+
+	/// the ids of all entries in [`read_list`](`NotPresent::read_list`) for faster lookup
+	read_set: HashSet<EntryId>,
+
+	/// When to forget the oldest read entries
+	pub limits: ReadListLimits,
+}
+
+/// Limits on how many and for how long read entries are remembered by [`NotPresent`].
+///
+/// The oldest entries are forgotten first. Keep in mind that if a forgotten entry is still present in the source,
+/// it's going to be considered unread and will be processed again
+#[derive(Clone, Copy, Debug)]
+pub struct ReadListLimits {
+	/// The max number of read entries to remember
+	pub max_len: usize,
+
+	/// Forget entries that were read longer than this ago. None means to never forget entries because of their age
+	pub max_age: Option<Duration>,
 }
 
 impl NotPresent {
 	/// Creates a new empty [`NotPresent`] Read Filter
 	#[must_use]
 	pub fn new() -> Self {
+		Self::with_limits(ReadListLimits::default())
+	}
+
+	/// Creates a new empty [`NotPresent`] Read Filter that forgets the oldest entries when they exceed the `limits`
+	#[must_use]
+	pub fn with_limits(limits: ReadListLimits) -> Self {
 		Self {
 			read_list: VecDeque::default(),
+			read_set: HashSet::default(),
+			limits,
 		}
 	}
 
@@ -41,7 +74,7 @@ impl NotPresent {
 	/// Checks if the `id` is unread
 	#[must_use]
 	pub fn is_unread(&self, id: &EntryId) -> bool {
-		!self.read_list.iter().any(|(ent_id, _)| ent_id == id)
+		!self.read_set.contains(id)
 	}
 
 	/// Provides a read only view into the inner collection
@@ -49,6 +82,37 @@ impl NotPresent {
 		self.read_list.iter()
 	}
 
+	/// Appends the `id` to the end of the read list, moving it there if it has already been read before
+	fn push(&mut self, id: EntryId, time: DateTime<Utc>) {
+		if !self.read_set.insert(id.clone()) {
+			// this should happen pretty rarely, so a linear search is fine
+			self.read_list.retain(|(read_id, _)| *read_id != id);
+		}
+
+		self.read_list.push_back((id, time));
+	}
+
+	/// Forgets the oldest entries until the read list fits into [`limits`](`NotPresent::limits`)
+	fn evict(&mut self, now: DateTime<Utc>) {
+		let cutoff = self
+			.limits
+			.max_age
+			.and_then(|max_age| chrono::Duration::from_std(max_age).ok())
+			.and_then(|max_age| now.checked_sub_signed(max_age));
+
+		while let Some((oldest_id, oldest_read_at)) = self.read_list.front() {
+			let too_many = self.read_list.len() > self.limits.max_len;
+			let too_old = cutoff.is_some_and(|cutoff| *oldest_read_at < cutoff);
+
+			if !too_many && !too_old {
+				break;
+			}
+
+			self.read_set.remove(oldest_id);
+			self.read_list.pop_front();
+		}
+	}
+
 	/// Checks if there wasn't any entry marked as read yet
 	#[must_use]
 	pub fn is_empty(&self) -> bool {
@@ -66,11 +130,9 @@ impl ReadFilter for NotPresent {
 #[async_trait]
 impl MarkAsRead for NotPresent {
 	async fn mark_as_read(&mut self, id: &EntryId) -> Result<(), FetcherError> {
-		self.read_list.push_back((id.clone(), chrono::Utc::now()));
-
-		while self.read_list.len() > MAX_LIST_LEN {
-			self.read_list.pop_front();
-		}
+		let now = chrono::Utc::now();
+		self.push(id.clone(), now);
+		self.evict(now);
 
 		Ok(())
 	}
@@ -89,10 +151,7 @@ impl Filter for NotPresent {
 			// retain elements with no id
 			let Some(id) = &elem.id else { return true };
 
-			!self
-				.read_list
-				.iter()
-				.any(|(read_elem_id, _)| read_elem_id == id)
+			self.is_unread(id)
 		});
 
 		let removed_elems = old_len - entries.len();
@@ -107,8 +166,28 @@ impl Filter for NotPresent {
 
 impl FromIterator<(EntryId, DateTime<Utc>)> for NotPresent {
 	fn from_iter<I: IntoIterator<Item = (EntryId, DateTime<Utc>)>>(iter: I) -> Self {
+		let mut rf = Self::new();
+		rf.extend(iter);
+		rf
+	}
+}
+
+impl Extend<(EntryId, DateTime<Utc>)> for NotPresent {
+	/// Appends already read entries to the end of the read list, forgetting the oldest ones if it doesn't fit into [`limits`](`NotPresent::limits`) anymore
+	fn extend<I: IntoIterator<Item = (EntryId, DateTime<Utc>)>>(&mut self, iter: I) {
+		for (id, time) in iter {
+			self.push(id, time);
+		}
+
+		self.evict(chrono::Utc::now());
+	}
+}
+
+impl Default for ReadListLimits {
+	fn default() -> Self {
 		Self {
-			read_list: iter.into_iter().collect(),
+			max_len: DEFAULT_MAX_LIST_LEN,
+			max_age: None,
 		}
 	}
 }
@@ -144,7 +223,7 @@ mod tests {
 	#[tokio::test]
 	async fn mark_as_read_full_queue() {
 		let mut rf = NotPresent::new();
-		let mut v = Vec::with_capacity(MAX_LIST_LEN);
+		let mut v = Vec::with_capacity(DEFAULT_MAX_LIST_LEN);
 
 		for i in 0..600 {
 			let id = EntryId(i.to_string());
@@ -152,14 +231,74 @@ mod tests {
 			v.push(id);
 		}
 
-		// keep only the last MAX_LIST_LEN elements
-		let trimmed_v = v[v.len() - MAX_LIST_LEN..].iter().collect::<Vec<_>>();
+		// keep only the last DEFAULT_MAX_LIST_LEN elements
+		let trimmed_v = v[v.len() - DEFAULT_MAX_LIST_LEN..]
+			.iter()
+			.collect::<Vec<_>>();
 
 		let rf_list = rf.read_list.iter().map(|(s, _date)| s).collect::<Vec<_>>();
 
 		assert_eq!(trimmed_v, rf_list);
 	}
 
+	#[tokio::test]
+	async fn custom_max_len() {
+		let mut rf = NotPresent::with_limits(ReadListLimits {
+			max_len: 2,
+			max_age: None,
+		});
+
+		for id in ["1", "2", "3"] {
+			rf.mark_as_read(&id.into()).await.unwrap();
+		}
+
+		assert_eq!(
+			&rf.read_list.iter().map(|(s, _date)| s).collect::<Vec<_>>(),
+			&[&"2".into(), &"3".into()]
+		);
+		assert!(rf.is_unread(&"1".into()));
+	}
+
+	#[test]
+	fn max_age() {
+		let now = chrono::Utc::now();
+		let days_ago = |days| now - chrono::Duration::days(days);
+
+		let mut rf: NotPresent = [
+			("old".into(), days_ago(40)),
+			("recent".into(), days_ago(20)),
+			("new".into(), days_ago(0)),
+		]
+		.into_iter()
+		.collect();
+		rf.limits.max_age = Some(Duration::from_hours(30 * 24));
+
+		rf.evict(now);
+
+		assert_eq!(
+			&rf.read_list.iter().map(|(s, _date)| s).collect::<Vec<_>>(),
+			&[&"recent".into(), &"new".into()]
+		);
+		assert!(rf.is_unread(&"old".into()));
+		assert!(!rf.is_unread(&"recent".into()));
+	}
+
+	#[tokio::test]
+	async fn mark_as_read_twice() {
+		let mut rf = NotPresent::new();
+
+		rf.mark_as_read(&"1".into()).await.unwrap();
+		rf.mark_as_read(&"2".into()).await.unwrap();
+		rf.mark_as_read(&"1".into()).await.unwrap();
+
+		assert_eq!(
+			&rf.read_list.iter().map(|(s, _date)| s).collect::<Vec<_>>(),
+			&[&"2".into(), &"1".into()]
+		);
+		assert!(!rf.is_unread(&"1".into()));
+		assert!(rf.is_unread(&"3".into()));
+	}
+
 	#[tokio::test]
 	async fn last_read() {
 		let mut rf = NotPresent::new();
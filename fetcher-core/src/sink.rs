@@ -10,12 +10,19 @@
 pub mod message;
 
 pub mod discord;
+pub mod file;
+pub mod rate_limited;
+pub mod slack;
 pub mod stdout;
+pub mod tag_format;
 pub mod telegram;
 
 pub mod error;
 
-pub use self::{discord::Discord, stdout::Stdout, telegram::Telegram};
+pub use self::{
+	discord::Discord, file::File, rate_limited::RateLimited, slack::Slack, stdout::Stdout,
+	tag_format::TagFormat, telegram::Telegram,
+};
 pub use crate::exec::Exec;
 
 use self::{
@@ -36,4 +43,25 @@ pub trait Sink: Debug + Send + Sync {
 		reply_to: Option<&MessageId>,
 		tag: Option<&str>,
 	) -> Result<Option<MessageId>, SinkError>;
+
+	/// Replace the contents of the previously sent message `msg_id` with `message`.
+	///
+	/// Returns the id of the edited message, or `None` if it can't be edited,
+	/// e.g. if the sink doesn't support editing or the message has been deleted since.
+	/// The message should be sent anew in that case
+	async fn edit(
+		&self,
+		_msg_id: &MessageId,
+		_message: &Message,
+		_tag: Option<&str>,
+	) -> Result<Option<MessageId>, SinkError> {
+		Ok(None)
+	}
+
+	/// Delete the previously sent message `msg_id`, e.g. because the entry it was sent for doesn't exist anymore.
+	///
+	/// Does nothing if the sink doesn't support deleting messages
+	async fn delete(&self, _msg_id: &MessageId) -> Result<(), SinkError> {
+		Ok(())
+	}
 }
@@ -12,29 +12,41 @@ use async_trait::async_trait;
 use serenity::{
 	all::{CreateEmbed, CreateEmbedFooter},
 	builder::CreateMessage,
-	http::Http as Bot,
+	http::{ErrorResponse, Http as Bot, HttpError},
 	model::{
 		channel::Message as DcMessage,
 		id::{ChannelId, MessageId as DcMessageId, UserId},
 	},
 };
+use url::Url;
 
 use super::{
 	Sink,
 	error::SinkError,
 	message::{Media, Message, MessageId, length_limiter::MessageLengthLimiter},
+	tag_format::{Markup, TagFormat},
 };
 use crate::utils::OptionExt;
 
+// https://discord.com/developers/docs/topics/opcodes-and-status-codes#json-json-error-codes
+const UNKNOWN_MESSAGE_ERROR_CODE: isize = 10008;
+
 // https://discord.com/developers/docs/resources/channel#create-message
 const MAX_MSG_LEN: usize = 2000;
-const MAX_EMBED_DESCIPTION_LEN: usize = 2000;
+
+// https://discord.com/developers/docs/resources/message#embed-object-embed-limits
+const MAX_EMBED_TITLE_LEN: usize = 256;
+const MAX_EMBED_DESCRIPTION_LEN: usize = 4096;
+const MAX_EMBED_FOOTER_LEN: usize = 2048;
+const MAX_EMBED_TOTAL_LEN: usize = 6000;
 
 /// Discord sink. Supports both text channels and DMs with a user
 #[derive(Debug)]
 pub struct Discord {
 	bot: Bot,
 	target: TargetInner,
+	use_embeds: bool,
+	tag_format: TagFormat,
 }
 
 /// Target for the [`Discord`] sink where it sends message to
@@ -54,134 +66,230 @@ enum TargetInner {
 }
 
 impl Discord {
-	/// Create a new [`Discord`] sink. Needs a valid Discord bot `token` and a `target` where to send messages to
+	/// Create a new [`Discord`] sink. Needs a valid Discord bot `token` and a `target` where to send messages to.
+	///
+	/// If `use_embeds` is set, messages are sent as rich embeds, otherwise as plain text
 	#[must_use]
-	pub fn new(token: &str, target: Target) -> Self {
+	pub fn new(token: &str, target: Target, use_embeds: bool) -> Self {
 		Self {
 			bot: Bot::new(token),
 			target: match target {
 				Target::Channel(i) => TargetInner::Channel(i.into()),
 				Target::User(i) => TargetInner::User(i.into()),
 			},
+			use_embeds,
+			tag_format: TagFormat::default(),
 		}
 	}
-}
 
-#[async_trait]
-impl Sink for Discord {
-	async fn send(
+	/// Show the tag of a text message as `tag_format` instead of a hashtag.
+	/// Embeds always show the tag as is in their footer
+	#[must_use]
+	pub fn with_tag_format(mut self, tag_format: TagFormat) -> Self {
+		self.tag_format = tag_format;
+		self
+	}
+
+	/// Send the message as one or more plain text messages, splitting it if it's too long
+	async fn send_as_text(
 		&self,
 		msg: &Message,
-		reply_to: Option<&MessageId>,
 		tag: Option<&str>,
-	) -> Result<Option<MessageId>, SinkError> {
-		let mut last_message = reply_to.try_map(|msgid| {
-			let dc_msgid = DcMessageId::from(u64::try_from(msgid.0)?);
+	) -> Result<Option<DcMessageId>, SinkError> {
+		let Message {
+			title, body, link, ..
+		} = msg;
 
-			Ok::<_, TryFromIntError>(dc_msgid)
-		})?;
+		let mut head = title.clone();
+
+		// add tag on top of the message
+		if let Some(tag) = tag {
+			head = Some({
+				let mut head = head
+					// add more padding between tag and title if both are present
+					.map(|mut s| {
+						s.insert(0, '\n');
+						s
+					})
+					.unwrap_or_default();
+
+				head.insert_str(
+					0,
+					&format!("{}\n", self.tag_format.render(tag, Markup::Markdown)),
+				);
+				head
+			});
+		}
+
+		let link = link.as_ref().map(Url::to_string);
+
+		let mut composed_msg = MessageLengthLimiter {
+			head: head.as_deref(),
+			body: body.as_deref(),
+			tail: link.as_deref(),
+		};
+
+		let mut last_message = None;
+		while let Some(text) = composed_msg.split_at(MAX_MSG_LEN) {
+			let msg = self
+				.target
+				.send_message(&self.bot, CreateMessage::new().content(&text))
+				.await
+				.map_err(|e| SinkError::Discord {
+					source: e,
+					msg: Box::new(text),
+				})?;
 
+			last_message = Some(msg.id);
+		}
+
+		Ok(last_message)
+	}
+
+	/// Send the message as one or more embeds (much pretty, so wow!), splitting the description between several embeds if it's too long
+	async fn send_as_embeds(
+		&self,
+		msg: &Message,
+		tag: Option<&str>,
+	) -> Result<Option<DcMessageId>, SinkError> {
 		let Message {
 			title,
 			body,
 			link,
 			media,
-		} = msg.clone(); // clone is to be able to include the message if an error happens
-
-		// if the body of the message won't fit into an embed, then just send as regular messages
-		if body.as_ref().map_or(0, |s| s.chars().count()) > MAX_EMBED_DESCIPTION_LEN {
-			let mut head = title;
-
-			// add tag as a hashtag on top of the message
-			if let Some(tag) = tag {
-				let tag = tag.replace(
-					|c| match c {
-						'_' => false,
-						c if c.is_alphabetic() || c.is_ascii_digit() => false,
-						_ => true,
-					},
-					"_",
-				);
+		} = msg;
 
-				head = Some({
-					let mut head = head
-						// add more padding between tag and title if both are present
-						.map(|mut s| {
-							s.insert(0, '\n');
-							s
-						})
-						.unwrap_or_default();
-
-					head.insert_str(0, &format!("#{tag}\n"));
-					head
-				});
-			}
+		// a title that doesn't fit into the embed title is put on top of the description instead
+		let (title, long_title) = match title.as_deref() {
+			Some(title) if title.chars().count() > MAX_EMBED_TITLE_LEN => (None, Some(title)),
+			title => (title, None),
+		};
 
-			let link = link.map(|s| s.to_string());
+		let image = media.iter().flatten().find_map(|media| match media {
+			Media::Photo(image) => Some(image),
+			Media::Video(_) | Media::Audio(_) => None,
+		});
 
-			let mut composed_msg = MessageLengthLimiter {
-				head: head.as_deref(),
-				body: body.as_deref(),
-				tail: link.as_deref(),
-			};
+		// a tag that doesn't fit into the footer is cut short
+		let tag = tag.map(|tag| match tag.char_indices().nth(MAX_EMBED_FOOTER_LEN) {
+			Some((end, _)) => &tag[..end],
+			None => tag,
+		});
 
-			while let Some(text) = composed_msg.split_at(MAX_MSG_LEN) {
-				let msg = self
-					.target
-					.send_message(&self.bot, CreateMessage::new().content(&text))
-					.await
-					.map_err(|e| SinkError::Discord {
-						source: e,
-						msg: Box::new(text),
-					})?;
+		// leave enough space for the title and the footer to not go over the total embed limit
+		let max_description_len = MAX_EMBED_DESCRIPTION_LEN.min(
+			MAX_EMBED_TOTAL_LEN
+				.saturating_sub(title.map_or(0, |s| s.chars().count()))
+				.saturating_sub(tag.map_or(0, |s| s.chars().count())),
+		);
 
-				last_message = Some(msg.id);
-			}
+		let mut description = MessageLengthLimiter {
+			head: long_title,
+			body: body.as_deref(),
+			tail: None,
+		};
+
+		let mut descriptions = Vec::new();
+		while let Some(part) = description.split_at(max_description_len) {
+			descriptions.push(Some(part));
+		}
+
+		// an embed without a description should still be sent if it contains anything else
+		if descriptions.is_empty() {
+			descriptions.push(None);
 		}
-		// send as an embed (much pretty, so wow!)
-		else {
+
+		let last_idx = descriptions.len() - 1;
+		let mut last_message = None;
+
+		for (idx, description) in descriptions.into_iter().enumerate() {
 			let mut embed = CreateEmbed::new();
 
-			if let Some(title) = title {
-				embed = embed.title(title);
-			}
+			// title and link go into the first embed...
+			if idx == 0 {
+				if let Some(title) = title {
+					embed = embed.title(title);
+				}
 
-			if let Some(body) = body {
-				embed = embed.description(body);
+				if let Some(link) = link {
+					embed = embed.url(link.as_str());
+				}
 			}
 
-			if let Some(link) = link {
-				embed = embed.url(link);
+			if let Some(description) = description {
+				embed = embed.description(description);
 			}
 
-			if let Some(tag) = tag {
-				embed = embed.footer(CreateEmbedFooter::new(tag));
-			}
+			// ...and the image and the tag go into the last one
+			if idx == last_idx {
+				if let Some(image) = image {
+					embed = embed.image(image.as_str());
+				}
 
-			if let Some(media) = media {
-				for media in media {
-					if let Media::Photo(image) = media {
-						embed = embed.image(image);
-					}
+				if let Some(tag) = tag {
+					embed = embed.footer(CreateEmbedFooter::new(tag));
 				}
 			}
 
 			let msg = self
 				.target
-				.send_message(&self.bot, CreateMessage::new().embed(embed))
+				.send_message(&self.bot, CreateMessage::new().embed(embed.clone()))
 				.await
 				.map_err(|e| SinkError::Discord {
 					source: e,
-					msg: Box::new(msg.clone()),
+					msg: Box::new(embed),
 				})?;
 
 			last_message = Some(msg.id);
 		}
 
+		Ok(last_message)
+	}
+}
+
+#[async_trait]
+impl Sink for Discord {
+	async fn send(
+		&self,
+		msg: &Message,
+		reply_to: Option<&MessageId>,
+		tag: Option<&str>,
+	) -> Result<Option<MessageId>, SinkError> {
+		let reply_to = reply_to.try_map(|msgid| {
+			let dc_msgid = DcMessageId::from(u64::try_from(msgid.0)?);
+
+			Ok::<_, TryFromIntError>(dc_msgid)
+		})?;
+
+		let sent = if self.use_embeds {
+			self.send_as_embeds(msg, tag).await?
+		} else {
+			self.send_as_text(msg, tag).await?
+		};
+
 		// If it does, we should crash and think of a new solution anyways
-		let msgid = last_message.map(|id| i64::try_from(id.get()).expect("not sure if Discord will ever return an ID that doesn't fit into MessageId. It shouldn't do that, probably...").into());
+		let msgid = sent.or(reply_to).map(|id| i64::try_from(id.get()).expect("not sure if Discord will ever return an ID that doesn't fit into MessageId. It shouldn't do that, probably...").into());
 		Ok(msgid)
 	}
+
+	async fn delete(&self, msg_id: &MessageId) -> Result<(), SinkError> {
+		let dc_msgid = DcMessageId::from(u64::try_from(msg_id.0)?);
+
+		match self.target.delete_message(&self.bot, dc_msgid).await {
+			Ok(()) => Ok(()),
+			Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(ErrorResponse {
+				error,
+				..
+			}))) if error.code == UNKNOWN_MESSAGE_ERROR_CODE => {
+				tracing::debug!("Message has already been deleted");
+				Ok(())
+			}
+			Err(e) => Err(SinkError::Discord {
+				source: e,
+				msg: Box::new(dc_msgid),
+			}),
+		}
+	}
 }
 
 impl TargetInner {
@@ -202,4 +310,33 @@ impl TargetInner {
 
 		Ok(msg)
 	}
+
+	async fn delete_message(&self, bot: &Bot, msg_id: DcMessageId) -> Result<(), serenity::Error> {
+		match self {
+			TargetInner::Channel(chan) => chan.delete_message(bot, msg_id).await,
+			TargetInner::User(user) => {
+				user.create_dm_channel(bot)
+					.await?
+					.id
+					.delete_message(bot, msg_id)
+					.await
+			}
+		}
+	}
+}
+
+/// Escape the characters Discord's markdown uses for inline formatting
+/// <https://support.discord.com/hc/en-us/articles/210298617-Markdown-Text-101-Chat-Formatting-Bold-Italic-Underline>
+pub(crate) fn escape(s: &str) -> String {
+	let mut escaped = String::with_capacity(s.len());
+
+	for c in s.chars() {
+		if matches!(c, '\\' | '*' | '_' | '~' | '`' | '|' | '[' | ']') {
+			escaped.push('\\');
+		}
+
+		escaped.push(c);
+	}
+
+	escaped
 }
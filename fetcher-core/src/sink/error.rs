@@ -6,9 +6,10 @@
 
 //! An error that happened while sending to a sink
 
+pub use super::slack::SlackError;
 pub use crate::exec::ExecError;
 
-use std::{error::Error as StdError, fmt::Debug, num::TryFromIntError};
+use std::{error::Error as StdError, fmt::Debug, num::TryFromIntError, path::PathBuf};
 
 #[expect(missing_docs, reason = "error message is self-documenting")]
 #[derive(thiserror::Error, Debug)]
@@ -28,11 +29,20 @@ pub enum SinkError {
 		msg: Box<dyn Debug + Send + Sync>,
 	},
 
+	#[error("Can't send via Slack. Message contents: {msg:?}")]
+	Slack {
+		source: SlackError,
+		msg: Box<dyn Debug + Send + Sync>,
+	},
+
 	#[error("Can't pass message to a process")]
 	Exec(#[from] ExecError),
 
 	#[error("Error writing to stdout")]
 	Stdout(#[source] std::io::Error),
+
+	#[error("Can't write to file {}", .1.to_string_lossy())]
+	File(#[source] std::io::Error, PathBuf),
 }
 
 impl SinkError {
@@ -41,6 +51,10 @@ impl SinkError {
 			SinkError::Telegram {
 				source: teloxide::RequestError::Network(_),
 				..
+			}
+			| SinkError::Slack {
+				source: SlackError::Request(_),
+				..
 			} => Some(self),
 			_ => None,
 		}
@@ -0,0 +1,119 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Local file sink
+//!
+//! This module contains the [`File`] sink and the [`Format`] enum that specifies how messages are written to the file
+
+use async_trait::async_trait;
+use std::path::PathBuf;
+use tokio::{fs, io::AsyncWriteExt};
+
+use super::{
+	Sink,
+	error::SinkError,
+	message::{Message, MessageId},
+	stdout::{self, format_json},
+};
+
+/// File sink. Appends every message to the end of a file, creating it and all its parent directories if they don't exist yet
+#[derive(Debug)]
+pub struct File {
+	/// Path of the file
+	pub path: PathBuf,
+
+	/// The format each message is written to the file in
+	pub format: Format,
+}
+
+/// The format in which messages are written to the file
+#[derive(Clone, Copy, Default, Debug)]
+pub enum Format {
+	/// A single JSON object per line, see <https://jsonlines.org>
+	#[default]
+	JsonLines,
+
+	/// The same human-readable format the [`Stdout`](`super::Stdout`) sink uses by default
+	Text,
+}
+
+#[async_trait]
+impl Sink for File {
+	/// Appends a message with an optional tag to the end of the file
+	///
+	/// # Errors
+	/// if there was an error creating or writing to the file
+	async fn send(
+		&self,
+		msg: &Message,
+		_reply_to: Option<&MessageId>,
+		tag: Option<&str>,
+	) -> Result<Option<MessageId>, SinkError> {
+		let formatted = match self.format {
+			Format::JsonLines => {
+				let mut line = format_json(msg, tag).to_string();
+				line.push('\n');
+				line
+			}
+			Format::Text => stdout::format_message(msg, tag, false),
+		};
+
+		if let Some(parent) = self.path.parent()
+			&& !parent.as_os_str().is_empty()
+		{
+			fs::create_dir_all(parent)
+				.await
+				.map_err(|e| SinkError::File(e, self.path.clone()))?;
+		}
+
+		let mut file = fs::OpenOptions::new()
+			.create(true)
+			.append(true)
+			.open(&self.path)
+			.await
+			.map_err(|e| SinkError::File(e, self.path.clone()))?;
+
+		file.write_all(formatted.as_bytes())
+			.await
+			.map_err(|e| SinkError::File(e, self.path.clone()))?;
+
+		Ok(None)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+	use super::*;
+	use crate::sink::message::Media;
+
+	use url::Url;
+
+	#[test]
+	fn json_line() {
+		let msg = Message {
+			title: Some("Title".to_owned()),
+			body: Some("Line 1\nLine 2".to_owned()),
+			link: Some(Url::parse("https://example.com/").unwrap()),
+			media: Some(vec![Media::Photo(
+				Url::parse("https://example.com/img.png").unwrap(),
+			)]),
+		};
+
+		assert_eq!(
+			format_json(&msg, Some("tag")).to_string(),
+			r#"{"body":"Line 1\nLine 2","link":"https://example.com/","media":[{"photo":"https://example.com/img.png"}],"tag":"tag","title":"Title"}"#
+		);
+	}
+
+	#[test]
+	fn json_line_empty() {
+		assert_eq!(
+			format_json(&Message::default(), None).to_string(),
+			r#"{"body":null,"link":null,"media":null,"tag":null,"title":null}"#
+		);
+	}
+}
@@ -8,6 +8,8 @@
 
 pub(crate) mod length_limiter;
 
+use serde_json::json;
+use sha2::{Digest, Sha256};
 use std::fmt::Debug;
 use url::Url;
 
@@ -30,6 +32,10 @@ pub struct Message {
 #[derive(Clone, Copy, Debug)]
 pub struct MessageId(pub i64);
 
+/// A hash of the contents of a message, see [`Message::content_hash()`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ContentHash(pub i64);
+
 // TODO: rename photo to image mb?
 /// A link to some kind of external media
 #[derive(Clone)]
@@ -38,6 +44,22 @@ pub enum Media {
 	Photo(Url),
 	/// A link to a video
 	Video(Url),
+	/// A link to an audio file, e.g. a podcast episode
+	Audio(Url),
+}
+
+impl Media {
+	/// Create the kind of media that matches the `mime_type`, e.g. `image/png` is a [`Media::Photo`].
+	/// Returns None for anything that isn't an image, a video, or audio
+	#[must_use]
+	pub fn from_mime_type(url: Url, mime_type: &str) -> Option<Self> {
+		match mime_type.split_once('/')?.0 {
+			"image" => Some(Self::Photo(url)),
+			"video" => Some(Self::Video(url)),
+			"audio" => Some(Self::Audio(url)),
+			_ => None,
+		}
+	}
 }
 
 impl Message {
@@ -46,6 +68,67 @@ impl Message {
 	pub const fn is_empty(&self) -> bool {
 		self.title.is_none() && self.body.is_none() && self.link.is_none() && self.media.is_none()
 	}
+
+	/// Represent the message as a JSON object with each media attachment as e.g. `{"photo": <url>}`
+	#[must_use]
+	pub fn to_json(&self) -> serde_json::Value {
+		let media = self.media.as_ref().map(|media| {
+			media
+				.iter()
+				.map(|media| match media {
+					Media::Photo(url) => json!({ "photo": url.as_str() }),
+					Media::Video(url) => json!({ "video": url.as_str() }),
+					Media::Audio(url) => json!({ "audio": url.as_str() }),
+				})
+				.collect::<Vec<_>>()
+		});
+
+		json!({
+			"title": self.title,
+			"body": self.body,
+			"link": self.link.as_ref().map(Url::as_str),
+			"media": media,
+		})
+	}
+
+	/// Hash all contents of the message to be able to tell later if it has changed.
+	/// Unlike [`std::hash::Hash`], it stays the same across restarts and Rust versions, so it can be saved externally
+	#[must_use]
+	pub fn content_hash(&self) -> ContentHash {
+		fn update(hasher: &mut Sha256, field: Option<&str>) {
+			// prefix with the length to tell apart e.g. a title "ab" with no body from a title "a" with a body "b"
+			match field {
+				Some(s) => {
+					hasher.update([1]);
+					hasher.update((s.len() as u64).to_le_bytes());
+					hasher.update(s);
+				}
+				None => hasher.update([0]),
+			}
+		}
+
+		let mut hasher = Sha256::new();
+		update(&mut hasher, self.title.as_deref());
+		update(&mut hasher, self.body.as_deref());
+		update(&mut hasher, self.link.as_ref().map(Url::as_str));
+
+		for media in self.media.iter().flatten() {
+			let (kind, url) = match media {
+				Media::Photo(url) => ("photo", url),
+				Media::Video(url) => ("video", url),
+				Media::Audio(url) => ("audio", url),
+			};
+
+			update(&mut hasher, Some(kind));
+			update(&mut hasher, Some(url.as_str()));
+		}
+
+		// the first 8 bytes are more than enough to tell if the contents have changed
+		let hash: [u8; 32] = hasher.finalize().into();
+		let [b0, b1, b2, b3, b4, b5, b6, b7, ..] = hash;
+
+		ContentHash(i64::from_le_bytes([b0, b1, b2, b3, b4, b5, b6, b7]))
+	}
 }
 
 impl From<i64> for MessageId {
@@ -70,6 +153,7 @@ impl Debug for Media {
 		match self {
 			Self::Photo(x) => f.debug_tuple("Photo").field(&x.as_str()).finish(),
 			Self::Video(x) => f.debug_tuple("Video").field(&x.as_str()).finish(),
+			Self::Audio(x) => f.debug_tuple("Audio").field(&x.as_str()).finish(),
 		}
 	}
 }
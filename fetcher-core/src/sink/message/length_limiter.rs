@@ -11,6 +11,12 @@ pub struct MessageLengthLimiter<'a> {
 }
 
 impl MessageLengthLimiter<'_> {
+	/// Check if the head and the tail are short enough to be put into parts of `max_len` characters.
+	/// [`split_at`](`MessageLengthLimiter::split_at`) panics if they aren't
+	pub(crate) fn fits_into(&self, max_len: usize) -> bool {
+		self.head.map_or(0, count_chars) <= max_len && self.tail.map_or(0, count_chars) <= max_len
+	}
+
 	pub(crate) fn split_at(&mut self, max_len: usize) -> Option<String> {
 		let should_insert_newline_after_head =
 			self.head.is_some() && (self.body.is_some() || self.tail.is_some());
@@ -85,28 +91,26 @@ fn compose_long_message(
 	}
 
 	if let Some(body_str) = body.take() {
-		// find out how much space has remained for the body
-		let space_left_for_body = max_len.checked_sub(split_part.chars().count()).expect(
-			"only the head should've been pushed to the split and we asserted that it isn't longer than len",
-		);
-
-		// find the index at which point the body no longer fits into the split
+		// insert a new line to separate body from everything else
+		let add_newline = !split_part.is_empty();
+
+		// find out how much space has remained for the body, leaving some space for the newline
+		let space_left_for_body = max_len
+			.checked_sub(split_part.chars().count())
+			.expect(
+				"only the head should've been pushed to the split and we asserted that it isn't longer than len",
+			)
+			.saturating_sub(usize::from(add_newline));
+
+		// find the index at which point the body no longer fits into the split.
+		// It's always at a char boundary, even if the char before it is longer than a byte
 		let body_fits_till = body_str
 			.char_indices()
 			.nth(space_left_for_body)
-			// TODO: is .len() valid here or should it be .chars().count()?
 			.map_or_else(|| body_str.len(), |(idx, _)| idx);
 
-		// mark if we should add a newline character and leave some space for it
-		let (body_fits_till, add_newline) = if split_part.is_empty() {
-			(body_fits_till, false)
-		} else {
-			(body_fits_till.saturating_sub(1), true)
-		};
-
 		// if at least some of the body does fit
 		if body_fits_till > 0 {
-			// insert a new line to separate body from everything else
 			if add_newline {
 				split_part.push('\n');
 			}
@@ -338,4 +342,30 @@ mod tests {
 		// MSG_COUNT bodies + 1 head & 1 tail
 		assert_eq!(msg.count(), BODY_COUNT + 2);
 	}
+
+	#[test]
+	fn split_multibyte_body_after_head() {
+		let body = "ы".repeat(10);
+		let mut msg = MessageLengthLimiter {
+			head: Some(HEAD),
+			body: Some(&body),
+			tail: None,
+		};
+
+		assert_eq!(msg.split_at(10).as_deref(), Some("HEAD\nыыыыы"));
+		assert_eq!(msg.split_at(10).as_deref(), Some("ыыыыы"));
+		assert_eq!(msg.split_at(10), None);
+	}
+
+	#[test]
+	fn head_too_long() {
+		let msg = MessageLengthLimiter {
+			head: Some(HEAD),
+			body: Some(BODY),
+			tail: Some(TAIL),
+		};
+
+		assert!(msg.fits_into(HEAD.len()));
+		assert!(!msg.fits_into(HEAD.len() - 1));
+	}
 }
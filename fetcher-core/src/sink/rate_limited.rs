@@ -0,0 +1,127 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`RateLimited`] sink wrapper
+
+use async_trait::async_trait;
+use std::time::Duration;
+use tokio::{
+	sync::Mutex,
+	time::{Instant, sleep_until},
+};
+
+use super::{
+	Sink,
+	error::SinkError,
+	message::{Message, MessageId},
+};
+
+/// A wrapper around a [`Sink`] that waits for at least [`min_interval`](`RateLimited::min_interval`)
+/// between two consecutive sends, to space out bursts of messages
+#[derive(Debug)]
+pub struct RateLimited<S> {
+	/// The sink to send messages with
+	pub sink: S,
+
+	/// The minimum time between two consecutive sends
+	pub min_interval: Duration,
+
+	last_sent: Mutex<Option<Instant>>,
+}
+
+impl<S: Sink> RateLimited<S> {
+	/// Wrap `sink` to wait at least `min_interval` between two consecutive sends
+	#[must_use]
+	pub fn new(sink: S, min_interval: Duration) -> Self {
+		Self {
+			sink,
+			min_interval,
+			last_sent: Mutex::new(None),
+		}
+	}
+}
+
+#[async_trait]
+impl<S: Sink> Sink for RateLimited<S> {
+	/// Sends the message with the wrapped sink, waiting for the rest of the interval since the last send first
+	///
+	/// # Errors
+	/// if the wrapped sink failed to send the message
+	async fn send(
+		&self,
+		message: &Message,
+		reply_to: Option<&MessageId>,
+		tag: Option<&str>,
+	) -> Result<Option<MessageId>, SinkError> {
+		// keep the lock for the entire send to not let other sends sneak in between
+		let mut last_sent = self.last_sent.lock().await;
+
+		if let Some(last_sent) = *last_sent {
+			let next_send = last_sent + self.min_interval;
+
+			if next_send > Instant::now() {
+				tracing::debug!(
+					"Waiting for {:?} before sending the next message",
+					next_send - Instant::now()
+				);
+				sleep_until(next_send).await;
+			}
+		}
+
+		let res = self.sink.send(message, reply_to, tag).await;
+		*last_sent = Some(Instant::now());
+
+		res
+	}
+
+	async fn edit(
+		&self,
+		msg_id: &MessageId,
+		message: &Message,
+		tag: Option<&str>,
+	) -> Result<Option<MessageId>, SinkError> {
+		self.sink.edit(msg_id, message, tag).await
+	}
+
+	async fn delete(&self, msg_id: &MessageId) -> Result<(), SinkError> {
+		self.sink.delete(msg_id).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+	use super::*;
+
+	#[derive(Debug)]
+	struct DummySink;
+
+	#[async_trait]
+	impl Sink for DummySink {
+		async fn send(
+			&self,
+			_message: &Message,
+			_reply_to: Option<&MessageId>,
+			_tag: Option<&str>,
+		) -> Result<Option<MessageId>, SinkError> {
+			Ok(None)
+		}
+	}
+
+	#[tokio::test]
+	async fn spaces_out_sends() {
+		let sink = RateLimited::new(DummySink, Duration::from_millis(50));
+		let msg = Message::default();
+
+		let start = Instant::now();
+		for _ in 0..3 {
+			sink.send(&msg, None, None).await.unwrap();
+		}
+
+		// only the sends after the first one should wait
+		assert!(start.elapsed() >= Duration::from_millis(100));
+	}
+}
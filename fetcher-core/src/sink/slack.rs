@@ -0,0 +1,284 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`Slack`] sink
+
+use std::{fmt::Debug, time::Duration};
+
+use async_trait::async_trait;
+use reqwest::{StatusCode, header::RETRY_AFTER};
+use serde_json::{Value, json};
+use tokio::time::sleep;
+use url::Url;
+
+use super::{
+	Sink,
+	error::SinkError,
+	message::{Media, Message, MessageId, length_limiter::MessageLengthLimiter},
+	tag_format::{Markup, TagFormat},
+};
+use crate::source::http::{self, HttpError};
+
+// https://api.slack.com/reference/block-kit/blocks#section
+const MAX_SECTION_LEN: usize = 3000;
+
+// https://api.slack.com/reference/block-kit/blocks
+const MAX_BLOCKS: usize = 50;
+
+/// How long to wait before retrying if Slack has rate limited us without saying for how long
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// Slack sink. Posts messages to a channel via an [incoming webhook](https://api.slack.com/messaging/webhooks).
+///
+/// Messages are sent as Block Kit sections with the title in bold, and photos are attached as image blocks.
+/// Incoming webhooks don't return ids of the posted messages, so they can't be replied to or edited later
+pub struct Slack {
+	webhook_url: Url,
+	tag_format: TagFormat,
+}
+
+#[expect(missing_docs, reason = "error message is self-documenting")]
+#[derive(thiserror::Error, Debug)]
+pub enum SlackError {
+	#[error("Can't create an HTTP client")]
+	Client(#[source] HttpError),
+
+	// the URL is stripped from the error since the webhook URL is a secret
+	#[error("Can't send an HTTP request to the webhook")]
+	Request(#[source] reqwest::Error),
+
+	#[error("The webhook responded with {status}: {body:?}")]
+	BadStatus { status: StatusCode, body: String },
+}
+
+impl Slack {
+	/// Create a new [`Slack`] sink that posts to the incoming webhook at `webhook_url`
+	#[must_use]
+	pub fn new(webhook_url: Url) -> Self {
+		Self {
+			webhook_url,
+			tag_format: TagFormat::default(),
+		}
+	}
+
+	/// Show the tag on top of the message as `tag_format` instead of a hashtag
+	#[must_use]
+	pub fn with_tag_format(mut self, tag_format: TagFormat) -> Self {
+		self.tag_format = tag_format;
+		self
+	}
+
+	/// Compose the Block Kit blocks of the message
+	fn compose(&self, msg: &Message, tag: Option<&str>) -> Vec<Value> {
+		let Message {
+			title,
+			body,
+			link,
+			media,
+		} = msg;
+
+		let mut head = title.as_deref().map(|title| format!("*{}*", escape(title)));
+
+		// add tag on top of the message
+		if let Some(tag) = tag {
+			let tag = self.tag_format.render(tag, Markup::Mrkdwn);
+			head = Some(match head {
+				// add more padding between tag and title if both are present
+				Some(head) => format!("{tag}\n\n{head}"),
+				None => tag,
+			});
+		}
+
+		let body = body.as_deref().map(escape);
+
+		// videos and audio can't be embedded, so they are left as links
+		let tail = link
+			.iter()
+			.chain(media.iter().flatten().filter_map(|media| match media {
+				Media::Photo(_) => None,
+				Media::Video(url) | Media::Audio(url) => Some(url),
+			}))
+			.map(|url| format!("<{}>", escape(url.as_str())))
+			.collect::<Vec<_>>()
+			.join("\n");
+
+		let tail = (!tail.is_empty()).then_some(tail);
+		let mut text = MessageLengthLimiter {
+			head: head.as_deref(),
+			body: body.as_deref(),
+			tail: tail.as_deref(),
+		};
+
+		// a head or a tail too long to fit into a single section is split just like the body
+		let whole_text;
+		if !text.fits_into(MAX_SECTION_LEN) {
+			whole_text = [head.as_deref(), body.as_deref(), tail.as_deref()]
+				.into_iter()
+				.flatten()
+				.collect::<Vec<_>>()
+				.join("\n");
+
+			text = MessageLengthLimiter {
+				head: None,
+				body: Some(&whole_text),
+				tail: None,
+			};
+		}
+
+		let mut blocks = Vec::new();
+		while let Some(part) = text.split_at(MAX_SECTION_LEN) {
+			blocks.push(json!({
+				"type": "section",
+				"text": {
+					"type": "mrkdwn",
+					"text": part,
+				},
+			}));
+		}
+
+		for media in media.iter().flatten() {
+			if let Media::Photo(url) = media {
+				blocks.push(json!({
+					"type": "image",
+					"image_url": url.as_str(),
+					"alt_text": title.as_deref().unwrap_or("image"),
+				}));
+			}
+		}
+
+		blocks
+	}
+
+	/// Post a single message with `blocks`, retrying if rate limited
+	async fn post(&self, blocks: &[Value], fallback_text: &str) -> Result<(), SlackError> {
+		let client = http::client().map_err(SlackError::Client)?;
+		let payload = json!({
+			"text": fallback_text,
+			"blocks": blocks,
+		});
+
+		loop {
+			tracing::info!("Posting a message to Slack");
+
+			let response = client
+				.post(self.webhook_url.as_str())
+				.json(&payload)
+				.send()
+				.await
+				.map_err(|e| SlackError::Request(e.without_url()))?;
+
+			let status = response.status();
+			if status == StatusCode::TOO_MANY_REQUESTS {
+				let retry_after = response
+					.headers()
+					.get(RETRY_AFTER)
+					.and_then(|v| v.to_str().ok())
+					.and_then(|v| v.parse().ok())
+					.map_or(DEFAULT_RETRY_AFTER, Duration::from_secs);
+
+				tracing::warn!(
+					"Exceeded Slack rate limit. Retrying in {}s",
+					retry_after.as_secs()
+				);
+				sleep(retry_after).await;
+				continue;
+			}
+
+			if !status.is_success() {
+				let body = response.text().await.unwrap_or_default();
+				return Err(SlackError::BadStatus { status, body });
+			}
+
+			return Ok(());
+		}
+	}
+}
+
+#[async_trait]
+impl Sink for Slack {
+	async fn send(
+		&self,
+		msg: &Message,
+		_reply_to: Option<&MessageId>,
+		tag: Option<&str>,
+	) -> Result<Option<MessageId>, SinkError> {
+		let blocks = self.compose(msg, tag);
+
+		// shown in notifications and in clients that can't render blocks
+		let fallback_text = msg
+			.title
+			.as_deref()
+			.or(msg.body.as_deref())
+			.or_else(|| msg.link.as_ref().map(Url::as_str))
+			.unwrap_or_default();
+
+		for blocks in blocks.chunks(MAX_BLOCKS) {
+			self.post(blocks, fallback_text)
+				.await
+				.map_err(|e| SinkError::Slack {
+					source: e,
+					msg: Box::new(blocks.to_vec()),
+				})?;
+		}
+
+		Ok(None)
+	}
+}
+
+/// Escape the control characters of Slack's mrkdwn
+/// <https://api.slack.com/reference/surfaces/formatting#escaping>
+pub(crate) fn escape(s: &str) -> String {
+	s.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+}
+
+impl Debug for Slack {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		// the webhook URL is a secret
+		f.debug_struct("Slack")
+			.field("tag_format", &self.tag_format)
+			.finish_non_exhaustive()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+	use super::*;
+
+	#[test]
+	fn compose() {
+		let slack = Slack::new(Url::parse("https://hooks.slack.com/services/T/B/X").unwrap());
+		let msg = Message {
+			title: Some("Breaking <news>".to_owned()),
+			body: Some("Body".to_owned()),
+			link: Some(Url::parse("https://example.com/news").unwrap()),
+			media: Some(vec![Media::Photo(
+				Url::parse("https://example.com/image.png").unwrap(),
+			)]),
+		};
+
+		let blocks = slack.compose(&msg, Some("tag"));
+		assert_eq!(
+			blocks,
+			[
+				json!({
+					"type": "section",
+					"text": {
+						"type": "mrkdwn",
+						"text": "#tag\n\n*Breaking &lt;news&gt;*\nBody\n<https://example.com/news>",
+					},
+				}),
+				json!({
+					"type": "image",
+					"image_url": "https://example.com/image.png",
+					"alt_text": "Breaking <news>",
+				}),
+			]
+		);
+	}
+}
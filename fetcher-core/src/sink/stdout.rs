@@ -9,17 +9,64 @@
 use crate::sink::{Message, Sink, error::SinkError};
 
 use async_trait::async_trait;
+use std::io::IsTerminal;
 use tokio::io::{self, AsyncWriteExt};
 
 use super::MessageId;
 
 /// Print message to stdout. Mostly used for debugging
-#[derive(Debug)]
-pub struct Stdout;
+#[derive(Default, Debug)]
+pub struct Stdout {
+	/// The format each message is printed in
+	pub format: Format,
+
+	/// Whether to style the [`Format::Pretty`] format with colors
+	pub color: Color,
+}
+
+/// The format in which messages are printed to stdout
+#[derive(Clone, Copy, Default, Debug)]
+pub enum Format {
+	/// A human-readable block of text, separated from other messages with dashes
+	#[default]
+	Pretty,
+
+	/// A single JSON object per line, to be consumed by other programs
+	Json,
+
+	/// A JSON object per message, indented for reading
+	JsonPretty,
+}
+
+/// When to style the output with ANSI colors
+#[derive(Clone, Copy, Default, Debug)]
+pub enum Color {
+	/// Only if stdout is a terminal and the `NO_COLOR` environment variable isn't set, i.e. never when piping the output somewhere
+	#[default]
+	Auto,
+
+	/// Even if stdout isn't a terminal
+	Always,
+
+	/// Always print plain text
+	Never,
+}
+
+impl Color {
+	fn is_enabled(self) -> bool {
+		match self {
+			Color::Auto => {
+				std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+			}
+			Color::Always => true,
+			Color::Never => false,
+		}
+	}
+}
 
 #[async_trait]
 impl Sink for Stdout {
-	/// Prints a message with an optional tag to stdout
+	/// Prints a message with an optional tag to stdout in the [`Format`] of the sink
 	///
 	/// # Errors
 	/// if there was an error writing to stdout
@@ -29,15 +76,82 @@ impl Sink for Stdout {
 		_reply_to: Option<&MessageId>,
 		tag: Option<&str>,
 	) -> Result<Option<MessageId>, SinkError> {
-		io::stdout().write_all(format!(
-			"------------------------------\nMessage:\nTitle: {title}\n\nBody:\n{body}\n\nLink: {link}\n\nMedia: {media:?}\n\nTag: {tag:?}\n------------------------------\n",
-			title = msg.title.as_deref().unwrap_or("None"),
-			body = msg.body.as_deref().unwrap_or("None"),
-			link = msg.link.as_ref().map(|url| url.as_str().to_owned()).as_deref().unwrap_or("None"),
-			media = msg.media,
-			tag = tag.unwrap_or("None")
-		).as_bytes()).await.map_err(SinkError::Stdout)?;
+		let formatted = match self.format {
+			Format::Pretty => format_message(msg, tag, self.color.is_enabled()),
+			Format::Json => format!("{}\n", format_json(msg, tag)),
+			Format::JsonPretty => format!("{:#}\n", format_json(msg, tag)),
+		};
+
+		io::stdout()
+			.write_all(formatted.as_bytes())
+			.await
+			.map_err(SinkError::Stdout)?;
 
 		Ok(None)
 	}
 }
+
+/// Format the message and the tag in a human-readable way, optionally styling it with ANSI escape codes
+pub(crate) fn format_message(msg: &Message, tag: Option<&str>, styled: bool) -> String {
+	let style = |s: &str, code: &str| {
+		if styled {
+			format!("\x1b[{code}m{s}\x1b[0m")
+		} else {
+			s.to_owned()
+		}
+	};
+
+	let separator = style("------------------------------", DIM);
+
+	format!(
+		"{separator}\nMessage:\nTitle: {title}\n\nBody:\n{body}\n\nLink: {link}\n\nMedia: {media:?}\n\nTag: {tag}\n{separator}\n",
+		title = style(msg.title.as_deref().unwrap_or("None"), BOLD),
+		body = msg.body.as_deref().unwrap_or("None"),
+		link = style(
+			msg.link.as_ref().map_or("None", |url| url.as_str()),
+			UNDERLINE
+		),
+		media = msg.media,
+		tag = style(&format!("{:?}", tag.unwrap_or("None")), YELLOW),
+	)
+}
+
+const BOLD: &str = "1";
+const DIM: &str = "2";
+const UNDERLINE: &str = "4";
+const YELLOW: &str = "33";
+
+/// Format the message and the tag as a JSON object, see [`Message::to_json()`]
+pub(crate) fn format_json(msg: &Message, tag: Option<&str>) -> serde_json::Value {
+	let mut json = msg.to_json();
+	json["tag"] = tag.into();
+
+	json
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn plain_has_no_escape_codes() {
+		let msg = Message {
+			title: Some("Title".to_owned()),
+			..Default::default()
+		};
+
+		assert!(!format_message(&msg, Some("tag"), false).contains('\x1b'));
+	}
+
+	#[test]
+	fn styled_title_and_tag() {
+		let msg = Message {
+			title: Some("Title".to_owned()),
+			..Default::default()
+		};
+
+		let formatted = format_message(&msg, Some("tag"), true);
+		assert!(formatted.contains("Title: \x1b[1mTitle\x1b[0m"));
+		assert!(formatted.contains("Tag: \x1b[33m\"tag\"\x1b[0m"));
+	}
+}
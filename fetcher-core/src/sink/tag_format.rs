@@ -0,0 +1,90 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains [`TagFormat`] that specifies how a tag is shown on top of a message
+
+use super::{discord, slack};
+
+/// How to show the tag on top of a message
+#[derive(Clone, Copy, Default, Debug)]
+pub enum TagFormat {
+	/// As a `#hashtag`. Everything but letters, digits, and underscores is replaced with an underscore to keep it a valid hashtag, e.g. to be clickable in Telegram
+	#[default]
+	Hashtag,
+
+	/// As a `[bracketed]` prefix
+	Bracketed,
+
+	/// As a bold header
+	Bold,
+}
+
+/// The markup language of the message the tag is put into
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Markup {
+	Html,
+	Markdown,
+
+	/// Slack's own flavor of markdown
+	Mrkdwn,
+}
+
+impl TagFormat {
+	/// Format the `tag` using the `markup` of the sink
+	pub(crate) fn render(self, tag: &str, markup: Markup) -> String {
+		match (self, markup) {
+			(Self::Hashtag, _) => {
+				let tag = tag.replace(
+					|c| match c {
+						'_' => false,
+						c if c.is_alphabetic() || c.is_ascii_digit() => false,
+						_ => true,
+					},
+					"_",
+				);
+
+				format!("#{tag}")
+			}
+			(Self::Bracketed, Markup::Html) => {
+				format!("[{}]", teloxide::utils::html::escape(tag))
+			}
+			(Self::Bracketed, Markup::Markdown) => format!("[{}]", discord::escape(tag)),
+			(Self::Bracketed, Markup::Mrkdwn) => format!("[{}]", slack::escape(tag)),
+			(Self::Bold, Markup::Html) => format!("<b>{}</b>", teloxide::utils::html::escape(tag)),
+			(Self::Bold, Markup::Markdown) => format!("**{}**", discord::escape(tag)),
+			(Self::Bold, Markup::Mrkdwn) => format!("*{}*", slack::escape(tag)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn hashtag() {
+		assert_eq!(
+			TagFormat::Hashtag.render("foo bar-1", Markup::Html),
+			"#foo_bar_1"
+		);
+	}
+
+	#[test]
+	fn escaped() {
+		assert_eq!(
+			TagFormat::Bold.render("a&b", Markup::Html),
+			"<b>a&amp;b</b>"
+		);
+		assert_eq!(
+			TagFormat::Bracketed.render("a&b", Markup::Markdown),
+			"[a&b]"
+		);
+		assert_eq!(
+			TagFormat::Bold.render("a*b_[c]", Markup::Markdown),
+			r"**a\*b\_\[c\]**"
+		);
+	}
+}
@@ -5,12 +5,14 @@
  */
 
 //! This module contains the [`Telegram`] sink, as well as [`LinkLocation`] enum that specifies where to put a link in a telegram message
+//! and [`Poll`] that specifies how to send entries as polls
 
 use crate::{
 	sink::{
 		Sink,
 		error::SinkError,
 		message::{Media, Message, MessageId, length_limiter::MessageLengthLimiter},
+		tag_format::{Markup, TagFormat},
 	},
 	utils::OptionExt,
 };
@@ -20,11 +22,14 @@ use std::{fmt::Debug, num::TryFromIntError, time::Duration};
 use teloxide::{
 	Bot, RequestError,
 	adaptors::{Throttle, throttle::Limits},
-	payloads::{SendMediaGroupSetters, SendMessageSetters},
+	payloads::{
+		EditMessageTextSetters, SendMediaGroupSetters, SendMessageSetters, SendPollSetters,
+	},
 	requests::{Request, Requester, RequesterExt},
 	types::{
-		ChatId, InputFile, InputMedia, InputMediaPhoto, InputMediaVideo, LinkPreviewOptions,
-		Message as TelMessage, MessageId as TelMessageId, ParseMode, ReplyParameters,
+		ChatId, InputFile, InputMedia, InputMediaAudio, InputMediaPhoto, InputMediaVideo,
+		LinkPreviewOptions, Message as TelMessage, MessageId as TelMessageId, ParseMode, PollType,
+		ReplyParameters,
 	},
 };
 use tokio::time::sleep;
@@ -32,6 +37,11 @@ use tokio::time::sleep;
 const MAX_TEXT_MSG_LEN: usize = 4096;
 const MAX_MEDIA_MSG_LEN: usize = 1024;
 
+// https://core.telegram.org/bots/api#sendpoll
+const MAX_POLL_QUESTION_LEN: usize = 300;
+const MAX_POLL_OPTIONS: usize = 10;
+const MAX_POLL_OPTION_LEN: usize = 100;
+
 const LINK_PREVIEW_DISABLED: LinkPreviewOptions = LinkPreviewOptions {
 	is_disabled: true,
 	url: None,
@@ -45,6 +55,9 @@ pub struct Telegram {
 	bot: Throttle<Bot>,
 	chat_id: ChatId,
 	link_location: LinkLocation,
+	web_page_preview: bool,
+	tag_format: TagFormat,
+	poll: Option<Poll>,
 }
 
 /// Where to put `message.link`
@@ -56,18 +69,132 @@ pub enum LinkLocation {
 	/// Put the link at the bottom of the message in a "Link" button
 	#[default]
 	Bottom,
+
+	/// Don't put the link into the text at all and show a web page preview of it instead.
+	/// Falls back to `PreferTitle` if the message contains media since media messages can't have previews
+	PreviewOnly,
+}
+
+/// Send entries as native Telegram polls, e.g. for trivia or word-of-the-day feeds.
+///
+/// The title of the entry becomes the question and the body is split into the options at every [`separator`](`Poll::separator`).
+/// Entries that can't be sent as a poll, e.g. ones without a title or with fewer than 2 options, are sent as regular messages instead.
+/// The tag, the link, and the media of the entry aren't a part of the poll
+#[derive(Clone, Debug)]
+pub struct Poll {
+	/// What separates the options in the body of the entry
+	pub separator: String,
+
+	/// Send the poll as a quiz with this option being the correct answer
+	pub quiz: Option<CorrectOption>,
+}
+
+/// Which option of a quiz is the correct answer
+#[derive(Clone, Debug)]
+pub enum CorrectOption {
+	/// The option at this index, starting from 0
+	Index(u8),
+
+	/// The first option that starts with this marker, e.g. `*`. The marker itself is removed from the option
+	Marker(String),
+}
+
+/// A message parsed into a poll, ready to be sent
+#[derive(Clone, Debug)]
+struct ParsedPoll {
+	question: String,
+	options: Vec<String>,
+	correct_option: Option<u8>,
 }
 
 impl Telegram {
-	/// Creates a new Telegram sink using the bot `token` that sends messages to chat with `chat_id` with `Message.link` put at `link_location`
+	/// Creates a new Telegram sink using the bot `token` that sends messages to chat with `chat_id` with `Message.link` put at `link_location`.
+	/// Web page previews of links in text messages are shown only if `web_page_preview` is true
 	#[must_use]
-	pub fn new(token: String, chat_id: i64, link_location: LinkLocation) -> Self {
+	pub fn new(
+		token: String,
+		chat_id: i64,
+		link_location: LinkLocation,
+		web_page_preview: bool,
+	) -> Self {
 		Self {
 			bot: Bot::new(token).throttle(Limits::default()),
 			chat_id: ChatId(chat_id),
 			link_location,
+			web_page_preview,
+			tag_format: TagFormat::default(),
+			poll: None,
 		}
 	}
+
+	/// Show the tag of a message as `tag_format` instead of a hashtag
+	#[must_use]
+	pub fn with_tag_format(mut self, tag_format: TagFormat) -> Self {
+		self.tag_format = tag_format;
+		self
+	}
+
+	/// Send the entries that can be parsed as a `poll` as one
+	#[must_use]
+	pub fn with_poll(mut self, poll: Poll) -> Self {
+		self.poll = Some(poll);
+		self
+	}
+}
+
+impl Default for Poll {
+	fn default() -> Self {
+		Self {
+			separator: "\n".to_owned(),
+			quiz: None,
+		}
+	}
+}
+
+impl Poll {
+	/// Parse the `message` into a question and its options. Returns None if it can't be sent as a poll
+	fn parse(&self, message: &Message) -> Option<ParsedPoll> {
+		let question = message.title.as_deref()?.trim();
+		if question.is_empty() || question.chars().count() > MAX_POLL_QUESTION_LEN {
+			return None;
+		}
+
+		let mut options = message
+			.body
+			.as_deref()?
+			.split(self.separator.as_str())
+			.map(str::trim)
+			.filter(|option| !option.is_empty())
+			.collect::<Vec<_>>();
+
+		let correct_option = match &self.quiz {
+			None => None,
+			Some(CorrectOption::Index(idx)) => Some(*idx),
+			Some(CorrectOption::Marker(marker)) => {
+				let idx = options
+					.iter()
+					.position(|option| option.starts_with(marker.as_str()))?;
+				options[idx] = options[idx][marker.len()..].trim_start();
+
+				Some(u8::try_from(idx).ok()?)
+			}
+		};
+
+		if !(2..=MAX_POLL_OPTIONS).contains(&options.len())
+			|| options
+				.iter()
+				.any(|option| option.is_empty() || option.chars().count() > MAX_POLL_OPTION_LEN)
+			|| correct_option.is_some_and(|idx| usize::from(idx) >= options.len())
+		{
+			return None;
+		}
+
+		Some(ParsedPoll {
+			question: question.to_owned(),
+			options: options.into_iter().map(ToOwned::to_owned).collect(),
+			correct_option,
+		})
+	}
 }
 
 #[async_trait]
@@ -89,7 +216,14 @@ impl Sink for Telegram {
 			Ok::<_, TryFromIntError>(tel_msg_id)
 		})?;
 
-		let (head, body, tail, media) = process_msg(message, tag, self.link_location);
+		if let Some(poll) = self.poll.as_ref().and_then(|poll| poll.parse(message)) {
+			let sent_msg = self.send_poll(&poll, reply_to).await?;
+			return Ok(Some(i64::from(sent_msg.id.0).into()));
+		}
+
+		let link_location = self.link_location_for(message);
+		let (head, body, tail, media) = process_msg(message, tag, self.tag_format, link_location);
+		let preview = self.link_preview(message, link_location);
 
 		let processed_msg = MessageLengthLimiter {
 			head: head.as_deref(),
@@ -97,9 +231,162 @@ impl Sink for Telegram {
 			tail: tail.as_deref(),
 		};
 
-		let msg_id = self.send_processed(processed_msg, media, reply_to).await?;
+		let msg_id = self
+			.send_processed(processed_msg, media, &preview, reply_to)
+			.await?;
 		Ok(msg_id.map(|tel_msgid| i64::from(tel_msgid.0).into()))
 	}
+
+	/// Edits the text of a message sent before.
+	///
+	/// Only messages without media that fit into a single Telegram message can be edited, the rest are sent anew
+	///
+	/// # Errors
+	/// * if Telegram returned an error
+	/// * if there's no internet connection
+	#[tracing::instrument(level = "debug", skip(message))]
+	async fn edit(
+		&self,
+		msg_id: &MessageId,
+		message: &Message,
+		tag: Option<&str>,
+	) -> Result<Option<MessageId>, SinkError> {
+		if message.media.is_some() {
+			tracing::debug!("Messages with media can't be edited");
+			return Ok(None);
+		}
+
+		if self
+			.poll
+			.as_ref()
+			.is_some_and(|poll| poll.parse(message).is_some())
+		{
+			tracing::debug!("Polls can't be edited");
+			return Ok(None);
+		}
+
+		let tel_msg_id = TelMessageId(msg_id.0.try_into()?);
+
+		let link_location = self.link_location_for(message);
+		let (head, body, tail, _) = process_msg(message, tag, self.tag_format, link_location);
+		let preview = self.link_preview(message, link_location);
+
+		let mut processed_msg = MessageLengthLimiter {
+			head: head.as_deref(),
+			body: body.as_deref(),
+			tail: tail.as_deref(),
+		};
+
+		let Some(text) = processed_msg.split_at(MAX_TEXT_MSG_LEN) else {
+			return Ok(None);
+		};
+
+		if processed_msg.split_at(MAX_TEXT_MSG_LEN).is_some() {
+			tracing::debug!(
+				"Message doesn't fit into a single Telegram message and can't be edited"
+			);
+			return Ok(None);
+		}
+
+		tracing::info!("Editing text message");
+
+		let edit_msg_cmd = self
+			.bot
+			.edit_message_text(self.chat_id, tel_msg_id, &text)
+			.parse_mode(ParseMode::Html)
+			.link_preview_options(preview);
+
+		match edit_msg_cmd.send().await {
+			Ok(_) => Ok(Some(*msg_id)),
+			Err(e)
+				if e.to_string()
+					.to_lowercase()
+					.contains("message is not modified") =>
+			{
+				tracing::debug!("Message hasn't changed since it was sent");
+				Ok(Some(*msg_id))
+			}
+			Err(e)
+				if e.to_string()
+					.to_lowercase()
+					.contains("message to edit not found") =>
+			{
+				tracing::warn!("Message that should be edited doesn't exist anymore");
+				Ok(None)
+			}
+			Err(e) => Err(SinkError::Telegram {
+				source: e,
+				msg: Box::new(text),
+			}),
+		}
+	}
+
+	/// Deletes a message sent before.
+	///
+	/// Messages that have already been deleted or are too old for a bot to delete are skipped
+	///
+	/// # Errors
+	/// * if Telegram returned an error
+	/// * if there's no internet connection
+	#[tracing::instrument(level = "debug")]
+	async fn delete(&self, msg_id: &MessageId) -> Result<(), SinkError> {
+		let tel_msg_id = TelMessageId(msg_id.0.try_into()?);
+
+		tracing::info!("Deleting message");
+
+		match self
+			.bot
+			.delete_message(self.chat_id, tel_msg_id)
+			.send()
+			.await
+		{
+			Ok(_) => Ok(()),
+			Err(e)
+				if e.to_string()
+					.to_lowercase()
+					.contains("message to delete not found") =>
+			{
+				tracing::debug!("Message has already been deleted");
+				Ok(())
+			}
+			Err(e)
+				if e.to_string()
+					.to_lowercase()
+					.contains("message can't be deleted") =>
+			{
+				tracing::warn!("Message can't be deleted anymore, skipping");
+				Ok(())
+			}
+			Err(e) => Err(SinkError::Telegram {
+				source: e,
+				msg: Box::new(*msg_id),
+			}),
+		}
+	}
+}
+
+impl Telegram {
+	fn link_location_for(&self, message: &Message) -> LinkLocation {
+		match self.link_location {
+			LinkLocation::PreviewOnly if message.media.is_some() => LinkLocation::PreferTitle,
+			other => other,
+		}
+	}
+
+	fn link_preview(&self, message: &Message, link_location: LinkLocation) -> LinkPreviewOptions {
+		match (link_location, &message.link) {
+			(LinkLocation::PreviewOnly, Some(link)) => LinkPreviewOptions {
+				is_disabled: false,
+				url: Some(link.to_string()),
+				..LINK_PREVIEW_DISABLED
+			},
+			_ if self.web_page_preview => LinkPreviewOptions {
+				is_disabled: false,
+				..LINK_PREVIEW_DISABLED
+			},
+			_ => LINK_PREVIEW_DISABLED,
+		}
+	}
 }
 
 impl Telegram {
@@ -108,34 +395,69 @@ impl Telegram {
 		&self,
 		mut msg: MessageLengthLimiter<'_>,
 		media: Option<&[Media]>,
+		preview: &LinkPreviewOptions,
 		reply_to: Option<TelMessageId>,
 	) -> Result<Option<TelMessageId>, SinkError> {
 		let mut last_message = reply_to;
 
 		// if the message contains media, send it and MAX_MEDIA_MSG_LEN chars first
 		if let Some(media) = media {
-			// send media only (i.e. without caption) if all the media wouldn't fit in a single message
-			if media.len() > 10 {
-				for ch in media.chunks(10) {
-					let sent_msg = self.send_media(ch, None, last_message).await?;
-					last_message = sent_msg.and_then(|v| v.first().map(|m| m.id));
+			// audio can't be in the same media group as photos and videos, so it's sent in a group of its own after them
+			let (audio, visual): (Vec<_>, Vec<_>) = media
+				.iter()
+				.cloned()
+				.partition(|m| matches!(m, Media::Audio(_)));
+
+			let mut any_sent = false;
+			let mut first_err = None;
+			// captions of media groups that failed to send, to send them as text instead if the others were sent
+			let mut unsent_captions = Vec::new();
+
+			for media in [visual, audio] {
+				// a media group can contain at most 10 items, the rest are sent as separate groups
+				for (i, ch) in media.chunks(10).enumerate() {
+					// only the first group gets a caption, if the title and the link are short enough to fit into it.
+					// The text might've also already been used up as the caption of the previous group
+					let media_caption = (i == 0 && msg.fits_into(MAX_MEDIA_MSG_LEN))
+						.then(|| msg.split_at(MAX_MEDIA_MSG_LEN))
+						.flatten();
+
+					match self
+						.send_media(ch, media_caption.as_deref(), last_message)
+						.await
+					{
+						Ok(sent_msg) => {
+							any_sent = true;
+							last_message = sent_msg.and_then(|v| v.first().map(|m| m.id));
+						}
+						Err(e) => {
+							tracing::error!(
+								"Failed to send a media group, trying to send the rest of the message anyways: {e}"
+							);
+
+							unsent_captions.extend(media_caption);
+							first_err.get_or_insert(e);
+						}
+					}
+				}
+			}
+
+			match first_err {
+				// nothing has been sent yet, the entire message can be safely retried later
+				Some(e) if !any_sent => return Err(e),
+				_ => {
+					for caption in unsent_captions {
+						let sent_msg = self.send_text(&caption, preview, last_message).await?;
+						last_message = Some(sent_msg.id);
+					}
 				}
-			} else {
-				let media_caption = msg.split_at(MAX_MEDIA_MSG_LEN).expect(
-					"should always return a valid split at least once since msg char len is > max_char_limit",
-				);
-
-				let sent_msg = self
-					.send_media(media, Some(&media_caption), last_message)
-					.await?;
-				last_message = sent_msg.and_then(|v| v.first().map(|m| m.id));
 			}
 		}
 
 		// send all remaining text in splits of MAX_TEXT_MSG_LEN
 		// whether we sent a media message first is not important
 		while let Some(text) = msg.split_at(MAX_TEXT_MSG_LEN) {
-			let sent_msg = self.send_text(&text, last_message).await?;
+			let sent_msg = self.send_text(&text, preview, last_message).await?;
 			last_message = Some(sent_msg.id);
 		}
 
@@ -148,6 +470,7 @@ impl Telegram {
 	async fn send_text(
 		&self,
 		message: &str,
+		preview: &LinkPreviewOptions,
 		mut reply_to: Option<TelMessageId>,
 	) -> Result<TelMessage, SinkError> {
 		tracing::debug!(
@@ -161,7 +484,7 @@ impl Telegram {
 				.bot
 				.send_message(self.chat_id, message)
 				.parse_mode(ParseMode::Html)
-				.link_preview_options(LINK_PREVIEW_DISABLED);
+				.link_preview_options(preview.clone());
 
 			let send_msg_cmd = if let Some(id) = reply_to {
 				send_msg_cmd.reply_parameters(ReplyParameters::new(id))
@@ -198,6 +521,60 @@ impl Telegram {
 		}
 	}
 
+	#[tracing::instrument(level = "trace", skip(self))]
+	async fn send_poll(
+		&self,
+		poll: &ParsedPoll,
+		mut reply_to: Option<TelMessageId>,
+	) -> Result<TelMessage, SinkError> {
+		loop {
+			tracing::info!("Sending poll");
+
+			let send_poll_cmd =
+				self.bot
+					.send_poll(self.chat_id, &poll.question, poll.options.iter().cloned());
+
+			let send_poll_cmd = if let Some(idx) = poll.correct_option {
+				send_poll_cmd.type_(PollType::Quiz).correct_option_id(idx)
+			} else {
+				send_poll_cmd
+			};
+
+			let send_poll_cmd = if let Some(id) = reply_to {
+				send_poll_cmd.reply_parameters(ReplyParameters::new(id))
+			} else {
+				send_poll_cmd
+			};
+
+			match send_poll_cmd.send().await {
+				Ok(message) => return Ok(message),
+				Err(e)
+					if e.to_string()
+						.to_lowercase()
+						.contains("replied message not found") =>
+				{
+					tracing::warn!(
+						"Message that should be replied to doesn't exist. Resending just as a regular poll"
+					);
+					reply_to = None;
+				}
+				Err(RequestError::RetryAfter(retry_after)) => {
+					tracing::error!(
+						"Exceeded rate limit while using Throttle Bot adapter, this shouldn't happen... Retrying in {}s",
+						retry_after.seconds()
+					);
+					sleep(retry_after.duration()).await;
+				}
+				Err(e) => {
+					return Err(SinkError::Telegram {
+						source: e,
+						msg: Box::new(poll.clone()),
+					});
+				}
+			}
+		}
+	}
+
 	/// Returns None if Media couldn't be sent but it's Telegram's fault
 	/// # Panics
 	/// if media.len() is more than 10
@@ -205,7 +582,7 @@ impl Telegram {
 	async fn send_media(
 		&self,
 		media: &[Media],
-		mut caption: Option<&str>,
+		caption: Option<&str>,
 		mut reply_to: Option<TelMessageId>,
 	) -> Result<Option<Vec<TelMessage>>, SinkError> {
 		assert!(
@@ -220,7 +597,12 @@ impl Telegram {
 
 		let media = media
 			.iter()
-			.map(|m| {
+			.enumerate()
+			.map(|(i, m)| {
+				// only the first item gets the caption, Telegram shows it for the entire album.
+				// The caption itself is kept to be able to send it as text if the media fails
+				let item_caption = caption.filter(|_| i == 0);
+
 				macro_rules! input_media {
 					// $type example: Photo
 					// $full_type example: InputMediaPhoto
@@ -228,7 +610,7 @@ impl Telegram {
 						let input_media = $full_type::new(InputFile::url($url.clone()))
 							.parse_mode(ParseMode::Html);
 
-						let input_media = if let Some(caption) = caption.take() {
+						let input_media = if let Some(caption) = item_caption {
 							input_media.caption(caption)
 						} else {
 							input_media
@@ -241,6 +623,7 @@ impl Telegram {
 				match m {
 					Media::Photo(url) => input_media!(Photo, InputMediaPhoto, url),
 					Media::Video(url) => input_media!(Video, InputMediaVideo, url),
+					Media::Audio(url) => input_media!(Audio, InputMediaAudio, url),
 				}
 			})
 			.collect::<Vec<_>>();
@@ -274,7 +657,9 @@ impl Telegram {
 						if let Some(caption) = caption {
 							tracing::info!("Sending the message as pure text...");
 
-							let msg = self.send_text(caption, reply_to).await?;
+							let msg = self
+								.send_text(caption, &LINK_PREVIEW_DISABLED, reply_to)
+								.await?;
 
 							return Ok(Some(vec![msg]));
 						} else {
@@ -298,7 +683,9 @@ impl Telegram {
 						tracing::warn!(
 							"Telegram disliked the media URL (\"Wrong file identifier/HTTP URL specified\"), sending the message as pure text"
 						);
-						let msg = self.send_text(caption, reply_to).await?;
+						let msg = self
+							.send_text(caption, &LINK_PREVIEW_DISABLED, reply_to)
+							.await?;
 
 						return Ok(Some(vec![msg]));
 					} else {
@@ -318,7 +705,9 @@ impl Telegram {
 						tracing::warn!(
 							"Telegram disliked the media URL (\"Wrong type of the web page content\"), sending the message as pure text"
 						);
-						let msg = self.send_text(caption, reply_to).await?;
+						let msg = self
+							.send_text(caption, &LINK_PREVIEW_DISABLED, reply_to)
+							.await?;
 
 						return Ok(Some(vec![msg]));
 					} else {
@@ -367,6 +756,7 @@ type HeadBodyTailMedia<'a> = (
 fn process_msg<'a>(
 	msg: &'a Message,
 	tag: Option<&str>,
+	tag_format: TagFormat,
 	link_location: LinkLocation,
 ) -> HeadBodyTailMedia<'a> {
 	let Message {
@@ -388,25 +778,19 @@ fn process_msg<'a>(
 			LinkLocation::PreferTitle => (Some(format!("<a href=\"{link}\">{title}</a>")), None),
 			// and it should be at the bottom, return both separately
 			LinkLocation::Bottom => (Some(title), Some(format!("<a href=\"{link}\">Link</a>"))),
+			// and it should only be previewed, leave it out of the text
+			LinkLocation::PreviewOnly => (Some(title), None),
 		},
 		// if only the title is present, just return itself
 		(Some(title), None) => (Some(title), None),
 		// and if only the link is present, but it at the bottom of the message, even if it should try to be in the title
+		(None, Some(_)) if matches!(link_location, LinkLocation::PreviewOnly) => (None, None),
 		(None, Some(link)) => (None, Some(format!("<a href=\"{link}\">Link</a>"))),
 		(None, None) => (None, None),
 	};
 
-	// add tag as a hashtag on top of the message
+	// add tag on top of the message
 	if let Some(tag) = tag {
-		let tag = tag.replace(
-			|c| match c {
-				'_' => false,
-				c if c.is_alphabetic() || c.is_ascii_digit() => false,
-				_ => true,
-			},
-			"_",
-		);
-
 		head = Some({
 			let mut head = head
 				// add more padding between tag and title if both are present
@@ -416,7 +800,7 @@ fn process_msg<'a>(
 				})
 				.unwrap_or_default();
 
-			head.insert_str(0, &format!("#{tag}\n"));
+			head.insert_str(0, &format!("{}\n", tag_format.render(tag, Markup::Html)));
 			head
 		});
 	}
@@ -429,6 +813,52 @@ impl Debug for Telegram {
 		f.debug_struct("Telegram")
 			.field("chat_id", &self.chat_id)
 			.field("link_location", &self.link_location)
+			.field("tag_format", &self.tag_format)
+			.field("poll", &self.poll)
 			.finish_non_exhaustive()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+	use super::*;
+
+	fn message(title: &str, body: &str) -> Message {
+		Message {
+			title: Some(title.to_owned()),
+			body: Some(body.to_owned()),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn parse_quiz() {
+		let poll = Poll {
+			separator: "|".to_owned(),
+			quiz: Some(CorrectOption::Marker("*".to_owned())),
+		};
+
+		let parsed = poll.parse(&message("2 + 2?", "3 | * 4 | 5")).unwrap();
+		assert_eq!(parsed.question, "2 + 2?");
+		assert_eq!(parsed.options, ["3", "4", "5"]);
+		assert_eq!(parsed.correct_option, Some(1));
+
+		// no option is marked as correct
+		assert!(poll.parse(&message("2 + 2?", "3 | 4 | 5")).is_none());
+	}
+
+	#[test]
+	fn parse_not_a_poll() {
+		let poll = Poll::default();
+
+		assert!(poll.parse(&message("Just news", "A single line")).is_none());
+		assert!(
+			poll.parse(&Message {
+				body: Some("a\nb".to_owned()),
+				..Default::default()
+			})
+			.is_none()
+		);
+	}
+}
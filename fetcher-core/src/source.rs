@@ -5,17 +5,66 @@
  */
 
 //! This module contains [`Source`]s that can fetch data and create new [`Entries`](`Entry`) out of it
+//!
+//! # Custom sources
+//! Anything that implements [`Fetch`] can be used as a source. Wrap it into a [`SourceWithSharedRF`] to give it a read filter
+//! and a [`Task`](`crate::task::Task`) will drive it just like a built-in one.
+//! A [`Box<dyn Fetch>`](`Fetch`) works, too, e.g. if the source is chosen at runtime
+//!
+//! ```
+//! use async_trait::async_trait;
+//! use fetcher_core::{
+//!     entry::Entry,
+//!     read_filter::Newer,
+//!     sink::Stdout,
+//!     source::{Fetch, SourceWithSharedRF, error::SourceError},
+//!     task::Task,
+//! };
+//! use std::sync::Arc;
+//! use tokio::sync::RwLock;
+//!
+//! #[derive(Debug)]
+//! struct MySource;
+//!
+//! #[async_trait]
+//! impl Fetch for MySource {
+//!     async fn fetch(&mut self) -> Result<Vec<Entry>, SourceError> {
+//!         Ok(vec![Entry {
+//!             id: Some("1".into()),
+//!             raw_contents: Some("Hello, World!".to_owned()),
+//!             ..Default::default()
+//!         }])
+//!     }
+//! }
+//!
+//! // the source marks the sent entries as read, and the filter removes them on the next run
+//! let rf = Arc::new(RwLock::new(Newer::new()));
+//!
+//! let task = Task::builder()
+//!     .source(SourceWithSharedRF {
+//!         source: MySource,
+//!         rf: Some(Arc::clone(&rf)),
+//!     })
+//!     .filter(rf)
+//!     .sink(Stdout::default())
+//!     .build()?;
+//! # Ok::<_, fetcher_core::task::builder::TaskBuilderError>(())
+//! ```
 // TODO: add google calendar source. Google OAuth2 is already implemented :)
 
 pub mod always_errors;
 pub mod email;
 pub mod file;
+pub mod gemini;
+pub mod graphql;
 pub mod http;
 pub mod reddit;
 
 pub mod error;
 
-pub use self::{email::Email, file::File, http::Http, reddit::Reddit};
+pub use self::{
+	email::Email, file::File, gemini::Gemini, graphql::GraphQl, http::Http, reddit::Reddit,
+};
 pub use crate::exec::Exec;
 
 use self::error::SourceError;
@@ -102,6 +151,13 @@ impl Fetch for String {
 	}
 }
 
+#[async_trait]
+impl Fetch for Box<dyn Fetch> {
+	async fn fetch(&mut self) -> Result<Vec<Entry>, SourceError> {
+		(**self).fetch().await
+	}
+}
+
 #[async_trait]
 impl<T> Fetch for Vec<T>
 where
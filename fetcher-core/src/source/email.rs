@@ -6,22 +6,26 @@
 
 //! A email source that uses IMAP to connect to an email server
 //!
-//! This module includes the [`Email`] source, the [`ViewMode`] enum, and the [`Filters`] struct
+//! This module includes the [`Email`] source, the [`ViewMode`] and [`TlsMode`] enums, and the [`Filters`] struct
 
 mod auth;
 mod filters;
+mod tls_mode;
 mod view_mode;
 
 pub use auth::Auth;
 pub use filters::Filters;
-use imap::TlsKind;
+use imap::{ConnectionMode, TlsKind};
+pub use tls_mode::TlsMode;
 pub use view_mode::ViewMode;
 
-use self::auth::GoogleAuthExt;
+use self::auth::OAuth2Ext;
 use super::{Fetch, MarkAsRead, Source};
 use crate::{
 	auth::Google as GoogleAuth,
 	auth::google::GoogleOAuth2Error as GoogleAuthError,
+	auth::Microsoft as MicrosoftAuth,
+	auth::microsoft::MicrosoftOAuth2Error as MicrosoftAuthError,
 	entry::{Entry, EntryId},
 	error::FetcherError,
 	sink::message::Message,
@@ -30,15 +34,29 @@ use crate::{
 
 use async_trait::async_trait;
 use mailparse::ParsedMail;
-use std::fmt::{Debug, Write as _};
+use std::{
+	fmt::{Debug, Write as _},
+	sync::Arc,
+};
+use tokio::sync::Mutex;
 
-const IMAP_PORT: u16 = 993;
+/// Appended to the bodies that have been cut short because of [`Email::max_body_len`]
+const TRUNCATED_NOTE: &str = "\n\n[The rest of the email has been truncated]";
 
 /// Email source. Fetches an email's subject and body fields using IMAP
 pub struct Email {
 	/// IMAP server URL
 	pub imap: String,
 
+	/// Port of the IMAP server, or the [default one](`TlsMode::default_port`) of the [`tls`](`Email::tls`) mode if not set
+	pub port: Option<u16>,
+
+	/// How to encrypt the connection to the IMAP server
+	pub tls: TlsMode,
+
+	/// Accept any TLS certificate of the IMAP server. Insecure, see [`Email::danger_accept_invalid_certs`]
+	pub accept_invalid_certs: bool,
+
 	/// Email address/IMAP login
 	pub email: String,
 
@@ -50,6 +68,9 @@ pub struct Email {
 
 	/// IMAP view mode, e.g. read only
 	pub view_mode: ViewMode,
+
+	/// Cut bodies longer than this many characters short right away, e.g. for huge digests of mailing lists
+	pub max_body_len: Option<usize>,
 }
 
 #[expect(missing_docs, reason = "error message is self-documenting")]
@@ -72,6 +93,9 @@ pub enum ImapError {
 	#[error(transparent)]
 	GoogleOAuth2(#[from] GoogleAuthError),
 
+	#[error(transparent)]
+	MicrosoftOAuth2(#[from] MicrosoftAuthError),
+
 	#[error("Authentication error")]
 	Auth(#[source] imap::Error),
 
@@ -88,36 +112,12 @@ macro_rules! authenticate {
 			Auth::GmailOAuth2(auth) => {
 				tracing::trace!("Logging in to IMAP with Google OAuth2");
 
-				let session = $client.authenticate(
-					"XOAUTH2",
-					&auth
-						.as_imap_oauth2($login)
-						.await
-						.map_err(ImapError::GoogleOAuth2)?,
-				);
-
-				match session {
-					Ok(session) => session,
-					// refresh access token and retry
-					Err((e, client)) => {
-						tracing::error!("Denied access to IMAP via OAuth2: {e}");
-						tracing::info!("Refreshing OAuth2 access token and trying again");
-
-						auth.get_new_access_token()
-							.await
-							.map_err(ImapError::GoogleOAuth2)?;
-
-						client
-							.authenticate(
-								"XOAUTH2",
-								&auth
-									.as_imap_oauth2($login)
-									.await
-									.map_err(ImapError::GoogleOAuth2)?,
-							)
-							.map_err(|(e, _)| ImapError::Auth(e))?
-					}
-				}
+				xoauth2!($login, auth.lock().await, $client)
+			}
+			Auth::OutlookOAuth2(auth) => {
+				tracing::trace!("Logging in to IMAP with Microsoft OAuth2");
+
+				xoauth2!($login, auth.lock().await, $client)
 			}
 			Auth::Password(password) => {
 				tracing::warn!("Logging in to IMAP with a password, this is insecure");
@@ -130,21 +130,71 @@ macro_rules! authenticate {
 	}};
 }
 
+macro_rules! xoauth2 {
+	($login:expr, $auth:expr, $client:expr) => {{
+		let mut auth = $auth;
+
+		let session = $client.authenticate("XOAUTH2", &auth.as_imap_oauth2($login).await?);
+
+		match session {
+			Ok(session) => session,
+			// refresh access token and retry
+			Err((e, client)) => {
+				tracing::error!("Denied access to IMAP via OAuth2: {e}");
+				tracing::info!("Refreshing OAuth2 access token and trying again");
+
+				auth.refresh_access_token().await?;
+
+				client
+					.authenticate("XOAUTH2", &auth.as_imap_oauth2($login).await?)
+					.map_err(|(e, _)| ImapError::Auth(e))?
+			}
+		}
+	}};
+}
+
 impl Email {
-	/// Creates an [`Email`] source for use with Gmail that uses [`Google OAuth2`](`crate::auth::Google`) to authenticate
+	/// Creates an [`Email`] source for use with Gmail that uses [`Google OAuth2`](`crate::auth::Google`) to authenticate.
+	/// The authenticator can be shared with other sources using the same account to avoid fetching an access token for each one of them
 	#[must_use]
 	pub fn new_gmail(
 		email: String,
-		auth: GoogleAuth,
+		auth: Arc<Mutex<GoogleAuth>>,
 		filters: Filters,
 		view_mode: ViewMode,
 	) -> Self {
 		Self {
 			imap: "imap.gmail.com".to_owned(),
+			port: None,
+			tls: TlsMode::ImplicitTls,
+			accept_invalid_certs: false,
 			email,
 			auth: Auth::GmailOAuth2(auth),
 			filters,
 			view_mode,
+			max_body_len: None,
+		}
+	}
+
+	/// Creates an [`Email`] source for use with Outlook and Office365 that uses [`Microsoft OAuth2`](`crate::auth::Microsoft`) to authenticate.
+	/// The authenticator can be shared with other sources using the same account to avoid fetching an access token for each one of them
+	#[must_use]
+	pub fn new_outlook(
+		email: String,
+		auth: Arc<Mutex<MicrosoftAuth>>,
+		filters: Filters,
+		view_mode: ViewMode,
+	) -> Self {
+		Self {
+			imap: "outlook.office365.com".to_owned(),
+			port: None,
+			tls: TlsMode::ImplicitTls,
+			accept_invalid_certs: false,
+			email,
+			auth: Auth::OutlookOAuth2(auth),
+			filters,
+			view_mode,
+			max_body_len: None,
 		}
 	}
 
@@ -159,12 +209,50 @@ impl Email {
 	) -> Self {
 		Self {
 			imap,
+			port: None,
+			tls: TlsMode::ImplicitTls,
+			accept_invalid_certs: false,
 			email,
 			auth: Auth::Password(password),
 			filters,
 			view_mode,
+			max_body_len: None,
 		}
 	}
+
+	/// Connect to the IMAP server on `port` instead of the default one of the [`tls`](`Email::tls`) mode
+	#[must_use]
+	pub const fn with_port(mut self, port: u16) -> Self {
+		self.port = Some(port);
+		self
+	}
+
+	/// Encrypt the connection to the IMAP server using `tls` instead of connecting over TLS right away.
+	/// Beware that [`TlsMode::None`] sends both the login credentials and the emails in plain text
+	#[must_use]
+	pub const fn with_tls(mut self, tls: TlsMode) -> Self {
+		self.tls = tls;
+		self
+	}
+
+	/// Accept any TLS certificate of the IMAP server, even expired, self-signed, or issued for another host, e.g. for a server on the LAN.
+	/// This is insecure since anyone can pretend to be the server then.
+	///
+	/// Prefer trusting the certificate authority of the server instead.
+	/// The certificate authorities trusted by the OS are used, and more can be added with the `SSL_CERT_FILE` or `SSL_CERT_DIR` environment variables
+	#[must_use]
+	pub const fn danger_accept_invalid_certs(mut self) -> Self {
+		self.accept_invalid_certs = true;
+		self
+	}
+
+	/// Cut bodies longer than `max_body_len` characters short at fetch time, before any actions are run.
+	/// They are cut at a line break or a space if possible, and a note that the email has been truncated is added at the end
+	#[must_use]
+	pub const fn with_max_body_len(mut self, max_body_len: usize) -> Self {
+		self.max_body_len = Some(max_body_len);
+		self
+	}
 }
 
 #[async_trait]
@@ -195,10 +283,7 @@ impl Source for Email {}
 impl Email {
 	async fn fetch_impl(&mut self) -> Result<Vec<Entry>, EmailError> {
 		tracing::debug!("Fetching emails");
-		let client = imap::ClientBuilder::new(&self.imap, IMAP_PORT)
-			.tls_kind(TlsKind::Rust)
-			.connect()
-			.map_err(ImapError::ConnectionFailed)?;
+		let client = self.connect()?;
 
 		let mut session = authenticate!(&self.email, &mut self.auth, client);
 
@@ -265,20 +350,37 @@ let uid =
 				parse(
 					&mailparse::parse_mail(body)?,
 					uid,
+					self.max_body_len,
 				)
 			})
 			.collect::<Result<Vec<Entry>, EmailError>>()
 	}
 
+	fn connect(&self) -> Result<imap::Client<imap::Connection>, ImapError> {
+		let port = self.port.unwrap_or_else(|| self.tls.default_port());
+		let mode = match self.tls {
+			TlsMode::ImplicitTls => ConnectionMode::Tls,
+			TlsMode::StartTls => ConnectionMode::StartTls,
+			TlsMode::None => {
+				tracing::warn!("Connecting to IMAP without TLS, this is insecure");
+				ConnectionMode::Plaintext
+			}
+		};
+
+		imap::ClientBuilder::new(&self.imap, port)
+			.mode(mode)
+			.tls_kind(TlsKind::Rust)
+			.danger_skip_tls_verify(self.accept_invalid_certs)
+			.connect()
+			.map_err(ImapError::ConnectionFailed)
+	}
+
 	async fn mark_as_read_impl(&mut self, id: &str) -> Result<(), ImapError> {
 		if let ViewMode::ReadOnly = self.view_mode {
 			return Ok(());
 		}
 
-		let client = imap::ClientBuilder::new(&self.imap, IMAP_PORT)
-			.tls_kind(TlsKind::Rust)
-			.connect()
-			.map_err(ImapError::ConnectionFailed)?;
+		let client = self.connect()?;
 
 		let mut session = authenticate!(&self.email, &mut self.auth, client);
 
@@ -303,7 +405,7 @@ let uid =
 	}
 }
 
-fn parse(mail: &ParsedMail, id: String) -> Result<Entry, EmailError> {
+fn parse(mail: &ParsedMail, id: String, max_body_len: Option<usize>) -> Result<Entry, EmailError> {
 	let subject = mail.headers.iter().find_map(|x| {
 		if x.get_key_ref() == "Subject" {
 			Some(x.get_value())
@@ -312,7 +414,7 @@ fn parse(mail: &ParsedMail, id: String) -> Result<Entry, EmailError> {
 		}
 	});
 
-	let body = {
+	let mut body = {
 		if mail.subparts.is_empty() {
 			mail
 		} else {
@@ -324,6 +426,10 @@ fn parse(mail: &ParsedMail, id: String) -> Result<Entry, EmailError> {
 		.get_body()?
 	};
 
+	if let Some(max_body_len) = max_body_len {
+		truncate_body(&mut body, max_body_len);
+	}
+
 	Ok(Entry {
 		id: Some(id.into()),
 		msg: Message {
@@ -335,17 +441,68 @@ fn parse(mail: &ParsedMail, id: String) -> Result<Entry, EmailError> {
 	})
 }
 
+/// Cut `body` down to at most `max_len` characters and add [`TRUNCATED_NOTE`].
+/// Prefers to cut at the last line break or whitespace if it's not too far back to not lose too much of the text
+fn truncate_body(body: &mut String, max_len: usize) {
+	let Some((end, _)) = body.char_indices().nth(max_len) else {
+		return;
+	};
+
+	let min_end = end / 2;
+	let end = body[..end]
+		.rfind('\n')
+		.filter(|&idx| idx >= min_end)
+		.or_else(|| {
+			body[..end]
+				.rfind(char::is_whitespace)
+				.filter(|&idx| idx >= min_end)
+		})
+		.unwrap_or(end);
+
+	body.truncate(end);
+	body.truncate(body.trim_end().len());
+	body.push_str(TRUNCATED_NOTE);
+}
+
 impl Debug for Email {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		f.debug_struct("Email")
 			.field("imap", &self.imap)
+			.field("port", &self.port)
+			.field("tls", &self.tls)
+			.field("accept_invalid_certs", &self.accept_invalid_certs)
 			.field("auth_type", match self.auth {
 				Auth::Password(_) => &"password",
 				Auth::GmailOAuth2(_) => &"gmail_oauth2",
+				Auth::OutlookOAuth2(_) => &"outlook_oauth2",
 			})
 			.field("email", &self.email)
 			.field("filters", &self.filters)
 			.field("view_mode", &self.view_mode)
+			.field("max_body_len", &self.max_body_len)
 			.finish()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn truncate_at_line_break() {
+		let mut body = "First line\nSecond line".to_owned();
+		truncate_body(&mut body, 15);
+		assert_eq!(body, format!("First line{TRUNCATED_NOTE}"));
+	}
+
+	#[test]
+	fn truncate_short() {
+		let mut body = "Short".to_owned();
+		truncate_body(&mut body, 5);
+		assert_eq!(body, "Short");
+
+		let mut body = "Длинноеслово".to_owned();
+		truncate_body(&mut body, 6);
+		assert_eq!(body, format!("Длинно{TRUNCATED_NOTE}"));
+	}
+}
@@ -4,13 +4,20 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use crate::auth::google::{Google as GoogleAuth, GoogleOAuth2Error as GoogleAuthError};
+use super::ImapError;
+use crate::auth::{Google as GoogleAuth, Microsoft as MicrosoftAuth};
+
+use std::sync::Arc;
+use tokio::sync::Mutex;
 
 /// Authentication type for IMAP
 pub enum Auth {
 	#[expect(clippy::doc_markdown, reason = "false positive")]
-	/// Google OAuth2 with full access to Gmail
-	GmailOAuth2(GoogleAuth),
+	/// Google OAuth2 with full access to Gmail. It can be shared between several sources to reuse the same access token
+	GmailOAuth2(Arc<Mutex<GoogleAuth>>),
+	#[expect(clippy::doc_markdown, reason = "false positive")]
+	/// Microsoft OAuth2 with IMAP access to an Outlook or Office365 mailbox. It can be shared between several sources to reuse the same access token
+	OutlookOAuth2(Arc<Mutex<MicrosoftAuth>>),
 	/// An insecure pure text password
 	Password(String),
 }
@@ -28,23 +35,41 @@ impl imap::Authenticator for ImapOAuth2<'_> {
 	}
 }
 
+/// An `OAuth2` authenticator that can be used to log in to IMAP via `XOAUTH2`
 #[async_trait::async_trait]
-pub(super) trait GoogleAuthExt {
-	async fn as_imap_oauth2<'a>(
-		&'a mut self,
-		email: &'a str,
-	) -> Result<ImapOAuth2<'a>, GoogleAuthError>;
+pub(super) trait OAuth2Ext {
+	async fn as_imap_oauth2<'a>(&'a mut self, email: &'a str) -> Result<ImapOAuth2<'a>, ImapError>;
+
+	/// Force fetch a new access token, e.g. if the old one has been rejected
+	async fn refresh_access_token(&mut self) -> Result<(), ImapError>;
 }
 
 #[async_trait::async_trait]
-impl GoogleAuthExt for GoogleAuth {
-	async fn as_imap_oauth2<'a>(
-		&'a mut self,
-		email: &'a str,
-	) -> Result<ImapOAuth2<'a>, GoogleAuthError> {
+impl OAuth2Ext for GoogleAuth {
+	async fn as_imap_oauth2<'a>(&'a mut self, email: &'a str) -> Result<ImapOAuth2<'a>, ImapError> {
 		Ok(ImapOAuth2 {
 			email,
 			token: self.access_token().await?,
 		})
 	}
+
+	async fn refresh_access_token(&mut self) -> Result<(), ImapError> {
+		self.get_new_access_token().await?;
+		Ok(())
+	}
+}
+
+#[async_trait::async_trait]
+impl OAuth2Ext for MicrosoftAuth {
+	async fn as_imap_oauth2<'a>(&'a mut self, email: &'a str) -> Result<ImapOAuth2<'a>, ImapError> {
+		Ok(ImapOAuth2 {
+			email,
+			token: self.access_token().await?,
+		})
+	}
+
+	async fn refresh_access_token(&mut self) -> Result<(), ImapError> {
+		self.get_new_access_token().await?;
+		Ok(())
+	}
 }
@@ -0,0 +1,31 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+/// How to encrypt the connection to the IMAP server
+#[derive(Clone, Copy, Default, Debug)]
+pub enum TlsMode {
+	/// Connect over TLS right away, usually on port 993
+	#[default]
+	ImplicitTls,
+
+	/// Connect in plain text and upgrade the connection with STARTTLS, usually on port 143
+	StartTls,
+
+	/// Don't encrypt the connection at all, usually on port 143.
+	/// This is insecure since both the login credentials and the emails are sent in plain text
+	None,
+}
+
+impl TlsMode {
+	/// The port IMAP servers usually listen on for this mode
+	#[must_use]
+	pub const fn default_port(self) -> u16 {
+		match self {
+			Self::ImplicitTls => 993,
+			Self::StartTls | Self::None => 143,
+		}
+	}
+}
@@ -10,6 +10,8 @@ pub use crate::exec::ExecError;
 
 use super::{
 	email::{EmailError, ImapError},
+	gemini::GeminiError,
+	graphql::GraphQlError,
 	http::HttpError,
 	reddit::RedditError,
 };
@@ -26,9 +28,15 @@ pub enum SourceError {
 	#[error("HTTP error")]
 	Http(#[from] HttpError),
 
+	#[error("GraphQL error")]
+	GraphQl(#[from] GraphQlError),
+
 	#[error("Email error")]
 	Email(#[from] Box<EmailError>),
 
+	#[error("Gemini error")]
+	Gemini(#[from] GeminiError),
+
 	#[error("Reddit error")]
 	Reddit(#[from] RedditError),
 
@@ -47,13 +55,15 @@ impl From<EmailError> for SourceError {
 
 impl SourceError {
 	pub(crate) fn is_connection_err(&self) -> Option<&(dyn StdError + Send + Sync)> {
-		#[expect(clippy::match_same_arms, reason = "clearer code")]
 		match self {
-			Self::Http(_) => Some(self),
+			Self::Http(e) | Self::GraphQl(GraphQlError::Http(e)) if e.is_connection_err() => {
+				Some(self)
+			}
 			Self::Email(email_err) => match &**email_err {
 				EmailError::Imap(ImapError::ConnectionFailed(_)) => Some(self),
 				_ => None,
 			},
+			Self::Gemini(e) if e.is_connection_err() => Some(self),
 			Self::Reddit(RedditError::Reddit(RouxError::Network(_))) => Some(self),
 			_ => None,
 		}
@@ -0,0 +1,614 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Gemini source
+//!
+//! This module contains the [`Gemini`] source that fetches documents from Gemini capsules,
+//! see <https://geminiprotocol.net/docs/protocol-specification.gmi>
+
+mod gemtext;
+pub mod known_hosts;
+
+pub use self::known_hosts::{Fingerprint, KnownHosts, SaveKnownHosts};
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use once_cell::sync::OnceCell;
+use std::{io, sync::Arc, time::Duration};
+use tokio::{
+	io::{AsyncReadExt, AsyncWriteExt},
+	net::TcpStream,
+	sync::Mutex,
+	time::timeout,
+};
+use tokio_rustls::{
+	TlsConnector,
+	rustls::{
+		ClientConfig, DigitallySignedStruct, SignatureScheme,
+		client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+		crypto::{CryptoProvider, ring, verify_tls12_signature, verify_tls13_signature},
+		pki_types::{CertificateDer, ServerName, UnixTime},
+	},
+};
+use url::Url;
+
+use self::gemtext::Line;
+use super::{Fetch, error::SourceError};
+use crate::{entry::Entry, external_save::ExternalSaveError, sink::message::Message};
+
+const DEFAULT_PORT: u16 = 1965;
+
+/// How many redirects to follow before giving up
+const MAX_REDIRECTS: usize = 5;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+static TLS_CONFIG: OnceCell<Arc<ClientConfig>> = OnceCell::new();
+
+/// A source that fetches a document from a Gemini capsule and turns it into entries
+#[derive(Debug)]
+pub struct Gemini {
+	/// The `gemini://` URL to fetch from
+	pub url: Url,
+
+	/// What entries to make out of the document
+	pub items: Items,
+
+	/// How to check the certificate of the capsule
+	pub certificate: CertificateCheck,
+}
+
+/// What entries to make out of a fetched gemtext document
+#[derive(Clone, Copy, Default, Debug)]
+pub enum Items {
+	/// A single entry with the whole document as both its [`raw_contents`](`Entry::raw_contents`) and its body
+	#[default]
+	Document,
+
+	/// An entry for each link line with the label as the title.
+	/// The resolved URL is used as both the link and the id.
+	///
+	/// Labels starting with a `YYYY-MM-DD` date, as in gemlog feeds, set the published date of the entry
+	/// and the date is removed from the title
+	Links,
+
+	/// An entry for each heading with the heading as the title and the text up to the next heading as the body.
+	/// The heading is used as the id
+	Headings,
+}
+
+/// How to check the certificate of a capsule. Most of them are self-signed, so they aren't checked against certificate authorities
+#[derive(Debug)]
+pub enum CertificateCheck {
+	/// Trust on first use: trust the certificate a host presents the first time and reject it if it changes later
+	Tofu(Arc<Mutex<KnownHosts>>),
+
+	/// Accept only certificates with one of these fingerprints
+	Pinned(Vec<Fingerprint>),
+
+	/// Accept any certificate. Insecure
+	DangerAcceptAny,
+}
+
+#[expect(missing_docs, reason = "error message is self-documenting")]
+#[derive(thiserror::Error, Debug)]
+pub enum GeminiError {
+	#[error("{0:?} is not a valid Gemini URL, it should start with gemini:// and have a host")]
+	BadUrl(String),
+
+	#[error("Can't connect to {1}")]
+	Connect(#[source] io::Error, String),
+
+	#[error("TLS handshake with {1} failed")]
+	Tls(#[source] io::Error, String),
+
+	#[error("{0} hasn't presented a certificate")]
+	NoCertificate(String),
+
+	#[error(
+		"The certificate of {host} has changed since it was first trusted. Expected fingerprint {expected}, found {found}. Remove the host from the known hosts if the change is legitimate"
+	)]
+	CertificateChanged {
+		host: String,
+		expected: Fingerprint,
+		found: Fingerprint,
+	},
+
+	#[error("The certificate of {host} with fingerprint {found} isn't pinned")]
+	CertificateNotPinned { host: String, found: Fingerprint },
+
+	#[error("Can't save the known hosts")]
+	SaveKnownHosts(#[source] ExternalSaveError),
+
+	#[error("Can't send the request to {1:?} or read the response")]
+	Io(#[source] io::Error, String),
+
+	#[error("Request to {0:?} timed out")]
+	Timeout(String),
+
+	#[error("Invalid response header from {0:?}")]
+	BadHeader(String),
+
+	#[error("{url:?} asks for input which isn't supported: {prompt:?}")]
+	InputRequired { url: String, prompt: String },
+
+	#[error("{url:?} requires a client certificate which isn't supported: {meta:?}")]
+	ClientCertificateRequired { url: String, meta: String },
+
+	#[error("{url:?} redirects to an invalid URL {to:?}")]
+	BadRedirect { url: String, to: String },
+
+	#[error("Too many redirects, the last one being to {0:?}")]
+	TooManyRedirects(String),
+
+	#[error("Request to {url:?} failed with status {status}: {meta:?}")]
+	Failure {
+		url: String,
+		status: u8,
+		meta: String,
+	},
+
+	#[error("{url:?} is {mime:?} and not a text document")]
+	NotText { url: String, mime: String },
+}
+
+impl GeminiError {
+	/// Whether the error is caused by the connection or the server and may go away on its own.
+	/// Only temporary failures (4x) count among the failure statuses
+	pub(crate) fn is_connection_err(&self) -> bool {
+		match self {
+			Self::Connect(..) | Self::Tls(..) | Self::Io(..) | Self::Timeout(_) => true,
+			Self::Failure { status, .. } => *status / 10 == 4,
+			_ => false,
+		}
+	}
+}
+
+/// A parsed response of a Gemini server
+#[derive(Debug)]
+struct Response {
+	/// The two digit status code
+	status: u8,
+
+	/// The MIME type, the redirect URL, or the error message depending on the status
+	meta: String,
+
+	/// The document, if the request has succeeded
+	body: Vec<u8>,
+}
+
+/// Accepts any certificate as long as the handshake is signed by it.
+/// The certificate itself is checked after the handshake, see [`CertificateCheck`]
+#[derive(Debug)]
+struct AcceptAnyCertificate(Arc<CryptoProvider>);
+
+impl Gemini {
+	/// Create a new [`Gemini`] source that fetches `url` as a single document, trusting certificates on first use without saving them anywhere
+	///
+	/// # Errors
+	/// if `url` isn't a `gemini://` URL or doesn't have a host
+	pub fn new(url: Url) -> Result<Self, GeminiError> {
+		if url.scheme() != "gemini" || url.host_str().is_none() {
+			return Err(GeminiError::BadUrl(url.to_string()));
+		}
+
+		Ok(Self {
+			url,
+			items: Items::default(),
+			certificate: CertificateCheck::Tofu(Arc::new(Mutex::new(KnownHosts::default()))),
+		})
+	}
+
+	/// Make `items` out of the fetched document instead of a single entry of the whole document
+	#[must_use]
+	pub fn with_items(mut self, items: Items) -> Self {
+		self.items = items;
+		self
+	}
+
+	/// Check the certificate of the capsule with `certificate`
+	#[must_use]
+	pub fn with_certificate_check(mut self, certificate: CertificateCheck) -> Self {
+		self.certificate = certificate;
+		self
+	}
+
+	async fn fetch_impl(&self) -> Result<Vec<Entry>, GeminiError> {
+		let (url, doc) = self.get().await?;
+
+		Ok(match self.items {
+			Items::Document => vec![Entry {
+				raw_contents: Some(doc.clone()),
+				msg: Message {
+					body: Some(doc),
+					link: Some(url),
+					..Default::default()
+				},
+				..Default::default()
+			}],
+			Items::Links => links(&doc, &url),
+			Items::Headings => headings(&doc, &url),
+		})
+	}
+
+	/// Get the document at [`Self::url`], following redirects.
+	/// Returns the URL of the document after all redirects as well as the document itself
+	async fn get(&self) -> Result<(Url, String), GeminiError> {
+		let mut url = self.url.clone();
+
+		for _ in 0..=MAX_REDIRECTS {
+			tracing::debug!("Sending a Gemini request to {url}");
+
+			let response = timeout(REQUEST_TIMEOUT, self.request(&url))
+				.await
+				.map_err(|_| GeminiError::Timeout(url.to_string()))??;
+
+			match response.status / 10 {
+				2 => {
+					// an empty MIME type means text/gemini
+					if !response.meta.is_empty() && !response.meta.starts_with("text/") {
+						return Err(GeminiError::NotText {
+							url: url.to_string(),
+							mime: response.meta,
+						});
+					}
+
+					let doc = String::from_utf8_lossy(&response.body).into_owned();
+					return Ok((url, doc));
+				}
+				3 => {
+					let to = url
+						.join(&response.meta)
+						.ok()
+						.filter(|to| to.scheme() == "gemini" && to.host_str().is_some())
+						.ok_or_else(|| GeminiError::BadRedirect {
+							url: url.to_string(),
+							to: response.meta.clone(),
+						})?;
+
+					tracing::debug!("{url} redirects to {to}");
+					url = to;
+				}
+				1 => {
+					return Err(GeminiError::InputRequired {
+						url: url.to_string(),
+						prompt: response.meta,
+					});
+				}
+				6 => {
+					return Err(GeminiError::ClientCertificateRequired {
+						url: url.to_string(),
+						meta: response.meta,
+					});
+				}
+				_ => {
+					return Err(GeminiError::Failure {
+						url: url.to_string(),
+						status: response.status,
+						meta: response.meta,
+					});
+				}
+			}
+		}
+
+		Err(GeminiError::TooManyRedirects(url.to_string()))
+	}
+
+	/// Send a single request for `url` and read the whole response
+	async fn request(&self, url: &Url) -> Result<Response, GeminiError> {
+		// IPv6 addresses are enclosed in brackets in URLs
+		let host = url
+			.host_str()
+			.ok_or_else(|| GeminiError::BadUrl(url.to_string()))?
+			.trim_start_matches('[')
+			.trim_end_matches(']');
+
+		let port = url.port().unwrap_or(DEFAULT_PORT);
+
+		// the certificates of hosts on non-default ports are tracked separately
+		let authority = if port == DEFAULT_PORT {
+			host.to_owned()
+		} else {
+			format!("{host}:{port}")
+		};
+
+		let server_name = ServerName::try_from(host.to_owned())
+			.map_err(|_| GeminiError::BadUrl(url.to_string()))?;
+
+		let tcp = TcpStream::connect((host, port))
+			.await
+			.map_err(|e| GeminiError::Connect(e, authority.clone()))?;
+
+		let mut stream = TlsConnector::from(tls_config())
+			.connect(server_name, tcp)
+			.await
+			.map_err(|e| GeminiError::Tls(e, authority.clone()))?;
+
+		let fingerprint = stream
+			.get_ref()
+			.1
+			.peer_certificates()
+			.and_then(|certs| certs.first())
+			.map(|cert| Fingerprint::of(cert))
+			.ok_or_else(|| GeminiError::NoCertificate(authority.clone()))?;
+
+		self.certificate.check(&authority, fingerprint).await?;
+
+		let io_err = |e| GeminiError::Io(e, url.to_string());
+
+		stream
+			.write_all(format!("{url}\r\n").as_bytes())
+			.await
+			.map_err(io_err)?;
+
+		let mut response = Vec::new();
+		if let Err(e) = stream.read_to_end(&mut response).await {
+			// a lot of servers close the connection without a TLS close_notify after sending the whole response
+			if e.kind() != io::ErrorKind::UnexpectedEof {
+				return Err(io_err(e));
+			}
+		}
+
+		parse_response(response).ok_or_else(|| GeminiError::BadHeader(url.to_string()))
+	}
+}
+
+#[async_trait]
+impl Fetch for Gemini {
+	/// Fetch the document at [`Gemini::url`] and make [`Gemini::items`] out of it
+	#[tracing::instrument(skip_all)]
+	async fn fetch(&mut self) -> Result<Vec<Entry>, SourceError> {
+		self.fetch_impl().await.map_err(Into::into)
+	}
+}
+
+impl CertificateCheck {
+	/// Check that the certificate with `fingerprint` presented by `host` can be trusted
+	async fn check(&self, host: &str, fingerprint: Fingerprint) -> Result<(), GeminiError> {
+		match self {
+			Self::Tofu(known_hosts) => known_hosts.lock().await.check(host, fingerprint).await,
+			Self::Pinned(pinned) if pinned.contains(&fingerprint) => Ok(()),
+			Self::Pinned(_) => Err(GeminiError::CertificateNotPinned {
+				host: host.to_owned(),
+				found: fingerprint,
+			}),
+			Self::DangerAcceptAny => Ok(()),
+		}
+	}
+}
+
+impl ServerCertVerifier for AcceptAnyCertificate {
+	fn verify_server_cert(
+		&self,
+		_end_entity: &CertificateDer<'_>,
+		_intermediates: &[CertificateDer<'_>],
+		_server_name: &ServerName<'_>,
+		_ocsp_response: &[u8],
+		_now: UnixTime,
+	) -> Result<ServerCertVerified, tokio_rustls::rustls::Error> {
+		Ok(ServerCertVerified::assertion())
+	}
+
+	fn verify_tls12_signature(
+		&self,
+		message: &[u8],
+		cert: &CertificateDer<'_>,
+		dss: &DigitallySignedStruct,
+	) -> Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+		verify_tls12_signature(
+			message,
+			cert,
+			dss,
+			&self.0.signature_verification_algorithms,
+		)
+	}
+
+	fn verify_tls13_signature(
+		&self,
+		message: &[u8],
+		cert: &CertificateDer<'_>,
+		dss: &DigitallySignedStruct,
+	) -> Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+		verify_tls13_signature(
+			message,
+			cert,
+			dss,
+			&self.0.signature_verification_algorithms,
+		)
+	}
+
+	fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+		self.0.signature_verification_algorithms.supported_schemes()
+	}
+}
+
+/// Get the TLS config shared between all Gemini requests, initializing it if it hasn't been yet
+fn tls_config() -> Arc<ClientConfig> {
+	let config = TLS_CONFIG.get_or_init(|| {
+		let provider = Arc::new(ring::default_provider());
+
+		let config = ClientConfig::builder_with_provider(Arc::clone(&provider))
+			.with_safe_default_protocol_versions()
+			.expect("the ring crypto provider should support the default protocol versions")
+			.dangerous()
+			.with_custom_certificate_verifier(Arc::new(AcceptAnyCertificate(provider)))
+			.with_no_client_auth();
+
+		Arc::new(config)
+	});
+
+	Arc::clone(config)
+}
+
+/// Split `response` into the status, the meta, and the body.
+/// Returns [`None`] if the header is invalid
+fn parse_response(mut response: Vec<u8>) -> Option<Response> {
+	let header_end = response.iter().position(|&b| b == b'\n')?;
+	let body = response.split_off(header_end + 1);
+
+	let header = std::str::from_utf8(&response).ok()?.trim_end();
+	let (status, meta) = header.split_at_checked(2)?;
+
+	if !status.bytes().all(|b| b.is_ascii_digit()) {
+		return None;
+	}
+
+	Some(Response {
+		status: status.parse().ok()?,
+		meta: meta.trim().to_owned(),
+		body,
+	})
+}
+
+/// Make an entry out of every link of `doc`
+fn links(doc: &str, base: &Url) -> Vec<Entry> {
+	gemtext::parse(doc)
+		.filter_map(|line| {
+			let Line::Link { url, label } = line else {
+				return None;
+			};
+
+			let url = match base.join(url) {
+				Ok(url) => url,
+				Err(e) => {
+					tracing::warn!("Skipping link with invalid URL {url:?}: {e}");
+					return None;
+				}
+			};
+
+			let published = label
+				.and_then(|label| label.get(..10))
+				.and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+				.and_then(|date| date.and_hms_opt(0, 0, 0))
+				.map(|date| date.and_utc());
+
+			// remove the date and the separator after it, e.g. "2024-01-02 - Title"
+			let title = match (label, published) {
+				(Some(label), Some(_)) => {
+					Some(label[10..].trim_start_matches([' ', '\t', '-', ':']))
+				}
+				(label, _) => label,
+			}
+			.filter(|title| !title.is_empty());
+
+			Some(Entry {
+				id: Some(url.as_str().into()),
+				published,
+				msg: Message {
+					title: title.map(ToOwned::to_owned),
+					link: Some(url),
+					..Default::default()
+				},
+				..Default::default()
+			})
+		})
+		.collect()
+}
+
+/// Make an entry out of every heading of `doc` and the text following it
+fn headings(doc: &str, base: &Url) -> Vec<Entry> {
+	let mut sections: Vec<(&str, Vec<&str>)> = Vec::new();
+
+	for line in gemtext::parse(doc) {
+		match (line, sections.last_mut()) {
+			(Line::Heading(heading), _) => sections.push((heading, Vec::new())),
+			(Line::Text(text), Some((_, body))) => body.push(text),
+			(Line::Link { url, label }, Some((_, body))) => {
+				body.push(label.unwrap_or(url));
+			}
+			// everything before the first heading
+			(_, None) => (),
+		}
+	}
+
+	sections
+		.into_iter()
+		.filter(|(heading, _)| !heading.is_empty())
+		.map(|(heading, body)| {
+			let body = body.join("\n").trim().to_owned();
+
+			Entry {
+				id: Some(heading.into()),
+				msg: Message {
+					title: Some(heading.to_owned()),
+					body: (!body.is_empty()).then_some(body),
+					link: Some(base.clone()),
+					..Default::default()
+				},
+				..Default::default()
+			}
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+	use super::*;
+
+	const GEMLOG: &str = "# My gemlog\n\
+		=> /about.gmi About me\n\
+		## Posts\n\
+		=> 2024-01-02-post.gmi 2024-01-02 - A post\n\
+		Some text\n";
+
+	#[test]
+	fn parse_link_entries() {
+		let base = Url::parse("gemini://example.com/gemlog/").unwrap();
+		let entries = links(GEMLOG, &base);
+
+		assert_eq!(entries.len(), 2);
+		assert_eq!(
+			entries[0].msg.link.as_ref().unwrap().as_str(),
+			"gemini://example.com/about.gmi"
+		);
+		assert_eq!(entries[0].msg.title.as_deref(), Some("About me"));
+		assert_eq!(entries[0].published, None);
+
+		assert_eq!(
+			entries[1].id.as_deref(),
+			Some("gemini://example.com/gemlog/2024-01-02-post.gmi")
+		);
+		assert_eq!(entries[1].msg.title.as_deref(), Some("A post"));
+		assert_eq!(
+			entries[1].published.unwrap().date_naive(),
+			NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()
+		);
+	}
+
+	#[test]
+	fn parse_heading_entries() {
+		let base = Url::parse("gemini://example.com/gemlog/").unwrap();
+		let entries = headings(GEMLOG, &base);
+
+		assert_eq!(entries.len(), 2);
+		assert_eq!(entries[0].msg.title.as_deref(), Some("My gemlog"));
+		assert_eq!(entries[0].msg.body.as_deref(), Some("About me"));
+		assert_eq!(entries[1].msg.title.as_deref(), Some("Posts"));
+		assert_eq!(
+			entries[1].msg.body.as_deref(),
+			Some("2024-01-02 - A post\nSome text")
+		);
+	}
+
+	#[test]
+	fn parse_response_header() {
+		let response = parse_response(b"20 text/gemini; lang=en\r\n# Hi\n".to_vec()).unwrap();
+		assert_eq!(response.status, 20);
+		assert_eq!(response.meta, "text/gemini; lang=en");
+		assert_eq!(response.body, b"# Hi\n");
+
+		let response = parse_response(b"31 /new\r\n".to_vec()).unwrap();
+		assert_eq!(response.status, 31);
+		assert_eq!(response.meta, "/new");
+
+		assert!(parse_response(b"OK\r\n".to_vec()).is_none(), "not a status");
+		assert!(
+			parse_response(b"20 text/gemini".to_vec()).is_none(),
+			"no CRLF"
+		);
+	}
+}
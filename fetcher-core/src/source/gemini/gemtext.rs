@@ -0,0 +1,102 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! A minimal parser of gemtext, the native document format of Gemini.
+//! See <https://geminiprotocol.net/docs/gemtext-specification.gmi>
+
+/// A line of a gemtext document
+#[derive(PartialEq, Eq, Debug)]
+pub(super) enum Line<'a> {
+	/// A link line, e.g. `=> gemini://example.com/ Example`
+	Link {
+		/// The URL of the link, possibly relative to the document
+		url: &'a str,
+		/// The label of the link, if it has one
+		label: Option<&'a str>,
+	},
+
+	/// A heading of any level, without the leading `#`s
+	Heading(&'a str),
+
+	/// Any other line, including lines of preformatted blocks
+	Text(&'a str),
+}
+
+/// Parse `doc` line by line. The toggle lines of preformatted blocks are skipped
+pub(super) fn parse(doc: &str) -> impl Iterator<Item = Line<'_>> {
+	let mut preformatted = false;
+
+	doc.lines().filter_map(move |line| {
+		if line.starts_with("```") {
+			preformatted = !preformatted;
+			return None;
+		}
+
+		if preformatted {
+			return Some(Line::Text(line));
+		}
+
+		if let Some(link) = line.strip_prefix("=>") {
+			let link = link.trim();
+			let (url, label) = match link.split_once(char::is_whitespace) {
+				Some((url, label)) => (url, Some(label.trim())),
+				None => (link, None),
+			};
+
+			if url.is_empty() {
+				return Some(Line::Text(line));
+			}
+
+			return Some(Line::Link {
+				url,
+				label: label.filter(|label| !label.is_empty()),
+			});
+		}
+
+		// only 3 levels of headings exist
+		let level = line.bytes().take_while(|&b| b == b'#').count();
+		if (1..=3).contains(&level) {
+			return Some(Line::Heading(line[level..].trim()));
+		}
+
+		Some(Line::Text(line))
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_lines() {
+		let doc = "# Gemlog\r\n\
+			Welcome\n\
+			=> 2024-01-02-post.gmi 2024-01-02 - A post\n\
+			=>\tgemini://example.com/\n\
+			```\n\
+			=> not-a-link\n\
+			```\n\
+			#### not a heading";
+
+		assert_eq!(
+			parse(doc).collect::<Vec<_>>(),
+			[
+				Line::Heading("Gemlog"),
+				Line::Text("Welcome"),
+				Line::Link {
+					url: "2024-01-02-post.gmi",
+					label: Some("2024-01-02 - A post"),
+				},
+				Line::Link {
+					url: "gemini://example.com/",
+					label: None,
+				},
+				Line::Text("=> not-a-link"),
+				Line::Text("#### not a heading"),
+			]
+		);
+	}
+}
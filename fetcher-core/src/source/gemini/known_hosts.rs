@@ -0,0 +1,181 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains [`KnownHosts`] that [`Gemini`](`super::Gemini`) uses to trust certificates on first use,
+//! as well as the [`SaveKnownHosts`] trait and [`Fingerprint`]
+
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use super::GeminiError;
+use crate::external_save::ExternalSaveError;
+
+/// SHA-256 fingerprint of a DER-encoded certificate
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
+pub struct Fingerprint(pub [u8; 32]);
+
+#[expect(missing_docs, reason = "error message is self-documenting")]
+#[derive(thiserror::Error, Debug)]
+#[error(
+	"{0:?} is not a SHA-256 fingerprint, it should be 64 hexadecimal digits, optionally separated by colons"
+)]
+pub struct BadFingerprintError(pub String);
+
+/// Certificates of hosts that have been trusted on first use
+#[derive(Default, Debug)]
+pub struct KnownHosts {
+	/// External save location for the known hosts.
+	/// It's called every time a new host is trusted
+	pub external_save: Option<Box<dyn SaveKnownHosts>>,
+
+	hosts: HashMap<String, Fingerprint>,
+}
+
+/// A place to save the known hosts to, to keep trusting the same certificates across restarts
+#[async_trait]
+pub trait SaveKnownHosts: fmt::Debug + Send + Sync {
+	/// Save all `hosts` externally
+	///
+	/// # Errors
+	/// It may return an error if there has been issues saving, e.g. writing to disk
+	async fn save_known_hosts(
+		&mut self,
+		hosts: &HashMap<String, Fingerprint>,
+	) -> Result<(), ExternalSaveError>;
+}
+
+impl Fingerprint {
+	/// Compute the fingerprint of the DER-encoded certificate `cert`
+	#[must_use]
+	pub fn of(cert: &[u8]) -> Self {
+		Self(Sha256::digest(cert).into())
+	}
+}
+
+impl KnownHosts {
+	/// Create a new [`KnownHosts`] with the provided `hosts` and [`Self::external_save`] set to `external_save`.
+	/// Use [`KnownHosts::default()`] to start with no known hosts that are kept only in memory
+	#[must_use]
+	pub fn new<E>(hosts: HashMap<String, Fingerprint>, external_save: E) -> Self
+	where
+		E: SaveKnownHosts + 'static,
+	{
+		Self {
+			external_save: Some(Box::new(external_save)),
+			hosts,
+		}
+	}
+
+	/// All known hosts and the fingerprints of their certificates
+	#[must_use]
+	pub fn hosts(&self) -> &HashMap<String, Fingerprint> {
+		&self.hosts
+	}
+
+	/// Check that `host` has presented the same certificate as the first time, or trust it if it's the first time it's been seen
+	///
+	/// # Errors
+	/// * if the certificate has changed
+	/// * if the newly trusted host couldn't be saved externally
+	pub async fn check(&mut self, host: &str, fingerprint: Fingerprint) -> Result<(), GeminiError> {
+		match self.hosts.get(host) {
+			Some(known) if *known == fingerprint => Ok(()),
+			Some(known) => Err(GeminiError::CertificateChanged {
+				host: host.to_owned(),
+				expected: *known,
+				found: fingerprint,
+			}),
+			None => {
+				tracing::info!("Trusting the certificate of {host} with fingerprint {fingerprint}");
+				self.hosts.insert(host.to_owned(), fingerprint);
+
+				if let Some(ext_save) = &mut self.external_save {
+					ext_save
+						.save_known_hosts(&self.hosts)
+						.await
+						.map_err(GeminiError::SaveKnownHosts)?;
+				}
+
+				Ok(())
+			}
+		}
+	}
+}
+
+impl fmt::Display for Fingerprint {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for byte in self.0 {
+			write!(f, "{byte:02x}")?;
+		}
+
+		Ok(())
+	}
+}
+
+impl FromStr for Fingerprint {
+	type Err = BadFingerprintError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let err = || BadFingerprintError(s.to_owned());
+
+		let digits = s.bytes().filter(|&b| b != b':').collect::<Vec<_>>();
+		if digits.len() != 64 {
+			return Err(err());
+		}
+
+		let mut fingerprint = [0; 32];
+		for (byte, pair) in fingerprint.iter_mut().zip(digits.chunks_exact(2)) {
+			let pair = std::str::from_utf8(pair).map_err(|_| err())?;
+			*byte = u8::from_str_radix(pair, 16).map_err(|_| err())?;
+		}
+
+		Ok(Self(fingerprint))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+	use super::*;
+
+	#[tokio::test]
+	async fn trust_on_first_use() {
+		let mut known_hosts = KnownHosts::default();
+		let first = Fingerprint::of(b"first");
+		let second = Fingerprint::of(b"second");
+
+		known_hosts.check("example.com", first).await.unwrap();
+		known_hosts.check("example.com", first).await.unwrap();
+		assert!(
+			matches!(
+				known_hosts.check("example.com", second).await,
+				Err(GeminiError::CertificateChanged { .. })
+			),
+			"a changed certificate should be rejected"
+		);
+	}
+
+	#[test]
+	fn fingerprint_roundtrip() {
+		let fingerprint = Fingerprint::of(b"cert");
+		let s = fingerprint.to_string();
+
+		assert_eq!(s.parse::<Fingerprint>().unwrap(), fingerprint);
+		assert_eq!(
+			s.to_uppercase()
+				.as_bytes()
+				.chunks(2)
+				.map(|pair| std::str::from_utf8(pair).unwrap())
+				.collect::<Vec<_>>()
+				.join(":")
+				.parse::<Fingerprint>()
+				.unwrap(),
+			fingerprint
+		);
+		assert!("abcd".parse::<Fingerprint>().is_err(), "too short");
+	}
+}
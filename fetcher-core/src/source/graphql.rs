@@ -0,0 +1,160 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains the [`GraphQl`] source
+
+use super::{
+	Fetch,
+	error::SourceError,
+	http::{Auth, Http, HttpError, PostBody},
+};
+use crate::entry::Entry;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use url::Url;
+
+/// A source that sends a GraphQL query to an endpoint and returns the `data` object of the response in the [`Entry.raw_contents`] field
+///
+/// The data can then be parsed with the [`Json`](`crate::action::transform::entry::json::Json`) transform.
+/// GraphQL errors in the response are returned as a [`GraphQlError::Errors`] even if the response was successful otherwise
+#[derive(Debug)]
+pub struct GraphQl {
+	http: Http,
+}
+
+#[expect(missing_docs, reason = "error message is self-documenting")]
+#[derive(thiserror::Error, Debug)]
+pub enum GraphQlError {
+	#[error(transparent)]
+	Http(#[from] HttpError),
+
+	#[error("Invalid GraphQL response")]
+	InvalidResponse(#[from] serde_json::Error),
+
+	#[error("GraphQL query failed: {}", .0.join("; "))]
+	Errors(Vec<String>),
+
+	#[error("GraphQL response contains neither data nor errors")]
+	NoData,
+}
+
+#[derive(Deserialize)]
+struct Response {
+	data: Option<Value>,
+	#[serde(default)]
+	errors: Vec<ResponseError>,
+}
+
+#[derive(Deserialize)]
+struct ResponseError {
+	message: String,
+}
+
+impl GraphQl {
+	/// Create a new GraphQL source that sends the `query` with the `variables` to the endpoint at `url`
+	///
+	/// # Errors
+	/// This method fails if TLS couldn't be initialized
+	pub fn new(url: Url, query: &str, variables: Option<Value>) -> Result<Self, HttpError> {
+		let body = json!({
+			"query": query,
+			"variables": variables.unwrap_or_else(|| json!({})),
+		});
+
+		Ok(Self {
+			http: Http::new_post_with_body(url, PostBody::Json(body))?,
+		})
+	}
+
+	/// Send all requests through the `proxy` instead of the default one
+	///
+	/// # Errors
+	/// This method fails if the proxy URL isn't supported or TLS couldn't be initialized
+	pub fn with_proxy(mut self, proxy: &Url) -> Result<Self, HttpError> {
+		self.http = self.http.with_proxy(proxy)?;
+		Ok(self)
+	}
+
+	/// Authenticate every request with `auth`
+	#[must_use]
+	pub fn with_auth(mut self, auth: Auth) -> Self {
+		self.http = self.http.with_auth(auth);
+		self
+	}
+
+	async fn fetch_impl(&self) -> Result<Entry, GraphQlError> {
+		let mut entry = self.http.fetch_impl().await?;
+		entry.raw_contents = Some(extract_data(
+			entry.raw_contents.as_deref().unwrap_or_default(),
+		)?);
+
+		Ok(entry)
+	}
+}
+
+/// Returns the `data` object of the GraphQL `response`, or all the errors it contains if there are any
+fn extract_data(response: &str) -> Result<String, GraphQlError> {
+	let response: Response = serde_json::from_str(response)?;
+
+	if !response.errors.is_empty() {
+		return Err(GraphQlError::Errors(
+			response.errors.into_iter().map(|e| e.message).collect(),
+		));
+	}
+
+	let data = response.data.ok_or(GraphQlError::NoData)?;
+	Ok(data.to_string())
+}
+
+#[async_trait]
+impl Fetch for GraphQl {
+	#[tracing::instrument(skip_all)]
+	async fn fetch(&mut self) -> Result<Vec<Entry>, SourceError> {
+		self.fetch_impl().await.map(|x| vec![x]).map_err(Into::into)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+
+	use super::*;
+
+	#[test]
+	fn extracts_data() {
+		let data = extract_data(r#"{"data": {"user": {"name": "fetcher"}}}"#).unwrap();
+		assert_eq!(
+			serde_json::from_str::<Value>(&data).unwrap(),
+			json!({"user": {"name": "fetcher"}})
+		);
+	}
+
+	#[test]
+	fn errors_fail_even_with_data() {
+		let res = extract_data(
+			r#"{
+				"data": {"user": null},
+				"errors": [{"message": "first"}, {"message": "second", "path": ["user"]}]
+			}"#,
+		);
+
+		match res {
+			Err(GraphQlError::Errors(errors)) => assert_eq!(errors, ["first", "second"]),
+			other => panic!("expected GraphQL errors, got {other:?}"),
+		}
+	}
+
+	#[test]
+	fn no_data_nor_errors() {
+		assert!(matches!(extract_data("{}"), Err(GraphQlError::NoData)));
+		assert!(matches!(
+			extract_data("not json"),
+			Err(GraphQlError::InvalidResponse(_))
+		));
+	}
+}
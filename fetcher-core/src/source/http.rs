@@ -12,7 +12,7 @@ use crate::{entry::Entry, sink::message::Message, source::error::SourceError};
 
 use async_trait::async_trait;
 use once_cell::sync::OnceCell;
-use reqwest::Client;
+use reqwest::{Certificate, Client, StatusCode};
 use std::{fmt::Debug, time::Duration};
 use url::Url;
 
@@ -21,13 +21,21 @@ use super::Fetch;
 const USER_AGENT: &str =
 	"Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:96.0) Gecko/20100101 Firefox/96.0";
 
-pub(crate) static CLIENT: OnceCell<reqwest::Client> = OnceCell::new();
+/// How many characters of the body of an unsuccessful response to keep in [`HttpError::BadStatus`]
+const MAX_ERROR_BODY_LEN: usize = 512;
+
+static CLIENT: OnceCell<reqwest::Client> = OnceCell::new();
+static DEFAULT_PROXY: OnceCell<Url> = OnceCell::new();
+static EXTRA_ROOT_CERTIFICATES: OnceCell<Vec<Certificate>> = OnceCell::new();
 
 /// A source that fetches from the [`URL`](`url`)
 pub struct Http {
 	/// The URL to fetch from
 	pub url: Url,
 	request: Request,
+	auth: Option<Auth>,
+	proxy: Option<Url>,
+	accept_invalid_certs: bool,
 	client: reqwest::Client,
 }
 
@@ -40,14 +48,73 @@ pub enum HttpError {
 	#[error("Failed to init TLS")]
 	TlsInitFailed(#[source] reqwest::Error),
 
+	#[error("Invalid proxy {1:?}")]
+	BadProxy(#[source] reqwest::Error, String),
+
+	#[error("Invalid root certificates, they should be PEM-encoded")]
+	BadRootCertificates(#[source] reqwest::Error),
+
 	#[error("Can't send an HTTP request to {1:?}")]
 	BadRequest(#[source] reqwest::Error, String),
+
+	#[error("HTTP request to {url:?} failed with status {status}. Response: {body:?}")]
+	BadStatus {
+		url: String,
+		status: StatusCode,
+		/// The beginning of the body of the response, up to 512 characters
+		body: String,
+	},
+}
+
+impl HttpError {
+	/// Whether the error is caused by the connection or the server and not by the request itself.
+	/// Client errors, e.g. 403 Forbidden, are not except for 429 Too Many Requests since they won't go away on their own
+	pub(crate) fn is_connection_err(&self) -> bool {
+		match self {
+			Self::BadStatus { status, .. } => {
+				!status.is_client_error() || *status == StatusCode::TOO_MANY_REQUESTS
+			}
+			_ => true,
+		}
+	}
 }
 
 #[derive(Debug)]
 pub(crate) enum Request {
 	Get,
-	Post(serde_json::Value),
+	Post(PostBody),
+}
+
+/// The body of a POST request and how it's encoded
+#[derive(Debug)]
+pub enum PostBody {
+	/// JSON, sent with the `application/json` content type
+	Json(serde_json::Value),
+
+	/// Key-value pairs, URL-encoded and sent with the `application/x-www-form-urlencoded` content type
+	Form(Vec<(String, String)>),
+
+	/// Any other body, sent as is with the provided content type
+	Raw {
+		/// The body
+		body: String,
+		/// The value of the `Content-Type` header, e.g. `application/xml`
+		content_type: String,
+	},
+}
+
+/// Credentials sent with every request, e.g. to access a private feed
+pub enum Auth {
+	/// HTTP basic auth
+	Basic {
+		/// The username
+		username: String,
+		/// The password, if there's one
+		password: Option<String>,
+	},
+
+	/// A token sent in the `Authorization: Bearer <token>` header
+	Bearer(String),
 }
 
 impl Http {
@@ -59,12 +126,52 @@ impl Http {
 		Self::new(url, Request::Get)
 	}
 
-	/// Create a new HTTP client that sends POST requests
+	/// Create a new HTTP client that sends POST requests with a JSON body
 	///
 	/// # Errors
 	/// This method fails if body isn't valid JSON or TLS couldn't be initialized
 	pub fn new_post(url: Url, body: &str) -> Result<Self, HttpError> {
-		Self::new(url, Request::Post(serde_json::from_str(body)?))
+		Self::new_post_with_body(url, PostBody::Json(serde_json::from_str(body)?))
+	}
+
+	/// Create a new HTTP client that sends POST requests with the provided `body`
+	///
+	/// # Errors
+	/// This method fails if TLS couldn't be initialized
+	pub fn new_post_with_body(url: Url, body: PostBody) -> Result<Self, HttpError> {
+		Self::new(url, Request::Post(body))
+	}
+
+	/// Send all requests through the `proxy` instead of the default one set via [`set_default_proxy`]
+	///
+	/// # Errors
+	/// This method fails if the proxy URL isn't supported or TLS couldn't be initialized
+	pub fn with_proxy(mut self, proxy: &Url) -> Result<Self, HttpError> {
+		self.proxy = Some(proxy.clone());
+		self.client = build_client(self.proxy.as_ref(), self.accept_invalid_certs)?;
+		Ok(self)
+	}
+
+	/// Accept any TLS certificate, even expired, self-signed, or issued for another host, e.g. for a server on the LAN.
+	/// This is insecure since anyone can pretend to be the server then.
+	/// Prefer trusting the certificate authority of the server with [`set_extra_root_certificates`] instead
+	///
+	/// # Errors
+	/// This method fails if TLS couldn't be initialized
+	pub fn danger_accept_invalid_certs(mut self) -> Result<Self, HttpError> {
+		self.accept_invalid_certs = true;
+		self.client = build_client(
+			self.proxy.as_ref().or_else(|| DEFAULT_PROXY.get()),
+			self.accept_invalid_certs,
+		)?;
+		Ok(self)
+	}
+
+	/// Authenticate every request with `auth`
+	#[must_use]
+	pub fn with_auth(mut self, auth: Auth) -> Self {
+		self.auth = Some(auth);
+		self
 	}
 }
 
@@ -79,26 +186,20 @@ impl Fetch for Http {
 
 impl Http {
 	fn new(url: Url, request: Request) -> Result<Self, HttpError> {
-		let client = CLIENT
-			.get_or_try_init(|| {
-				reqwest::ClientBuilder::new()
-					.timeout(Duration::from_secs(30))
-					.build()
-					.map_err(HttpError::TlsInitFailed)
-			})?
-			.clone();
-
 		Ok(Self {
 			url,
 			request,
-			client,
+			auth: None,
+			proxy: None,
+			accept_invalid_certs: false,
+			client: client()?,
 		})
 	}
 
-	async fn fetch_impl(&self) -> Result<Entry, HttpError> {
+	pub(crate) async fn fetch_impl(&self) -> Result<Entry, HttpError> {
 		tracing::debug!("Sending an HTTP request");
 
-		let page = send_request(&self.client, &self.request, &self.url).await?;
+		let page = send_request(&self.client, &self.request, self.auth.as_ref(), &self.url).await?;
 
 		// tracing::trace!("Done. Body: ----------------------------------------\n{page:?}\n----------------------------------------\n");
 
@@ -113,9 +214,64 @@ impl Http {
 	}
 }
 
+/// Set the proxy all HTTP requests are sent through, e.g. `http://proxy:3128` or `socks5://127.0.0.1:9050`
+///
+/// If it's not set, the proxy is read from the `HTTP_PROXY`, `HTTPS_PROXY`, and `ALL_PROXY` environment variables instead.
+///
+/// It should be called before any sources or actions are created since they can't pick up the new proxy after that
+///
+/// # Errors
+/// This function fails with the passed proxy if the default proxy has already been set
+pub fn set_default_proxy(proxy: Url) -> Result<(), Url> {
+	DEFAULT_PROXY.set(proxy)
+}
+
+/// Trust the certificate authorities in the PEM-encoded `pem_bundle` in addition to the built-in ones,
+/// e.g. a private certificate authority of a LAN.
+///
+/// It should be called before any sources or actions are created since they can't pick up the new certificates after that
+///
+/// # Errors
+/// This function fails if the certificates couldn't be parsed. They are ignored if they have already been set before
+pub fn set_extra_root_certificates(pem_bundle: &[u8]) -> Result<(), HttpError> {
+	let certs = Certificate::from_pem_bundle(pem_bundle).map_err(HttpError::BadRootCertificates)?;
+	_ = EXTRA_ROOT_CERTIFICATES.set(certs);
+
+	Ok(())
+}
+
+/// Get the HTTP client shared between everything that sends HTTP requests, initializing it if it hasn't been yet
+pub(crate) fn client() -> Result<Client, HttpError> {
+	CLIENT
+		.get_or_try_init(|| build_client(DEFAULT_PROXY.get(), false))
+		.cloned()
+}
+
+fn build_client(proxy: Option<&Url>, accept_invalid_certs: bool) -> Result<Client, HttpError> {
+	let mut builder = reqwest::ClientBuilder::new()
+		.timeout(Duration::from_secs(30))
+		.user_agent(USER_AGENT)
+		.danger_accept_invalid_certs(accept_invalid_certs);
+
+	for cert in EXTRA_ROOT_CERTIFICATES.get().into_iter().flatten() {
+		builder = builder.add_root_certificate(cert.clone());
+	}
+
+	// setting a proxy manually disables the proxies from the environment
+	if let Some(proxy) = proxy {
+		let proxy = reqwest::Proxy::all(proxy.as_str())
+			.map_err(|e| HttpError::BadProxy(e, proxy.to_string()))?;
+
+		builder = builder.proxy(proxy);
+	}
+
+	builder.build().map_err(HttpError::TlsInitFailed)
+}
+
 pub(crate) async fn send_request(
 	client: &Client,
 	request: &Request,
+	auth: Option<&Auth>,
 	url: &Url,
 ) -> Result<String, HttpError> {
 	let request = match request {
@@ -124,28 +280,62 @@ pub(crate) async fn send_request(
 
 			client.get(url.as_str())
 		}
-		Request::Post(json) => {
+		Request::Post(body) => {
 			tracing::trace!(
 				"Making an HTTP POST request to {:?} with {:#?}",
 				url.as_str(),
-				json
+				body
 			);
 
-			client.post(url.as_str()).json(json)
+			let request = client.post(url.as_str());
+
+			match body {
+				PostBody::Json(json) => request.json(json),
+				PostBody::Form(form) => request.form(form),
+				PostBody::Raw { body, content_type } => request
+					.header(reqwest::header::CONTENT_TYPE, content_type)
+					.body(body.clone()),
+			}
 		}
 	};
 
+	let request = match auth {
+		Some(Auth::Basic { username, password }) => request.basic_auth(username, password.as_ref()),
+		Some(Auth::Bearer(token)) => request.bearer_auth(token),
+		None => request,
+	};
+
 	let response = request
-		.header(reqwest::header::USER_AGENT, USER_AGENT)
 		.send()
 		.await
 		.map_err(|e| HttpError::BadRequest(e, url.to_string()))?;
 
+	let status = response.status();
+
 	tracing::trace!("Getting text body of the response");
-	response
+	let body = response
 		.text()
 		.await
-		.map_err(|e| HttpError::BadRequest(e, url.to_string()))
+		.map_err(|e| HttpError::BadRequest(e, url.to_string()))?;
+
+	if !status.is_success() {
+		return Err(HttpError::BadStatus {
+			url: url.to_string(),
+			status,
+			body: truncate_body(body),
+		});
+	}
+
+	Ok(body)
+}
+
+pub(crate) fn truncate_body(mut body: String) -> String {
+	if let Some((end, _)) = body.char_indices().nth(MAX_ERROR_BODY_LEN) {
+		body.truncate(end);
+		body.push_str("...");
+	}
+
+	body
 }
 
 impl Debug for Http {
@@ -153,6 +343,37 @@ impl Debug for Http {
 		f.debug_struct("Http")
 			.field("url", &self.url.as_str())
 			.field("request", &self.request)
+			.field("auth", &self.auth)
+			.field("proxy", &self.proxy.as_ref().map(Url::as_str))
+			.field("accept_invalid_certs", &self.accept_invalid_certs)
 			.finish_non_exhaustive()
 	}
 }
+
+// don't leak the credentials into the logs
+impl Debug for Auth {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Basic { username, .. } => f
+				.debug_struct("Basic")
+				.field("username", username)
+				.finish_non_exhaustive(),
+			Self::Bearer(_) => f.write_str("Bearer"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn truncate_error_body() {
+		assert_eq!(truncate_body("short".to_owned()), "short");
+
+		let long = "ы".repeat(MAX_ERROR_BODY_LEN + 1);
+		let truncated = truncate_body(long);
+		assert_eq!(truncated.chars().count(), MAX_ERROR_BODY_LEN + "...".len());
+		assert!(truncated.ends_with("ы..."));
+	}
+}
@@ -4,7 +4,7 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-//! This module contains the [`Reddit`] subbreddit API source
+//! This module contains the [`Reddit`] subbreddit and user API source
 
 use super::Fetch;
 use crate::{
@@ -17,19 +17,48 @@ use crate::{
 
 use async_trait::async_trait;
 use roux::{
-	Subreddit,
+	Subreddit, User,
+	submission::SubmissionData,
 	util::{FeedOption, TimePeriod},
 };
 use std::fmt::Debug;
 use url::Url;
 
-/// Source that fetches posts from a subreddit using the Reddit API
+/// The default and the maximum amount of posts fetched at once
+pub const DEFAULT_LIMIT: u32 = 100;
+
+const USER_AGENT: &str = concat!("fetcher/", env!("CARGO_PKG_VERSION"));
+
+/// Source that fetches posts from a subreddit or posts submitted by a user using the Reddit API
 pub struct Reddit {
-	/// Sorting algorithm
+	/// Sorting algorithm. Posts of users are always sorted by newest
 	pub sort: Sort,
 	/// If score of a post is below this threshold, it gets skipped
 	pub score_threshold: Option<u32>,
-	subreddit: Subreddit,
+	/// The maximum amount of posts to fetch, up to [`DEFAULT_LIMIT`]
+	pub limit: u32,
+	feed: Feed,
+}
+
+enum Feed {
+	Subreddit {
+		subreddit: Subreddit,
+		auth: Option<RedditAuth>,
+	},
+	User(User),
+}
+
+/// Credentials of a Reddit "script" app to access the API with higher rate limits
+#[derive(Clone)]
+pub struct RedditAuth {
+	/// Client ID of the app
+	pub client_id: String,
+	/// Client secret of the app
+	pub client_secret: String,
+	/// Username of the developer of the app
+	pub username: String,
+	/// Password of the developer of the app
+	pub password: String,
 }
 
 #[expect(missing_docs, reason = "error message is self-documenting")]
@@ -66,14 +95,46 @@ pub enum Sort {
 }
 
 impl Reddit {
-	/// Creates a new [`Reddit`] source.
+	/// Creates a new [`Reddit`] source that fetches posts from a subreddit
 	#[must_use]
 	pub fn new(subreddit: &str, sort: Sort, score_threshold: Option<u32>) -> Self {
 		Self {
 			sort,
 			score_threshold,
-			subreddit: Subreddit::new(subreddit),
+			limit: DEFAULT_LIMIT,
+			feed: Feed::Subreddit {
+				subreddit: Subreddit::new(subreddit),
+				auth: None,
+			},
+		}
+	}
+
+	/// Creates a new [`Reddit`] source that fetches posts submitted by a user, newest first
+	#[must_use]
+	pub fn new_user(user: &str, score_threshold: Option<u32>) -> Self {
+		Self {
+			sort: Sort::Latest,
+			score_threshold,
+			limit: DEFAULT_LIMIT,
+			feed: Feed::User(User::new(user)),
+		}
+	}
+
+	/// Fetch at most `limit` posts, up to [`DEFAULT_LIMIT`]
+	#[must_use]
+	pub fn with_limit(mut self, limit: u32) -> Self {
+		self.limit = limit.min(DEFAULT_LIMIT);
+		self
+	}
+
+	/// Access the subreddit as the `auth` app instead of anonymously. Does nothing for user posts
+	#[must_use]
+	pub fn with_auth(mut self, new_auth: RedditAuth) -> Self {
+		if let Feed::Subreddit { auth, .. } = &mut self.feed {
+			*auth = Some(new_auth);
 		}
+
+		self
 	}
 }
 
@@ -90,36 +151,11 @@ impl Fetch for Reddit {
 
 impl Reddit {
 	async fn fetch_impl(&self) -> Result<Vec<Entry>, RedditError> {
-		let s = &self.subreddit;
-
-		macro_rules! top_in {
-			($tp:tt) => {
-				s.top(
-					100,
-					Some(FeedOption::new().limit(100).period(TimePeriod::$tp)),
-				)
-				.await
-			};
-		}
-
-		let posts = match self.sort {
-			Sort::Latest => s.latest(100, None).await,
-			Sort::Rising => s.rising(100, None).await,
-			Sort::Hot => s.hot(100, None).await,
-			Sort::TopDay => top_in!(Today),
-			Sort::TopWeek => top_in!(ThisWeek),
-			Sort::TopMonth => top_in!(ThisMonth),
-			Sort::TopYear => top_in!(ThisYear),
-			Sort::TopAllTime => top_in!(AllTime),
-		}?;
+		let posts = self.fetch_posts().await?;
 
 		let entries = posts
-			.data
-			.children
 			.into_iter()
 			.filter_map(|post| {
-				let post = post.data;
-
 				if let Some(score_threshold) = self.score_threshold {
 					if post.score < score_threshold.into() {
 						return None;
@@ -154,6 +190,13 @@ impl Reddit {
 
 				body.insert_str(0, &format!("Score: {}\n\n", post.score));
 
+				let preview = post
+					.preview
+					.as_ref()
+					.and_then(|preview| preview.images.first())
+					// Reddit returns the preview URLs HTML-escaped
+					.map(|img| html_escape::decode_html_entities(&img.source.url).into_owned());
+
 				let media = if is_picture {
 					let url = link.expect(
 						"should contain a valid picture url since we confirmed it with is_picture",
@@ -166,6 +209,11 @@ impl Reddit {
 					);
 
 					Some(vec![Media::Video(url)])
+				} else if let Some(preview) = preview {
+					match Url::try_from(preview.as_str()) {
+						Ok(url) => Some(vec![Media::Photo(url)]),
+						Err(e) => return Some(Err(InvalidUrlError(e, preview))),
+					}
 				} else {
 					None
 				};
@@ -195,14 +243,82 @@ impl Reddit {
 
 		Ok(entries)
 	}
+
+	async fn fetch_posts(&self) -> Result<Vec<SubmissionData>, RedditError> {
+		match &self.feed {
+			Feed::Subreddit {
+				subreddit,
+				auth: Some(auth),
+			} => {
+				// access tokens are shortlived, so it's easier to just get a new one every time
+				let subreddit = roux::Reddit::new(USER_AGENT, &auth.client_id, &auth.client_secret)
+					.username(&auth.username)
+					.password(&auth.password)
+					.subreddit(&subreddit.name)
+					.await?;
+
+				self.fetch_subreddit(&subreddit).await
+			}
+			Feed::Subreddit {
+				subreddit,
+				auth: None,
+			} => self.fetch_subreddit(subreddit).await,
+			Feed::User(user) => Ok(user
+				.submitted(Some(FeedOption::new().limit(self.limit)))
+				.await?
+				.data
+				.children
+				.into_iter()
+				.map(|post| post.data)
+				.collect()),
+		}
+	}
+
+	async fn fetch_subreddit(&self, s: &Subreddit) -> Result<Vec<SubmissionData>, RedditError> {
+		macro_rules! top_in {
+			($tp:tt) => {
+				s.top(
+					self.limit,
+					Some(FeedOption::new().limit(self.limit).period(TimePeriod::$tp)),
+				)
+				.await
+			};
+		}
+
+		let posts = match self.sort {
+			Sort::Latest => s.latest(self.limit, None).await,
+			Sort::Rising => s.rising(self.limit, None).await,
+			Sort::Hot => s.hot(self.limit, None).await,
+			Sort::TopDay => top_in!(Today),
+			Sort::TopWeek => top_in!(ThisWeek),
+			Sort::TopMonth => top_in!(ThisMonth),
+			Sort::TopYear => top_in!(ThisYear),
+			Sort::TopAllTime => top_in!(AllTime),
+		}?;
+
+		Ok(posts
+			.data
+			.children
+			.into_iter()
+			.map(|post| post.data)
+			.collect())
+	}
 }
 
 impl Debug for Reddit {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		f.debug_struct("Reddit")
-			.field("subreddit", &self.subreddit.name)
-			.field("sort", &self.sort)
+		let mut f = f.debug_struct("Reddit");
+
+		match &self.feed {
+			Feed::Subreddit { subreddit, auth } => f
+				.field("subreddit", &subreddit.name)
+				.field("auth", &auth.as_ref().map(|auth| &auth.client_id)),
+			Feed::User(user) => f.field("user", &user.user),
+		};
+
+		f.field("sort", &self.sort)
 			.field("score_threshold", &self.score_threshold)
+			.field("limit", &self.limit)
 			.finish()
 	}
 }
@@ -6,28 +6,41 @@
 
 //! This module contains the basic block of [`fetcher`](`crate`) that is a [`Task`]
 
+pub mod builder;
+pub mod dead_letters;
 pub mod entry_to_msg_map;
 
-use self::entry_to_msg_map::EntryToMsgMap;
+use self::{
+	dead_letters::{DeadLetter, SaveDeadLetter},
+	entry_to_msg_map::{EntryToMsgMap, SentMessage},
+};
 use crate::{
 	action::Action,
 	entry::{Entry, EntryId},
-	error::FetcherError,
-	sink::{
-		Sink,
-		message::{Message, MessageId},
-	},
+	error::{FetcherError, TooManyEntriesError},
+	job::timepoint::TimePoint,
+	sink::{Sink, error::SinkError, message::Message},
 	source::Source,
 };
 
-use std::{borrow::Cow, collections::HashSet};
+use std::{
+	borrow::Cow,
+	collections::HashSet,
+	time::{Duration, Instant},
+};
 
 /// A core primitive of [`fetcher`](`crate`).
 ///
 /// Contains everything from a [`Source`] that allows to fetch some data, to a [`Sink`] that takes that data and sends it somewhere.
 /// It also contains any transformators
+///
+/// Use [`Task::run()`] to run it once. To run it repeatedly, put it into a [`Job`](`crate::job::Job`)
+/// with a [`refresh_time`](`crate::job::Job::refresh_time`), e.g. [`TimePoint::Duration`](`crate::job::timepoint::TimePoint::Duration`) to run it at a fixed interval
 #[derive(Debug)]
 pub struct Task {
+	/// Name of the task, if the job it's in has more than one. Used to tell which task an error happened in
+	pub name: Option<String>,
+
 	/// An optional tag that may be put near a message body to differentiate this task from others that may be similar
 	pub tag: Option<String>,
 
@@ -37,41 +50,142 @@ pub struct Task {
 	/// A list of optional transformators which to run the data received from the source through
 	pub actions: Option<Vec<Action>>,
 
-	/// Map of an entry to a message. Used when an entry is a reply to an older entry to be able to show that as a message, too
+	/// Map of an entry to a message of each sink. Used when an entry is a reply to an older entry to be able to show that as a message, too
 	pub entry_to_msg_map: Option<EntryToMsgMap>,
+
+	/// Edit the message an entry has already been sent as, if there's one in the [`entry_to_msg_map`](`Task::entry_to_msg_map`), instead of sending a new one.
+	/// It's edited only if the contents of the message have changed since.
+	///
+	/// Entries that have already been read are filtered out by the read filter before they reach the sinks,
+	/// so only the entries that come through it are updated, e.g. all of them if the task has no read filter
+	pub update_in_place: bool,
+
+	/// Delete the messages of entries that were sent before but aren't present in the source anymore, e.g. retracted posts.
+	/// Uses the [`entry_to_msg_map`](`Task::entry_to_msg_map`) to find them.
+	///
+	/// An entry is present if it has been fetched or made by a transform during the run, even if a filter has dropped it afterwards
+	pub delete_removed: bool,
+
+	/// Cancel the task if a single run takes longer than this, e.g. because of a connection that hangs.
+	/// Entries already sent by then stay sent and are marked as read, if every sink has sent them. None means no limit
+	pub timeout: Option<Duration>,
+
+	/// Fail the run before anything is sent if there are more entries than this to send to the first sink,
+	/// e.g. because of an item query that matches a lot more than it should. Entries that have been read or filtered out don't count.
+	/// None means no limit
+	pub max_entries: Option<usize>,
+
+	/// Where to save the messages that a sink failed to send, to be able to inspect or resend them later.
+	/// Entries of these messages aren't marked as read, so they are retried on the next run
+	pub dead_letters: Option<Box<dyn SaveDeadLetter>>,
+
+	/// Refresh the task on its own schedule instead of the [`refresh_time`](`crate::job::Job::refresh_time`) of the job it's in,
+	/// e.g. a [`TimePoint::Cron`](`crate::job::timepoint::TimePoint::Cron`) to run it only during business hours
+	pub refresh_time: Option<TimePoint>,
 }
 
 impl Task {
-	/// Run a task (both the source and the sink part) once to completion
+	/// Run a task (both the source and the sink part) once to completion.
+	///
+	/// Fetches the entries, runs them through all actions including the sinks, and marks them as read
 	///
 	/// # Errors
 	/// If there was an error fetching the data, sending the data, or saving what data was successfully sent to an external location
-	#[tracing::instrument(skip(self))]
 	pub async fn run(&mut self) -> Result<(), FetcherError> {
+		self.run_in_job(None).await
+	}
+
+	/// Run the task, recording metrics under the name of the job it's in
+	#[tracing::instrument(skip(self))]
+	pub(crate) async fn run_in_job(&mut self, job: Option<&str>) -> Result<(), FetcherError> {
 		tracing::trace!("Running task");
 
+		let labels = [
+			("job", job.unwrap_or_default().to_owned()),
+			("task", self.name.clone().unwrap_or_default()),
+		];
+
+		metrics::counter!("fetcher_task_runs_total", &labels).increment(1);
+
+		let mut progress = SendProgress::default();
+		let res = match self.timeout {
+			Some(timeout) => {
+				tokio::time::timeout(timeout, self.fetch_and_process(&labels, &mut progress))
+					.await
+					.unwrap_or(Err(FetcherError::Timeout(timeout)))
+			}
+			None => self.fetch_and_process(&labels, &mut progress).await,
+		};
+
+		// mark the entries that have been sent as read even if the run has stopped half way through,
+		// e.g. because it has timed out, to not send them again on the next run
+		let res = match self.source.as_mut() {
+			Some(source) => progress.mark_as_read(&mut **source).await.and(res),
+			None => res,
+		};
+
+		if res.is_err() {
+			metrics::counter!("fetcher_task_errors_total", &labels).increment(1);
+		}
+
+		res
+	}
+
+	async fn fetch_and_process(
+		&mut self,
+		labels: &[(&'static str, String)],
+		progress: &mut SendProgress,
+	) -> Result<(), FetcherError> {
 		let raw = match &mut self.source {
-			Some(source) => source.fetch().await?,
+			Some(source) => {
+				let fetch_start = Instant::now();
+				let raw = source.fetch().await;
+				metrics::histogram!("fetcher_fetch_duration_seconds", labels)
+					.record(fetch_start.elapsed());
+
+				raw?
+			}
 			None => vec![Entry::default()], // return just an empty entry if there is no source
 		};
 
 		tracing::debug!("Got {} raw entries from the sources", raw.len());
 		tracing::trace!("Raw entries: {raw:#?}");
+		metrics::counter!("fetcher_entries_fetched_total", labels).increment(raw.len() as u64);
 
-		self.process_entries(raw).await?;
-
-		Ok(())
+		self.process_entries(raw, labels, progress).await
 	}
 
 	// TODO: figure out a way to split into several functions to avoid 15 level nesting?
 	// It's a bit difficult because this function can't be a method because we are borrowing self.actions
 	// throughout the entire process
-	async fn process_entries(&mut self, mut entries: Vec<Entry>) -> Result<(), FetcherError> {
+	async fn process_entries(
+		&mut self,
+		mut entries: Vec<Entry>,
+		labels: &[(&'static str, String)],
+		progress: &mut SendProgress,
+	) -> Result<(), FetcherError> {
+		let sink_count = self
+			.actions
+			.iter()
+			.flatten()
+			.filter(|act| matches!(act, Action::Sink(_)))
+			.count();
+
+		// index of the current sink, in order of appearance, to keep track of the messages of each one separately
+		let mut sink_idx = 0;
+		let mut sink_errors = Vec::new();
+
+		// ids of all entries the source currently has, including the ones that have been filtered out later on
+		// and the ones that transforms have split the fetched entries into, e.g. the items of a feed
+		let mut present_ids = self.delete_removed.then(|| entry_ids(&entries));
+
 		for act in self.actions.iter().flatten() {
+			if sink_idx < sink_count {
+				progress.in_flight = entry_ids(&entries);
+			}
+
 			match act {
-				Action::Filter(f) => {
-					f.filter(&mut entries).await;
-				}
+				Action::Filter(f) => f.filter(&mut entries).await,
 				Action::Transform(tr) => {
 					let mut fully_transformed = Vec::new();
 
@@ -80,46 +194,136 @@ impl Task {
 					}
 
 					entries = fully_transformed;
+
+					if let Some(present_ids) = &mut present_ids {
+						present_ids.extend(entry_ids(&entries));
+					}
 				}
 				Action::Sink(s) => {
-					let undeduped_len = entries.len();
-					tracing::trace!("Entries to send before dedup: {undeduped_len}");
-
 					entries = remove_duplicates(entries);
 
-					if undeduped_len - entries.len() > 0 {
-						tracing::info!(
-							"Removed {} duplicate entries before sending",
-							undeduped_len - entries.len()
-						);
+					// only the entries that are about to be sent to the first sink count, i.e. not the ones that have been read or filtered out
+					check_max_entries(&entries, self.max_entries.filter(|_| sink_idx == 0))?;
+
+					if let Some((present_ids, map)) =
+						present_ids.as_ref().zip(self.entry_to_msg_map.as_mut())
+					{
+						delete_removed(&**s, sink_idx, map, present_ids).await?;
 					}
 
 					tracing::trace!("Sending entries: {entries:#?}");
 
 					// entries should be sorted newest to oldest but we should send oldest first
-					for entry in entries.iter().rev() {
-						let msg_id = send_entry(
+					for (i, entry) in entries.iter().rev().enumerate() {
+						let sent_msg = match send_entry(
 							&**s,
+							sink_idx,
 							self.entry_to_msg_map.as_mut(),
 							self.tag.as_deref(),
+							self.update_in_place,
 							entry,
 						)
-						.await?;
+						.await
+						{
+							Ok(sent_msg) => sent_msg,
+							Err(e) => {
+								// don't send any newer entries to this sink to not skip over the failed one
+								// but let the rest of the sinks do their job
+								tracing::error!(
+									"Sink #{sink_idx} failed to send an entry, skipping the rest of the entries for it: {e}"
+								);
+
+								if let Some(dead_letters) = self.dead_letters.as_mut() {
+									save_dead_letter(&mut **dead_letters, sink_idx, entry, &e)
+										.await;
+								}
+
+								progress.unsent_ids.extend(
+									entries
+										.iter()
+										.rev()
+										.skip(i)
+										.filter_map(|ent| ent.id.clone()),
+								);
+								sink_errors.push(e);
+								break;
+							}
+						};
+
+						metrics::counter!("fetcher_entries_sent_total", labels).increment(1);
 
 						if let Some(entry_id) = entry.id.as_ref() {
-							mark_entry_as_read(
-								entry_id,
-								msg_id,
-								self.source.as_mut(),
-								self.entry_to_msg_map.as_mut(),
-							)
-							.await?;
+							progress.sent(entry_id, sink_idx + 1 == sink_count);
+
+							if let Some((sent_msg, map)) =
+								sent_msg.zip(self.entry_to_msg_map.as_mut())
+							{
+								tracing::debug!(
+									"Associating entry {entry_id:?} with message {:?} of sink #{sink_idx}",
+									sent_msg.id
+								);
+								map.insert_sent(sink_idx, entry_id.clone(), sent_msg)
+									.await?;
+							}
 						}
 					}
+
+					sink_idx += 1;
 				}
 			}
 		}
 
+		// report the first error. The rest have already been logged
+		match sink_errors.into_iter().next() {
+			Some(e) => Err(e.into()),
+			None => Ok(()),
+		}
+	}
+}
+
+/// Which entries have been sent during a run, to mark them as read after it, even if it has stopped early because of an error
+#[derive(Default, Debug)]
+struct SendProgress {
+	/// ids of all sent entries, oldest first. They are marked as read only after all sinks have had a go at them
+	sent_ids: Vec<EntryId>,
+
+	/// ids of entries that a sink failed to send or skipped because of that. They shouldn't be marked as read by the other sinks
+	unsent_ids: HashSet<EntryId>,
+
+	/// ids of entries that haven't been through every sink yet, e.g. because the run has stopped before the last one.
+	/// They aren't marked as read either
+	in_flight: HashSet<EntryId>,
+}
+
+impl SendProgress {
+	/// Record that the entry `entry_id` has been sent by a sink, the `last_sink` of the task if true
+	fn sent(&mut self, entry_id: &EntryId, last_sink: bool) {
+		if !self.sent_ids.contains(entry_id) {
+			self.sent_ids.push(entry_id.clone());
+		}
+
+		if last_sink {
+			self.in_flight.remove(entry_id);
+		}
+	}
+
+	/// Mark the sent entries as read, oldest first.
+	///
+	/// Marking an entry as read might also mark every older entry as read, e.g. with the newer read filter,
+	/// so stop at the first entry some sink hasn't sent to retry it and all newer ones on the next run
+	async fn mark_as_read(&self, source: &mut dyn Source) -> Result<(), FetcherError> {
+		for entry_id in &self.sent_ids {
+			if self.unsent_ids.contains(entry_id) || self.in_flight.contains(entry_id) {
+				tracing::debug!(
+					"Entry {entry_id:?} hasn't been sent by some sink, not marking it and any newer entries as read"
+				);
+				break;
+			}
+
+			tracing::debug!("Marking {entry_id:?} as read");
+			source.mark_as_read(entry_id).await?;
+		}
+
 		Ok(())
 	}
 }
@@ -127,10 +331,12 @@ impl Task {
 #[tracing::instrument(level = "trace", skip_all, fields(entry_id = ?entry.id))]
 async fn send_entry(
 	sink: &dyn Sink,
+	sink_idx: usize,
 	mut entry_to_msg_map: Option<&mut EntryToMsgMap>,
 	tag: Option<&str>,
+	update_in_place: bool,
 	entry: &Entry,
-) -> Result<Option<MessageId>, FetcherError> {
+) -> Result<Option<SentMessage>, SinkError> {
 	tracing::trace!("Sending entry");
 
 	// send message if it isn't empty or raw_contents of they aren't
@@ -138,48 +344,142 @@ async fn send_entry(
 		return Ok(None);
 	}
 
-	let msg = if entry.msg.is_empty() {
-		Cow::Owned(Message {
-			body: Some(
-				entry
-					.raw_contents
-					.clone()
-					.expect("raw_contents should be some because of the early return check"),
-			),
-			..entry.msg.clone()
-		})
-	} else {
-		Cow::Borrowed(&entry.msg)
-	};
+	let msg = msg_to_send(entry);
+	let hash = msg.content_hash();
+
+	if let Some(sent_msg) = entry_to_msg_map.as_ref().and_then(|map| {
+		entry
+			.id
+			.as_ref()
+			.and_then(|eid| map.get_sent(sink_idx, eid))
+	}) {
+		// the entry hasn't been marked as read because some other sink failed to send it,
+		// so don't send it again to the sinks that already have
+		if !update_in_place {
+			tracing::debug!(
+				"Entry has already been sent as message {:?}, skipping",
+				sent_msg.id
+			);
+			return Ok(Some(*sent_msg));
+		}
+
+		if sent_msg.hash == Some(hash) {
+			tracing::debug!(
+				"Message {:?} is already up to date, not editing it",
+				sent_msg.id
+			);
+			return Ok(Some(*sent_msg));
+		}
+
+		tracing::debug!("Editing already sent message {:?} to {msg:?}", sent_msg.id);
+
+		if let Some(id) = sink.edit(&sent_msg.id, &msg, tag).await? {
+			return Ok(Some(SentMessage {
+				id,
+				hash: Some(hash),
+			}));
+		}
+
+		tracing::debug!(
+			"Message {:?} can't be edited, sending a new one instead",
+			sent_msg.id
+		);
+	}
 
 	let reply_to = entry_to_msg_map
 		.as_mut()
-		.and_then(|map| map.get_if_exists(entry.reply_to.as_ref()));
+		.and_then(|map| map.get_if_exists(sink_idx, entry.reply_to.as_ref()));
 
 	tracing::debug!("Sending {msg:?} to a sink with tag {tag:?}, replying to {reply_to:?}");
-	Ok(sink.send(&msg, reply_to, tag).await?)
+	let id = sink.send(&msg, reply_to, tag).await?;
+
+	Ok(id.map(|id| SentMessage {
+		id,
+		hash: Some(hash),
+	}))
+}
+
+/// The message of the `entry`, or its raw contents as the body if it's empty
+fn msg_to_send(entry: &Entry) -> Cow<'_, Message> {
+	match &entry.raw_contents {
+		Some(raw_contents) if entry.msg.is_empty() => Cow::Owned(Message {
+			body: Some(raw_contents.clone()),
+			..entry.msg.clone()
+		}),
+		_ => Cow::Borrowed(&entry.msg),
+	}
+}
+
+/// Save the message of the `entry` that the sink #`sink_idx` has failed to send with `error`.
+/// Errors are only logged since the entry is going to be retried on the next run anyways
+async fn save_dead_letter(
+	dead_letters: &mut dyn SaveDeadLetter,
+	sink_idx: usize,
+	entry: &Entry,
+	error: &SinkError,
+) {
+	let letter = DeadLetter {
+		sink_idx,
+		entry_id: entry.id.as_ref(),
+		msg: &msg_to_send(entry),
+		error,
+	};
+
+	if let Err(e) = dead_letters.save_dead_letter(&letter).await {
+		tracing::error!("Failed to save the message that couldn't be sent: {e}");
+	}
 }
 
-async fn mark_entry_as_read(
-	entry_id: &EntryId,
-	msg_id: Option<MessageId>,
-	// source: Option<&mut dyn Source>, // TODO: this doesn't work. Why?
-	source: Option<&mut Box<dyn Source>>,
-	entry_to_msg_map: Option<&mut EntryToMsgMap>,
+/// Delete the messages of all entries in the `entry_to_msg_map` of the sink #`sink_idx` that aren't in `present_ids` anymore
+async fn delete_removed(
+	sink: &dyn Sink,
+	sink_idx: usize,
+	entry_to_msg_map: &mut EntryToMsgMap,
+	present_ids: &HashSet<EntryId>,
 ) -> Result<(), FetcherError> {
-	if let Some(mar) = source {
-		tracing::debug!("Marking {entry_id:?} as read");
-		mar.mark_as_read(entry_id).await?;
+	// an empty fetch is much more likely to be a hiccup of the source than every single entry being removed at once
+	if present_ids.is_empty() {
+		tracing::debug!("No entries are present, not deleting anything");
+		return Ok(());
 	}
 
-	if let Some((msgid, map)) = msg_id.zip(entry_to_msg_map) {
-		tracing::debug!("Associating entry {entry_id:?} with message {msgid:?}");
-		map.insert(entry_id.clone(), msgid).await?;
+	let removed = entry_to_msg_map
+		.entry_ids(sink_idx)
+		.filter(|eid| !present_ids.contains(*eid))
+		.filter_map(|eid| Some((eid.clone(), *entry_to_msg_map.get(sink_idx, eid)?)))
+		.collect::<Vec<_>>();
+
+	for (entry_id, msg_id) in removed {
+		tracing::info!(
+			"Entry {entry_id:?} has been removed from the source, deleting message {msg_id:?} of sink #{sink_idx}"
+		);
+
+		// forget about the message only after it has been deleted successfully to retry next time if it wasn't
+		sink.delete(&msg_id).await?;
+		entry_to_msg_map.remove(sink_idx, &entry_id).await?;
 	}
 
 	Ok(())
 }
 
+/// Fail if there are more `entries` to send than `max_entries` to not flood the sinks
+fn check_max_entries(
+	entries: &[Entry],
+	max_entries: Option<usize>,
+) -> Result<(), TooManyEntriesError> {
+	match max_entries {
+		Some(max) if entries.len() > max => Err(TooManyEntriesError {
+			count: entries.len(),
+			max,
+		}),
+		_ => Ok(()),
+	}
+}
+
+fn entry_ids(entries: &[Entry]) -> HashSet<EntryId> {
+	entries.iter().filter_map(|ent| ent.id.clone()).collect()
+}
+
 fn remove_duplicates(entries: Vec<Entry>) -> Vec<Entry> {
 	let num_og_entries = entries.len();
 
@@ -200,7 +500,7 @@ fn remove_duplicates(entries: Vec<Entry>) -> Vec<Entry> {
 
 	let num_removed = num_og_entries - uniq.len();
 	if num_removed > 0 {
-		tracing::trace!("Removed {} duplicate entries", num_removed);
+		tracing::info!("Removed {num_removed} duplicate entries before sending");
 	}
 
 	uniq
@@ -0,0 +1,240 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains [`TaskBuilder`] that makes it easier to create a [`Task`] in code
+
+use std::time::Duration;
+
+use super::{Task, dead_letters::SaveDeadLetter, entry_to_msg_map::EntryToMsgMap};
+use crate::{
+	action::{Action, filter::Filter, transform::Transform},
+	job::timepoint::TimePoint,
+	sink::Sink,
+	source::Source,
+};
+
+/// A builder of a [`Task`]. Actions, including sinks, are run in the order they were added in.
+///
+/// Create one with [`Task::builder()`]
+#[derive(Default, Debug)]
+pub struct TaskBuilder {
+	name: Option<String>,
+	tag: Option<String>,
+	source: Option<Box<dyn Source>>,
+	actions: Vec<Action>,
+	entry_to_msg_map: Option<EntryToMsgMap>,
+	update_in_place: bool,
+	delete_removed: bool,
+	timeout: Option<Duration>,
+	max_entries: Option<usize>,
+	dead_letters: Option<Box<dyn SaveDeadLetter>>,
+	refresh_time: Option<TimePoint>,
+}
+
+#[expect(missing_docs, reason = "error message is self-documenting")]
+#[derive(thiserror::Error, Debug)]
+pub enum TaskBuilderError {
+	#[error("The task has neither a source nor any actions and thus would do nothing")]
+	Empty,
+
+	#[error("update_in_place requires an entry to message map to find the messages to edit")]
+	UpdateInPlaceWithoutEntryToMsgMap,
+
+	#[error("delete_removed requires an entry to message map to find the messages to delete")]
+	DeleteRemovedWithoutEntryToMsgMap,
+}
+
+impl Task {
+	/// Create a [`TaskBuilder`] to set up a new [`Task`] step by step
+	#[must_use]
+	pub fn builder() -> TaskBuilder {
+		TaskBuilder::default()
+	}
+}
+
+impl TaskBuilder {
+	/// Set the [`name`](`Task::name`) of the task
+	#[must_use]
+	pub fn name(mut self, name: impl Into<String>) -> Self {
+		self.name = Some(name.into());
+		self
+	}
+
+	/// Set the [`tag`](`Task::tag`) of the task
+	#[must_use]
+	pub fn tag(mut self, tag: impl Into<String>) -> Self {
+		self.tag = Some(tag.into());
+		self
+	}
+
+	/// Set the [`source`](`Task::source`) to fetch entries from
+	#[must_use]
+	pub fn source<S>(mut self, source: S) -> Self
+	where
+		S: Source + 'static,
+	{
+		self.source = Some(Box::new(source));
+		self
+	}
+
+	/// Add an action to run after all the ones that have already been added
+	#[must_use]
+	pub fn action(mut self, action: impl Into<Action>) -> Self {
+		self.actions.push(action.into());
+		self
+	}
+
+	/// Add a filter, same as [`action()`](`TaskBuilder::action`)
+	#[must_use]
+	pub fn filter<F>(self, filter: F) -> Self
+	where
+		F: Filter + 'static,
+	{
+		self.action(Action::Filter(Box::new(filter)))
+	}
+
+	/// Add a transform, same as [`action()`](`TaskBuilder::action`)
+	#[must_use]
+	pub fn transform<T>(self, transform: T) -> Self
+	where
+		T: Transform + 'static,
+	{
+		self.action(Action::Transform(Box::new(transform)))
+	}
+
+	/// Add a sink to send the entries to, same as [`action()`](`TaskBuilder::action`)
+	#[must_use]
+	pub fn sink<S>(self, sink: S) -> Self
+	where
+		S: Sink + 'static,
+	{
+		self.action(Action::Sink(Box::new(sink)))
+	}
+
+	/// Set the [`entry_to_msg_map`](`Task::entry_to_msg_map`)
+	#[must_use]
+	pub fn entry_to_msg_map(mut self, entry_to_msg_map: EntryToMsgMap) -> Self {
+		self.entry_to_msg_map = Some(entry_to_msg_map);
+		self
+	}
+
+	/// Set [`update_in_place`](`Task::update_in_place`). Requires an [`entry_to_msg_map`](`TaskBuilder::entry_to_msg_map`)
+	#[must_use]
+	pub fn update_in_place(mut self, update_in_place: bool) -> Self {
+		self.update_in_place = update_in_place;
+		self
+	}
+
+	/// Set [`delete_removed`](`Task::delete_removed`). Requires an [`entry_to_msg_map`](`TaskBuilder::entry_to_msg_map`)
+	#[must_use]
+	pub fn delete_removed(mut self, delete_removed: bool) -> Self {
+		self.delete_removed = delete_removed;
+		self
+	}
+
+	/// Set the [`timeout`](`Task::timeout`) of a single run
+	#[must_use]
+	pub fn timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = Some(timeout);
+		self
+	}
+
+	/// Set the [`max_entries`](`Task::max_entries`) a single run is allowed to send
+	#[must_use]
+	pub fn max_entries(mut self, max_entries: usize) -> Self {
+		self.max_entries = Some(max_entries);
+		self
+	}
+
+	/// Set where to save [`dead_letters`](`Task::dead_letters`)
+	#[must_use]
+	pub fn dead_letters<D>(mut self, dead_letters: D) -> Self
+	where
+		D: SaveDeadLetter + 'static,
+	{
+		self.dead_letters = Some(Box::new(dead_letters));
+		self
+	}
+
+	/// Set the [`refresh_time`](`Task::refresh_time`) to run the task on instead of the one of its job
+	#[must_use]
+	pub fn refresh_time(mut self, refresh_time: TimePoint) -> Self {
+		self.refresh_time = Some(refresh_time);
+		self
+	}
+
+	/// Create the [`Task`]
+	///
+	/// # Errors
+	/// * if there's neither a source nor any actions
+	/// * if [`update_in_place`](`TaskBuilder::update_in_place`) or [`delete_removed`](`TaskBuilder::delete_removed`) is set without an [`entry_to_msg_map`](`TaskBuilder::entry_to_msg_map`)
+	pub fn build(self) -> Result<Task, TaskBuilderError> {
+		if self.source.is_none() && self.actions.is_empty() {
+			return Err(TaskBuilderError::Empty);
+		}
+
+		if self.entry_to_msg_map.is_none() {
+			if self.update_in_place {
+				return Err(TaskBuilderError::UpdateInPlaceWithoutEntryToMsgMap);
+			}
+
+			if self.delete_removed {
+				return Err(TaskBuilderError::DeleteRemovedWithoutEntryToMsgMap);
+			}
+		}
+
+		Ok(Task {
+			name: self.name,
+			tag: self.tag,
+			source: self.source,
+			actions: (!self.actions.is_empty()).then_some(self.actions),
+			entry_to_msg_map: self.entry_to_msg_map,
+			update_in_place: self.update_in_place,
+			delete_removed: self.delete_removed,
+			timeout: self.timeout,
+			max_entries: self.max_entries,
+			dead_letters: self.dead_letters,
+			refresh_time: self.refresh_time,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+	use super::*;
+	use crate::sink::Stdout;
+
+	#[test]
+	fn build() {
+		let task = Task::builder()
+			.name("task")
+			.tag("tag")
+			.sink(Stdout::default())
+			.build()
+			.unwrap();
+
+		assert_eq!(task.name.as_deref(), Some("task"));
+		assert_eq!(task.tag.as_deref(), Some("tag"));
+		assert!(matches!(task.actions.as_deref(), Some([Action::Sink(_)])));
+	}
+
+	#[test]
+	fn invalid() {
+		assert!(matches!(
+			Task::builder().build(),
+			Err(TaskBuilderError::Empty)
+		));
+
+		assert!(matches!(
+			Task::builder()
+				.sink(Stdout::default())
+				.update_in_place(true)
+				.build(),
+			Err(TaskBuilderError::UpdateInPlaceWithoutEntryToMsgMap)
+		));
+	}
+}
@@ -0,0 +1,42 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains [`DeadLetter`] and the [`SaveDeadLetter`] trait
+
+use async_trait::async_trait;
+use std::fmt::Debug;
+
+use crate::{
+	entry::EntryId,
+	external_save::ExternalSaveError,
+	sink::{error::SinkError, message::Message},
+};
+
+/// A message that a sink has failed to send
+#[derive(Debug)]
+pub struct DeadLetter<'a> {
+	/// Index of the sink that failed to send the message, in order of appearance in the task
+	pub sink_idx: usize,
+
+	/// Id of the entry the message was made from, if it has one
+	pub entry_id: Option<&'a EntryId>,
+
+	/// The message that failed to be sent
+	pub msg: &'a Message,
+
+	/// The error the sink failed with
+	pub error: &'a SinkError,
+}
+
+/// A place to save messages that failed to be sent to, to be able to inspect or resend them later
+#[async_trait]
+pub trait SaveDeadLetter: Debug + Send + Sync {
+	/// Save the `letter` externally
+	///
+	/// # Errors
+	/// It may return an error if there has been issues saving, e.g. writing to disk
+	async fn save_dead_letter(&mut self, letter: &DeadLetter<'_>) -> Result<(), ExternalSaveError>;
+}
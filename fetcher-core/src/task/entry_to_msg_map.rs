@@ -9,20 +9,36 @@
 use std::collections::HashMap;
 
 use crate::{
-	entry::EntryId, error::FetcherError, external_save::ExternalSave, sink::message::MessageId,
+	entry::EntryId,
+	error::FetcherError,
+	external_save::ExternalSave,
+	sink::message::{ContentHash, MessageId},
 };
 
-/// Map [`entries`][entry] to [`messages`][message]
+/// Map [`entries`][entry] to [`messages`][message].
+///
+/// Every sink of a task has its own map, indexed by the order the sinks appear in the task
 ///
 /// [entry]: crate::entry::Entry
 /// [message]: crate::sink::message::Message
 #[derive(Default, Debug)]
 pub struct EntryToMsgMap {
 	/// External save location for that map.
-	/// It's called every time on [`Self::insert()`]
+	/// It's called every time on [`Self::insert()`] and [`Self::remove()`]
 	pub external_save: Option<Box<dyn ExternalSave>>,
 
-	map: HashMap<EntryId, MessageId>,
+	maps: Vec<HashMap<EntryId, SentMessage>>,
+}
+
+/// A message an entry has been sent as
+#[derive(Clone, Copy, Debug)]
+pub struct SentMessage {
+	/// Id of the message
+	pub id: MessageId,
+
+	/// Hash of the contents of the message when it was last sent or edited. Used to edit it only if they have changed since.
+	/// None if it's unknown, e.g. for messages saved before the hashes have been kept
+	pub hash: Option<ContentHash>,
 }
 
 impl EntryToMsgMap {
@@ -35,31 +51,70 @@ impl EntryToMsgMap {
 	{
 		Self {
 			external_save: Some(Box::new(external_save)),
-			map: HashMap::new(),
+			maps: Vec::new(),
 		}
 	}
 
-	/// Create a new [`EntryToMsgMap`] with the provided `map` and `external_save` parameters
+	/// Create a new [`EntryToMsgMap`] with the provided `map` of the first sink and `external_save` parameters
 	#[must_use]
-	pub fn new_with_map<E>(map: HashMap<EntryId, MessageId>, external_save: E) -> Self
+	pub fn new_with_map<E>(map: HashMap<EntryId, SentMessage>, external_save: E) -> Self
+	where
+		E: ExternalSave + 'static,
+	{
+		Self::new_with_maps(vec![map], external_save)
+	}
+
+	/// Create a new [`EntryToMsgMap`] with the provided `maps` of each sink and `external_save` parameters
+	#[must_use]
+	pub fn new_with_maps<E>(maps: Vec<HashMap<EntryId, SentMessage>>, external_save: E) -> Self
 	where
 		E: ExternalSave + 'static,
 	{
 		Self {
 			external_save: Some(Box::new(external_save)),
-			map,
+			maps,
 		}
 	}
 
-	/// Insert a mapping from [`EntryId`] `eid` to [`MessageId`] `msgid` and save that externally
+	/// Insert a mapping from [`EntryId`] `eid` to [`MessageId`] `msgid` of the sink #`sink`, whose contents are unknown, and save that externally
+	///
+	/// # Errors
+	/// if external save has failed
+	pub async fn insert(
+		&mut self,
+		sink: usize,
+		eid: EntryId,
+		msgid: MessageId,
+	) -> Result<(), FetcherError> {
+		self.insert_sent(
+			sink,
+			eid,
+			SentMessage {
+				id: msgid,
+				hash: None,
+			},
+		)
+		.await
+	}
+
+	/// Insert a mapping from [`EntryId`] `eid` to the [`SentMessage`] `msg` of the sink #`sink` and save that externally
 	///
 	/// # Errors
 	/// if external save has failed
-	pub async fn insert(&mut self, eid: EntryId, msgid: MessageId) -> Result<(), FetcherError> {
-		self.map.insert(eid, msgid);
+	pub async fn insert_sent(
+		&mut self,
+		sink: usize,
+		eid: EntryId,
+		msg: SentMessage,
+	) -> Result<(), FetcherError> {
+		if self.maps.len() <= sink {
+			self.maps.resize_with(sink + 1, HashMap::new);
+		}
+
+		self.maps[sink].insert(eid, msg);
 		if let Some(ext_save) = &mut self.external_save {
 			ext_save
-				.save_entry_to_msg_map(&self.map)
+				.save_entry_to_msg_map(&self.maps)
 				.await
 				.map_err(FetcherError::ExternalSave)?;
 		}
@@ -67,15 +122,49 @@ impl EntryToMsgMap {
 		Ok(())
 	}
 
-	/// Get the [`MessageId`] corresponding to the provided [`EntryId`]
+	/// Remove the mapping of [`EntryId`] `eid` of the sink #`sink` and save that externally
+	///
+	/// # Errors
+	/// if external save has failed
+	pub async fn remove(
+		&mut self,
+		sink: usize,
+		eid: &EntryId,
+	) -> Result<Option<MessageId>, FetcherError> {
+		let Some(msg) = self.maps.get_mut(sink).and_then(|map| map.remove(eid)) else {
+			return Ok(None);
+		};
+
+		if let Some(ext_save) = &mut self.external_save {
+			ext_save
+				.save_entry_to_msg_map(&self.maps)
+				.await
+				.map_err(FetcherError::ExternalSave)?;
+		}
+
+		Ok(Some(msg.id))
+	}
+
+	/// Iterate over all [`EntryId`]s that have a message of the sink #`sink` associated with them
+	pub fn entry_ids(&self, sink: usize) -> impl Iterator<Item = &EntryId> {
+		self.maps.get(sink).into_iter().flat_map(HashMap::keys)
+	}
+
+	/// Get the [`MessageId`] of the sink #`sink` corresponding to the provided [`EntryId`]
+	#[must_use]
+	pub fn get(&self, sink: usize, eid: &EntryId) -> Option<&MessageId> {
+		self.get_sent(sink, eid).map(|msg| &msg.id)
+	}
+
+	/// Get the [`SentMessage`] of the sink #`sink` corresponding to the provided [`EntryId`], together with the hash of its contents
 	#[must_use]
-	pub fn get(&self, eid: &EntryId) -> Option<&MessageId> {
-		self.map.get(eid)
+	pub fn get_sent(&self, sink: usize, eid: &EntryId) -> Option<&SentMessage> {
+		self.maps.get(sink).and_then(|map| map.get(eid))
 	}
 
-	/// Get the [`MessageId`] corresponding to the provided [`EntryId`] if it exists
+	/// Get the [`MessageId`] of the sink #`sink` corresponding to the provided [`EntryId`] if it exists
 	#[must_use]
-	pub fn get_if_exists(&self, eid: Option<&EntryId>) -> Option<&MessageId> {
-		eid.and_then(|eid| self.map.get(eid))
+	pub fn get_if_exists(&self, sink: usize, eid: Option<&EntryId>) -> Option<&MessageId> {
+		eid.and_then(|eid| self.get(sink, eid))
 	}
 }
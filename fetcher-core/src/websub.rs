@@ -0,0 +1,277 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! This module contains [`Hub`] to subscribe to updates of a feed through [WebSub](https://www.w3.org/TR/websub/) instead of polling it
+//!
+//! It also contains helpers to authenticate the notifications the hub sends
+
+use hmac::{Hmac, Mac};
+use rand::{Rng, distributions::Alphanumeric};
+use reqwest::header::LINK;
+use sha1::Sha1;
+use sha2::{Sha256, Sha384, Sha512};
+use url::Url;
+
+use crate::source::http::{self, HttpError};
+
+/// How many characters long the generated secrets are
+const SECRET_LEN: usize = 32;
+
+/// A hub that a feed advertises to be subscribed to through
+#[derive(Clone, Debug)]
+pub struct Hub {
+	/// The URL of the hub itself
+	pub url: Url,
+
+	/// The URL of the feed that the hub knows it as, i.e. its "self" link.
+	/// It may be different from the URL the feed has been fetched from
+	pub topic: Url,
+}
+
+#[expect(missing_docs, reason = "error message is self-documenting")]
+#[derive(thiserror::Error, Debug)]
+pub enum WebSubError {
+	#[error(transparent)]
+	Http(#[from] HttpError),
+
+	#[error("Can't parse the feed at {1:?} to find its hub")]
+	BadFeed(#[source] feed_rs::parser::ParseFeedError, String),
+}
+
+impl Hub {
+	/// Fetch the `feed` and find the hub it advertises, either in the `Link` headers of the response or in the links of the feed itself.
+	/// Returns [`None`] if the feed doesn't have a hub
+	///
+	/// # Errors
+	/// * if the feed couldn't be fetched
+	/// * if the feed doesn't advertise a hub in the headers and couldn't be parsed
+	pub async fn discover(feed: &Url) -> Result<Option<Self>, WebSubError> {
+		tracing::debug!("Looking for a WebSub hub of {feed}");
+
+		let client = http::client()?;
+		let response = client
+			.get(feed.as_str())
+			.send()
+			.await
+			.map_err(|e| HttpError::BadRequest(e, feed.to_string()))?;
+
+		let status = response.status();
+		let (mut hub, mut topic) = (None, None);
+
+		for header in response.headers().get_all(LINK) {
+			let Ok(header) = header.to_str() else {
+				continue;
+			};
+
+			for (url, rels) in parse_link_header(header) {
+				let Ok(url) = feed.join(url) else {
+					continue;
+				};
+
+				if hub.is_none() && rels.contains(&"hub") {
+					hub = Some(url.clone());
+				}
+
+				if topic.is_none() && rels.contains(&"self") {
+					topic = Some(url);
+				}
+			}
+		}
+
+		let body = response
+			.text()
+			.await
+			.map_err(|e| HttpError::BadRequest(e, feed.to_string()))?;
+
+		if !status.is_success() {
+			return Err(HttpError::BadStatus {
+				url: feed.to_string(),
+				status,
+				body: http::truncate_body(body),
+			}
+			.into());
+		}
+
+		// the headers take precedence over the feed
+		if hub.is_none() || topic.is_none() {
+			match feed_rs::parser::parse(body.as_bytes()) {
+				Ok(parsed) => {
+					for link in parsed.links {
+						let Ok(url) = feed.join(&link.href) else {
+							continue;
+						};
+
+						match link.rel.as_deref() {
+							Some("hub") if hub.is_none() => hub = Some(url),
+							Some("self") if topic.is_none() => topic = Some(url),
+							_ => (),
+						}
+					}
+				}
+				Err(e) if hub.is_none() => return Err(WebSubError::BadFeed(e, feed.to_string())),
+				Err(_) => (),
+			}
+		}
+
+		Ok(hub.map(|url| Self {
+			url,
+			topic: topic.unwrap_or_else(|| feed.clone()),
+		}))
+	}
+
+	/// Ask the hub to send notifications about updates of [`Self::topic`] to the `callback`, signed with the `secret`.
+	///
+	/// The hub then verifies the subscription by sending a GET request to the `callback`
+	/// that should be answered with the `hub.challenge` query parameter of it.
+	/// The subscription expires after the `hub.lease_seconds` query parameter of that request and should be renewed before that
+	///
+	/// # Errors
+	/// if the hub couldn't be reached or has refused the subscription
+	pub async fn subscribe(&self, callback: &Url, secret: &str) -> Result<(), WebSubError> {
+		tracing::debug!(
+			"Subscribing to {} through the WebSub hub {}",
+			self.topic,
+			self.url
+		);
+
+		let client = http::client()?;
+		let response = client
+			.post(self.url.as_str())
+			.form(&[
+				("hub.mode", "subscribe"),
+				("hub.topic", self.topic.as_str()),
+				("hub.callback", callback.as_str()),
+				("hub.secret", secret),
+			])
+			.send()
+			.await
+			.map_err(|e| HttpError::BadRequest(e, self.url.to_string()))?;
+
+		let status = response.status();
+		if !status.is_success() {
+			let body = response.text().await.unwrap_or_default();
+
+			return Err(HttpError::BadStatus {
+				url: self.url.to_string(),
+				status,
+				body: http::truncate_body(body),
+			}
+			.into());
+		}
+
+		Ok(())
+	}
+}
+
+/// Generate a random secret to subscribe to a hub with
+#[must_use]
+pub fn generate_secret() -> String {
+	rand::thread_rng()
+		.sample_iter(&Alphanumeric)
+		.take(SECRET_LEN)
+		.map(char::from)
+		.collect()
+}
+
+/// Check that the `signature` in the `X-Hub-Signature` header of a notification, e.g. `sha256=<hex digest>`,
+/// is the HMAC of the `body` with the `secret` the subscription has been made with
+#[must_use]
+pub fn verify_signature(secret: &str, signature: &str, body: &[u8]) -> bool {
+	/// Check the `signature` with the HMAC `M`, in constant time
+	fn verify<M: Mac + hmac::digest::KeyInit>(secret: &str, signature: &[u8], body: &[u8]) -> bool {
+		let Ok(mut mac) = <M as hmac::digest::KeyInit>::new_from_slice(secret.as_bytes()) else {
+			return false;
+		};
+
+		mac.update(body);
+		mac.verify_slice(signature).is_ok()
+	}
+
+	let Some((method, digest)) = signature.trim().split_once('=') else {
+		return false;
+	};
+
+	let Some(digest) = decode_hex(digest) else {
+		return false;
+	};
+
+	match method {
+		"sha1" => verify::<Hmac<Sha1>>(secret, &digest, body),
+		"sha256" => verify::<Hmac<Sha256>>(secret, &digest, body),
+		"sha384" => verify::<Hmac<Sha384>>(secret, &digest, body),
+		"sha512" => verify::<Hmac<Sha512>>(secret, &digest, body),
+		_ => false,
+	}
+}
+
+/// Parse a `Link` header into the URLs and their relations, e.g. `<https://hub.example.com/>; rel="hub"`
+fn parse_link_header(header: &str) -> impl Iterator<Item = (&str, Vec<&str>)> {
+	header.split(',').filter_map(|link| {
+		let mut parts = link.split(';');
+
+		let url = parts.next()?.trim().strip_prefix('<')?.strip_suffix('>')?;
+
+		let rels = parts
+			.filter_map(|param| param.trim().strip_prefix("rel="))
+			.flat_map(|rel| rel.trim_matches('"').split_whitespace())
+			.collect();
+
+		Some((url, rels))
+	})
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+	if !s.len().is_multiple_of(2) {
+		return None;
+	}
+
+	(0..s.len())
+		.step_by(2)
+		.map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn link_header() {
+		let links = parse_link_header(
+			r#"<https://hub.example.com/>; rel="hub", <https://example.com/feed.xml>; rel=self"#,
+		)
+		.collect::<Vec<_>>();
+
+		assert_eq!(
+			links,
+			[
+				("https://hub.example.com/", vec!["hub"]),
+				("https://example.com/feed.xml", vec!["self"]),
+			]
+		);
+	}
+
+	#[test]
+	fn signature() {
+		// HMAC-SHA256 of "body" with the key "secret"
+		let signature = "sha256=dc46983557fea127b43af721467eb9b3fde2338fe3e14f51952aa8478c13d355";
+
+		assert!(verify_signature("secret", signature, b"body"));
+		assert!(
+			!verify_signature("secret", signature, b"other body"),
+			"the body has been tampered with"
+		);
+		assert!(
+			!verify_signature("other secret", signature, b"body"),
+			"wrong secret"
+		);
+		assert!(!verify_signature("secret", "sha256=zz", b"body"), "not hex");
+		assert!(
+			!verify_signature("secret", "md5=dc46", b"body"),
+			"unsupported method"
+		);
+	}
+}
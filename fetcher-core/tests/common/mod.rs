@@ -0,0 +1,107 @@
+//! Sources and sinks shared between the tests
+
+#![allow(dead_code)] // not every test uses every one of them
+#![allow(clippy::unwrap_used)]
+
+use async_trait::async_trait;
+use fetcher_core::{
+	entry::{Entry, EntryId},
+	error::FetcherError,
+	read_filter::MarkAsRead,
+	sink::{
+		Sink,
+		error::SinkError,
+		message::{Message, MessageId},
+	},
+	source::{Fetch, Source, error::SourceError},
+};
+use std::sync::{
+	Arc, Mutex,
+	atomic::{AtomicUsize, Ordering},
+};
+
+/// Body of every entry created with [`entry()`]
+pub const BODY: &str = "body";
+
+/// Returns the same entries on every fetch and keeps the ids of the ones marked as read
+#[derive(Default, Debug)]
+pub struct DummySource {
+	pub entries: Vec<Entry>,
+	pub read: Arc<Mutex<Vec<EntryId>>>,
+}
+
+/// Fails to send every message
+#[derive(Debug)]
+pub struct FailingSink;
+
+/// Sends every message as the message `msg_id` and counts how many it has sent
+#[derive(Default, Debug)]
+pub struct CountingSink {
+	pub msg_id: Option<i64>,
+	pub sent: Arc<AtomicUsize>,
+}
+
+/// An entry with the id `id` and [`BODY`] as its body
+pub fn entry(id: &str) -> Entry {
+	Entry {
+		id: Some(EntryId(id.to_owned())),
+		msg: Message {
+			body: Some(BODY.to_owned()),
+			..Default::default()
+		},
+		..Default::default()
+	}
+}
+
+impl DummySource {
+	pub fn new(entries: Vec<Entry>) -> Self {
+		Self {
+			entries,
+			read: Arc::default(),
+		}
+	}
+}
+
+#[async_trait]
+impl Fetch for DummySource {
+	async fn fetch(&mut self) -> Result<Vec<Entry>, SourceError> {
+		Ok(self.entries.clone())
+	}
+}
+
+#[async_trait]
+impl MarkAsRead for DummySource {
+	async fn mark_as_read(&mut self, id: &EntryId) -> Result<(), FetcherError> {
+		self.read.lock().unwrap().push(id.clone());
+		Ok(())
+	}
+
+	async fn set_read_only(&mut self) {}
+}
+
+impl Source for DummySource {}
+
+#[async_trait]
+impl Sink for FailingSink {
+	async fn send(
+		&self,
+		_message: &Message,
+		_reply_to: Option<&MessageId>,
+		_tag: Option<&str>,
+	) -> Result<Option<MessageId>, SinkError> {
+		Err(SinkError::Stdout(std::io::Error::other("failed")))
+	}
+}
+
+#[async_trait]
+impl Sink for CountingSink {
+	async fn send(
+		&self,
+		_message: &Message,
+		_reply_to: Option<&MessageId>,
+		_tag: Option<&str>,
+	) -> Result<Option<MessageId>, SinkError> {
+		self.sent.fetch_add(1, Ordering::SeqCst);
+		Ok(self.msg_id.map(MessageId))
+	}
+}
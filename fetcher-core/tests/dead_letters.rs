@@ -0,0 +1,98 @@
+//! This test asserts that a message a sink failed to send is saved as a dead letter
+//! and that its entry isn't marked as read even if some other sink has sent it successfully
+
+#![allow(clippy::missing_assert_message)]
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use self::common::{BODY, CountingSink, DummySource, FailingSink, entry};
+use async_trait::async_trait;
+use fetcher_core::{
+	action::Action,
+	entry::EntryId,
+	error::FetcherError,
+	external_save::ExternalSaveError,
+	task::{
+		Task,
+		dead_letters::{DeadLetter, SaveDeadLetter},
+	},
+};
+use std::sync::{Arc, Mutex};
+
+const ENTRY_ID: &str = "0";
+
+/// Sink index, entry id, and message body of every saved dead letter
+type Saved = Arc<Mutex<Vec<(usize, Option<EntryId>, Option<String>)>>>;
+
+#[derive(Debug)]
+struct DeadLetters {
+	saved: Saved,
+}
+
+#[async_trait]
+impl SaveDeadLetter for DeadLetters {
+	async fn save_dead_letter(&mut self, letter: &DeadLetter<'_>) -> Result<(), ExternalSaveError> {
+		self.saved.lock().unwrap().push((
+			letter.sink_idx,
+			letter.entry_id.cloned(),
+			letter.msg.body.clone(),
+		));
+		Ok(())
+	}
+}
+
+/// Run a task with the `sinks` and return the ids of the entries marked as read and the saved dead letters
+async fn run(sinks: Vec<Action>) -> (Vec<EntryId>, Vec<(usize, Option<EntryId>, Option<String>)>) {
+	let source = DummySource::new(vec![entry(ENTRY_ID)]);
+	let read = Arc::clone(&source.read);
+	let saved = Arc::new(Mutex::new(Vec::new()));
+
+	let mut task = sinks
+		.into_iter()
+		.fold(Task::builder().source(source), |task, sink| {
+			task.action(sink)
+		})
+		.dead_letters(DeadLetters {
+			saved: Arc::clone(&saved),
+		})
+		.build()
+		.unwrap();
+
+	assert!(matches!(task.run().await, Err(FetcherError::Sink(_))));
+
+	let read = read.lock().unwrap().clone();
+	let saved = saved.lock().unwrap().clone();
+	(read, saved)
+}
+
+#[tokio::test]
+async fn dead_letters() {
+	let (read, saved) = run(vec![
+		Action::Sink(Box::new(FailingSink)),
+		Action::Sink(Box::new(CountingSink::default())),
+	])
+	.await;
+
+	assert!(read.is_empty());
+	assert_eq!(
+		saved,
+		[(0, Some(EntryId(ENTRY_ID.into())), Some(BODY.to_owned()))]
+	);
+}
+
+#[tokio::test]
+async fn dead_letters_ok_sink_first() {
+	let (read, saved) = run(vec![
+		Action::Sink(Box::new(CountingSink::default())),
+		Action::Sink(Box::new(FailingSink)),
+	])
+	.await;
+
+	assert!(read.is_empty());
+	assert_eq!(
+		saved,
+		[(1, Some(EntryId(ENTRY_ID.into())), Some(BODY.to_owned()))]
+	);
+}
@@ -0,0 +1,157 @@
+//! This test asserts that the message of an entry that has been removed from the source is deleted
+//! and that nothing is deleted if the source returned no entries at all or the entry has only been filtered out
+
+#![allow(clippy::missing_assert_message)]
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::unwrap_used)]
+
+use async_trait::async_trait;
+use fetcher_core::{
+	action::{
+		Action,
+		filter::{
+			Take,
+			take::{TakeAmount, TakeFrom},
+		},
+	},
+	entry::{Entry, EntryId},
+	error::FetcherError,
+	read_filter::MarkAsRead,
+	sink::{
+		Sink,
+		error::SinkError,
+		message::{Message, MessageId},
+	},
+	source::{Fetch, Source, error::SourceError},
+	task::{Task, entry_to_msg_map::EntryToMsgMap},
+};
+use std::sync::{Arc, Mutex};
+
+/// Returns the next list of entry ids on every fetch
+#[derive(Debug)]
+struct DummySource(Vec<Vec<&'static str>>);
+
+/// Sends messages with the entry body as its id and remembers all deleted messages
+#[derive(Debug)]
+struct DeletingSink {
+	deleted: Arc<Mutex<Vec<i64>>>,
+}
+
+#[async_trait]
+impl Fetch for DummySource {
+	async fn fetch(&mut self) -> Result<Vec<Entry>, SourceError> {
+		Ok(self
+			.0
+			.remove(0)
+			.into_iter()
+			.map(|id| Entry {
+				id: Some(EntryId(id.into())),
+				msg: Message {
+					body: Some(id.to_owned()),
+					..Default::default()
+				},
+				..Default::default()
+			})
+			.collect())
+	}
+}
+
+#[async_trait]
+impl MarkAsRead for DummySource {
+	async fn mark_as_read(&mut self, _id: &EntryId) -> Result<(), FetcherError> {
+		Ok(())
+	}
+
+	async fn set_read_only(&mut self) {}
+}
+
+impl Source for DummySource {}
+
+#[async_trait]
+impl Sink for DeletingSink {
+	async fn send(
+		&self,
+		message: &Message,
+		_reply_to: Option<&MessageId>,
+		_tag: Option<&str>,
+	) -> Result<Option<MessageId>, SinkError> {
+		Ok(Some(MessageId(
+			message.body.as_deref().unwrap().parse().unwrap(),
+		)))
+	}
+
+	async fn delete(&self, msg_id: &MessageId) -> Result<(), SinkError> {
+		self.deleted.lock().unwrap().push(msg_id.0);
+		Ok(())
+	}
+}
+
+/// Run the task once for each of the `fetches`, keeping only the first `take` entries of each one if set
+async fn run_all(fetches: Vec<Vec<&'static str>>, take: Option<usize>) -> (Vec<i64>, Vec<String>) {
+	let deleted = Arc::new(Mutex::new(Vec::new()));
+	let runs = fetches.len();
+
+	let take = take.map(|take| {
+		Action::Filter(Box::new(Take {
+			from: TakeFrom::Beginning,
+			amount: TakeAmount::Count(take),
+		}))
+	});
+	let sink = Action::Sink(Box::new(DeletingSink {
+		deleted: Arc::clone(&deleted),
+	}));
+
+	let mut task = Task {
+		name: None,
+		tag: None,
+		source: Some(Box::new(DummySource(fetches))),
+		actions: Some(take.into_iter().chain([sink]).collect()),
+		entry_to_msg_map: Some(EntryToMsgMap::default()),
+		update_in_place: false,
+		delete_removed: true,
+		timeout: None,
+		max_entries: None,
+		dead_letters: None,
+		refresh_time: None,
+	};
+
+	for _ in 0..runs {
+		task.run().await.unwrap();
+	}
+
+	let mut remaining = task
+		.entry_to_msg_map
+		.unwrap()
+		.entry_ids(0)
+		.map(|eid| eid.0.clone())
+		.collect::<Vec<_>>();
+	remaining.sort();
+
+	let deleted = deleted.lock().unwrap().clone();
+	(deleted, remaining)
+}
+
+#[tokio::test]
+async fn delete_removed() {
+	assert_eq!(
+		run_all(vec![vec!["1", "2", "3"], vec!["1", "3"]], None).await,
+		(vec![2], vec!["1".to_owned(), "3".to_owned()])
+	);
+}
+
+#[tokio::test]
+async fn keep_all_on_empty_fetch() {
+	assert_eq!(
+		run_all(vec![vec!["1", "2"], vec![]], None).await,
+		(vec![], vec!["1".to_owned(), "2".to_owned()])
+	);
+}
+
+#[tokio::test]
+async fn keep_filtered_out() {
+	// "1" is still in the source on the second run but only the newer entry "2" is sent
+	assert_eq!(
+		run_all(vec![vec!["1"], vec!["2", "1"]], Some(1)).await,
+		(vec![], vec!["1".to_owned(), "2".to_owned()])
+	);
+}
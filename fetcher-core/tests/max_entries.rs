@@ -0,0 +1,65 @@
+//! This test asserts that a task that is about to send more entries than its `max_entries` fails before sending any of them
+//! and that entries filtered out before the first sink don't count
+
+#![allow(clippy::missing_assert_message)]
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use self::common::{CountingSink, DummySource, entry};
+use fetcher_core::{
+	action::filter::{
+		Take,
+		take::{TakeAmount, TakeFrom},
+	},
+	error::{FetcherError, TooManyEntriesError},
+	task::Task,
+};
+use std::sync::{Arc, atomic::Ordering};
+
+const ENTRY_COUNT: usize = 3;
+
+/// Run a task with the `max_entries` limit that takes `take` out of [`ENTRY_COUNT`] entries before sending them.
+/// Returns the result of the run, how many entries have been sent, and how many have been marked as read
+async fn run(max_entries: usize, take: usize) -> (Result<(), FetcherError>, usize, usize) {
+	let source = DummySource::new((0..ENTRY_COUNT).map(|i| entry(&i.to_string())).collect());
+	let read = Arc::clone(&source.read);
+	let sink = CountingSink::default();
+	let sent = Arc::clone(&sink.sent);
+
+	let mut task = Task::builder()
+		.source(source)
+		.filter(Take {
+			from: TakeFrom::Beginning,
+			amount: TakeAmount::Count(take),
+		})
+		.sink(sink)
+		.max_entries(max_entries)
+		.build()
+		.unwrap();
+
+	let res = task.run().await;
+	let read = read.lock().unwrap().len();
+	(res, sent.load(Ordering::SeqCst), read)
+}
+
+#[tokio::test]
+async fn max_entries() {
+	let (res, sent, read) = run(ENTRY_COUNT - 1, ENTRY_COUNT).await;
+
+	assert!(matches!(
+		res,
+		Err(FetcherError::TooManyEntries(TooManyEntriesError { count: ENTRY_COUNT, max }))
+			if max == ENTRY_COUNT - 1
+	));
+	assert_eq!((sent, read), (0, 0));
+}
+
+#[tokio::test]
+async fn max_entries_after_filters() {
+	let (res, sent, read) = run(ENTRY_COUNT - 1, 1).await;
+
+	assert!(res.is_ok());
+	assert_eq!((sent, read), (1, 1));
+}
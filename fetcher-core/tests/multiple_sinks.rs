@@ -0,0 +1,50 @@
+//! This test asserts that a sink that failed to send a message doesn't prevent the other sinks of the task from sending it
+//! and that message ids of every sink are tracked separately
+
+#![allow(clippy::missing_assert_message)]
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use self::common::{CountingSink, DummySource, FailingSink, entry};
+use fetcher_core::{
+	entry::EntryId,
+	error::FetcherError,
+	task::{Task, entry_to_msg_map::EntryToMsgMap},
+};
+use std::sync::{
+	Arc,
+	atomic::{AtomicUsize, Ordering},
+};
+
+const ENTRY_ID: &str = "0";
+
+#[tokio::test]
+async fn multiple_sinks() {
+	let sent = Arc::new(AtomicUsize::new(0));
+
+	let mut task = Task::builder()
+		.source(DummySource::new(vec![entry(ENTRY_ID)]))
+		.sink(CountingSink {
+			msg_id: Some(1),
+			sent: Arc::clone(&sent),
+		})
+		.sink(FailingSink)
+		.sink(CountingSink {
+			msg_id: Some(3),
+			sent: Arc::clone(&sent),
+		})
+		.entry_to_msg_map(EntryToMsgMap::default())
+		.build()
+		.unwrap();
+
+	assert!(matches!(task.run().await, Err(FetcherError::Sink(_))));
+	assert_eq!(sent.load(Ordering::SeqCst), 2);
+
+	let map = task.entry_to_msg_map.unwrap();
+	let entry_id = EntryId(ENTRY_ID.into());
+	assert_eq!(map.get(0, &entry_id).map(|id| id.0), Some(1));
+	assert!(map.get(1, &entry_id).is_none());
+	assert_eq!(map.get(2, &entry_id).map(|id| id.0), Some(3));
+}
@@ -68,15 +68,22 @@ async fn reply_to() {
 	let mut entry_to_msg_map = EntryToMsgMap::default();
 
 	entry_to_msg_map
-		.insert(ENTRY_ID.to_owned().into(), MESSAGE_ID.into())
+		.insert(0, ENTRY_ID.to_owned().into(), MESSAGE_ID.into())
 		.await
 		.unwrap();
 
 	let mut task = Task {
+		name: None,
 		tag: None,
 		source: Some(Box::new(DummySource)),
 		actions: Some(vec![Action::Sink(Box::new(DummySink))]),
 		entry_to_msg_map: Some(entry_to_msg_map),
+		update_in_place: false,
+		delete_removed: false,
+		timeout: None,
+		max_entries: None,
+		dead_letters: None,
+		refresh_time: None,
 	};
 
 	task.run().await.unwrap();
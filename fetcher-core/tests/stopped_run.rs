@@ -0,0 +1,88 @@
+//! This test asserts that the entries sent before a run has stopped because of an error are still marked as read,
+//! but only if every sink has sent them
+
+#![allow(clippy::missing_assert_message)]
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use self::common::{CountingSink, DummySource, entry};
+use async_trait::async_trait;
+use fetcher_core::{
+	action::transform::{
+		Transform,
+		error::{RawContentsNotSetError, TransformError},
+	},
+	entry::{Entry, EntryId},
+	error::FetcherError,
+	task::Task,
+};
+use std::sync::{
+	Arc,
+	atomic::{AtomicUsize, Ordering},
+};
+
+/// Fails to transform every entry
+#[derive(Debug)]
+struct FailingTransform;
+
+#[async_trait]
+impl Transform for FailingTransform {
+	async fn transform(&self, entry: Entry) -> Result<Vec<Entry>, TransformError> {
+		Err(TransformError {
+			kind: RawContentsNotSetError.into(),
+			original_entry: entry,
+		})
+	}
+}
+
+#[tokio::test]
+async fn error_after_the_last_sink() {
+	let sent = Arc::new(AtomicUsize::new(0));
+	let source = DummySource::new(vec![entry("1"), entry("0")]);
+	let read = Arc::clone(&source.read);
+
+	let mut task = Task::builder()
+		.source(source)
+		.sink(CountingSink {
+			msg_id: None,
+			sent: Arc::clone(&sent),
+		})
+		.transform(FailingTransform)
+		.build()
+		.unwrap();
+
+	assert!(matches!(task.run().await, Err(FetcherError::Transform(_))));
+	assert_eq!(sent.load(Ordering::SeqCst), 2);
+
+	// every sink has sent them, so they shouldn't be sent again
+	assert_eq!(
+		*read.lock().unwrap(),
+		[EntryId("0".to_owned()), EntryId("1".to_owned())]
+	);
+}
+
+#[tokio::test]
+async fn error_between_sinks() {
+	let sent = Arc::new(AtomicUsize::new(0));
+	let source = DummySource::new(vec![entry("0")]);
+	let read = Arc::clone(&source.read);
+
+	let mut task = Task::builder()
+		.source(source)
+		.sink(CountingSink {
+			msg_id: None,
+			sent: Arc::clone(&sent),
+		})
+		.transform(FailingTransform)
+		.sink(CountingSink::default())
+		.build()
+		.unwrap();
+
+	assert!(matches!(task.run().await, Err(FetcherError::Transform(_))));
+	assert_eq!(sent.load(Ordering::SeqCst), 1);
+
+	// the second sink hasn't got to send it yet
+	assert!(read.lock().unwrap().is_empty());
+}
@@ -0,0 +1,116 @@
+//! This test asserts that a task that takes longer than its timeout is cancelled with a timeout error
+//! and that the entries it has sent by then are marked as read
+
+#![allow(clippy::missing_assert_message)]
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use self::common::{DummySource, entry};
+use async_trait::async_trait;
+use fetcher_core::{
+	entry::{Entry, EntryId},
+	error::FetcherError,
+	read_filter::MarkAsRead,
+	sink::{
+		Sink,
+		error::SinkError,
+		message::{Message, MessageId},
+	},
+	source::{Fetch, Source, error::SourceError},
+	task::Task,
+};
+use std::{
+	sync::{
+		Arc,
+		atomic::{AtomicUsize, Ordering},
+	},
+	time::Duration,
+};
+
+const TIMEOUT: Duration = Duration::from_millis(10);
+
+/// Never finishes fetching
+#[derive(Debug)]
+struct HangingSource;
+
+#[async_trait]
+impl Fetch for HangingSource {
+	async fn fetch(&mut self) -> Result<Vec<Entry>, SourceError> {
+		std::future::pending().await
+	}
+}
+
+#[async_trait]
+impl MarkAsRead for HangingSource {
+	async fn mark_as_read(&mut self, _id: &EntryId) -> Result<(), FetcherError> {
+		Ok(())
+	}
+
+	async fn set_read_only(&mut self) {}
+}
+
+impl Source for HangingSource {}
+
+/// Sends the first message and never finishes sending the next one
+#[derive(Default, Debug)]
+struct HangingSink {
+	sent: AtomicUsize,
+}
+
+#[async_trait]
+impl Sink for HangingSink {
+	async fn send(
+		&self,
+		_message: &Message,
+		_reply_to: Option<&MessageId>,
+		_tag: Option<&str>,
+	) -> Result<Option<MessageId>, SinkError> {
+		if self.sent.fetch_add(1, Ordering::SeqCst) > 0 {
+			std::future::pending::<()>().await;
+		}
+
+		Ok(None)
+	}
+}
+
+#[tokio::test]
+async fn timeout() {
+	let mut task = Task {
+		name: None,
+		tag: None,
+		source: Some(Box::new(HangingSource)),
+		actions: None,
+		entry_to_msg_map: None,
+		update_in_place: false,
+		delete_removed: false,
+		timeout: Some(TIMEOUT),
+		max_entries: None,
+		dead_letters: None,
+		refresh_time: None,
+	};
+
+	let err = task.run().await.unwrap_err();
+	assert!(matches!(err, FetcherError::Timeout(timeout) if timeout == TIMEOUT));
+
+	// timeouts are treated the same as other network errors and aren't counted against the error limit
+	assert!(err.is_connection_error().is_some());
+}
+
+#[tokio::test]
+async fn sent_entries_are_marked_as_read() {
+	// sorted newest to oldest, i.e. "0" is sent first
+	let source = DummySource::new(vec![entry("1"), entry("0")]);
+	let read = Arc::clone(&source.read);
+
+	let mut task = Task::builder()
+		.source(source)
+		.sink(HangingSink::default())
+		.timeout(TIMEOUT)
+		.build()
+		.unwrap();
+
+	assert!(matches!(task.run().await, Err(FetcherError::Timeout(_))));
+	assert_eq!(*read.lock().unwrap(), [EntryId("0".to_owned())]);
+}
@@ -0,0 +1,156 @@
+//! This test asserts that an entry that has already been sent is edited instead of being sent again,
+//! that it's sent anew if the sink can't edit the message, and that it's left alone if it hasn't changed
+
+#![allow(clippy::missing_assert_message)]
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::unwrap_used)]
+
+use async_trait::async_trait;
+use fetcher_core::{
+	action::Action,
+	entry::{Entry, EntryId},
+	error::FetcherError,
+	read_filter::MarkAsRead,
+	sink::{
+		Sink,
+		error::SinkError,
+		message::{Message, MessageId},
+	},
+	source::{Fetch, Source, error::SourceError},
+	task::{Task, entry_to_msg_map::EntryToMsgMap},
+};
+use std::sync::{
+	Arc,
+	atomic::{AtomicI64, Ordering},
+};
+
+const ENTRY_ID: &str = "0";
+
+/// Returns the same entry every time, with a different body each time if `changes` is true
+#[derive(Debug)]
+struct DummySource {
+	changes: bool,
+	fetched: i64,
+}
+
+/// Sends messages with increasing ids and edits them only if `can_edit` is true
+#[derive(Debug)]
+struct EditingSink {
+	can_edit: bool,
+	sent: Arc<AtomicI64>,
+	edited: Arc<AtomicI64>,
+}
+
+#[async_trait]
+impl Fetch for DummySource {
+	async fn fetch(&mut self) -> Result<Vec<Entry>, SourceError> {
+		if self.changes {
+			self.fetched += 1;
+		}
+
+		Ok(vec![Entry {
+			id: Some(EntryId(ENTRY_ID.into())),
+			msg: Message {
+				body: Some(format!("body {}", self.fetched)),
+				..Default::default()
+			},
+			..Default::default()
+		}])
+	}
+}
+
+#[async_trait]
+impl MarkAsRead for DummySource {
+	async fn mark_as_read(&mut self, _id: &EntryId) -> Result<(), FetcherError> {
+		Ok(())
+	}
+
+	async fn set_read_only(&mut self) {}
+}
+
+impl Source for DummySource {}
+
+#[async_trait]
+impl Sink for EditingSink {
+	async fn send(
+		&self,
+		_message: &Message,
+		_reply_to: Option<&MessageId>,
+		_tag: Option<&str>,
+	) -> Result<Option<MessageId>, SinkError> {
+		Ok(Some(MessageId(
+			self.sent.fetch_add(1, Ordering::SeqCst) + 1,
+		)))
+	}
+
+	async fn edit(
+		&self,
+		msg_id: &MessageId,
+		_message: &Message,
+		_tag: Option<&str>,
+	) -> Result<Option<MessageId>, SinkError> {
+		if !self.can_edit {
+			return Ok(None);
+		}
+
+		self.edited.fetch_add(1, Ordering::SeqCst);
+		Ok(Some(*msg_id))
+	}
+}
+
+async fn run_twice(can_edit: bool, changes: bool) -> (i64, i64, Option<i64>) {
+	let sent = Arc::new(AtomicI64::new(0));
+	let edited = Arc::new(AtomicI64::new(0));
+
+	let mut task = Task {
+		name: None,
+		tag: None,
+		source: Some(Box::new(DummySource {
+			changes,
+			fetched: 0,
+		})),
+		actions: Some(vec![Action::Sink(Box::new(EditingSink {
+			can_edit,
+			sent: Arc::clone(&sent),
+			edited: Arc::clone(&edited),
+		}))]),
+		entry_to_msg_map: Some(EntryToMsgMap::default()),
+		update_in_place: true,
+		delete_removed: false,
+		timeout: None,
+		max_entries: None,
+		dead_letters: None,
+		refresh_time: None,
+	};
+
+	task.run().await.unwrap();
+	task.run().await.unwrap();
+
+	let msg_id = task
+		.entry_to_msg_map
+		.unwrap()
+		.get(0, &EntryId(ENTRY_ID.into()))
+		.map(|id| id.0);
+
+	(
+		sent.load(Ordering::SeqCst),
+		edited.load(Ordering::SeqCst),
+		msg_id,
+	)
+}
+
+#[tokio::test]
+async fn edit_sent_message() {
+	assert_eq!(run_twice(true, true).await, (1, 1, Some(1)));
+}
+
+#[tokio::test]
+async fn send_anew_if_not_editable() {
+	assert_eq!(run_twice(false, true).await, (2, 0, Some(2)));
+}
+
+#[tokio::test]
+async fn dont_edit_unchanged_message() {
+	assert_eq!(run_twice(true, false).await, (1, 0, Some(1)));
+	assert_eq!(run_twice(false, false).await, (1, 0, Some(1)));
+}
@@ -4,15 +4,20 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use crate::settings::{context::StaticContext, external_data_provider::ExternalDataFromDataDir};
+use crate::settings::{
+	context::{Secrets, StaticContext, Storage},
+	external_data_provider::ExternalDataFromDataDir,
+};
 use fetcher_config::jobs::{
 	Job as JobConfig,
 	named::{JobName, JobWithTaskNames},
 };
+use fetcher_core::sink::stdout::Format;
 
 use argh::FromArgs;
 use color_eyre::{Report, Result};
-use std::{path::PathBuf, str::FromStr};
+use std::{net::SocketAddr, num::NonZeroUsize, path::PathBuf, str::FromStr};
+use url::Url;
 
 /// Automation and scalping tool
 #[derive(FromArgs, Debug)]
@@ -32,6 +37,42 @@ pub struct Args {
 	#[argh(option)]
 	pub log_path: Option<PathBuf>,
 
+	/// where to keep read filters and sent message ids: "files" (default) or "sqlite"
+	#[argh(option)]
+	pub storage: Option<Storage>,
+
+	/// where to keep login credentials: "files" (default) or "keyring"
+	#[argh(option)]
+	pub secrets: Option<Secrets>,
+
+	/// serve Prometheus metrics on this address, e.g. "127.0.0.1:9000"
+	#[argh(option)]
+	pub metrics_address: Option<SocketAddr>,
+
+	/// run a task right away when a POST request is sent to /trigger/<job>/<task> (or /trigger/<job> for jobs with a single task) on this address, e.g. "127.0.0.1:9001". Requests have to contain an "Authorization: Bearer <secret>" header with the secret from the `FETCHER_TRIGGER_SECRET` environment variable
+	#[argh(option)]
+	pub trigger_address: Option<SocketAddr>,
+
+	/// the public URL of the server set up with --trigger-address that `WebSub` hubs can reach, e.g. `https://fetcher.example.com/`. Tasks with `websub: true` subscribe to the hubs of their feeds with callbacks at <url>/websub/<job>/<task> and are run right away when the hub notifies about an update
+	#[argh(option)]
+	pub websub_callback: Option<Url>,
+
+	/// send all HTTP requests through this HTTP(S) or SOCKS5 proxy. Overrides the proxy set in the environment
+	#[argh(option)]
+	pub proxy: Option<Url>,
+
+	/// trust the certificate authorities in this PEM file in addition to the built-in ones for all HTTP requests, e.g. a private certificate authority of a LAN
+	#[argh(option)]
+	pub ca_cert: Option<PathBuf>,
+
+	/// dry run: fetch and process everything but only print the result to stdout, without marking anything as read. Works with both "run" and "run-manual"
+	#[argh(switch)]
+	pub dry_run: bool,
+
+	/// format of the messages printed to stdout during a dry run: "pretty" (default), "json", or "json-pretty"
+	#[argh(option)]
+	pub format: Option<StdoutFormat>,
+
 	/// print version and exit
 	#[argh(switch, short = 'v', long = "version")]
 	pub print_version: bool,
@@ -44,6 +85,8 @@ pub enum TopLvlSubcommand {
 	RunManual(RunManual),
 	MarkOldAsRead(MarkOldAsRead),
 	Verify(Verify),
+	Test(Test),
+	Schema(Schema),
 	Save(Save),
 }
 
@@ -63,6 +106,10 @@ pub struct Run {
 	#[argh(switch)]
 	pub dry_run: bool,
 
+	/// run at most this many tasks at the same time, across all jobs
+	#[argh(option)]
+	pub max_concurrent_tasks: Option<NonZeroUsize>,
+
 	/// run only these jobs and tasks formatted as "job\[:task\]..."
 	#[argh(positional)]
 	pub run_filter: Vec<String>,
@@ -95,6 +142,24 @@ pub struct Verify {
 	pub job_run_filter: Vec<String>,
 }
 
+/// Run a job or a single task of it once and print the entries it fetched as JSON, without sending them anywhere or marking them as read
+#[derive(FromArgs, Debug)]
+#[argh(subcommand, name = "test")]
+pub struct Test {
+	/// also print the entries after they have been processed, i.e. right before they would've been sent
+	#[argh(switch)]
+	pub transformed: bool,
+
+	/// the job and optionally the task to run, formatted as "job\[:task\]"
+	#[argh(positional)]
+	pub run_filter: String,
+}
+
+/// Print the JSON Schema of the job config format
+#[derive(FromArgs, Debug)]
+#[argh(subcommand, name = "schema")]
+pub struct Schema {}
+
 /// Save a setting
 #[derive(FromArgs, Debug)]
 #[argh(subcommand, name = "save")]
@@ -107,9 +172,12 @@ pub struct Save {
 #[derive(Debug)]
 pub enum Setting {
 	GoogleOAuth2,
+	MicrosoftOAuth2,
 	EmailPassword,
 	Telegram,
 	Discord,
+	Slack,
+	Reddit,
 }
 
 impl FromStr for Setting {
@@ -118,18 +186,42 @@ impl FromStr for Setting {
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
 		Ok(match s {
 			"google_oauth" => Self::GoogleOAuth2,
+			"microsoft_oauth" => Self::MicrosoftOAuth2,
 			"email_password" => Self::EmailPassword,
 			"telegram" => Self::Telegram,
 			"discord" => Self::Discord,
+			"slack" => Self::Slack,
+			"reddit" => Self::Reddit,
 			s => {
 				return Err(format!(
-					"{s:?} is not a valid setting. Available settings: google_oauth, email_password, telegram"
+					"{s:?} is not a valid setting. Available settings: google_oauth, microsoft_oauth, email_password, telegram, discord, slack, reddit"
 				));
 			}
 		})
 	}
 }
 
+/// Wrapper around the stdout sink format foreign enum to implement `FromStr`
+#[derive(Clone, Copy, Debug)]
+pub struct StdoutFormat(pub Format);
+
+impl FromStr for StdoutFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(Self(match s {
+			"pretty" => Format::Pretty,
+			"json" => Format::Json,
+			"json-pretty" => Format::JsonPretty,
+			other => {
+				return Err(format!(
+					"unknown format \"{other}\", expected \"pretty\", \"json\", or \"json-pretty\""
+				));
+			}
+		}))
+	}
+}
+
 /// Wrapper around Job foreign struct to implement `FromStr` from valid job in JSON format
 #[derive(Debug)]
 pub struct JsonJobConfig(Vec<(JobName, JobConfig)>);
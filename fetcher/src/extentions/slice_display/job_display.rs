@@ -21,6 +21,7 @@ impl fmt::Display for JobDisplay<'_> {
 			JobWithTaskNames {
 				inner: _,
 				task_names,
+				websub_feeds: _,
 			},
 		)) = self;
 
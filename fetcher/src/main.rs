@@ -16,22 +16,27 @@ pub mod args;
 pub mod error_handling;
 pub mod extentions;
 pub mod settings;
+pub mod trigger_server;
 
 use crate::{
 	args::{Args, Setting},
 	error_handling::{DEFAULT_MAX_ERROR_LIMIT, ErrorHandling, PrevErrors},
 	extentions::{ErrorChainExt, SliceDisplayExt, slice_display::job_display::JobDisplay},
 	settings::{
-		config::jobs::filter::JobFilter, context::Context as OwnedContext,
-		context::StaticContext as Context,
+		config::jobs::filter::JobFilter,
+		context::{Context as OwnedContext, Secrets, StaticContext as Context, Storage},
 	},
 };
-use fetcher_config::jobs::named::{JobName, JobWithTaskNames};
+use fetcher_config::jobs::{
+	Job as JobConfig,
+	named::{JobName, JobWithTaskNames, TaskName},
+};
 use fetcher_core::{
 	action::Action,
-	error::FetcherError,
+	entry::Entry,
+	error::{FetcherError, TaskError},
 	job::{Job, timepoint::TimePoint},
-	sink::{Sink, Stdout},
+	sink::{Sink, Stdout, stdout::Format},
 };
 
 use color_eyre::{
@@ -39,15 +44,23 @@ use color_eyre::{
 	eyre::{WrapErr, eyre},
 };
 use futures::{StreamExt, stream::FuturesUnordered};
-use std::{collections::HashMap, fmt::Write, ops::ControlFlow, path::PathBuf, time::Duration};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder};
+use std::{
+	collections::HashMap, fmt::Write, net::SocketAddr, ops::ControlFlow, path::PathBuf, sync::Arc,
+	time::Duration,
+};
 use tap::TapOptional;
 use tokio::{
 	select,
-	sync::watch::{self, Receiver},
+	sync::{
+		Semaphore,
+		watch::{self, Receiver},
+	},
 	task::JoinError,
 	time::sleep,
 };
 use tracing::Instrument;
+use url::Url;
 
 type Jobs = HashMap<JobName, JobWithTaskNames>;
 
@@ -111,18 +124,52 @@ async fn async_main() -> Result<()> {
 		return Ok(());
 	}
 
-	let cx = create_context(args.data_path, args.config_path, args.log_path)?;
+	let cx = create_context(
+		args.data_path,
+		args.config_path,
+		args.log_path,
+		args.storage.unwrap_or_default(),
+		args.secrets.unwrap_or_default(),
+	)?;
 	tracing::info!("Running fetcher {version}");
 
-	match args.subcommand {
-		Some(args::TopLvlSubcommand::Run(run_args)) => run_command(run_args, cx).await,
-		None => run_command(args::Run::default(), cx).await,
-		Some(args::TopLvlSubcommand::RunManual(args::RunManual { job_config })) => {
-			run_jobs(job_config.decode(cx)?, ErrorHandling::Forward, cx).await?;
+	if let Some(metrics_address) = args.metrics_address {
+		set_up_metrics(metrics_address)?;
+	}
+
+	set_up_http(args.proxy, args.ca_cert)?;
+
+	let stdout_format = args.format.map(|f| f.0).unwrap_or_default();
+
+	// run is the default subcommand
+	let subcommand = args
+		.subcommand
+		.unwrap_or_else(|| args::TopLvlSubcommand::Run(args::Run::default()));
+
+	match subcommand {
+		args::TopLvlSubcommand::Run(mut run_args) => {
+			run_args.dry_run |= args.dry_run;
+			run_command(
+				run_args,
+				stdout_format,
+				args.trigger_address,
+				args.websub_callback,
+				cx,
+			)
+			.await
+		}
+		args::TopLvlSubcommand::RunManual(args::RunManual { job_config }) => {
+			let mut jobs = job_config.decode(cx)?.collect::<Vec<_>>();
+
+			if args.dry_run {
+				make_dry(jobs.iter_mut().map(|(_, job)| job), stdout_format).await;
+			}
+
+			run_jobs(jobs, ErrorHandling::Forward, cx).await?;
 
 			Ok(())
 		}
-		Some(args::TopLvlSubcommand::MarkOldAsRead(args::MarkOldAsRead { run_filter })) => {
+		args::TopLvlSubcommand::MarkOldAsRead(args::MarkOldAsRead { run_filter }) => {
 			let run_filter = run_filter
 				.into_iter()
 				.map(|s| s.parse())
@@ -142,6 +189,8 @@ async fn async_main() -> Result<()> {
 				job.inner.refresh_time = None;
 
 				for task in &mut job.inner.tasks {
+					task.refresh_time = None;
+
 					if let Some(actions) = task.actions.take() {
 						let no_sink_acts = actions
 							.into_iter()
@@ -160,34 +209,22 @@ async fn async_main() -> Result<()> {
 
 			Ok(())
 		}
-		Some(args::TopLvlSubcommand::Verify(args::Verify { job_run_filter })) => {
-			let job_run_filter = job_run_filter
-				.into_iter()
-				.map(|s| s.parse::<JobFilter>())
-				.map(|res| {
-					res.map(|mut filter| {
-						filter.task = None;
-						filter
-					})
-				})
-				.collect::<Result<Vec<_>>>()?;
-			let job_run_filter = if job_run_filter.is_empty() {
-				None
-			} else {
-				Some(job_run_filter)
-			};
-
-			let _: Option<Jobs> = get_jobs(job_run_filter, cx)?;
-			tracing::info!("Everything verified to be working properly, exiting...");
+		args::TopLvlSubcommand::Test(test_args) => test_command(test_args, cx).await,
+		args::TopLvlSubcommand::Verify(verify_args) => verify_command(verify_args, cx),
+		args::TopLvlSubcommand::Schema(args::Schema {}) => {
+			println!("{}", serde_json::to_string_pretty(&JobConfig::schema())?);
 
 			Ok(())
 		}
-		Some(args::TopLvlSubcommand::Save(save)) => {
+		args::TopLvlSubcommand::Save(save) => {
 			match save.setting {
 				Setting::GoogleOAuth2 => settings::data::google_oauth2::prompt(cx).await?,
+				Setting::MicrosoftOAuth2 => settings::data::microsoft_oauth2::prompt(cx).await?,
 				Setting::EmailPassword => settings::data::email_password::prompt(cx)?,
 				Setting::Telegram => settings::data::telegram::prompt(cx)?,
 				Setting::Discord => settings::data::discord::prompt(cx)?,
+				Setting::Slack => settings::data::slack::prompt(cx)?,
+				Setting::Reddit => settings::data::reddit::prompt(cx)?,
 			}
 
 			Ok(())
@@ -200,6 +237,8 @@ fn create_context(
 	data_path: Option<PathBuf>,
 	config_path: Option<PathBuf>,
 	log_path: Option<PathBuf>,
+	storage: Storage,
+	secrets: Secrets,
 ) -> Result<Context> {
 	let data_path = match data_path {
 		Some(p) => p,
@@ -218,6 +257,8 @@ fn create_context(
 		data_path,
 		conf_paths,
 		log_path,
+		storage,
+		secrets,
 	})))
 }
 
@@ -239,13 +280,20 @@ fn version() -> String {
 	}
 }
 
-async fn run_command(run_args: args::Run, cx: Context) -> Result<()> {
+async fn run_command(
+	run_args: args::Run,
+	stdout_format: Format,
+	trigger_address: Option<SocketAddr>,
+	websub_callback: Option<Url>,
+	cx: Context,
+) -> Result<()> {
 	tracing::trace!("Running in run mode with {run_args:#?}");
 
 	let args::Run {
 		once,
 		no_skip_read: ignore_read,
 		dry_run,
+		max_concurrent_tasks,
 		run_filter,
 	} = run_args;
 
@@ -271,6 +319,10 @@ async fn run_command(run_args: args::Run, cx: Context) -> Result<()> {
 
 		for job in jobs.values_mut() {
 			job.inner.refresh_time = None;
+
+			for task in &mut job.inner.tasks {
+				task.refresh_time = None;
+			}
 		}
 	}
 
@@ -304,28 +356,29 @@ async fn run_command(run_args: args::Run, cx: Context) -> Result<()> {
 	}
 
 	if dry_run {
-		tracing::trace!("Making all jobs dry");
+		make_dry(jobs.values_mut(), stdout_format).await;
+	}
 
-		for job in jobs.values_mut() {
-			for task in &mut job.inner.tasks {
-				// don't save read filtered items to the fs
-				if let Some(source) = &mut task.source {
-					source.set_read_only().await;
-				}
+	if let Some(max_concurrent_tasks) = max_concurrent_tasks {
+		tracing::trace!("Limiting all jobs to {max_concurrent_tasks} concurrent tasks");
+		let task_limit = Arc::new(Semaphore::new(max_concurrent_tasks.get()));
 
-				// don't send anything anywhere, just print
-				for act in task.actions.iter_mut().flatten() {
-					if let Action::Sink(sink) = act {
-						*sink = Box::new(Stdout);
-					}
-				}
+		for job in jobs.values_mut() {
+			job.inner.task_limit = Some(Arc::clone(&task_limit));
+		}
+	}
 
-				// don't save entry to msg map to the fs
-				if let Some(entry_to_msg_map) = &mut task.entry_to_msg_map {
-					entry_to_msg_map.external_save = None;
-				}
-			}
+	if let Some(trigger_address) = trigger_address {
+		if once {
+			tracing::warn!("Not listening for triggers since all jobs are run only once");
+		} else {
+			trigger_server::set_up(trigger_address, websub_callback.as_ref(), jobs.iter_mut())
+				.await?;
 		}
+	} else if !once && jobs.values().any(|job| !job.websub_feeds.is_empty()) {
+		tracing::warn!(
+			"WebSub is enabled for some tasks but --trigger-address isn't set to receive the updates, polling them instead"
+		);
 	}
 
 	let error_handling = if once {
@@ -340,6 +393,152 @@ async fn run_command(run_args: args::Run, cx: Context) -> Result<()> {
 	Ok(())
 }
 
+fn verify_command(verify_args: args::Verify, cx: Context) -> Result<()> {
+	let args::Verify { job_run_filter } = verify_args;
+
+	let job_run_filter = job_run_filter
+		.into_iter()
+		.map(|s| s.parse::<JobFilter>())
+		.map(|res| {
+			res.map(|mut filter| {
+				filter.task = None;
+				filter
+			})
+		})
+		.collect::<Result<Vec<_>>>()?;
+	let job_run_filter = if job_run_filter.is_empty() {
+		None
+	} else {
+		Some(job_run_filter)
+	};
+
+	let problems = settings::config::jobs::verify_all(job_run_filter.as_deref(), cx);
+	if problems.is_empty() {
+		tracing::info!("Everything verified to be working properly, exiting...");
+		return Ok(());
+	}
+
+	for problem in &problems {
+		tracing::error!("{problem:?}");
+	}
+
+	Err(eyre!(
+		"Found {} problems in the job configs",
+		problems.len()
+	))
+}
+
+async fn test_command(test_args: args::Test, cx: Context) -> Result<()> {
+	let args::Test {
+		transformed,
+		run_filter,
+	} = test_args;
+
+	let Some(mut jobs) = get_jobs(Some(vec![run_filter.parse()?]), cx)? else {
+		return Ok(());
+	};
+
+	// nothing is sent in test mode, so the format doesn't matter
+	make_dry(jobs.values_mut(), Format::default()).await;
+
+	let mut output = Vec::new();
+	for (job_name, job) in &mut jobs {
+		for (idx, task) in job.inner.tasks.iter_mut().enumerate() {
+			let task_name = job
+				.task_names
+				.as_ref()
+				.and_then(|names| names.get(&idx))
+				.map(TaskName::as_str);
+
+			let fetched = match &mut task.source {
+				Some(source) => source.fetch().await.map_err(FetcherError::from)?,
+				None => vec![Entry::default()],
+			};
+
+			let mut task_output = serde_json::json!({
+				"job": job_name.as_str(),
+				"task": task_name,
+				"fetched": fetched.iter().map(entry_to_json).collect::<Vec<_>>(),
+			});
+
+			if transformed {
+				let mut entries = fetched;
+
+				for act in task.actions.iter().flatten() {
+					match act {
+						// show everything there is, not only the unread entries
+						Action::Filter(f) if f.is_readfilter() => (),
+						Action::Filter(f) => f.filter(&mut entries).await,
+						Action::Transform(tr) => {
+							let mut fully_transformed = Vec::new();
+
+							for entry in entries {
+								fully_transformed
+									.extend(tr.transform(entry).await.map_err(FetcherError::from)?);
+							}
+
+							entries = fully_transformed;
+						}
+						Action::Sink(_) => (),
+					}
+				}
+
+				task_output["transformed"] = entries.iter().map(entry_to_json).collect();
+			}
+
+			output.push(task_output);
+		}
+	}
+
+	println!("{}", serde_json::to_string_pretty(&output)?);
+	Ok(())
+}
+
+fn entry_to_json(entry: &Entry) -> serde_json::Value {
+	serde_json::json!({
+		"id": entry.id.as_deref(),
+		"reply_to": entry.reply_to.as_deref(),
+		"published": entry.published.map(|date| date.to_rfc3339()),
+		"raw_contents": entry.raw_contents,
+		"msg": entry.msg.to_json(),
+	})
+}
+
+/// Make sure the jobs make no permanent changes to the system: nothing is marked as read or saved and everything is printed to stdout instead of being sent
+async fn make_dry(jobs: impl IntoIterator<Item = &mut JobWithTaskNames>, stdout_format: Format) {
+	tracing::trace!("Making all jobs dry");
+
+	// don't save newly trusted gemini hosts to the fs. They are shared between all jobs
+	settings::data::runtime_external_save::gemini_known_hosts::set_read_only().await;
+
+	for job in jobs {
+		for task in &mut job.inner.tasks {
+			// don't save read filtered items to the fs
+			if let Some(source) = &mut task.source {
+				source.set_read_only().await;
+			}
+
+			// don't send anything anywhere, just print
+			for act in task.actions.iter_mut().flatten() {
+				if let Action::Sink(sink) = act {
+					*sink = Box::new(Stdout {
+						format: stdout_format,
+						..Default::default()
+					});
+				}
+			}
+
+			// don't save entry to msg map to the fs
+			if let Some(entry_to_msg_map) = &mut task.entry_to_msg_map {
+				entry_to_msg_map.external_save = None;
+			}
+
+			// don't save messages that failed to be sent to the fs
+			task.dead_letters = None;
+		}
+	}
+}
+
 #[tracing::instrument(level = "debug", skip(cx))]
 #[allow(clippy::needless_pass_by_value)]
 fn get_jobs(run_filter: Option<Vec<JobFilter>>, cx: Context) -> Result<Option<Jobs>> {
@@ -407,6 +606,7 @@ async fn run_jobs(
 			)
 		})
 		.collect::<FuturesUnordered<_>>();
+	let job_count = jobs.len();
 
 	let mut errors: Vec<(JobName, Report)> = jobs
 		.filter_map(|(job_name, async_task_res)| async move {
@@ -429,6 +629,12 @@ async fn run_jobs(
 		.collect()
 		.await;
 
+	tracing::info!(
+		"Jobs finished: {} succeeded, {} failed",
+		job_count - errors.len(),
+		errors.len()
+	);
+
 	match errors.len() {
 		0 => Ok(()),
 		1 => {
@@ -447,6 +653,43 @@ async fn run_jobs(
 	}
 }
 
+/// Set up the proxy and the extra certificate authorities that all HTTP requests use
+fn set_up_http(proxy: Option<Url>, ca_cert: Option<PathBuf>) -> Result<()> {
+	if let Some(proxy) = proxy {
+		tracing::info!(
+			"Sending all HTTP requests through the proxy at {:?}",
+			proxy.host_str()
+		);
+
+		// can't fail since the default proxy is set only once
+		_ = fetcher_core::source::http::set_default_proxy(proxy);
+	}
+
+	if let Some(ca_cert) = ca_cert {
+		let pem = std::fs::read(&ca_cert)
+			.wrap_err_with(|| format!("Can't read the CA certificates at {}", ca_cert.display()))?;
+
+		fetcher_core::source::http::set_extra_root_certificates(&pem)
+			.wrap_err_with(|| format!("Can't use the CA certificates at {}", ca_cert.display()))?;
+	}
+
+	Ok(())
+}
+
+/// Serve metrics of all tasks in the Prometheus format on the address
+fn set_up_metrics(address: SocketAddr) -> Result<()> {
+	const DURATION_BUCKETS: &[f64] = &[0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+
+	PrometheusBuilder::new()
+		.with_http_listener(address)
+		.set_buckets_for_metric(Matcher::Suffix("_seconds".to_owned()), DURATION_BUCKETS)?
+		.install()
+		.wrap_err_with(|| format!("Can't serve metrics on {address}"))?;
+
+	tracing::info!("Serving metrics on {address}");
+	Ok(())
+}
+
 fn set_up_signal_handler() -> Receiver<()> {
 	let (shutdown_tx, shutdown_rx) = watch::channel(());
 	let (force_close_tx, mut force_close_rx) = watch::channel(());
@@ -496,7 +739,7 @@ async fn run_job(
 	mut shutdown_rx: Receiver<()>,
 	cx: Context,
 ) -> (JobName, Result<Result<()>, JoinError>) {
-	fn fold_task_errors(mut errors: Vec<FetcherError>) -> Report {
+	fn fold_task_errors(mut errors: Vec<TaskError>) -> Report {
 		// for acc_report.error(err). I believe this way it is clearer what the fold does
 		#[allow(clippy::redundant_closure_for_method_calls)]
 		match errors.len() {
@@ -554,11 +797,11 @@ async fn run_job(
 /// ControlFlow::Break -> stop running the job with a result
 #[tracing::instrument(level = "debug", skip(job_name, job, cx))]
 async fn handle_errors(
-	results: Result<(), Vec<FetcherError>>,
+	results: Result<(), Vec<TaskError>>,
 	stradegy: &mut ErrorHandling,
 	(job_name, job): (&JobName, &Job),
 	cx: Context,
-) -> ControlFlow<Result<(), Vec<FetcherError>>> {
+) -> ControlFlow<Result<(), Vec<TaskError>>> {
 	let Err(errors) = results else {
 		return ControlFlow::Break(Ok(()));
 	};
@@ -595,7 +838,7 @@ async fn handle_errors(
 
 // count errors and sleep exponentially
 async fn handle_errors_sleep(
-	errors: &[FetcherError],
+	errors: &[TaskError],
 	prev_errors: &mut PrevErrors,
 	job_name: &JobName,
 	job_refresh_time: Option<&TimePoint>,
@@ -623,13 +866,32 @@ async fn handle_errors_sleep(
 					prev_errors.reset();
 				}
 			}
+			TimePoint::Cron(schedule) => {
+				let now = chrono::Local::now();
+
+				// the job has been run at least twice since the last error and the sleep after it
+				let ran_twice = chrono::Duration::from_std(last_error.elapsed())
+					.ok()
+					.zip(chrono::Duration::from_std(last_error_sleep_dur).ok())
+					.and_then(|(elapsed, sleep_dur)| {
+						now.checked_sub_signed(elapsed)?
+							.checked_add_signed(sleep_dur)
+					})
+					.and_then(|since| schedule.after(&since).nth(1))
+					.is_some_and(|second_run| second_run <= now);
+
+				if ran_twice {
+					prev_errors.reset();
+				}
+			}
 		}
 	}
 
 	// log and filter out network connection errors.
 	// they shouldn't be counted against the max error limit because they are ~usually~ temporary and not critical
 	let errors_without_net = errors.iter().filter(|e| {
-		e.is_connection_error()
+		e.error
+			.is_connection_error()
 			.tap_some(|net_err| {
 				tracing::warn!("Network error: {}", net_err.display_chain());
 			})
@@ -654,7 +916,7 @@ async fn handle_errors_sleep(
 
 		// log and report all other errors (except for network errors up above)
 		for (i, err) in errors_without_net.enumerate() {
-			if let FetcherError::Transform(transform_err) = &err
+			if let FetcherError::Transform(transform_err) = &err.error
 				&& let Err(e) = settings::log::log_transform_err(transform_err, job_name)
 			{
 				tracing::error!("Error logging transform error: {e:?}");
@@ -711,7 +973,7 @@ async fn report_error(job_name: &str, err: &str, context: Context) -> Result<()>
 		body: Some(err.to_owned()),
 		..Default::default()
 	};
-	Telegram::new(bot, admin_chat_id, LinkLocation::default())
+	Telegram::new(bot, admin_chat_id, LinkLocation::default(), false)
 		.send(&msg, None, Some(job_name))
 		.await
 		.map_err(fetcher_core::error::FetcherError::Sink)?;
@@ -5,14 +5,18 @@
  */
 
 pub mod actions;
+pub mod env_vars;
 pub mod jobs;
 pub mod templates;
 
 #[cfg_attr(not(target_os = "linux"), expect(unused_imports))]
 use super::PREFIX;
 use super::proj_dirs;
+use fetcher_config::suggestion::did_you_mean;
 
-use color_eyre::Result;
+use color_eyre::{Report, Result, eyre::eyre};
+use figment::error::Kind;
+use itertools::Itertools;
 use std::path::PathBuf;
 
 const CONFIG_FILE_EXT: &str = "yml";
@@ -28,3 +32,37 @@ pub fn default_cfg_dirs() -> Result<Vec<PathBuf>> {
 
 	Ok(dirs)
 }
+
+/// Turn an error from parsing a config into a report that suggests the right name for every misspelled key or variant
+pub fn explain_parse_error(err: figment::Error) -> Report {
+	let msg = err
+		.into_iter()
+		.map(|err| {
+			let (found, suggestion) = match &err.kind {
+				Kind::UnknownField(found, expected) | Kind::UnknownVariant(found, expected) => {
+					(found.as_str(), did_you_mean(found, expected))
+				}
+				_ => return err.to_string(),
+			};
+
+			let Some(suggestion) = suggestion else {
+				return err.to_string();
+			};
+
+			// the key of the struct or enum the name is misspelled in, e.g. `telegram` for `telegram.chat_idd`.
+			// List indices and the misspelled name itself don't say much
+			let parent = err
+				.path
+				.iter()
+				.rev()
+				.find(|key| *key != found && key.parse::<usize>().is_err());
+
+			match parent {
+				Some(parent) => format!("{err}. Did you mean `{suggestion}` in `{parent}`?"),
+				None => format!("{err}. Did you mean `{suggestion}`?"),
+			}
+		})
+		.join("\n");
+
+	eyre!(msg)
+}
@@ -55,7 +55,10 @@ pub fn find_in(action_path: &Path, name: &str) -> Result<Option<Vec<ActionConfig
 		));
 	}
 
-	let action_config: Vec<ActionConfig> = Figment::new().merge(Yaml::file(path)).extract()?;
+	let action_config: Vec<ActionConfig> = Figment::new()
+		.merge(Yaml::file(path))
+		.extract()
+		.map_err(super::explain_parse_error)?;
 
 	Ok(Some(action_config))
 }
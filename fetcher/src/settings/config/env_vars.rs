@@ -0,0 +1,110 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Replaces `${VAR}` in config values with the value of the environment variable `VAR`.
+//! `$${` can be used to write a literal `${`
+
+use color_eyre::{Result, eyre::eyre};
+use figment::{
+	Figment,
+	providers::Serialized,
+	value::{Dict, Value},
+};
+use std::env;
+
+/// Replace all environment variables in the string values of the config.
+/// Only the values that have changed are overridden to keep the source of all other values intact for error reporting
+pub fn interpolate(conf: Figment) -> Result<Figment> {
+	let Value::Dict(_, root) = conf.extract::<Value>()? else {
+		return Ok(conf);
+	};
+
+	match interpolate_dict(&root, "")? {
+		Some(changed) => Ok(conf.merge(Serialized::defaults(changed))),
+		None => Ok(conf),
+	}
+}
+
+/// Returns only the changed values, or None if nothing has changed
+fn interpolate_dict(dict: &Dict, path: &str) -> Result<Option<Dict>> {
+	let mut changed = Dict::new();
+
+	for (key, val) in dict {
+		let field = if path.is_empty() {
+			key.clone()
+		} else {
+			format!("{path}.{key}")
+		};
+
+		if let Some(new_val) = interpolate_value(val, &field)? {
+			changed.insert(key.clone(), new_val);
+		}
+	}
+
+	Ok((!changed.is_empty()).then_some(changed))
+}
+
+fn interpolate_value(val: &Value, field: &str) -> Result<Option<Value>> {
+	Ok(match val {
+		Value::String(_, s) => interpolate_str(s, field)?.map(Value::from),
+		Value::Dict(_, dict) => interpolate_dict(dict, field)?.map(Value::from),
+		// arrays are overridden as a whole, so they need to be rebuilt entirely if anything has changed
+		Value::Array(_, arr) => {
+			let mut any_changed = false;
+			let new_arr = arr
+				.iter()
+				.enumerate()
+				.map(|(idx, item)| {
+					let new_item = interpolate_value(item, &format!("{field}[{idx}]"))?;
+					any_changed |= new_item.is_some();
+
+					Ok(new_item.unwrap_or_else(|| item.clone()))
+				})
+				.collect::<Result<Vec<_>>>()?;
+
+			any_changed.then(|| Value::from(new_arr))
+		}
+		_ => None,
+	})
+}
+
+/// Returns the string with all environment variables replaced, or None if it doesn't contain any
+fn interpolate_str(s: &str, field: &str) -> Result<Option<String>> {
+	if !s.contains("${") {
+		return Ok(None);
+	}
+
+	let mut out = String::with_capacity(s.len());
+	let mut rest = s;
+
+	while let Some(start) = rest.find("${") {
+		// escaped with $${
+		if rest[..start].ends_with('$') {
+			out.push_str(&rest[..start]);
+			out.push('{');
+			rest = &rest[start + 2..];
+			continue;
+		}
+
+		out.push_str(&rest[..start]);
+
+		let var_start = &rest[start + 2..];
+		let end = var_start
+			.find('}')
+			.ok_or_else(|| eyre!("Unclosed \"${{\" in field {field}"))?;
+		let var = &var_start[..end];
+
+		let var_val = env::var(var).map_err(|e| {
+			eyre!("Environment variable {var:?} used in field {field} can't be read: {e}")
+		})?;
+		out.push_str(&var_val);
+
+		rest = &var_start[end + 1..];
+	}
+
+	out.push_str(rest);
+	Ok(Some(out))
+}
@@ -7,7 +7,7 @@
 pub mod filter;
 
 use self::filter::JobFilter;
-use super::CONFIG_FILE_EXT;
+use super::{CONFIG_FILE_EXT, env_vars};
 use crate::{
 	Jobs,
 	settings::{
@@ -19,13 +19,20 @@ use fetcher_config::jobs::{
 	named::{JobName, JobWithTaskNames},
 };
 
-use color_eyre::{Result, eyre::eyre};
+use color_eyre::{
+	Report, Result,
+	eyre::{WrapErr, eyre},
+};
 use figment::{
 	Figment,
 	providers::{Format, Yaml},
 };
 use serde::Deserialize;
-use std::{fmt::Write, io, path::Path};
+use std::{
+	fmt::Write,
+	io,
+	path::{Path, PathBuf},
+};
 use walkdir::{DirEntry, WalkDir};
 
 const JOBS_DIR_NAME: &str = "jobs";
@@ -40,6 +47,11 @@ struct TemplatesField {
 	templates: fetcher_config::jobs::job::TemplatesField,
 }
 
+#[derive(Deserialize, Debug)]
+struct IncludeField {
+	include: fetcher_config::jobs::job::IncludeField,
+}
+
 pub fn get_all(filter: Option<&[JobFilter]>, cx: Context) -> Result<Jobs> {
 	cx.conf_paths
 		.iter()
@@ -138,9 +150,105 @@ pub fn get_all_from(
 
 #[tracing::instrument(skip(cx))]
 pub fn get(path: &Path, name: JobName, cx: Context) -> Result<Option<(JobName, JobWithTaskNames)>> {
+	let Some(job) = extract(path, cx)? else {
+		return Ok(None);
+	};
+
+	Ok(Some(
+		job.decode_from_conf(name, &ExternalDataFromDataDir { cx })?,
+	))
+}
+
+/// Parse, check, and decode all enabled jobs matching the filter, collecting every problem found along the way instead of stopping at the first one
+pub fn verify_all(filter: Option<&[JobFilter]>, cx: Context) -> Vec<Report> {
+	let mut problems = Vec::new();
+
+	for cfg_dir in &cx.conf_paths {
+		let jobs_dir = cfg_dir.join(JOBS_DIR_NAME);
+
+		for dir_entry in WalkDir::new(&jobs_dir).follow_links(true) {
+			let Some(job_config_path) = dir_entry_is_job_config_file(&dir_entry) else {
+				continue;
+			};
+			let job_name = JobName::from_job_config_path(job_config_path, &jobs_dir);
+
+			if let Some(filter) = filter
+				&& !filter.iter().any(|filter| filter.job_matches(&job_name))
+			{
+				continue;
+			}
+
+			tracing::debug!("Verifying job {job_name}");
+			let invalid_config = || format!("invalid config at: {}", job_config_path.display());
+
+			let job = match extract(job_config_path, cx) {
+				Ok(Some(job)) => job,
+				Ok(None) => continue,
+				Err(e) => {
+					problems.push(e.wrap_err(invalid_config()));
+					continue;
+				}
+			};
+
+			for lint in job.lint() {
+				problems.push(eyre!("{lint}").wrap_err(invalid_config()));
+			}
+
+			if let Err(e) = job.decode_from_conf(job_name, &ExternalDataFromDataDir { cx }) {
+				problems.push(Report::from(e).wrap_err(invalid_config()));
+			}
+		}
+	}
+
+	problems
+}
+
+/// Parse a job config together with all of its templates. Returns None if the job is disabled
+fn extract(path: &Path, cx: Context) -> Result<Option<ConfigJob>> {
 	tracing::trace!("Parsing a job from file");
 
+	let full_conf = merge_templates(path, cx)?;
+
+	// extract the disabled field and ignore the config if it's set to true
+	let DisabledField { disabled } = full_conf.extract()?;
+	if disabled.unwrap_or(false) {
+		tracing::trace!("Job is disabled, skipping...");
+		return Ok(None);
+	}
+
+	let full_conf = env_vars::interpolate(full_conf)?;
+	Ok(Some(
+		full_conf
+			.extract()
+			.map_err(settings::config::explain_parse_error)?,
+	))
+}
+
+fn merge_templates(path: &Path, cx: Context) -> Result<Figment> {
+	merge_with_includes(path, cx, &mut Vec::new())
+}
+
+/// Merge the config at `path` on top of its templates and included files, recursively.
+///
+/// `include_stack` contains all files that are currently being included, to detect include cycles
+fn merge_with_includes(
+	path: &Path,
+	cx: Context,
+	include_stack: &mut Vec<PathBuf>,
+) -> Result<Figment> {
+	let canonical_path = path
+		.canonicalize()
+		.wrap_err_with(|| format!("Can't include {}", path.display()))?;
+
+	if include_stack.contains(&canonical_path) {
+		return Err(eyre!(
+			"Include cycle detected: {} includes itself",
+			path.display()
+		));
+	}
+
 	let TemplatesField { templates } = Figment::new().merge(Yaml::file(path)).extract()?;
+	let IncludeField { include } = Figment::new().merge(Yaml::file(path)).extract()?;
 
 	let mut full_conf = Figment::new();
 
@@ -156,21 +264,24 @@ pub fn get(path: &Path, name: JobName, cx: Context) -> Result<Option<(JobName, J
 		}
 	}
 
-	// append the config itself
-	let full_conf = full_conf.merge(Yaml::file(path));
+	// prepend included files, relative to the current one
+	if let Some(include) = include {
+		include_stack.push(canonical_path);
 
-	// extract the disabled field and ignore the config if it's set to true
-	let DisabledField { disabled } = full_conf.extract()?;
-	if disabled.unwrap_or(false) {
-		tracing::trace!("Job is disabled, skipping...");
-		return Ok(None);
-	}
+		for included in include {
+			let included_path = path
+				.parent()
+				.map_or_else(|| included.clone(), |dir| dir.join(&included));
+			tracing::trace!("Including {included_path:?}");
 
-	let job: ConfigJob = full_conf.extract()?;
+			full_conf = full_conf.merge(merge_with_includes(&included_path, cx, include_stack)?);
+		}
 
-	Ok(Some(
-		job.decode_from_conf(name, &ExternalDataFromDataDir { cx })?,
-	))
+		include_stack.pop();
+	}
+
+	// append the config itself
+	Ok(full_conf.merge(Yaml::file(path)))
 }
 
 /// Checks if the dir entry is a valid job config file
@@ -4,7 +4,7 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use std::path::PathBuf;
+use std::{path::PathBuf, str::FromStr};
 
 pub type StaticContext = &'static Context;
 
@@ -13,4 +13,57 @@ pub struct Context {
 	pub data_path: PathBuf,
 	pub conf_paths: Vec<PathBuf>,
 	pub log_path: PathBuf,
+	pub storage: Storage,
+	pub secrets: Secrets,
+}
+
+/// Where runtime state, i.e. read filters and entry to message maps, is kept
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Storage {
+	/// A separate JSON file per task in the data directory
+	#[default]
+	Files,
+
+	/// A single `SQLite` database in the data directory
+	Sqlite,
+}
+
+impl FromStr for Storage {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"files" => Ok(Self::Files),
+			"sqlite" => Ok(Self::Sqlite),
+			other => Err(format!(
+				"unknown storage \"{other}\", expected \"files\" or \"sqlite\""
+			)),
+		}
+	}
+}
+
+/// Where login credentials are kept
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Secrets {
+	/// A separate JSON file per service in the data directory
+	#[default]
+	Files,
+
+	/// The OS keyring, i.e. Secret Service, macOS Keychain, or Windows Credential Manager.
+	/// Credentials that are missing from it are still read from the data directory
+	Keyring,
+}
+
+impl FromStr for Secrets {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"files" => Ok(Self::Files),
+			"keyring" => Ok(Self::Keyring),
+			other => Err(format!(
+				"unknown secret storage \"{other}\", expected \"files\" or \"keyring\""
+			)),
+		}
+	}
 }
@@ -7,17 +7,42 @@
 pub mod discord;
 pub mod email_password;
 pub mod google_oauth2;
+pub mod microsoft_oauth2;
+pub mod reddit;
 pub mod runtime_external_save;
+pub mod slack;
 pub mod telegram;
 
-use super::proj_dirs;
+use super::{
+	config::env_vars,
+	context::{Secrets, StaticContext as Context},
+	proj_dirs,
+};
+
+use fetcher_config::jobs::external_data::ExternalDataError;
 
-use color_eyre::Result;
+use color_eyre::{Result, eyre::WrapErr};
+use figment::{
+	Figment,
+	providers::{Format, Yaml},
+};
+use serde::{Serialize, de::DeserializeOwned};
 use std::{
+	collections::HashMap,
+	fs,
 	io::{self, Write},
-	path::PathBuf,
+	path::{Path, PathBuf},
 };
 
+/// Service name of all entries in the OS keyring
+const KEYRING_SERVICE: &str = "fetcher";
+
+/// Suffix of a field that contains the path to a file to read the value of the field from, e.g. `token_file` for `token`
+const FILE_FIELD_SUFFIX: &str = "_file";
+
+/// Name of the file in the config directory that can contain the credentials of all services at once
+const SECRETS_FILE_NAME: &str = "secrets.yml";
+
 pub fn prompt_user_for(prompt: &str) -> io::Result<String> {
 	print!("{prompt}");
 	io::stdout().flush()?;
@@ -28,6 +53,146 @@ pub fn prompt_user_for(prompt: &str) -> io::Result<String> {
 	Ok(input.trim().to_owned())
 }
 
+/// Read the credentials saved as `file_name`.
+///
+/// They are read from the secrets file in the config directory if they are present there.
+/// Otherwise, if the OS keyring is used, they are read from there, falling back to the data directory if they aren't present in it or the keyring is inaccessible
+pub fn read_secret<T: DeserializeOwned>(
+	cx: Context,
+	file_name: &str,
+) -> Result<T, ExternalDataError> {
+	if let Some(secret) = read_from_secrets_file(cx, file_name)? {
+		return Ok(secret);
+	}
+
+	if cx.secrets == Secrets::Keyring {
+		match keyring::Entry::new(KEYRING_SERVICE, file_name).and_then(|e| e.get_password()) {
+			Ok(raw) => return parse_data_file(&raw, Path::new(file_name)),
+			Err(keyring::Error::NoEntry) => {
+				tracing::debug!(
+					"{file_name} isn't in the keyring, reading it from the data directory"
+				);
+			}
+			Err(e) => {
+				tracing::warn!(
+					"Can't read {file_name} from the keyring, reading it from the data directory instead: {e}"
+				);
+			}
+		}
+	}
+
+	read_data_file(&cx.data_path.join(file_name))
+}
+
+/// Read the credentials saved as `file_name` from the first secrets file in the config directories that contains them.
+///
+/// The secrets file maps the name of the service, e.g. `telegram` for `telegram.json`, to the same fields as in its data file.
+/// Environment variables can be used in it the same way as in job configs
+fn read_from_secrets_file<T: DeserializeOwned>(
+	cx: Context,
+	file_name: &str,
+) -> Result<Option<T>, ExternalDataError> {
+	let service = file_name.strip_suffix(".json").unwrap_or(file_name);
+
+	for conf_path in &cx.conf_paths {
+		let path = conf_path.join(SECRETS_FILE_NAME);
+		if !path.exists() {
+			continue;
+		}
+
+		let mut secrets: HashMap<String, serde_json::Value> =
+			env_vars::interpolate(Figment::new().merge(Yaml::file(&path)))
+				.and_then(|conf| Ok(conf.extract()?))
+				.map_err(|e| (io::Error::new(io::ErrorKind::InvalidData, e), &path))?;
+
+		if let Some(secret) = secrets.remove(service) {
+			tracing::trace!("Reading {service} credentials from {path:?}");
+			return decode_data(secret, &path).map(Some);
+		}
+	}
+
+	Ok(None)
+}
+
+/// Save the credentials as `file_name`, either to the OS keyring or to the data directory
+pub fn save_secret<T: Serialize>(cx: Context, file_name: &str, secret: &T) -> Result<()> {
+	let json = serde_json::to_string(secret)?;
+
+	match cx.secrets {
+		Secrets::Keyring => {
+			keyring::Entry::new(KEYRING_SERVICE, file_name)
+				.and_then(|e| e.set_password(&json))
+				.wrap_err_with(|| format!("keyring entry: {file_name}"))?;
+		}
+		Secrets::Files => {
+			let path = cx.data_path.join(file_name);
+
+			if let Some(parent) = path.parent() {
+				fs::create_dir_all(parent)?;
+			}
+
+			fs::write(&path, json).wrap_err_with(|| path.to_string_lossy().into_owned())?;
+		}
+	}
+
+	Ok(())
+}
+
+/// Read a JSON data file.
+///
+/// Any `<field>_file` in it is replaced with `<field>` set to the contents of the file at that path, without the trailing newline.
+/// This allows to keep secrets in separate files, e.g. Docker secrets
+pub fn read_data_file<T: DeserializeOwned>(path: &Path) -> Result<T, ExternalDataError> {
+	let raw = fs::read_to_string(path).map_err(|e| (e, path))?;
+
+	parse_data_file(&raw, path)
+}
+
+/// Parse the contents of a JSON data file at `path`. See [`read_data_file`]
+fn parse_data_file<T: DeserializeOwned>(raw: &str, path: &Path) -> Result<T, ExternalDataError> {
+	let conf: serde_json::Value = serde_json::from_str(raw).map_err(|e| (e, path))?;
+
+	decode_data(conf, path)
+}
+
+/// Replace all `<field>_file` fields in `conf` with the contents of the files they point to and decode it, see [`read_data_file`]
+fn decode_data<T: DeserializeOwned>(
+	mut conf: serde_json::Value,
+	path: &Path,
+) -> Result<T, ExternalDataError> {
+	if let Some(fields) = conf.as_object_mut() {
+		let file_fields = fields
+			.keys()
+			.filter(|key| key.ends_with(FILE_FIELD_SUFFIX))
+			.cloned()
+			.collect::<Vec<_>>();
+
+		for file_field in file_fields {
+			let Some(serde_json::Value::String(secret_path)) = fields.remove(&file_field) else {
+				return Err((
+					io::Error::new(
+						io::ErrorKind::InvalidData,
+						format!("{file_field} should be a path to a file"),
+					),
+					path,
+				)
+					.into());
+			};
+
+			let secret = fs::read_to_string(&secret_path).map_err(|e| (e, &secret_path))?;
+			let secret = secret.trim_end_matches(['\n', '\r']).to_owned();
+
+			let field = file_field
+				.strip_suffix(FILE_FIELD_SUFFIX)
+				.expect("only fields ending with the suffix are collected")
+				.to_owned();
+			fields.insert(field, secret.into());
+		}
+	}
+
+	Ok(serde_json::from_value(conf).map_err(|e| (e, path))?)
+}
+
 pub fn default_data_path() -> Result<PathBuf> {
 	#[cfg(target_os = "linux")]
 	{
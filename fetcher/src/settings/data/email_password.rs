@@ -4,38 +4,23 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use super::prompt_user_for;
+use super::{prompt_user_for, read_secret, save_secret};
 use crate::settings::context::StaticContext as Context;
 use fetcher_config::{jobs::external_data::ExternalDataError, settings::EmailPassword as Config};
 
-use color_eyre::{Result, eyre::WrapErr};
-use std::fs;
+use color_eyre::Result;
 
 const FILE_NAME: &str = "email_password.json";
 
 pub fn get(cx: Context) -> Result<String, ExternalDataError> {
-	let path = cx.data_path.join(FILE_NAME);
-
-	let raw = fs::read_to_string(&path).map_err(|e| (e, &path))?;
-	let conf: Config = serde_json::from_str(&raw).map_err(|e| (e, &path))?;
+	let conf: Config = read_secret(cx, FILE_NAME)?;
 
 	Ok(conf.decode_from_conf())
 }
 
 pub fn prompt(cx: Context) -> Result<()> {
 	let pass = prompt_user_for("Email password")?;
-	let path = cx.data_path.join(FILE_NAME);
-
-	if let Some(parent) = path.parent() {
-		fs::create_dir_all(parent)?;
-	}
-
-	fs::write(
-		&path,
-		serde_json::to_string(&Config::encode_into_conf(pass))
-			.expect("Config should always serialize to JSON without issues"),
-	)
-	.wrap_err_with(|| path.to_string_lossy().into_owned())?;
+	save_secret(cx, FILE_NAME, &Config::encode_into_conf(pass))?;
 
 	Ok(())
 }
@@ -4,22 +4,29 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use super::prompt_user_for;
+use super::{prompt_user_for, read_secret, save_secret};
 use crate::settings::context::StaticContext as Context;
 use fetcher_config::{jobs::external_data::ExternalDataError, settings::Google as Config};
 use fetcher_core as fcore;
 
-use color_eyre::{Result, eyre::WrapErr};
-use std::fs;
+use color_eyre::Result;
+use once_cell::sync::OnceCell;
+use std::sync::Arc;
+use tokio::sync::Mutex;
 
 const FILE_NAME: &str = "google_oauth2.json";
 
-pub fn get(cx: Context) -> Result<fcore::auth::Google, ExternalDataError> {
-	let path = cx.data_path.join(FILE_NAME);
-	let raw = fs::read_to_string(&path).map_err(|e| (e, &path))?;
-	let conf: Config = serde_json::from_str(&raw).map_err(|e| (e, &path))?;
+/// The authenticator shared between all tasks so that an access token is fetched only once and then reused by all of them until it expires
+static SHARED: OnceCell<Arc<Mutex<fcore::auth::Google>>> = OnceCell::new();
 
-	Ok(conf.decode_from_conf())
+pub fn get(cx: Context) -> Result<Arc<Mutex<fcore::auth::Google>>, ExternalDataError> {
+	SHARED
+		.get_or_try_init(|| {
+			let conf: Config = read_secret(cx, FILE_NAME)?;
+
+			Ok(Arc::new(Mutex::new(conf.decode_from_conf())))
+		})
+		.map(Arc::clone)
 }
 
 pub async fn prompt(cx: Context) -> Result<()> {
@@ -36,17 +43,7 @@ pub async fn prompt(cx: Context) -> Result<()> {
 
 	let gauth = fcore::auth::Google::new(client_id, client_secret, refresh_token);
 
-	let path = cx.data_path.join(FILE_NAME);
-
-	if let Some(parent) = path.parent() {
-		fs::create_dir_all(parent)?;
-	}
-
-	fs::write(
-		&path,
-		serde_json::to_string(&Config::encode_into_conf(gauth))?,
-	)
-	.wrap_err_with(|| path.to_string_lossy().into_owned())?;
+	save_secret(cx, FILE_NAME, &Config::encode_into_conf(gauth))?;
 
 	Ok(())
 }
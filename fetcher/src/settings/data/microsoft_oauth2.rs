@@ -0,0 +1,100 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::{prompt_user_for, read_secret, save_secret};
+use crate::settings::context::StaticContext as Context;
+use fetcher_config::{jobs::external_data::ExternalDataError, settings::Microsoft as Config};
+use fetcher_core::{
+	self as fcore,
+	auth::microsoft::{self, SaveRefreshToken},
+	external_save::ExternalSaveError,
+};
+
+use async_trait::async_trait;
+use color_eyre::Result;
+use once_cell::sync::OnceCell;
+use std::{io, sync::Arc};
+use tokio::sync::Mutex;
+use url::Url;
+
+const FILE_NAME: &str = "microsoft_oauth2.json";
+
+/// The authenticator shared between all tasks so that an access token is fetched only once and then reused by all of them until it expires
+static SHARED: OnceCell<Arc<Mutex<fcore::auth::Microsoft>>> = OnceCell::new();
+
+/// Saves the refresh token, that Microsoft has replaced, back to where it has been read from, together with the rest of the credentials
+#[derive(Debug)]
+struct SecretSave {
+	cx: Context,
+	client_id: String,
+	client_secret: Option<String>,
+}
+
+pub fn get(cx: Context) -> Result<Arc<Mutex<fcore::auth::Microsoft>>, ExternalDataError> {
+	SHARED
+		.get_or_try_init(|| {
+			let conf: Config = read_secret(cx, FILE_NAME)?;
+			let auth = conf.decode_from_conf();
+
+			let save = SecretSave {
+				cx,
+				client_id: auth.client_id.clone(),
+				client_secret: auth.client_secret.clone(),
+			};
+
+			Ok(Arc::new(Mutex::new(auth.with_external_save(save))))
+		})
+		.map(Arc::clone)
+}
+
+pub async fn prompt(cx: Context) -> Result<()> {
+	let client_id = prompt_user_for("Microsoft OAuth2 client id: ")?;
+	let client_secret =
+		prompt_user_for("Microsoft OAuth2 client secret (leave empty for public clients): ")?;
+	let client_secret = (!client_secret.is_empty()).then_some(client_secret);
+
+	let authorize_url = Url::parse_with_params(
+		microsoft::MICROSOFT_AUTHORIZE_URL,
+		[
+			("client_id", client_id.as_str()),
+			("response_type", "code"),
+			("redirect_uri", microsoft::MICROSOFT_REDIRECT_URI),
+			("response_mode", "query"),
+			("scope", microsoft::MICROSOFT_IMAP_SCOPE),
+		],
+	)?;
+
+	let access_code = prompt_user_for(&format!(
+		"Open the link below and paste the \"code\" parameter of the URL you are redirected to:\n{authorize_url}\nAccess code: "
+	))?;
+	let refresh_token =
+		microsoft::generate_refresh_token(&client_id, client_secret.as_deref(), &access_code)
+			.await?;
+
+	let auth = fcore::auth::Microsoft::new(client_id, client_secret, refresh_token);
+
+	save_secret(cx, FILE_NAME, &Config::encode_into_conf(auth))?;
+
+	Ok(())
+}
+
+#[async_trait]
+impl SaveRefreshToken for SecretSave {
+	async fn save_refresh_token(&mut self, refresh_token: &str) -> Result<(), ExternalSaveError> {
+		let auth = fcore::auth::Microsoft::new(
+			self.client_id.clone(),
+			self.client_secret.clone(),
+			refresh_token.to_owned(),
+		);
+
+		save_secret(self.cx, FILE_NAME, &Config::encode_into_conf(auth)).map_err(|e| {
+			ExternalSaveError {
+				source: io::Error::other(e),
+				path: Some(Box::new(FILE_NAME)),
+			}
+		})
+	}
+}
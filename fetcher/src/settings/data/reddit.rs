@@ -0,0 +1,37 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::{prompt_user_for, read_secret, save_secret};
+use crate::settings::context::StaticContext as Context;
+use fetcher_config::{jobs::external_data::ExternalDataError, settings::Reddit as Config};
+use fetcher_core::source::reddit::RedditAuth;
+
+use color_eyre::Result;
+
+const FILE_NAME: &str = "reddit.json";
+
+pub fn get(cx: Context) -> Result<RedditAuth, ExternalDataError> {
+	let conf: Config = read_secret(cx, FILE_NAME)?;
+
+	Ok(conf.decode_from_conf())
+}
+
+pub fn prompt(cx: Context) -> Result<()> {
+	let client_id = prompt_user_for("Reddit app client ID: ")?;
+	let client_secret = prompt_user_for("Reddit app client secret: ")?;
+	let username = prompt_user_for("Username of the developer of the app: ")?;
+	let password = prompt_user_for("Password of the developer of the app: ")?;
+
+	let auth = RedditAuth {
+		client_id,
+		client_secret,
+		username,
+		password,
+	};
+	save_secret(cx, FILE_NAME, &Config::encode_into_conf(auth))?;
+
+	Ok(())
+}
@@ -4,14 +4,17 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+pub mod dead_letters;
 pub mod entry_to_msg_map;
+pub mod gemini_known_hosts;
 pub mod read_filter;
+pub mod sqlite;
 
 use fetcher_core::{
 	entry::EntryId,
 	external_save::{ExternalSave, ExternalSaveError},
 	read_filter::ReadFilter,
-	sink::message::MessageId,
+	task::entry_to_msg_map::SentMessage,
 };
 
 use async_trait::async_trait;
@@ -67,11 +70,11 @@ impl ExternalSave for TruncatingFileWriter {
 
 	async fn save_entry_to_msg_map(
 		&mut self,
-		map: &HashMap<EntryId, MessageId>,
+		maps: &[HashMap<EntryId, SentMessage>],
 	) -> Result<(), ExternalSaveError> {
 		let map_conf =
 			fetcher_config::jobs::task::entry_to_msg_map::EntryToMsgMap::encode_into_conf(
-				map.clone(),
+				maps.to_vec(),
 			);
 		let s = serde_json::to_string(&map_conf)
 			.expect("An EntryToMsgMap should always be serializable");
@@ -0,0 +1,79 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::DisplayPath;
+use crate::{extentions::ErrorChainExt, settings::context::StaticContext};
+use fetcher_config::jobs::named::{JobName, TaskName};
+use fetcher_core::{
+	external_save::ExternalSaveError,
+	task::dead_letters::{DeadLetter, SaveDeadLetter},
+};
+
+use async_trait::async_trait;
+use std::{io, path::PathBuf};
+use tokio::{fs, io::AsyncWriteExt};
+
+const DEAD_LETTERS_DATA_DIR: &str = "dead_letters";
+
+/// Appends every dead letter as a line of JSON to a file.
+/// The file is shared between both storage kinds since it's meant to be read by humans
+#[derive(Debug)]
+pub struct AppendingFileWriter {
+	path: PathBuf,
+}
+
+#[must_use]
+pub fn get(job: &JobName, task: Option<&TaskName>, cx: StaticContext) -> AppendingFileWriter {
+	let mut path = cx.data_path.join(DEAD_LETTERS_DATA_DIR).join(&**job);
+
+	if let Some(task) = task {
+		path.push(&**task);
+	}
+
+	AppendingFileWriter { path }
+}
+
+#[async_trait]
+impl SaveDeadLetter for AppendingFileWriter {
+	async fn save_dead_letter(&mut self, letter: &DeadLetter<'_>) -> Result<(), ExternalSaveError> {
+		let json = serde_json::json!({
+			"failed_at": chrono::Utc::now().to_rfc3339(),
+			"sink": letter.sink_idx,
+			"entry_id": letter.entry_id.map(|id| id.0.as_str()),
+			"msg": letter.msg.to_json(),
+			"error": letter.error.display_chain(),
+		});
+
+		let mut line = json.to_string();
+		line.push('\n');
+
+		self.append(line.as_bytes())
+			.await
+			.map_err(|source| ExternalSaveError {
+				source,
+				path: Some(Box::new(DisplayPath(self.path.clone()))),
+			})
+	}
+}
+
+impl AppendingFileWriter {
+	async fn append(&self, data: &[u8]) -> io::Result<()> {
+		if let Some(parent) = self.path.parent() {
+			fs::create_dir_all(parent).await?;
+		}
+
+		let mut file = fs::OpenOptions::new()
+			.create(true)
+			.append(true)
+			.open(&self.path)
+			.await?;
+
+		file.write_all(data).await?;
+		file.flush().await?;
+
+		Ok(())
+	}
+}
@@ -6,8 +6,8 @@
 
 use std::fs;
 
-use super::TruncatingFileWriter;
-use crate::settings::context::StaticContext;
+use super::{TruncatingFileWriter, sqlite};
+use crate::settings::context::{StaticContext, Storage};
 use fetcher_config::jobs::{
 	external_data::ExternalDataError,
 	named::{JobName, TaskName},
@@ -22,6 +22,10 @@ pub fn get(
 	task: Option<&TaskName>,
 	cx: StaticContext,
 ) -> Result<EntryToMsgMap, ExternalDataError> {
+	if cx.storage == Storage::Sqlite {
+		return sqlite::entry_to_msg_map(job, task, cx);
+	}
+
 	let path = {
 		let mut path = cx.data_path.join(ENTRY_TO_MSG_MAP_DATA_DIR).join(&**job);
 
@@ -46,7 +50,7 @@ pub fn get(
 		Ok(map_raw) => {
 			let conf: EntryToMsgMapConf = serde_json::from_str(&map_raw).map_err(|e| (e, &path))?;
 
-			Ok(EntryToMsgMap::new_with_map(
+			Ok(EntryToMsgMap::new_with_maps(
 				conf.decode_from_conf(),
 				TruncatingFileWriter::new(path),
 			))
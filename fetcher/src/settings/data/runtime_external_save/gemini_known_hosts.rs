@@ -0,0 +1,98 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::{DisplayPath, TruncatingFileWriter};
+use crate::settings::context::StaticContext;
+use fetcher_config::jobs::external_data::ExternalDataError;
+use fetcher_core::{
+	external_save::ExternalSaveError,
+	source::gemini::{Fingerprint, KnownHosts, SaveKnownHosts},
+};
+
+use async_trait::async_trait;
+use once_cell::sync::OnceCell;
+use std::{collections::HashMap, fmt::Write, fs, io, sync::Arc};
+use tokio::sync::Mutex;
+
+/// A plain text file with a "<host> <fingerprint>" line per host to make it easy to remove a host by hand.
+/// It's shared between both storage kinds, as well as between all jobs
+const FILE_NAME: &str = "gemini_known_hosts";
+
+static KNOWN_HOSTS: OnceCell<Arc<Mutex<KnownHosts>>> = OnceCell::new();
+
+pub fn get(cx: StaticContext) -> Result<Arc<Mutex<KnownHosts>>, ExternalDataError> {
+	KNOWN_HOSTS
+		.get_or_try_init(|| {
+			let path = cx.data_path.join(FILE_NAME);
+
+			let hosts = match fs::read_to_string(&path) {
+				Ok(raw) => parse(&raw).map_err(|e| (e, &path))?,
+				Err(e) if e.kind() == io::ErrorKind::NotFound => {
+					tracing::debug!("Gemini known hosts file doesn't exist yet");
+					HashMap::new()
+				}
+				Err(e) => return Err((e, &path).into()),
+			};
+
+			Ok(Arc::new(Mutex::new(KnownHosts::new(
+				hosts,
+				TruncatingFileWriter::new(path),
+			))))
+		})
+		.cloned()
+}
+
+/// Keep newly trusted hosts only in memory, if the known hosts have been loaded at all
+pub async fn set_read_only() {
+	if let Some(known_hosts) = KNOWN_HOSTS.get() {
+		known_hosts.lock().await.external_save = None;
+	}
+}
+
+#[async_trait]
+impl SaveKnownHosts for TruncatingFileWriter {
+	async fn save_known_hosts(
+		&mut self,
+		hosts: &HashMap<String, Fingerprint>,
+	) -> Result<(), ExternalSaveError> {
+		let mut hosts = hosts.iter().collect::<Vec<_>>();
+		hosts.sort_unstable_by_key(|(host, _)| *host);
+
+		let mut s = String::new();
+		for (host, fingerprint) in hosts {
+			_ = writeln!(s, "{host} {fingerprint}");
+		}
+
+		self.write(s.as_bytes())
+			.await
+			.map_err(|source| ExternalSaveError {
+				source,
+				path: Some(Box::new(DisplayPath(self.path.clone()))),
+			})
+	}
+}
+
+fn parse(raw: &str) -> io::Result<HashMap<String, Fingerprint>> {
+	raw.lines()
+		.map(str::trim)
+		.filter(|line| !line.is_empty())
+		.map(|line| {
+			let (host, fingerprint) = line.split_once(char::is_whitespace).ok_or_else(|| {
+				io::Error::new(
+					io::ErrorKind::InvalidData,
+					format!("expected \"<host> <fingerprint>\", found {line:?}"),
+				)
+			})?;
+
+			let fingerprint = fingerprint
+				.trim()
+				.parse::<Fingerprint>()
+				.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+			Ok((host.to_owned(), fingerprint))
+		})
+		.collect()
+}
@@ -4,14 +4,14 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use super::TruncatingFileWriter;
-use crate::settings::context::StaticContext as Context;
+use super::{TruncatingFileWriter, sqlite};
+use crate::settings::context::{StaticContext as Context, Storage};
 use fetcher_config::jobs::{
 	external_data::ExternalDataError,
 	named::{JobName, TaskName},
 	read_filter::{Kind as ReadFilterKind, ReadFilter as ReadFilterConf},
 };
-use fetcher_core::read_filter::ReadFilter;
+use fetcher_core::read_filter::{ReadFilter, ReadListLimits};
 
 use std::fs;
 
@@ -22,8 +22,13 @@ pub fn get(
 	job: &JobName,
 	task: Option<&TaskName>,
 	expected_rf_kind: ReadFilterKind,
+	read_list_limits: ReadListLimits,
 	cx: Context,
 ) -> Result<Box<dyn ReadFilter>, ExternalDataError> {
+	if cx.storage == Storage::Sqlite {
+		return sqlite::read_filter(job, task, expected_rf_kind, read_list_limits, cx);
+	}
+
 	let path = {
 		let mut path = cx.data_path.join(READ_DATA_DIR).join(&**job);
 
@@ -54,9 +59,9 @@ pub fn get(
 				));
 			}
 
-			return Ok(conf.decode_from_conf(TruncatingFileWriter::new(path)));
+			return Ok(conf.decode_from_conf(TruncatingFileWriter::new(path), read_list_limits));
 		}
 	}
 
-	Ok(expected_rf_kind.new_from_kind(TruncatingFileWriter::new(path)))
+	Ok(expected_rf_kind.new_from_kind(TruncatingFileWriter::new(path), read_list_limits))
 }
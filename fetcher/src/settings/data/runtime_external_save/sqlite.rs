@@ -0,0 +1,600 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Keeps read filters and entry to message maps of all tasks in a single `SQLite` database
+//! instead of a separate file per task
+
+use super::DisplayPath;
+use crate::settings::context::StaticContext as Context;
+use fetcher_config::jobs::{
+	external_data::ExternalDataError,
+	named::{JobName, TaskName},
+	read_filter::Kind as ReadFilterKind,
+};
+use fetcher_core::{
+	entry::EntryId,
+	external_save::{ExternalSave, ExternalSaveError},
+	read_filter::{ExternalSaveRFWrapper, Newer, NotPresent, ReadFilter, ReadListLimits},
+	sink::message::{ContentHash, MessageId},
+	task::entry_to_msg_map::{EntryToMsgMap, SentMessage},
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use once_cell::sync::OnceCell;
+use rusqlite::{Connection, OptionalExtension, params};
+use std::{
+	collections::HashMap,
+	io,
+	path::PathBuf,
+	sync::{Arc, Mutex},
+};
+
+const DB_FILE: &str = "state.sqlite3";
+
+const SCHEMA: &str = "
+	CREATE TABLE IF NOT EXISTS read_filter (
+		job TEXT NOT NULL,
+		task TEXT NOT NULL,
+		kind TEXT NOT NULL,
+		PRIMARY KEY (job, task)
+	);
+
+	CREATE TABLE IF NOT EXISTS read_entry (
+		job TEXT NOT NULL,
+		task TEXT NOT NULL,
+		id TEXT NOT NULL,
+		read_at INTEGER NOT NULL,
+		PRIMARY KEY (job, task, id)
+	);
+
+	CREATE TABLE IF NOT EXISTS entry_to_msg (
+		job TEXT NOT NULL,
+		task TEXT NOT NULL,
+		sink INTEGER NOT NULL,
+		entry_id TEXT NOT NULL,
+		msg_id INTEGER NOT NULL,
+		PRIMARY KEY (job, task, sink, entry_id)
+	);
+";
+
+/// Changes to [`SCHEMA`] made after it had been released, applied in order to databases created before them.
+/// How many of them have already been applied is kept in `user_version`
+const MIGRATIONS: &[&str] = &[
+	// hash of the contents of the message to edit it only if they have changed
+	"ALTER TABLE entry_to_msg ADD COLUMN hash INTEGER;",
+];
+
+type Db = Arc<Mutex<Connection>>;
+
+static DB: OnceCell<Db> = OnceCell::new();
+
+/// An [`ExternalSave`] that saves into the row of the task in the `SQLite` database
+#[derive(Debug)]
+pub struct SqliteSave {
+	db: Db,
+	path: PathBuf,
+	job: String,
+	task: String,
+}
+
+/// The part of a read filter that is saved to the database.
+/// It's copied out of the read filter to be moved to the blocking thread that does the saving
+enum ReadFilterState {
+	Newer {
+		last_read: Option<EntryId>,
+	},
+	NotPresent {
+		newest: Option<(EntryId, DateTime<Utc>)>,
+		oldest_read_at: Option<DateTime<Utc>>,
+	},
+}
+
+#[tracing::instrument(level = "debug", skip(cx))]
+pub fn read_filter(
+	job: &JobName,
+	task: Option<&TaskName>,
+	expected_rf_kind: ReadFilterKind,
+	read_list_limits: ReadListLimits,
+	cx: Context,
+) -> Result<Box<dyn ReadFilter>, ExternalDataError> {
+	let save = SqliteSave::new(job, task, cx)?;
+	load_read_filter(save, expected_rf_kind, read_list_limits)
+}
+
+fn load_read_filter(
+	save: SqliteSave,
+	expected_rf_kind: ReadFilterKind,
+	read_list_limits: ReadListLimits,
+) -> Result<Box<dyn ReadFilter>, ExternalDataError> {
+	let db_err = |e| (to_io_err(e), &save.path);
+
+	let (kind, read_list) = {
+		let conn = save.db.lock().expect("lock should never be poisoned");
+
+		let kind = conn
+			.query_row(
+				"SELECT kind FROM read_filter WHERE job = ?1 AND task = ?2",
+				params![save.job, save.task],
+				|row| row.get::<_, String>(0),
+			)
+			.optional()
+			.map_err(db_err)?;
+
+		let read_list = conn
+			.prepare(
+				"SELECT id, read_at FROM read_entry WHERE job = ?1 AND task = ?2 ORDER BY read_at",
+			)
+			.and_then(|mut stmt| {
+				stmt.query_map(params![save.job, save.task], |row| {
+					Ok((
+						EntryId(row.get(0)?),
+						DateTime::<Utc>::from_timestamp_micros(row.get(1)?).unwrap_or_default(),
+					))
+				})?
+				.collect::<Result<Vec<_>, _>>()
+			})
+			.map_err(db_err)?;
+
+		drop(conn);
+		(kind, read_list)
+	};
+
+	let Some(kind) = kind else {
+		tracing::debug!("Read filter isn't saved in the database yet");
+		return Ok(expected_rf_kind.new_from_kind(save, read_list_limits));
+	};
+
+	let kind: ReadFilterKind = serde_json::from_value(serde_json::Value::String(kind))
+		.map_err(|e| (io::Error::from(e), &save.path))?;
+
+	// the old read filter saved in the database is of the same type as the one set in config
+	if kind != expected_rf_kind {
+		return Err(ExternalDataError::new_rf_incompat_with_path(
+			expected_rf_kind,
+			kind,
+			&save.path,
+		));
+	}
+
+	Ok(match kind {
+		ReadFilterKind::NewerThanRead => Box::new(ExternalSaveRFWrapper {
+			rf: Newer {
+				last_read_id: read_list.into_iter().last().map(|(id, _)| id),
+			},
+			external_save: Some(save),
+		}),
+		ReadFilterKind::NotPresentInReadList => Box::new(ExternalSaveRFWrapper {
+			rf: {
+				let mut rf = NotPresent::with_limits(read_list_limits);
+				rf.extend(read_list);
+				rf
+			},
+			external_save: Some(save),
+		}),
+		ReadFilterKind::NotOlderThan => {
+			unreachable!("{kind} read filter has no state and is never saved")
+		}
+	})
+}
+
+#[tracing::instrument(level = "debug", skip(cx))]
+pub fn entry_to_msg_map(
+	job: &JobName,
+	task: Option<&TaskName>,
+	cx: Context,
+) -> Result<EntryToMsgMap, ExternalDataError> {
+	let save = SqliteSave::new(job, task, cx)?;
+	load_entry_to_msg_map(save)
+}
+
+fn load_entry_to_msg_map(save: SqliteSave) -> Result<EntryToMsgMap, ExternalDataError> {
+	let rows = {
+		let conn = save.db.lock().expect("lock should never be poisoned");
+
+		conn.prepare(
+			"SELECT sink, entry_id, msg_id, hash FROM entry_to_msg WHERE job = ?1 AND task = ?2",
+		)
+		.and_then(|mut stmt| {
+			stmt.query_map(params![save.job, save.task], |row| {
+				Ok((
+					row.get::<_, u32>(0)? as usize,
+					EntryId(row.get(1)?),
+					SentMessage {
+						id: MessageId(row.get(2)?),
+						hash: row.get::<_, Option<i64>>(3)?.map(ContentHash),
+					},
+				))
+			})?
+			.collect::<Result<Vec<_>, _>>()
+		})
+		.map_err(|e| (to_io_err(e), &save.path))?
+	};
+
+	let mut maps: Vec<HashMap<EntryId, SentMessage>> = Vec::new();
+	for (sink, entry_id, msg) in rows {
+		if maps.len() <= sink {
+			maps.resize_with(sink + 1, HashMap::new);
+		}
+
+		maps[sink].insert(entry_id, msg);
+	}
+
+	Ok(EntryToMsgMap::new_with_maps(maps, save))
+}
+
+impl SqliteSave {
+	fn new(job: &JobName, task: Option<&TaskName>, cx: Context) -> Result<Self, ExternalDataError> {
+		let path = cx.data_path.join(DB_FILE);
+
+		let db = DB
+			.get_or_try_init(|| open(&path).map(|conn| Arc::new(Mutex::new(conn))))
+			.map_err(|e| (e, &path))?;
+
+		Ok(Self {
+			db: Arc::clone(db),
+			path,
+			job: (**job).to_owned(),
+			// tasks without a name are stored with an empty one
+			task: task.map(|t| (**t).to_owned()).unwrap_or_default(),
+		})
+	}
+
+	/// Runs `f` with the database connection, job, and task names on a blocking thread
+	/// not to block the async runtime while waiting for the lock or the disk
+	async fn with_conn<T, F>(&self, f: F) -> Result<T, ExternalSaveError>
+	where
+		T: Send + 'static,
+		F: FnOnce(&mut Connection, &str, &str) -> rusqlite::Result<T> + Send + 'static,
+	{
+		let db = Arc::clone(&self.db);
+		let job = self.job.clone();
+		let task = self.task.clone();
+
+		let res = tokio::task::spawn_blocking(move || {
+			let mut conn = db.lock().expect("lock should never be poisoned");
+			f(&mut conn, &job, &task)
+		})
+		.await
+		.map_err(|e| self.save_err(io::Error::other(e)))?;
+
+		res.map_err(|e| self.save_err(to_io_err(e)))
+	}
+
+	fn save_err(&self, source: io::Error) -> ExternalSaveError {
+		ExternalSaveError {
+			source,
+			path: Some(Box::new(DisplayPath(self.path.clone()))),
+		}
+	}
+}
+
+#[async_trait]
+impl ExternalSave for SqliteSave {
+	async fn save_read_filter(
+		&mut self,
+		read_filter: &dyn ReadFilter,
+	) -> Result<(), ExternalSaveError> {
+		let state = {
+			let read_filter = read_filter.as_any().await;
+
+			if let Some(newer) = read_filter.downcast_ref::<Newer>() {
+				ReadFilterState::Newer {
+					last_read: newer.last_read().cloned(),
+				}
+			} else if let Some(not_present) = read_filter.downcast_ref::<NotPresent>() {
+				ReadFilterState::NotPresent {
+					newest: not_present.iter().last().cloned(),
+					oldest_read_at: not_present.iter().next().map(|(_, read_at)| *read_at),
+				}
+			} else {
+				tracing::warn!("Unknown read filter type, not saving it");
+				return Ok(());
+			}
+		};
+
+		self.with_conn(move |conn, job, task| write_read_filter(conn, job, task, state))
+			.await
+	}
+
+	async fn save_entry_to_msg_map(
+		&mut self,
+		maps: &[HashMap<EntryId, SentMessage>],
+	) -> Result<(), ExternalSaveError> {
+		let maps = maps.to_vec();
+
+		self.with_conn(move |conn, job, task| write_entry_to_msg_map(conn, job, task, &maps))
+			.await
+	}
+}
+
+fn open(path: &PathBuf) -> io::Result<Connection> {
+	if let Some(parent) = path.parent() {
+		std::fs::create_dir_all(parent)?;
+	}
+
+	let conn = Connection::open(path).map_err(to_io_err)?;
+	init(&conn).map_err(to_io_err)?;
+
+	Ok(conn)
+}
+
+/// Create the tables if they don't exist yet and bring them up to date
+fn init(conn: &Connection) -> rusqlite::Result<()> {
+	conn.execute_batch(SCHEMA)?;
+	migrate(conn)
+}
+
+/// Apply the [`MIGRATIONS`] that haven't been applied to the database yet
+fn migrate(conn: &Connection) -> rusqlite::Result<()> {
+	let applied: u32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+	for (version, migration) in (1..).zip(MIGRATIONS).skip(applied as usize) {
+		tracing::debug!("Migrating the database to version {version}");
+
+		conn.execute_batch(migration)?;
+		conn.pragma_update(None, "user_version", version)?;
+	}
+
+	Ok(())
+}
+
+fn write_read_filter(
+	conn: &mut Connection,
+	job: &str,
+	task: &str,
+	state: ReadFilterState,
+) -> rusqlite::Result<()> {
+	let tx = conn.transaction()?;
+
+	let kind = match state {
+		ReadFilterState::Newer { last_read } => {
+			// only the last read id is needed
+			tx.execute(
+				"DELETE FROM read_entry WHERE job = ?1 AND task = ?2",
+				params![job, task],
+			)?;
+
+			if let Some(last_read_id) = last_read {
+				insert_read_entry(&tx, job, task, &last_read_id, Utc::now())?;
+			}
+
+			ReadFilterKind::NewerThanRead
+		}
+		ReadFilterState::NotPresent {
+			newest,
+			oldest_read_at,
+		} => {
+			// only the newest entry can be new since the read filter is saved every time an entry is marked as read
+			if let Some((id, read_at)) = newest {
+				insert_read_entry(&tx, job, task, &id, read_at)?;
+			}
+
+			// drop everything the read filter has already evicted
+			if let Some(oldest_read_at) = oldest_read_at {
+				tx.execute(
+					"DELETE FROM read_entry WHERE job = ?1 AND task = ?2 AND read_at < ?3",
+					params![job, task, oldest_read_at.timestamp_micros()],
+				)?;
+			}
+
+			ReadFilterKind::NotPresentInReadList
+		}
+	};
+
+	let kind = serde_json::to_value(kind)
+		.ok()
+		.and_then(|v| v.as_str().map(ToOwned::to_owned))
+		.expect("read filter kind should always serialize to a string");
+
+	tx.execute(
+		"INSERT OR REPLACE INTO read_filter (job, task, kind) VALUES (?1, ?2, ?3)",
+		params![job, task, kind],
+	)?;
+
+	tx.commit()
+}
+
+fn write_entry_to_msg_map(
+	conn: &mut Connection,
+	job: &str,
+	task: &str,
+	maps: &[HashMap<EntryId, SentMessage>],
+) -> rusqlite::Result<()> {
+	let tx = conn.transaction()?;
+
+	// the maps are always saved whole, so drop the entries that have been removed from them, too
+	tx.execute(
+		"DELETE FROM entry_to_msg WHERE job = ?1 AND task = ?2",
+		params![job, task],
+	)?;
+
+	{
+		let mut stmt = tx.prepare(
+			"INSERT OR REPLACE INTO entry_to_msg (job, task, sink, entry_id, msg_id, hash) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+		)?;
+
+		for (sink, map) in maps.iter().enumerate() {
+			let sink = u32::try_from(sink).expect("a task should never have that many sinks");
+
+			for (entry_id, msg) in map {
+				stmt.execute(params![
+					job,
+					task,
+					sink,
+					entry_id.0,
+					msg.id.0,
+					msg.hash.map(|hash| hash.0)
+				])?;
+			}
+		}
+	}
+
+	tx.commit()
+}
+
+fn insert_read_entry(
+	conn: &Connection,
+	job: &str,
+	task: &str,
+	id: &EntryId,
+	read_at: DateTime<Utc>,
+) -> rusqlite::Result<usize> {
+	conn.execute(
+		"INSERT OR REPLACE INTO read_entry (job, task, id, read_at) VALUES (?1, ?2, ?3, ?4)",
+		params![job, task, id.0, read_at.timestamp_micros()],
+	)
+}
+
+fn to_io_err(e: rusqlite::Error) -> io::Error {
+	io::Error::other(e)
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used)]
+
+	use super::*;
+
+	use fetcher_core::read_filter::MarkAsRead;
+
+	fn in_memory_save(task: &str) -> SqliteSave {
+		let conn = Connection::open_in_memory().unwrap();
+		init(&conn).unwrap();
+
+		SqliteSave {
+			db: Arc::new(Mutex::new(conn)),
+			path: PathBuf::from(":memory:"),
+			job: "job".to_owned(),
+			task: task.to_owned(),
+		}
+	}
+
+	fn same_db(save: &SqliteSave, task: &str) -> SqliteSave {
+		SqliteSave {
+			db: Arc::clone(&save.db),
+			path: save.path.clone(),
+			job: save.job.clone(),
+			task: task.to_owned(),
+		}
+	}
+
+	#[tokio::test]
+	async fn read_filter_round_trip() {
+		let mut save = in_memory_save("task");
+
+		let mut rf = NotPresent::new();
+		for id in ["1", "2", "3"] {
+			rf.mark_as_read(&EntryId(id.to_owned())).await.unwrap();
+			save.save_read_filter(&rf).await.unwrap();
+		}
+
+		let loaded = load_read_filter(
+			same_db(&save, "task"),
+			ReadFilterKind::NotPresentInReadList,
+			ReadListLimits::default(),
+		)
+		.unwrap();
+		let loaded = loaded.as_any().await;
+		let loaded = loaded.downcast_ref::<NotPresent>().unwrap();
+
+		let ids = loaded
+			.iter()
+			.map(|(id, _)| id.0.as_str())
+			.collect::<Vec<_>>();
+		assert_eq!(ids, ["1", "2", "3"]);
+
+		// a different task doesn't see the read entries of this one
+		let other = load_read_filter(
+			same_db(&save, "other"),
+			ReadFilterKind::NotPresentInReadList,
+			ReadListLimits::default(),
+		)
+		.unwrap();
+		let other = other.as_any().await;
+		assert!(other.downcast_ref::<NotPresent>().unwrap().is_empty());
+
+		// the saved read filter is of a different type than the one expected
+		assert!(
+			load_read_filter(
+				same_db(&save, "task"),
+				ReadFilterKind::NewerThanRead,
+				ReadListLimits::default(),
+			)
+			.is_err()
+		);
+	}
+
+	#[tokio::test]
+	async fn entry_to_msg_map_round_trip() {
+		let mut save = in_memory_save("task");
+
+		let with_hash = SentMessage {
+			id: MessageId(1),
+			hash: Some(ContentHash(-42)),
+		};
+		let without_hash = SentMessage {
+			id: MessageId(2),
+			hash: None,
+		};
+
+		let maps = vec![
+			HashMap::from([(EntryId("a".to_owned()), with_hash)]),
+			HashMap::from([(EntryId("b".to_owned()), without_hash)]),
+		];
+		save.save_entry_to_msg_map(&maps).await.unwrap();
+
+		let loaded = load_entry_to_msg_map(same_db(&save, "task")).unwrap();
+
+		let a = loaded.get_sent(0, &EntryId("a".to_owned())).unwrap();
+		assert_eq!(a.id.0, 1);
+		assert_eq!(a.hash.map(|hash| hash.0), Some(-42));
+
+		let b = loaded.get_sent(1, &EntryId("b".to_owned())).unwrap();
+		assert_eq!(b.id.0, 2);
+		assert!(b.hash.is_none());
+
+		assert!(loaded.get_sent(0, &EntryId("b".to_owned())).is_none());
+	}
+
+	#[test]
+	fn migrate_adds_hash_column() {
+		let conn = Connection::open_in_memory().unwrap();
+
+		// the schema of the entry_to_msg table before the hash column had been added to it
+		conn.execute_batch(
+			"CREATE TABLE entry_to_msg (
+				job TEXT NOT NULL,
+				task TEXT NOT NULL,
+				sink INTEGER NOT NULL,
+				entry_id TEXT NOT NULL,
+				msg_id INTEGER NOT NULL,
+				PRIMARY KEY (job, task, sink, entry_id)
+			);
+			INSERT INTO entry_to_msg (job, task, sink, entry_id, msg_id) VALUES ('job', 'task', 0, 'a', 1);",
+		)
+		.unwrap();
+
+		init(&conn).unwrap();
+
+		let version: u32 = conn
+			.query_row("PRAGMA user_version", [], |row| row.get(0))
+			.unwrap();
+		assert_eq!(version as usize, MIGRATIONS.len());
+
+		let hash: Option<i64> = conn
+			.query_row(
+				"SELECT hash FROM entry_to_msg WHERE entry_id = 'a'",
+				[],
+				|row| row.get(0),
+			)
+			.unwrap();
+		assert!(hash.is_none());
+
+		// running it again on an up to date database is a no-op
+		init(&conn).unwrap();
+	}
+}
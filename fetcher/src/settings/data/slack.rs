@@ -0,0 +1,28 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::{prompt_user_for, read_secret, save_secret};
+use crate::settings::context::StaticContext as Context;
+use fetcher_config::{jobs::external_data::ExternalDataError, settings::Slack as Config};
+
+use color_eyre::{Result, eyre::WrapErr};
+use url::Url;
+
+const FILE_NAME: &str = "slack.json";
+
+pub fn get(cx: Context) -> Result<Url, ExternalDataError> {
+	let conf: Config = read_secret(cx, FILE_NAME)?;
+
+	Ok(conf.decode_from_conf())
+}
+
+pub fn prompt(cx: Context) -> Result<()> {
+	let webhook_url = prompt_user_for("Slack incoming webhook URL: ")?;
+	let webhook_url = Url::parse(&webhook_url).wrap_err("invalid webhook URL")?;
+	save_secret(cx, FILE_NAME, &Config::encode_into_conf(webhook_url))?;
+
+	Ok(())
+}
@@ -13,7 +13,16 @@ use fetcher_config::jobs::{
 	named::{JobName, TaskName},
 	read_filter::Kind as ReadFilterKind,
 };
-use fetcher_core::{auth, read_filter::ReadFilter, task::entry_to_msg_map::EntryToMsgMap};
+use fetcher_core::{
+	auth,
+	read_filter::{ReadFilter, ReadListLimits},
+	source::{gemini::KnownHosts, reddit::RedditAuth},
+	task::{dead_letters::SaveDeadLetter, entry_to_msg_map::EntryToMsgMap},
+};
+
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use url::Url;
 
 pub struct ExternalDataFromDataDir {
 	pub cx: StaticContext,
@@ -22,10 +31,14 @@ pub struct ExternalDataFromDataDir {
 impl ProvideExternalData for ExternalDataFromDataDir {
 	type ReadFilter = Box<dyn ReadFilter>;
 
-	fn google_oauth2(&self) -> ExternalDataResult<auth::Google> {
+	fn google_oauth2(&self) -> ExternalDataResult<Arc<Mutex<auth::Google>>> {
 		data::google_oauth2::get(self.cx).into()
 	}
 
+	fn microsoft_oauth2(&self) -> ExternalDataResult<Arc<Mutex<auth::Microsoft>>> {
+		data::microsoft_oauth2::get(self.cx).into()
+	}
+
 	fn email_password(&self) -> ExternalDataResult<String> {
 		data::email_password::get(self.cx).into()
 	}
@@ -38,13 +51,33 @@ impl ProvideExternalData for ExternalDataFromDataDir {
 		data::discord::get(self.cx).into()
 	}
 
+	fn slack_webhook_url(&self) -> ExternalDataResult<Url> {
+		data::slack::get(self.cx).into()
+	}
+
+	fn reddit_auth(&self) -> ExternalDataResult<RedditAuth> {
+		data::reddit::get(self.cx).into()
+	}
+
+	fn gemini_known_hosts(&self) -> ExternalDataResult<Arc<Mutex<KnownHosts>>> {
+		data::runtime_external_save::gemini_known_hosts::get(self.cx).into()
+	}
+
 	fn read_filter(
 		&self,
 		job: &JobName,
 		task: Option<&TaskName>,
 		expected_rf: ReadFilterKind,
+		read_list_limits: ReadListLimits,
 	) -> ExternalDataResult<Self::ReadFilter> {
-		data::runtime_external_save::read_filter::get(job, task, expected_rf, self.cx).into()
+		data::runtime_external_save::read_filter::get(
+			job,
+			task,
+			expected_rf,
+			read_list_limits,
+			self.cx,
+		)
+		.into()
 	}
 
 	fn entry_to_msg_map(
@@ -55,6 +88,16 @@ impl ProvideExternalData for ExternalDataFromDataDir {
 		data::runtime_external_save::entry_to_msg_map::get(job, task, self.cx).into()
 	}
 
+	fn dead_letters(
+		&self,
+		job: &JobName,
+		task: Option<&TaskName>,
+	) -> ExternalDataResult<Box<dyn SaveDeadLetter>> {
+		ExternalDataResult::Ok(Box::new(data::runtime_external_save::dead_letters::get(
+			job, task, self.cx,
+		)))
+	}
+
 	fn import(&self, name: &str) -> ExternalDataResult<Vec<ActionConfig>> {
 		match config::actions::find(name, self.cx) {
 			Ok(Some(x)) => ExternalDataResult::Ok(x),
@@ -0,0 +1,437 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! An HTTP server that runs a task right away when a POST request is sent to `/trigger/<job>/<task>`, e.g. by a webhook.
+//!
+//! It also receives the callbacks of `WebSub` hubs at `/websub/<job>/<task>` and runs the task when the hub notifies about an update of its feed
+
+use crate::extentions::ErrorChainExt;
+use fetcher_config::jobs::named::{JobName, JobWithTaskNames};
+use fetcher_core::websub::{self, Hub};
+
+use color_eyre::{
+	Result,
+	eyre::{WrapErr, eyre},
+};
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::{
+	Method, Request, Response, StatusCode,
+	body::{Bytes, Incoming},
+	header::AUTHORIZATION,
+	server::conn::http1,
+	service::service_fn,
+};
+use hyper_util::rt::TokioIo;
+use std::{
+	collections::HashMap,
+	convert::Infallible,
+	net::SocketAddr,
+	sync::{Arc, OnceLock},
+	time::Duration,
+};
+use tokio::{
+	net::TcpListener,
+	sync::mpsc::{self, error::TrySendError},
+	time::{sleep, timeout},
+};
+use url::Url;
+
+/// The environment variable that contains the secret every request has to be authorized with
+pub const SECRET_ENV_VAR: &str = "FETCHER_TRIGGER_SECRET";
+
+/// How many triggers of a single job can be waiting to be run at the same time
+const TRIGGER_QUEUE_LEN: usize = 16;
+
+/// The largest notification body of a `WebSub` hub that is accepted
+const MAX_NOTIFICATION_LEN: usize = 4 * 1024 * 1024;
+
+/// How long to wait for the hub to verify a subscription before trying to subscribe again
+const VERIFICATION_TIMEOUT: Duration = Duration::from_mins(10);
+
+/// How long to wait before retrying after the hub couldn't be discovered or subscribed to
+const WEBSUB_RETRY_DELAY: Duration = Duration::from_hours(1);
+
+/// How long a subscription lasts if the hub hasn't said
+const DEFAULT_LEASE: Duration = Duration::from_hours(24);
+
+#[derive(Debug)]
+struct Triggers {
+	secret: String,
+
+	/// The trigger of the job and the index of the task in it by "job/task", or just by "job" for jobs with a single task
+	tasks: HashMap<String, (mpsc::Sender<usize>, usize)>,
+
+	/// `WebSub` subscriptions by the path of their callback
+	subscriptions: HashMap<String, Arc<Subscription>>,
+}
+
+/// A `WebSub` subscription to the feed of a task
+#[derive(Debug)]
+struct Subscription {
+	feed: Url,
+	callback: Url,
+
+	/// The secret the hub signs notifications with
+	secret: String,
+
+	/// The hub of the feed, once it has been discovered
+	hub: OnceLock<Hub>,
+
+	/// The trigger of the job and the index of the task in it
+	trigger: (mpsc::Sender<usize>, usize),
+
+	/// Sends the lease of the subscription once the hub has verified it
+	leases: mpsc::Sender<Duration>,
+}
+
+/// Serve triggers of the tasks of all `jobs` on the `address`.
+/// Requests have to contain an `Authorization: Bearer <secret>` header with the secret set in [`SECRET_ENV_VAR`].
+///
+/// If `websub_callback` is set, tasks with `WebSub` enabled subscribe to the hubs of their feeds with callbacks under it
+pub async fn set_up<'a>(
+	address: SocketAddr,
+	websub_callback: Option<&Url>,
+	jobs: impl IntoIterator<Item = (&'a JobName, &'a mut JobWithTaskNames)>,
+) -> Result<()> {
+	let secret = std::env::var(SECRET_ENV_VAR)
+		.ok()
+		.filter(|s| !s.is_empty())
+		.ok_or_else(|| eyre!("{SECRET_ENV_VAR} has to be set to accept triggers"))?;
+
+	let mut tasks = HashMap::new();
+	let mut subscriptions = Vec::new();
+	for (job_name, job) in jobs {
+		let (tx, rx) = mpsc::channel(TRIGGER_QUEUE_LEN);
+		job.inner.trigger = Some(rx);
+
+		let task_names = match &job.task_names {
+			Some(task_names) => task_names
+				.iter()
+				.map(|(idx, task_name)| {
+					(
+						*idx,
+						format!("{}/{}", job_name.as_str(), task_name.as_str()),
+					)
+				})
+				.collect(),
+			None => vec![(0, job_name.as_str().to_owned())],
+		};
+
+		for (idx, name) in task_names {
+			if let Some(feed) = job.websub_feeds.get(&idx) {
+				match websub_callback {
+					Some(callback) => subscriptions.push((
+						Subscription::new(feed.clone(), callback, &name, (tx.clone(), idx))?,
+						name.clone(),
+					)),
+					None => tracing::warn!(
+						"WebSub is enabled for {name} but --websub-callback isn't set, polling it instead"
+					),
+				}
+			}
+
+			tasks.insert(name, (tx.clone(), idx));
+		}
+	}
+
+	let listener = TcpListener::bind(address)
+		.await
+		.wrap_err_with(|| format!("Can't listen for triggers on {address}"))?;
+
+	tracing::info!("Listening for triggers on {address}");
+
+	let mut subscriptions_by_path = HashMap::new();
+	for ((subscription, leases), name) in subscriptions {
+		let subscription = Arc::new(subscription);
+		subscriptions_by_path.insert(
+			subscription.callback.path().to_owned(),
+			Arc::clone(&subscription),
+		);
+
+		tokio::spawn(async move { subscription.maintain(&name, leases).await });
+	}
+
+	let triggers = Arc::new(Triggers {
+		secret,
+		tasks,
+		subscriptions: subscriptions_by_path,
+	});
+	tokio::spawn(async move {
+		loop {
+			let stream = match listener.accept().await {
+				Ok((stream, _)) => stream,
+				Err(e) => {
+					tracing::warn!("Failed to accept a trigger connection: {e}");
+					continue;
+				}
+			};
+
+			let triggers = Arc::clone(&triggers);
+			tokio::spawn(async move {
+				let service = service_fn(|req| {
+					let triggers = Arc::clone(&triggers);
+					async move { Ok::<_, Infallible>(triggers.handle(req).await) }
+				});
+
+				if let Err(e) = http1::Builder::new()
+					.serve_connection(TokioIo::new(stream), service)
+					.await
+				{
+					tracing::debug!("Trigger connection closed with an error: {e}");
+				}
+			});
+		}
+	});
+
+	Ok(())
+}
+
+impl Triggers {
+	async fn handle(&self, req: Request<Incoming>) -> Response<Full<Bytes>> {
+		// hubs can't be given the secret, notifications are signed instead
+		if let Some(subscription) = self.subscriptions.get(req.uri().path()) {
+			return subscription.handle(req).await;
+		}
+
+		let authorized = req
+			.headers()
+			.get(AUTHORIZATION)
+			.and_then(|header| header.to_str().ok())
+			.and_then(|header| header.strip_prefix("Bearer "))
+			.is_some_and(|secret| constant_time_eq(secret.as_bytes(), self.secret.as_bytes()));
+
+		// don't tell anything about the jobs before the request is authorized
+		if !authorized {
+			return response(StatusCode::UNAUTHORIZED, "Unauthorized");
+		}
+
+		let Some((trigger, idx)) = req
+			.uri()
+			.path()
+			.strip_prefix("/trigger/")
+			.and_then(|task| self.tasks.get(task))
+		else {
+			return response(StatusCode::NOT_FOUND, "No such job or task");
+		};
+
+		if req.method() != Method::POST {
+			return response(StatusCode::METHOD_NOT_ALLOWED, "Only POST is allowed");
+		}
+
+		tracing::debug!("Received a trigger for {}", req.uri().path());
+
+		match trigger.try_send(*idx) {
+			Ok(()) => response(StatusCode::ACCEPTED, "Triggered"),
+			Err(TrySendError::Full(_)) => response(
+				StatusCode::TOO_MANY_REQUESTS,
+				"Too many triggers are already waiting to be run",
+			),
+			Err(TrySendError::Closed(_)) => response(
+				StatusCode::SERVICE_UNAVAILABLE,
+				"The job isn't running anymore",
+			),
+		}
+	}
+}
+
+impl Subscription {
+	/// Create a subscription of the task `name` to the `feed` with a callback under `callback_base`.
+	/// Returns the receiver of the leases of the subscription, too
+	fn new(
+		feed: Url,
+		callback_base: &Url,
+		name: &str,
+		trigger: (mpsc::Sender<usize>, usize),
+	) -> Result<(Self, mpsc::Receiver<Duration>)> {
+		let mut callback = callback_base.clone();
+		callback
+			.path_segments_mut()
+			.map_err(|()| eyre!("{callback_base} can't be used as the WebSub callback"))?
+			.pop_if_empty()
+			.push("websub")
+			.extend(name.split('/'));
+
+		let (leases, leases_rx) = mpsc::channel(1);
+
+		Ok((
+			Self {
+				feed,
+				callback,
+				secret: websub::generate_secret(),
+				hub: OnceLock::new(),
+				trigger,
+				leases,
+			},
+			leases_rx,
+		))
+	}
+
+	/// Discover the hub of the feed and keep the subscription to it alive, renewing it before its lease runs out
+	async fn maintain(&self, name: &str, mut leases: mpsc::Receiver<Duration>) {
+		let hub = loop {
+			match Hub::discover(&self.feed).await {
+				Ok(Some(hub)) => break self.hub.get_or_init(|| hub),
+				Ok(None) => {
+					tracing::info!(
+						"The feed of {name} doesn't advertise a WebSub hub, polling it instead"
+					);
+					return;
+				}
+				Err(e) => {
+					tracing::warn!(
+						"Can't discover the WebSub hub of {name}, retrying in {}m: {}",
+						WEBSUB_RETRY_DELAY.as_secs() / 60,
+						e.display_chain()
+					);
+					sleep(WEBSUB_RETRY_DELAY).await;
+				}
+			}
+		};
+
+		loop {
+			let renew_in = match hub.subscribe(&self.callback, &self.secret).await {
+				Ok(()) => match timeout(VERIFICATION_TIMEOUT, leases.recv()).await {
+					Ok(Some(lease)) => {
+						tracing::info!(
+							"Subscribed to {} through the WebSub hub {} for {}s",
+							hub.topic,
+							hub.url,
+							lease.as_secs()
+						);
+
+						// leave some time to renew the subscription before it expires
+						lease.saturating_sub(lease / 10)
+					}
+					Ok(None) => return,
+					Err(_) => {
+						tracing::warn!(
+							"The WebSub hub {} hasn't verified the subscription of {name}, make sure it can reach {}",
+							hub.url,
+							self.callback
+						);
+						WEBSUB_RETRY_DELAY
+					}
+				},
+				Err(e) => {
+					tracing::warn!(
+						"Can't subscribe to the WebSub hub {} of {name}, retrying in {}m: {}",
+						hub.url,
+						WEBSUB_RETRY_DELAY.as_secs() / 60,
+						e.display_chain()
+					);
+					WEBSUB_RETRY_DELAY
+				}
+			};
+
+			sleep(renew_in).await;
+		}
+	}
+
+	/// Handle a request from the hub: either the verification of a subscription or a notification about an update
+	async fn handle(&self, req: Request<Incoming>) -> Response<Full<Bytes>> {
+		match *req.method() {
+			Method::GET => self.verify(&req),
+			Method::POST => self.notify(req).await,
+			_ => response(
+				StatusCode::METHOD_NOT_ALLOWED,
+				"Only GET and POST are allowed",
+			),
+		}
+	}
+
+	/// Answer the challenge of the hub if it verifies the subscription this has asked for
+	fn verify(&self, req: &Request<Incoming>) -> Response<Full<Bytes>> {
+		let query = url::form_urlencoded::parse(req.uri().query().unwrap_or_default().as_bytes())
+			.collect::<HashMap<_, _>>();
+
+		let param = |name| query.get(name).map(AsRef::as_ref);
+		let Some(hub) = self.hub.get() else {
+			return response(StatusCode::NOT_FOUND, "No subscription has been requested");
+		};
+
+		match param("hub.mode") {
+			Some("subscribe") if param("hub.topic") == Some(hub.topic.as_str()) => {
+				let Some(challenge) = param("hub.challenge") else {
+					return response(StatusCode::BAD_REQUEST, "hub.challenge is missing");
+				};
+
+				let lease = param("hub.lease_seconds")
+					.and_then(|secs| secs.parse().ok())
+					.map_or(DEFAULT_LEASE, Duration::from_secs);
+
+				// the lease of a renewal that is already being waited for is the same
+				_ = self.leases.try_send(lease);
+
+				response(StatusCode::OK, challenge.to_owned())
+			}
+			Some("denied") => {
+				tracing::warn!(
+					"The WebSub hub {} has denied the subscription to {}: {}",
+					hub.url,
+					hub.topic,
+					param("hub.reason").unwrap_or("no reason given")
+				);
+
+				response(StatusCode::OK, "OK")
+			}
+			// only subscriptions to the topic are ever requested
+			_ => response(StatusCode::NOT_FOUND, "No such subscription"),
+		}
+	}
+
+	/// Run the task if the notification has been signed by the hub
+	async fn notify(&self, req: Request<Incoming>) -> Response<Full<Bytes>> {
+		let signature = req
+			.headers()
+			.get("X-Hub-Signature")
+			.and_then(|header| header.to_str().ok())
+			.map(ToOwned::to_owned);
+
+		let body = match Limited::new(req.into_body(), MAX_NOTIFICATION_LEN)
+			.collect()
+			.await
+		{
+			Ok(body) => body.to_bytes(),
+			Err(_) => return response(StatusCode::PAYLOAD_TOO_LARGE, "The body is too large"),
+		};
+
+		let signed = signature
+			.is_some_and(|signature| websub::verify_signature(&self.secret, &signature, &body));
+
+		// the hub should be told the notification has been received either way
+		if !signed {
+			tracing::warn!(
+				"Ignoring a WebSub notification about {} with a missing or invalid signature",
+				self.feed
+			);
+
+			return response(StatusCode::ACCEPTED, "Accepted");
+		}
+
+		tracing::debug!("Received a WebSub notification about {}", self.feed);
+
+		let (trigger, idx) = &self.trigger;
+		match trigger.try_send(*idx) {
+			// a run that will see the update is already waiting
+			Ok(()) | Err(TrySendError::Full(_)) => response(StatusCode::ACCEPTED, "Accepted"),
+			Err(TrySendError::Closed(_)) => response(
+				StatusCode::SERVICE_UNAVAILABLE,
+				"The job isn't running anymore",
+			),
+		}
+	}
+}
+
+fn response(status: StatusCode, body: impl Into<Bytes>) -> Response<Full<Bytes>> {
+	let mut response = Response::new(Full::new(body.into()));
+	*response.status_mut() = status;
+	response
+}
+
+/// Compare the secrets in a way that takes the same amount of time no matter where they differ
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+	a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}